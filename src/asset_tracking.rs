@@ -1,12 +1,30 @@
 //! A high-level way to load collections of asset handles as resources.
+//!
+//! Also keeps track of assets that failed to load, so a missing file shows up as a warning
+//! and a magenta placeholder instead of a silent hole.
 
 use std::collections::VecDeque;
 
-use bevy::prelude::*;
+use bevy::{
+    asset::{RecursiveDependencyLoadState, UntypedAssetLoadFailedEvent},
+    prelude::*,
+    scene::SceneInstance,
+};
+use bevy_shuffle_bag::ShuffleBag;
+use rand::Rng;
+
+use crate::screens::Screen;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ResourceHandles>();
-    app.add_systems(PreUpdate, load_resource_assets);
+    app.init_resource::<AssetFailures>();
+    app.init_resource::<PlaceholderAssets>();
+    app.add_systems(
+        PreUpdate,
+        (record_asset_failures, load_resource_assets).chain(),
+    );
+    app.add_systems(Update, substitute_failed_scenes);
+    app.add_systems(OnEnter(Screen::Gameplay), summarize_asset_failures);
 }
 
 pub(crate) trait LoadResource {
@@ -25,23 +43,26 @@ impl LoadResource for App {
         let assets = world.resource::<AssetServer>();
         let handle = assets.add(value);
         let mut handles = world.resource_mut::<ResourceHandles>();
-        handles
-            .waiting
-            .push_back((handle.untyped(), |world, handle| {
+        handles.waiting.push_back((
+            handle.untyped(),
+            std::any::type_name::<T>().to_string(),
+            |world, handle| {
                 let assets = world.resource::<Assets<T>>();
                 if let Some(value) = assets.get(handle.id().typed::<T>()) {
                     world.insert_resource(value.clone());
                 }
-            }));
+            },
+        ));
         self
     }
 
     fn load_asset<T: Asset>(&mut self, path: impl Into<String>) -> &mut Self {
-        let handle: Handle<T> = self.world().load_asset(path.into());
+        let path = path.into();
+        let handle: Handle<T> = self.world().load_asset(path.clone());
         let mut handles = self.world_mut().resource_mut::<ResourceHandles>();
         handles
             .waiting
-            .push_back((handle.untyped(), |_world, _handle| {}));
+            .push_back((handle.untyped(), path, |_world, _handle| {}));
         self
     }
 }
@@ -53,7 +74,8 @@ type InsertLoadedResource = fn(&mut World, &UntypedHandle);
 pub(crate) struct ResourceHandles {
     // Use a queue for waiting assets so they can be cycled through and moved to
     // `finished` one at a time.
-    waiting: VecDeque<(UntypedHandle, InsertLoadedResource)>,
+    /// The handle, what it's for, and how to insert it once loaded.
+    waiting: VecDeque<(UntypedHandle, String, InsertLoadedResource)>,
     finished: Vec<UntypedHandle>,
 }
 
@@ -76,14 +98,142 @@ fn load_resource_assets(world: &mut World) {
     world.resource_scope(|world, mut resource_handles: Mut<ResourceHandles>| {
         world.resource_scope(|world, assets: Mut<AssetServer>| {
             for _ in 0..resource_handles.waiting.len() {
-                let (handle, insert_fn) = resource_handles.waiting.pop_front().unwrap();
+                let (handle, context, insert_fn) = resource_handles.waiting.pop_front().unwrap();
                 if assets.is_loaded_with_dependencies(&handle) {
                     insert_fn(world, &handle);
                     resource_handles.finished.push(handle);
+                } else if let Some(RecursiveDependencyLoadState::Failed(error)) =
+                    assets.get_recursive_dependency_load_state(&handle)
+                {
+                    // Waiting would stall the loading screen forever, go on without them.
+                    warn!("{context} is missing assets and will load without them: {error}");
+                    insert_fn(world, &handle);
+                    resource_handles.finished.push(handle);
                 } else {
-                    resource_handles.waiting.push_back((handle, insert_fn));
+                    resource_handles
+                        .waiting
+                        .push_back((handle, context, insert_fn));
                 }
             }
         });
     });
 }
+
+/// Every asset that failed to load this session.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct AssetFailures(pub(crate) Vec<AssetFailure>);
+
+#[derive(Debug)]
+pub(crate) struct AssetFailure {
+    pub(crate) path: String,
+    pub(crate) error: String,
+}
+
+fn record_asset_failures(
+    mut failed: MessageReader<UntypedAssetLoadFailedEvent>,
+    mut failures: ResMut<AssetFailures>,
+) {
+    for failure in failed.read() {
+        warn!("Failed to load {}: {}", failure.path, failure.error);
+        failures.0.push(AssetFailure {
+            path: failure.path.to_string(),
+            error: failure.error.to_string(),
+        });
+    }
+}
+
+fn summarize_asset_failures(failures: Res<AssetFailures>) {
+    if failures.0.is_empty() {
+        return;
+    }
+    let paths: Vec<_> = failures.0.iter().map(|f| f.path.as_str()).collect();
+    warn!(
+        "{} asset(s) failed to load: {}",
+        paths.len(),
+        paths.join(", ")
+    );
+}
+
+/// Whether `handle` failed, including labeled sub-assets like `model.glb#Scene0` whose file failed.
+fn load_failed<A: Asset>(server: &AssetServer, handle: &Handle<A>) -> bool {
+    if server.load_state(handle.id()).is_failed() {
+        return true;
+    }
+    handle.path().is_some_and(|path| {
+        path.label().is_some()
+            && server
+                .get_handle_untyped(path.without_label())
+                .is_some_and(|file| server.load_state(file.id()).is_failed())
+    })
+}
+
+pub(crate) trait PickLoaded<A: Asset> {
+    /// Like [`ShuffleBag::pick`], but skips handles that failed to load.
+    /// `None` if a few picks in a row all failed.
+    fn pick_loaded(&mut self, rng: &mut impl Rng, server: &AssetServer) -> Option<Handle<A>>;
+}
+
+impl<A: Asset> PickLoaded<A> for ShuffleBag<Handle<A>> {
+    fn pick_loaded(&mut self, rng: &mut impl Rng, server: &AssetServer) -> Option<Handle<A>> {
+        const ATTEMPTS: usize = 8;
+        (0..ATTEMPTS)
+            .map(|_| self.pick(rng).clone())
+            .find(|handle| !load_failed(server, handle))
+    }
+}
+
+#[derive(Resource)]
+struct PlaceholderAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PlaceholderAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(0.5));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.0, 1.0),
+                unlit: true,
+                ..default()
+            });
+        Self { mesh, material }
+    }
+}
+
+/// On a [`SceneRoot`] whose scene failed to load, with a placeholder child in its place.
+#[derive(Component)]
+struct MissingScene;
+
+/// Scenes that haven't been instanced yet are the only ones that can still fail.
+fn substitute_failed_scenes(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    placeholder: Res<PlaceholderAssets>,
+    scenes: Query<
+        (Entity, &SceneRoot, Option<&ChildOf>),
+        (Without<SceneInstance>, Without<MissingScene>),
+    >,
+    names: Query<&Name>,
+) {
+    for (entity, scene, child_of) in &scenes {
+        if !load_failed(&server, &scene.0) {
+            continue;
+        }
+        let name = |entity: Entity| names.get(entity).map_or("Unnamed", |name| name.as_str());
+        let owner = child_of.map_or("nothing", |child_of| name(child_of.parent()));
+        warn!(
+            "{} (on {owner}) has no model, {} failed to load",
+            name(entity),
+            scene.0.path().map_or_else(String::new, ToString::to_string)
+        );
+        commands.entity(entity).insert(MissingScene).with_child((
+            Name::new("Missing Scene Placeholder"),
+            Mesh3d(placeholder.mesh.clone()),
+            MeshMaterial3d(placeholder.material.clone()),
+        ));
+    }
+}
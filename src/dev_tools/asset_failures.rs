@@ -0,0 +1,22 @@
+//! Lists every asset that failed to load, so missing files are found in one glance.
+
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use super::input::ListAssetFailures;
+use crate::asset_tracking::AssetFailures;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(list_asset_failures);
+}
+
+fn list_asset_failures(_on: On<Start<ListAssetFailures>>, failures: Res<AssetFailures>) {
+    if failures.0.is_empty() {
+        info!("No assets failed to load");
+        return;
+    }
+    info!("{} assets failed to load:", failures.0.len());
+    for failure in &failures.0 {
+        info!("  {}: {}", failure.path, failure.error);
+    }
+}
@@ -5,6 +5,7 @@ use std::any::Any as _;
 use super::input::{ForceFreeCursor, ToggleDebugUi};
 use crate::RenderLayer;
 use crate::gameplay::crosshair::CrosshairState;
+use crate::gameplay::dig::walk_grid::WalkGridGizmos;
 use crate::gameplay::level::LevelAssets;
 use crate::gameplay::player::input::BlocksInput;
 use crate::{
@@ -169,6 +170,8 @@ fn update_debug_ui_text(
         DebugState::Physics => "Physics",
         DebugState::Landmass => "Landmass",
         DebugState::Skeleton => "Skeleton",
+        DebugState::Spawners => "Spawners",
+        DebugState::Inspect => "Inspect",
     }
     .to_string();
 }
@@ -194,9 +197,12 @@ fn toggle_lighting_debug_ui(mut config_store: ResMut<GizmoConfigStore>) {
 fn toggle_landmass_debug_ui(
     mut debug: ResMut<EnableLandmassDebug>,
     mut navmesh: ResMut<NavmeshGizmoConfig>,
+    mut config_store: ResMut<GizmoConfigStore>,
 ) {
     **debug = !**debug;
     navmesh.detail_navmesh.enabled = !navmesh.detail_navmesh.enabled;
+    let walk_grid = config_store.config_mut::<WalkGridGizmos>().0;
+    walk_grid.enabled = !walk_grid.enabled;
 }
 
 fn toggle_fps_overlay(mut config: ResMut<FpsOverlayConfig>) {
@@ -312,7 +318,7 @@ fn pickup_debug_ui(world: &mut World) {
 }
 
 #[derive(Resource, Debug, Default, Eq, PartialEq)]
-enum DebugState {
+pub(super) enum DebugState {
     #[default]
     None,
     Ui,
@@ -320,6 +326,8 @@ enum DebugState {
     Physics,
     Landmass,
     Skeleton,
+    Spawners,
+    Inspect,
 }
 
 impl DebugState {
@@ -330,7 +338,9 @@ impl DebugState {
             Self::Lighting => Self::Physics,
             Self::Physics => Self::Landmass,
             Self::Landmass => Self::Skeleton,
-            Self::Skeleton => Self::None,
+            Self::Skeleton => Self::Spawners,
+            Self::Spawners => Self::Inspect,
+            Self::Inspect => Self::None,
         }
     }
 }
@@ -0,0 +1,324 @@
+//! Live tuning for enemy gunners' bullet patterns, toggled with F9.
+//! Enemies are grouped by pattern and stats; adjust a group or all of them from the numpad:
+//! 7/9 select group, 8/2 select stat, +/- adjust, Enter logs the numbers for the map editor.
+//! +/- also work from the main row for keyboards without a numpad.
+
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use super::input::TuneEnemies;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::npc::{
+        NpcDead,
+        shooting::{NpcShooter, ShooterTuning},
+    },
+    screens::Screen,
+    theme::GameFont,
+};
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const MUTED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(toggle_enemy_tuning);
+    app.add_systems(OnExit(Screen::Gameplay), close_enemy_tuning);
+    // Nothing runs while the panel is closed.
+    app.add_systems(
+        Update,
+        (tuning_keys, update_tuning_panel)
+            .chain()
+            .run_if(resource_exists::<EnemyTuning>.and(in_state(Screen::Gameplay)))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// Only exists while the panel is open.
+#[derive(Resource, Default)]
+struct EnemyTuning {
+    /// 0 adjusts every enemy, otherwise the group at `group - 1`.
+    group: usize,
+    stat: TunedStat,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum TunedStat {
+    #[default]
+    FireInterval,
+    ProjectileSpeed,
+    ProjectileCount,
+    Range,
+    SpreadAngle,
+}
+
+impl TunedStat {
+    const ALL: [Self; 5] = [
+        Self::FireInterval,
+        Self::ProjectileSpeed,
+        Self::ProjectileCount,
+        Self::Range,
+        Self::SpreadAngle,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::FireInterval => "fire_rate",
+            Self::ProjectileSpeed => "projectile_speed",
+            Self::ProjectileCount => "projectile_count",
+            Self::Range => "range",
+            Self::SpreadAngle => "spread half angle",
+        }
+    }
+
+    fn value(self, tuning: &ShooterTuning) -> String {
+        match self {
+            Self::FireInterval => format!("{:.2}s", tuning.fire_interval),
+            Self::ProjectileSpeed => format!("{:.1}", tuning.projectile_speed),
+            Self::ProjectileCount => format!("{}", tuning.projectile_count),
+            Self::Range => format!("{:.1}", tuning.range),
+            Self::SpreadAngle => format!("{:.0}°", tuning.spread_half_angle.to_degrees()),
+        }
+    }
+
+    /// Moves the stat by `steps` increments, keeping it usable.
+    fn adjust(self, tuning: &mut ShooterTuning, steps: i32) {
+        let steps_f = steps as f32;
+        match self {
+            Self::FireInterval => {
+                tuning.fire_interval = (tuning.fire_interval + 0.1 * steps_f).max(0.1);
+            }
+            Self::ProjectileSpeed => {
+                tuning.projectile_speed = (tuning.projectile_speed + 0.5 * steps_f).max(0.5);
+            }
+            Self::ProjectileCount => {
+                tuning.projectile_count =
+                    tuning.projectile_count.saturating_add_signed(steps).max(1);
+            }
+            Self::Range => tuning.range = (tuning.range + 1.0 * steps_f).max(1.0),
+            Self::SpreadAngle => {
+                tuning.spread_half_angle = (tuning.spread_half_angle + 5f32.to_radians() * steps_f)
+                    .clamp(0.0, std::f32::consts::PI);
+            }
+        }
+    }
+}
+
+/// Living shooters that share a pattern and stats.
+struct ShooterGroup {
+    pattern: &'static str,
+    tuning: ShooterTuning,
+    shooters: Vec<Entity>,
+}
+
+fn shooter_groups<'a>(
+    shooters: impl IntoIterator<Item = (Entity, &'a NpcShooter)>,
+) -> Vec<ShooterGroup> {
+    let mut groups: Vec<ShooterGroup> = Vec::new();
+    for (entity, shooter) in shooters {
+        let (pattern, tuning) = (shooter.pattern_name(), shooter.tuning());
+        match groups
+            .iter_mut()
+            .find(|group| group.pattern == pattern && group.tuning == tuning)
+        {
+            Some(group) => group.shooters.push(entity),
+            None => groups.push(ShooterGroup {
+                pattern,
+                tuning,
+                shooters: vec![entity],
+            }),
+        }
+    }
+    // Query order isn't stable, keep the selected group from jumping around.
+    for group in &mut groups {
+        group.shooters.sort_unstable();
+    }
+    groups.sort_by_key(|group| (group.pattern, group.shooters[0]));
+    groups
+}
+
+#[derive(Component)]
+struct EnemyTuningPanel;
+
+#[derive(Component, Default, PartialEq)]
+struct EnemyTuningLines(Vec<(String, Color)>);
+
+fn toggle_enemy_tuning(
+    _on: On<Start<TuneEnemies>>,
+    mut commands: Commands,
+    tuning: Option<Res<EnemyTuning>>,
+    screen: Res<State<Screen>>,
+    panel: Query<Entity, With<EnemyTuningPanel>>,
+) {
+    if tuning.is_some() {
+        commands.remove_resource::<EnemyTuning>();
+        for panel in &panel {
+            commands.entity(panel).despawn();
+        }
+        return;
+    }
+    if *screen.get() != Screen::Gameplay {
+        return;
+    }
+    commands.init_resource::<EnemyTuning>();
+    commands.spawn((
+        Name::new("Enemy Tuning Panel"),
+        EnemyTuningPanel,
+        EnemyTuningLines::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(16.0),
+            top: Val::Px(120.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn close_enemy_tuning(mut commands: Commands) {
+    commands.remove_resource::<EnemyTuning>();
+}
+
+fn tuning_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut tuning: ResMut<EnemyTuning>,
+    mut shooters: Query<(Entity, &mut NpcShooter), Without<NpcDead>>,
+) {
+    let groups = shooter_groups(shooters.iter());
+    if keys.just_pressed(KeyCode::Numpad9) {
+        tuning.group = (tuning.group + 1) % (groups.len() + 1);
+    }
+    if keys.just_pressed(KeyCode::Numpad7) {
+        tuning.group = tuning.group.checked_sub(1).unwrap_or(groups.len());
+    }
+    // Groups merge or die off, fall back to all of them.
+    if tuning.group > groups.len() {
+        tuning.group = 0;
+    }
+    let stat_index = TunedStat::ALL
+        .iter()
+        .position(|stat| *stat == tuning.stat)
+        .unwrap_or(0);
+    if keys.just_pressed(KeyCode::Numpad2) {
+        tuning.stat = TunedStat::ALL[(stat_index + 1) % TunedStat::ALL.len()];
+    }
+    if keys.just_pressed(KeyCode::Numpad8) {
+        tuning.stat =
+            TunedStat::ALL[(stat_index + TunedStat::ALL.len() - 1) % TunedStat::ALL.len()];
+    }
+
+    if keys.just_pressed(KeyCode::NumpadEnter) {
+        log_fgd_values(&groups);
+    }
+
+    let steps = match (
+        keys.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]),
+        keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]),
+    ) {
+        (true, false) => 1,
+        (false, true) => -1,
+        _ => return,
+    };
+    let selected = match tuning.group {
+        0 => groups.iter().collect::<Vec<_>>(),
+        group => groups.get(group - 1).into_iter().collect(),
+    };
+    for group in selected {
+        let mut adjusted = group.tuning;
+        tuning.stat.adjust(&mut adjusted, steps);
+        for &entity in &group.shooters {
+            if let Ok((_, mut shooter)) = shooters.get_mut(entity) {
+                shooter.set_tuning(adjusted);
+            }
+        }
+    }
+}
+
+/// Prints each group as entity properties to paste into TrenchBroom.
+fn log_fgd_values(groups: &[ShooterGroup]) {
+    let mut out = String::from("Enemy gunner values (include threat scaling):");
+    for group in groups {
+        let tuning = &group.tuning;
+        out.push_str(&format!(
+            "\n// {} enemies\n\"pattern\" \"{}\"\n\"fire_rate\" \"{}\"\n\"projectile_speed\" \"{}\"\n\"projectile_count\" \"{}\"\n\"range\" \"{}\"\n// SPREAD_HALF_ANGLE = {:.4} ({:.1}°)",
+            group.shooters.len(),
+            group.pattern,
+            tuning.fire_interval,
+            tuning.projectile_speed,
+            tuning.projectile_count,
+            tuning.range,
+            tuning.spread_half_angle,
+            tuning.spread_half_angle.to_degrees(),
+        ));
+    }
+    info!("{out}");
+}
+
+fn update_tuning_panel(
+    mut commands: Commands,
+    tuning: Res<EnemyTuning>,
+    shooters: Query<(Entity, &NpcShooter), Without<NpcDead>>,
+    panel: Single<(Entity, &mut EnemyTuningLines), With<EnemyTuningPanel>>,
+    font: Res<GameFont>,
+) {
+    let groups = shooter_groups(&shooters);
+    let mut lines = vec![(
+        "[7/9] group  [8/2] stat  [+/-] adjust  [enter] log".to_string(),
+        MUTED_COLOR,
+    )];
+    let all_color = if tuning.group == 0 {
+        SELECTED_COLOR
+    } else {
+        Color::WHITE
+    };
+    lines.push((
+        format!("all groups ({}): {}", groups.len(), tuning.stat.label()),
+        all_color,
+    ));
+    for (index, group) in groups.iter().enumerate() {
+        let selected = tuning.group == index + 1;
+        let color = if selected {
+            SELECTED_COLOR
+        } else {
+            Color::WHITE
+        };
+        lines.push((
+            format!("{} x{}", group.pattern, group.shooters.len()),
+            color,
+        ));
+        for stat in TunedStat::ALL {
+            let marker = if selected && stat == tuning.stat {
+                ">"
+            } else {
+                " "
+            };
+            lines.push((
+                format!("  {marker} {}: {}", stat.label(), stat.value(&group.tuning)),
+                if selected { color } else { MUTED_COLOR },
+            ));
+        }
+    }
+
+    let (panel, mut current) = panel.into_inner();
+    let lines = EnemyTuningLines(lines);
+    if *current == lines {
+        return;
+    }
+    commands.entity(panel).despawn_related::<Children>();
+    for (line, color) in &lines.0 {
+        commands.spawn((
+            Text::new(line.clone()),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(*color),
+            ChildOf(panel),
+        ));
+    }
+    *current = lines;
+}
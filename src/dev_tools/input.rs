@@ -16,6 +16,22 @@ pub(crate) struct ToggleDebugUi;
 #[action_output(bool)]
 pub(crate) struct ForceFreeCursor;
 
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleInputRecording;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct PlayInputRecording;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ListAssetFailures;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct TuneEnemies;
+
 #[derive(Debug, Component, Default)]
 struct DevToolsInputContext;
 
@@ -26,6 +42,10 @@ fn setup_dev_tools_input(mut commands: Commands) {
         actions!(DevToolsInputContext[
             (Action::<ToggleDebugUi>::new(), bindings![KeyCode::F3]),
             (Action::<ForceFreeCursor>::new(), bindings![KeyCode::Backquote]),
+            (Action::<ToggleInputRecording>::new(), bindings![KeyCode::F6]),
+            (Action::<PlayInputRecording>::new(), bindings![KeyCode::F7]),
+            (Action::<ListAssetFailures>::new(), bindings![KeyCode::F8]),
+            (Action::<TuneEnemies>::new(), bindings![KeyCode::F9]),
         ]),
     ));
 }
@@ -0,0 +1,194 @@
+//! Records the player's input actions every frame and plays them back, for reproducing bugs.
+//!
+//! Frame times are recorded too and replayed through [`TimeUpdateStrategy`], so fixed steps
+//! land on the same inputs. Start recording right after the level loads and play it back
+//! from the same point, anything that rolls `rand::rng()` can still drift.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_enhanced_input::prelude::*;
+use bincode::{Decode, Encode};
+
+use super::input::{PlayInputRecording, ToggleInputRecording};
+use crate::gameplay::player::input::PlayerInputContext;
+
+const RECORDING_PATH: &str = "input.replay";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputReplay>();
+    app.add_observer(toggle_recording);
+    app.add_observer(start_playback);
+    app.add_systems(
+        PreUpdate,
+        (
+            play_back_input.before(EnhancedInputSystems::Update),
+            record_input.after(EnhancedInputSystems::Update),
+        ),
+    );
+}
+
+#[derive(Encode, Decode, Default)]
+struct InputRecording {
+    frames: Vec<RecordedFrame>,
+}
+
+#[derive(Encode, Decode)]
+struct RecordedFrame {
+    delta_nanos: u64,
+    /// Values in the order the player's actions were spawned.
+    actions: Vec<RecordedValue>,
+}
+
+#[derive(Encode, Decode, Clone, Copy)]
+enum RecordedValue {
+    Bool(bool),
+    Axis1D(f32),
+    Axis2D([f32; 2]),
+    Axis3D([f32; 3]),
+}
+
+impl From<ActionValue> for RecordedValue {
+    fn from(value: ActionValue) -> Self {
+        match value {
+            ActionValue::Bool(value) => Self::Bool(value),
+            ActionValue::Axis1D(value) => Self::Axis1D(value),
+            ActionValue::Axis2D(value) => Self::Axis2D(value.to_array()),
+            ActionValue::Axis3D(value) => Self::Axis3D(value.to_array()),
+        }
+    }
+}
+
+impl From<RecordedValue> for ActionValue {
+    fn from(value: RecordedValue) -> Self {
+        match value {
+            RecordedValue::Bool(value) => Self::Bool(value),
+            RecordedValue::Axis1D(value) => Self::Axis1D(value),
+            RecordedValue::Axis2D(value) => Self::Axis2D(Vec2::from_array(value)),
+            RecordedValue::Axis3D(value) => Self::Axis3D(Vec3::from_array(value)),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+enum InputReplay {
+    #[default]
+    Idle,
+    Recording(InputRecording),
+    Playing {
+        recording: InputRecording,
+        frame: usize,
+    },
+}
+
+fn toggle_recording(_on: On<Start<ToggleInputRecording>>, mut replay: ResMut<InputReplay>) {
+    match std::mem::take(&mut *replay) {
+        InputReplay::Idle => {
+            info!("Recording input");
+            *replay = InputReplay::Recording(default());
+        }
+        InputReplay::Recording(recording) => {
+            let frames = recording.frames.len();
+            let written = bincode::encode_to_vec(&recording, bincode::config::standard())
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| std::fs::write(RECORDING_PATH, bytes).map_err(Into::into));
+            match written {
+                Ok(()) => info!("Saved {frames} frames of input to {RECORDING_PATH}"),
+                Err(error) => error!("Failed to save input recording: {error}"),
+            }
+        }
+        playing @ InputReplay::Playing { .. } => {
+            warn!("Can't record input during playback");
+            *replay = playing;
+        }
+    }
+}
+
+fn start_playback(_on: On<Start<PlayInputRecording>>, mut replay: ResMut<InputReplay>) {
+    if !matches!(*replay, InputReplay::Idle) {
+        warn!("Stop recording before playing input back");
+        return;
+    }
+    let recording = std::fs::read(RECORDING_PATH)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| {
+            bincode::decode_from_slice::<InputRecording, _>(&bytes, bincode::config::standard())
+                .map_err(Into::into)
+        });
+    match recording {
+        Ok((recording, _)) => {
+            info!(
+                "Playing back {} frames of input from {RECORDING_PATH}",
+                recording.frames.len()
+            );
+            *replay = InputReplay::Playing {
+                recording,
+                frame: 0,
+            };
+        }
+        Err(error) => error!("Failed to load input recording: {error}"),
+    }
+}
+
+fn record_input(
+    time: Res<Time<Real>>,
+    mut replay: ResMut<InputReplay>,
+    context: Option<Single<&Actions<PlayerInputContext>>>,
+    values: Query<&ActionValue>,
+) {
+    let InputReplay::Recording(recording) = &mut *replay else {
+        return;
+    };
+    let Some(context) = context else { return };
+    recording.frames.push(RecordedFrame {
+        delta_nanos: time.delta().as_nanos() as u64,
+        actions: context
+            .iter()
+            .map(|action| {
+                values
+                    .get(action)
+                    .map_or(RecordedValue::Bool(false), |value| (*value).into())
+            })
+            .collect(),
+    });
+}
+
+/// Mocks every player action with the recorded value, which also shuts out live input.
+fn play_back_input(
+    mut commands: Commands,
+    mut replay: ResMut<InputReplay>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    context: Option<Single<&Actions<PlayerInputContext>>>,
+) {
+    let InputReplay::Playing { recording, frame } = &mut *replay else {
+        return;
+    };
+    let Some(context) = context else { return };
+
+    let Some(recorded) = recording.frames.get(*frame) else {
+        info!("Input playback finished");
+        for action in context.iter() {
+            commands.entity(action).remove::<ActionMock>();
+        }
+        *time_strategy = TimeUpdateStrategy::Automatic;
+        *replay = InputReplay::Idle;
+        return;
+    };
+
+    for (action, &value) in context.iter().zip(&recorded.actions) {
+        let value = ActionValue::from(value);
+        let state = if value.as_bool() {
+            ActionState::Fired
+        } else {
+            ActionState::None
+        };
+        commands
+            .entity(action)
+            .insert(ActionMock::new(state, value, MockSpan::Manual));
+    }
+    // Time for this frame is already in, so line up the next one.
+    if let Some(next) = recording.frames.get(*frame + 1) {
+        *time_strategy = TimeUpdateStrategy::ManualDuration(Duration::from_nanos(next.delta_nanos));
+    }
+    *frame += 1;
+}
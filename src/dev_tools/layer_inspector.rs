@@ -0,0 +1,185 @@
+//! Collision layer inspection, for tracking down layers set wrong somewhere.
+//! With the `Inspect` debug UI active, a panel describes whatever collider is under the
+//! crosshair on any layer: its name, components, collision layer memberships and filters,
+//! rigid body and whether it's a sensor.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use super::debug_ui::DebugState;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::player::{Player, camera::PlayerCamera},
+    screens::Screen,
+    theme::GameFont,
+    third_party::avian3d::CollisionLayer,
+};
+
+const INSPECT_DISTANCE: f32 = 50.0;
+/// Component names listed per line of the panel.
+const COMPONENTS_PER_LINE: usize = 4;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InspectedCollider>();
+    app.add_systems(
+        Update,
+        (look_at_collider, update_layer_panel)
+            .chain()
+            .run_if(in_state(Screen::Gameplay).and(inspecting))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+#[derive(Resource, Default, PartialEq)]
+struct InspectedCollider(Option<Entity>);
+
+#[derive(Component)]
+struct LayerPanel;
+
+/// While inspecting, and on the frame it's turned off to clean up.
+fn inspecting(debug_state: Res<DebugState>) -> bool {
+    *debug_state == DebugState::Inspect || debug_state.is_changed()
+}
+
+fn look_at_collider(
+    debug_state: Res<DebugState>,
+    camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    player: Option<Single<Entity, With<Player>>>,
+    spatial_query: SpatialQuery,
+    mut inspected: ResMut<InspectedCollider>,
+) {
+    let hit = match (*debug_state == DebugState::Inspect, camera) {
+        (true, Some(camera)) => {
+            let camera_transform = camera.compute_transform();
+            let mut filter = SpatialQueryFilter::default();
+            if let Some(player) = player {
+                filter.excluded_entities.insert(*player);
+            }
+            spatial_query
+                .cast_ray(
+                    camera_transform.translation,
+                    camera_transform.forward(),
+                    INSPECT_DISTANCE,
+                    true,
+                    &filter,
+                )
+                .map(|hit| hit.entity)
+        }
+        _ => None,
+    };
+    inspected.set_if_neq(InspectedCollider(hit));
+}
+
+/// Exclusive for the component list, which needs the world's component info.
+fn update_layer_panel(world: &mut World) {
+    let text = world
+        .resource::<InspectedCollider>()
+        .0
+        .and_then(|entity| describe_collider(world, entity));
+    let panel = world
+        .query_filtered::<Entity, With<LayerPanel>>()
+        .iter(world)
+        .next();
+
+    match (text, panel) {
+        (Some(text), Some(panel)) => {
+            if let Some(mut current) = world.get_mut::<Text>(panel) {
+                current.set_if_neq(Text::new(text));
+            }
+        }
+        (Some(text), None) => {
+            let font = world.resource::<GameFont>().0.clone();
+            world.spawn((
+                Name::new("Layer Panel"),
+                LayerPanel,
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(16.0),
+                    top: Val::Px(48.0),
+                    max_width: Val::Px(420.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                Text::new(text),
+                TextFont {
+                    font,
+                    font_size: 14.0,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+                Pickable::IGNORE,
+                DespawnOnExit(Screen::Gameplay),
+            ));
+        }
+        (None, Some(panel)) => {
+            world.despawn(panel);
+        }
+        (None, None) => {}
+    }
+}
+
+fn describe_collider(world: &World, entity: Entity) -> Option<String> {
+    let mut components: Vec<String> = world
+        .inspect_entity(entity)
+        .ok()?
+        .map(|info| short_name(&info.name().to_string()))
+        .collect();
+    components.sort();
+
+    let name = world.get::<Name>(entity).map_or("(unnamed)", Name::as_str);
+    let mut lines = vec![format!("{name} {entity}")];
+
+    let layers = world.get::<CollisionLayers>(entity);
+    let default_note = if layers.is_none() { " (default)" } else { "" };
+    let layers = layers.copied().unwrap_or_default();
+    let names = |mask| {
+        let names = CollisionLayer::names(mask);
+        if names.is_empty() {
+            "none".to_string()
+        } else {
+            names.join(", ")
+        }
+    };
+    lines.push(format!(
+        "memberships: {}{default_note}",
+        names(layers.memberships)
+    ));
+    lines.push(format!("filters: {}{default_note}", names(layers.filters)));
+
+    // Colliders can hang off a rigid body further up the hierarchy.
+    let body = world.get::<ColliderOf>(entity).map_or(entity, |of| of.body);
+    let rigid_body = match world.get::<RigidBody>(body) {
+        Some(rigid_body) if body == entity => format!("{rigid_body:?}"),
+        Some(rigid_body) => format!("{rigid_body:?} on {body}"),
+        None => "none".to_string(),
+    };
+    lines.push(format!("rigid body: {rigid_body}"));
+    lines.push(format!("sensor: {}", world.get::<Sensor>(entity).is_some()));
+
+    lines.push(format!("components ({}):", components.len()));
+    for chunk in components.chunks(COMPONENTS_PER_LINE) {
+        lines.push(format!("  {}", chunk.join(", ")));
+    }
+    Some(lines.join("\n"))
+}
+
+/// Drops the module paths from a type name, including inside generics, so
+/// `avian3d::collision::CollisionLayers` reads as `CollisionLayers`.
+fn short_name(full: &str) -> String {
+    let mut short = String::with_capacity(full.len());
+    // Where the path segment being read starts in `short`.
+    let mut segment_start = 0;
+    let mut chars = full.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            short.truncate(segment_start);
+            continue;
+        }
+        short.push(c);
+        if !(c.is_alphanumeric() || c == '_') {
+            segment_start = short.len();
+        }
+    }
+    short
+}
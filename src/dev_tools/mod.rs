@@ -2,9 +2,14 @@
 
 use bevy::{dev_tools::states::log_transitions, prelude::*};
 
+mod asset_failures;
 mod debug_ui;
+mod enemy_tuning;
 mod input;
+mod input_replay;
+mod layer_inspector;
 pub(crate) mod log_components;
+mod spawner_inspector;
 mod validate_preloading;
 
 use crate::{menus::Menu, screens::loading::LoadingScreen};
@@ -17,9 +22,14 @@ pub(super) fn plugin(app: &mut App) {
     );
 
     app.add_plugins((
+        asset_failures::plugin,
         debug_ui::plugin,
+        enemy_tuning::plugin,
         input::plugin,
+        input_replay::plugin,
+        layer_inspector::plugin,
         validate_preloading::plugin,
         log_components::plugin,
+        spawner_inspector::plugin,
     ));
 }
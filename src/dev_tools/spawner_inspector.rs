@@ -0,0 +1,378 @@
+//! Spawner inspection for level design.
+//! With the `Spawners` debug UI active, looking at an NPC, enemy or body spawner shows its
+//! queue and everything it has spawned, and lets you poke it from the numpad:
+//! 1 queue spawn, 2 direct spawn the selected model, 3 select the next model, 4 clear the spawned list.
+
+use avian3d::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use super::debug_ui::DebugState;
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::{
+        grave::{BodySpawner, SpawnBody, SpawnerState},
+        npc::{
+            EnemySpawner, EnemySpawnerState, NpcDead, NpcRegistry, NpcSpawner, NpcSpawnerState,
+            SpawnEnemy, SpawnNpc,
+        },
+        player::camera::{PlayerCamera, WorldModelCamera},
+    },
+    screens::Screen,
+    theme::GameFont,
+    third_party::avian3d::CollisionLayer,
+};
+
+const INSPECT_DISTANCE: f32 = 30.0;
+const DEBUG_COLLIDER_RADIUS: f32 = 0.3;
+
+const HIGHLIGHT_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const ALIVE_COLOR: Color = Color::srgb(0.5, 1.0, 0.5);
+const DEAD_COLOR: Color = Color::srgb(1.0, 0.4, 0.4);
+const MUTED_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InspectedSpawner>();
+    app.add_observer(add_debug_collider::<NpcSpawner>);
+    app.add_observer(add_debug_collider::<EnemySpawner>);
+    app.add_observer(add_debug_collider::<BodySpawner>);
+    app.add_systems(
+        Update,
+        (look_at_spawner, spawner_panel_keys, update_spawner_panel)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// Spawners are just points, give them something to look at.
+fn add_debug_collider<T: Component>(add: On<Add, T>, mut commands: Commands) {
+    commands.entity(add.entity).insert((
+        Collider::sphere(DEBUG_COLLIDER_RADIUS),
+        Sensor,
+        CollisionLayers::new(CollisionLayer::Debug, LayerMask::NONE),
+    ));
+}
+
+#[derive(Resource, Default)]
+struct InspectedSpawner {
+    entity: Option<Entity>,
+    /// Index into the sorted prefab keys used for direct spawns.
+    selected_model: usize,
+}
+
+#[derive(Component)]
+struct SpawnerPanel;
+
+#[derive(Component, Default, PartialEq)]
+struct SpawnerPanelLines(Vec<(String, Color)>);
+
+#[derive(Clone, Copy)]
+enum SpawnerKind {
+    Npc,
+    Enemy,
+    Body,
+}
+
+impl SpawnerKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Npc => "NpcSpawner",
+            Self::Enemy => "EnemySpawner",
+            Self::Body => "BodySpawner",
+        }
+    }
+}
+
+struct SpawnerView<'a> {
+    kind: SpawnerKind,
+    name: &'a str,
+    /// Model used when the queue is empty, bodies don't have one.
+    default_model: Option<&'a str>,
+    queue: &'a [String],
+    index: usize,
+    spawned: &'a [(Entity, String)],
+}
+
+#[derive(SystemParam)]
+struct Spawners<'w, 's> {
+    npcs: Query<'w, 's, (&'static NpcSpawner, &'static mut NpcSpawnerState)>,
+    enemies: Query<'w, 's, (&'static EnemySpawner, &'static mut EnemySpawnerState)>,
+    bodies: Query<'w, 's, (&'static BodySpawner, &'static mut SpawnerState)>,
+}
+
+impl Spawners<'_, '_> {
+    fn view(&self, entity: Entity) -> Option<SpawnerView<'_>> {
+        if let Ok((spawner, state)) = self.npcs.get(entity) {
+            return Some(SpawnerView {
+                kind: SpawnerKind::Npc,
+                name: &spawner.name,
+                default_model: Some(&spawner.model),
+                queue: &state.queue,
+                index: state.index,
+                spawned: &state.spawned,
+            });
+        }
+        if let Ok((spawner, state)) = self.enemies.get(entity) {
+            return Some(SpawnerView {
+                kind: SpawnerKind::Enemy,
+                name: &spawner.name,
+                default_model: Some(&spawner.model),
+                queue: &state.queue,
+                index: state.index,
+                spawned: &state.spawned,
+            });
+        }
+        if let Ok((spawner, state)) = self.bodies.get(entity) {
+            return Some(SpawnerView {
+                kind: SpawnerKind::Body,
+                name: &spawner.name,
+                default_model: None,
+                queue: &state.queue,
+                index: state.index,
+                spawned: &state.spawned,
+            });
+        }
+        None
+    }
+
+    fn clear_spawned(&mut self, entity: Entity) {
+        if let Ok((_, mut state)) = self.npcs.get_mut(entity) {
+            state.spawned.clear();
+        } else if let Ok((_, mut state)) = self.enemies.get_mut(entity) {
+            state.spawned.clear();
+        } else if let Ok((_, mut state)) = self.bodies.get_mut(entity) {
+            state.spawned.clear();
+        }
+    }
+}
+
+fn look_at_spawner(
+    debug_state: Res<DebugState>,
+    player: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    spatial_query: SpatialQuery,
+    mut inspected: ResMut<InspectedSpawner>,
+) {
+    let hit = match (*debug_state == DebugState::Spawners, player) {
+        (true, Some(player)) => {
+            let camera_transform = player.compute_transform();
+            spatial_query
+                .cast_ray(
+                    camera_transform.translation,
+                    camera_transform.forward(),
+                    INSPECT_DISTANCE,
+                    true,
+                    &SpatialQueryFilter::from_mask(CollisionLayer::Debug),
+                )
+                .map(|hit| hit.entity)
+        }
+        _ => None,
+    };
+
+    if inspected.entity != hit {
+        inspected.entity = hit;
+    }
+}
+
+fn spawner_panel_keys(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut inspected: ResMut<InspectedSpawner>,
+    mut spawners: Spawners,
+    registry: Res<NpcRegistry>,
+) {
+    let Some(entity) = inspected.entity else {
+        return;
+    };
+    let models = sorted_models(&registry);
+
+    if keys.just_pressed(KeyCode::Numpad3) && !models.is_empty() {
+        inspected.selected_model = (inspected.selected_model + 1) % models.len();
+    }
+    if keys.just_pressed(KeyCode::Numpad4) {
+        spawners.clear_spawned(entity);
+    }
+
+    let Some(view) = spawners.view(entity) else {
+        return;
+    };
+    let spawner_name = view.name.to_string();
+
+    if keys.just_pressed(KeyCode::Numpad1) {
+        match view.kind {
+            SpawnerKind::Npc => commands.trigger(SpawnNpc::Queue {
+                spawner_name,
+                overrides: default(),
+            }),
+            SpawnerKind::Enemy => commands.trigger(SpawnEnemy::Queue {
+                spawner_name,
+                alerted: false,
+            }),
+            SpawnerKind::Body => commands.trigger(SpawnBody::Queue { spawner_name }),
+        }
+    } else if keys.just_pressed(KeyCode::Numpad2) {
+        let Some(model) = models.get(inspected.selected_model) else {
+            return;
+        };
+        let model = model.to_string();
+        match view.kind {
+            SpawnerKind::Npc => commands.trigger(SpawnNpc::Direct {
+                spawner_name,
+                model,
+                overrides: default(),
+            }),
+            SpawnerKind::Enemy => commands.trigger(SpawnEnemy::Direct {
+                spawner_name,
+                model,
+                alerted: false,
+            }),
+            SpawnerKind::Body => commands.trigger(SpawnBody::Direct {
+                spawner_name,
+                npc_name: model,
+            }),
+        }
+    }
+}
+
+fn sorted_models(registry: &NpcRegistry) -> Vec<&str> {
+    let mut models: Vec<&str> = registry.prefabs.keys().map(String::as_str).collect();
+    models.sort_unstable();
+    models
+}
+
+fn update_spawner_panel(
+    mut commands: Commands,
+    inspected: Res<InspectedSpawner>,
+    spawners: Spawners,
+    registry: Res<NpcRegistry>,
+    transforms: Query<&GlobalTransform>,
+    dead: Query<(), With<NpcDead>>,
+    player: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    camera: Option<Single<(&Camera, &GlobalTransform), With<WorldModelCamera>>>,
+    mut panel: Option<Single<(Entity, &mut Node, &mut SpawnerPanelLines), With<SpawnerPanel>>>,
+    font: Res<GameFont>,
+) {
+    let view = inspected.entity.and_then(|entity| spawners.view(entity));
+    let (Some(entity), Some(view), Some(player), Some(camera)) =
+        (inspected.entity, view, player, camera)
+    else {
+        if let Some(panel) = panel {
+            commands.entity(panel.0).despawn();
+        }
+        return;
+    };
+
+    let player_pos = player.translation();
+    let mut lines = vec![(
+        format!("{} '{}'", view.kind.label(), view.name),
+        Color::WHITE,
+    )];
+
+    if let Some(model) = view.default_model {
+        lines.push((format!("default model: {model}"), MUTED_COLOR));
+    }
+    if view.queue.is_empty() {
+        lines.push(("queue: (empty)".to_string(), MUTED_COLOR));
+    } else {
+        lines.push(("queue:".to_string(), MUTED_COLOR));
+        for (i, model) in view.queue.iter().enumerate() {
+            if i == view.index {
+                lines.push((format!("> {i}: {model}"), HIGHLIGHT_COLOR));
+            } else {
+                lines.push((format!("  {i}: {model}"), Color::WHITE));
+            }
+        }
+    }
+
+    lines.push((format!("spawned ({}):", view.spawned.len()), MUTED_COLOR));
+    for (spawned, model) in view.spawned {
+        let line = match transforms.get(*spawned) {
+            Ok(transform) => {
+                let distance = transform.translation().distance(player_pos);
+                if dead.contains(*spawned) {
+                    (
+                        format!("  {model} {spawned}: dead, {distance:.1}m"),
+                        DEAD_COLOR,
+                    )
+                } else {
+                    (
+                        format!("  {model} {spawned}: alive, {distance:.1}m"),
+                        ALIVE_COLOR,
+                    )
+                }
+            }
+            Err(_) => (format!("  {model} {spawned}: despawned"), MUTED_COLOR),
+        };
+        lines.push(line);
+    }
+
+    let models = sorted_models(&registry);
+    let selected = models
+        .get(inspected.selected_model)
+        .copied()
+        .unwrap_or("none");
+    lines.push((
+        format!("[1] queue spawn  [2] spawn {selected}  [3] next model  [4] clear spawned"),
+        MUTED_COLOR,
+    ));
+
+    // Float the panel next to the spawner, falling back to the corner if it's off screen.
+    let (camera, camera_transform) = *camera;
+    let anchor = transforms
+        .get(entity)
+        .ok()
+        .and_then(|t| {
+            camera
+                .world_to_viewport(camera_transform, t.translation())
+                .ok()
+        })
+        .unwrap_or(Vec2::splat(16.0));
+    let lines = SpawnerPanelLines(lines);
+
+    match panel.as_deref_mut() {
+        Some((panel, node, current)) => {
+            node.left = Val::Px(anchor.x + 16.0);
+            node.top = Val::Px(anchor.y);
+            if **current != lines {
+                commands.entity(*panel).despawn_related::<Children>();
+                spawn_lines(&mut commands, *panel, &lines, &font);
+                **current = lines;
+            }
+        }
+        None => {
+            let panel = commands
+                .spawn((
+                    Name::new("Spawner Panel"),
+                    SpawnerPanel,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(anchor.x + 16.0),
+                        top: Val::Px(anchor.y),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(8.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+                    Pickable::IGNORE,
+                    DespawnOnExit(Screen::Gameplay),
+                ))
+                .id();
+            spawn_lines(&mut commands, panel, &lines, &font);
+            commands.entity(panel).insert(lines);
+        }
+    }
+}
+
+fn spawn_lines(commands: &mut Commands, panel: Entity, lines: &SpawnerPanelLines, font: &GameFont) {
+    for (line, color) in &lines.0 {
+        commands.spawn((
+            Text::new(line.clone()),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(*color),
+            ChildOf(panel),
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+//! Explosive barrels that blow up when shot, hurting anything nearby and setting off other barrels.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource,
+    game_running,
+    gameplay::{
+        explosion::Explosion,
+        npc::{Health, shooting::Faction},
+    },
+    third_party::avian3d::CollisionLayer,
+};
+
+/// Queue key that makes an `EnemySpawner` place a barrel instead of an enemy.
+pub(crate) const BARREL_SPAWN_KEY: &str = "barrel";
+
+const BARREL_HEALTH: f32 = 30.0;
+const BARREL_RADIUS: f32 = 0.4;
+const BARREL_HEIGHT: f32 = 1.2;
+/// Barrels aren't on anyone's side, so every faction can set them off.
+const BARREL_FACTION: &str = "prop";
+/// Delay between running out of health and exploding, so chain reactions ripple outwards.
+const FUSE_SECONDS: f32 = 0.15;
+const BLAST_RADIUS: f32 = 4.0;
+/// Damage right at the barrel, falling off linearly to nothing at `BLAST_RADIUS`.
+const BLAST_DAMAGE: f32 = 60.0;
+/// Speed things are thrown away from the blast at point blank.
+const BLAST_KNOCKBACK: f32 = 12.0;
+const CRATER_RADIUS: f32 = 2.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<BarrelAssets>();
+    app.add_observer(setup_barrel);
+    app.add_systems(
+        Update,
+        (light_barrel_fuses, detonate_barrels)
+            .chain()
+            .run_if(game_running),
+    );
+}
+
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct ExplosiveBarrel;
+
+/// Counting down to the explosion, so chain reactions go off one after another.
+#[derive(Component)]
+struct BarrelFuse(Timer);
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct BarrelAssets {
+    body: Handle<Mesh>,
+    stripe: Handle<Mesh>,
+    paint: Handle<StandardMaterial>,
+    warning: Handle<StandardMaterial>,
+}
+
+impl FromWorld for BarrelAssets {
+    fn from_world(world: &mut World) -> Self {
+        let (body, stripe) = {
+            let mut meshes = world.resource_mut::<Assets<Mesh>>();
+            (
+                meshes.add(Cylinder::new(BARREL_RADIUS, BARREL_HEIGHT)),
+                meshes.add(Cylinder::new(BARREL_RADIUS * 1.02, BARREL_HEIGHT * 0.15)),
+            )
+        };
+
+        let (paint, warning) = {
+            let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+            (
+                materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.7, 0.08, 0.05),
+                    perceptual_roughness: 0.6,
+                    ..default()
+                }),
+                materials.add(StandardMaterial {
+                    base_color: Color::srgb(1.0, 0.8, 0.1),
+                    emissive: LinearRgba::rgb(4.0, 2.5, 0.2),
+                    ..default()
+                }),
+            )
+        };
+
+        Self {
+            body,
+            stripe,
+            paint,
+            warning,
+        }
+    }
+}
+
+fn setup_barrel(add: On<Add, ExplosiveBarrel>, mut commands: Commands, assets: Res<BarrelAssets>) {
+    commands.entity(add.entity).insert((
+        Name::new("Explosive Barrel"),
+        Health(BARREL_HEALTH),
+        Faction(BARREL_FACTION.to_string()),
+        RigidBody::Dynamic,
+        Collider::cylinder(BARREL_RADIUS, BARREL_HEIGHT),
+        CollisionLayers::new(CollisionLayer::Prop, LayerMask::ALL),
+        Mesh3d(assets.body.clone()),
+        MeshMaterial3d(assets.paint.clone()),
+        children![(
+            Name::new("Barrel Stripe"),
+            Mesh3d(assets.stripe.clone()),
+            MeshMaterial3d(assets.warning.clone()),
+            Transform::from_xyz(0.0, BARREL_HEIGHT * 0.2, 0.0),
+        )],
+    ));
+}
+
+fn light_barrel_fuses(
+    mut commands: Commands,
+    barrels: Query<
+        (Entity, &Health),
+        (With<ExplosiveBarrel>, Without<BarrelFuse>, Changed<Health>),
+    >,
+) {
+    for (entity, health) in &barrels {
+        if health.0 <= 0.0 {
+            commands
+                .entity(entity)
+                .insert(BarrelFuse(Timer::from_seconds(
+                    FUSE_SECONDS,
+                    TimerMode::Once,
+                )));
+        }
+    }
+}
+
+fn detonate_barrels(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut fuses: Query<(Entity, &GlobalTransform, &mut BarrelFuse)>,
+) {
+    for (entity, transform, mut fuse) in &mut fuses {
+        fuse.0.tick(time.delta());
+        if fuse.0.is_finished() {
+            commands.trigger(Explosion {
+                position: transform.translation(),
+                radius: BLAST_RADIUS,
+                damage: BLAST_DAMAGE,
+                carve_radius: CRATER_RADIUS,
+                impulse: BLAST_KNOCKBACK,
+                faction: None,
+                source: Some(entity),
+            });
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Treasure buried in voxel volumes. A buried item stays hidden until enough of the dirt around
+//! it is dug away, then rises out of the ground and is collected by walking over it, paying out
+//! crusts, an item or both.
+
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::{
+    game_running,
+    gameplay::{
+        crusts::{Crusts, CrustsRewarded},
+        dig::{VOXEL_SIZE, VoxelSim, VoxelWorldBounds, world_to_voxel},
+        inventory::{InventoryAssets, Item},
+        loadout::GiveItem,
+        player::Player,
+    },
+};
+
+/// Seconds a revealed item takes to rise out of the dirt.
+const RISE_SECONDS: f32 = 0.6;
+/// How far below where it was buried the item starts rising from.
+const RISE_DEPTH: f32 = 0.3;
+/// Risen items this close to the player are collected.
+const COLLECT_RADIUS: f32 = 1.2;
+const TREASURE_SIZE: f32 = 0.3;
+const SPIN_SPEED: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<BuriedItemAssets>();
+    app.add_observer(setup_buried_item);
+    app.add_systems(
+        Update,
+        (
+            link_buried_items,
+            reveal_buried_items,
+            raise_unearthed_items,
+            collect_unearthed_items,
+        )
+            .chain()
+            .run_if(game_running),
+    );
+}
+
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct BuriedItem {
+    /// Item given on pickup, by the same names as `starting_loadout`. Empty = none.
+    pub item: String,
+    /// Crusts given on pickup.
+    pub crusts: u32,
+    /// Half the size of the box around the item that has to be dug out, in world units.
+    pub reveal_radius: f32,
+    /// Fraction of that box that has to be air before the item shows.
+    pub reveal_air_ratio: f32,
+}
+
+impl Default for BuriedItem {
+    fn default() -> Self {
+        Self {
+            item: String::new(),
+            crusts: 5,
+            reveal_radius: 0.75,
+            reveal_air_ratio: 0.5,
+        }
+    }
+}
+
+/// The voxel volume an item is buried in.
+#[derive(Component)]
+struct BuriedIn(Entity);
+
+/// Dug up and rising out of the ground, collectible once `rise` finishes.
+#[derive(Component)]
+struct Unearthed {
+    rise: Timer,
+    /// Where the item was buried.
+    rest: Vec3,
+}
+
+#[derive(Resource)]
+struct BuriedItemAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for BuriedItemAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(TREASURE_SIZE));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(0.9, 0.75, 0.3),
+                emissive: LinearRgba::rgb(0.6, 0.45, 0.1),
+                metallic: 0.8,
+                ..default()
+            });
+        Self { mesh, material }
+    }
+}
+
+fn setup_buried_item(
+    add: On<Add, BuriedItem>,
+    mut commands: Commands,
+    items: Query<&BuriedItem>,
+    assets: Res<BuriedItemAssets>,
+    inventory_assets: Res<InventoryAssets>,
+) {
+    let Ok(buried) = items.get(add.entity) else {
+        return;
+    };
+    let key = buried.item.trim();
+    let item = Item::from_key(key);
+    if item.is_none() && !key.is_empty() {
+        warn!("Buried item has an unknown item {key:?}, it'll only give crusts");
+    }
+
+    let mut entity_commands = commands.entity(add.entity);
+    entity_commands.insert((Name::new("Buried Item"), Visibility::Hidden));
+    // Tools show as themselves, everything else as a lump of treasure.
+    match item.and_then(|item| inventory_assets.world_model(&item)) {
+        Some((scene, scale)) => {
+            entity_commands
+                .with_child((SceneRoot(scene), Transform::from_scale(Vec3::splat(scale))));
+        }
+        None => {
+            entity_commands.with_child((
+                Mesh3d(assets.mesh.clone()),
+                MeshMaterial3d(assets.material.clone()),
+            ));
+        }
+    }
+}
+
+fn link_buried_items(
+    mut commands: Commands,
+    unlinked: Query<(Entity, &GlobalTransform), (With<BuriedItem>, Without<BuriedIn>)>,
+    volumes: Query<(Entity, &VoxelWorldBounds)>,
+) {
+    for (entity, transform) in &unlinked {
+        let position = transform.translation();
+        let volume = volumes.iter().find(|(_, bounds)| {
+            position.cmpge(bounds.min).all() && position.cmple(bounds.max).all()
+        });
+        if let Some((volume, _)) = volume {
+            commands.entity(entity).insert(BuriedIn(volume));
+        }
+    }
+}
+
+fn reveal_buried_items(
+    mut commands: Commands,
+    items: Query<
+        (
+            Entity,
+            &BuriedItem,
+            Ref<BuriedIn>,
+            &Transform,
+            &GlobalTransform,
+        ),
+        Without<Unearthed>,
+    >,
+    sims: Query<(Ref<VoxelSim>, &GlobalTransform)>,
+) {
+    for (entity, buried, buried_in, transform, global_transform) in &items {
+        let Ok((sim, sim_transform)) = sims.get(buried_in.0) else {
+            continue;
+        };
+        // Nothing to recheck until the player digs or the sim moves some dirt.
+        if !sim.is_changed() && !buried_in.is_added() {
+            continue;
+        }
+        let center = world_to_voxel(sim_transform, global_transform.translation());
+        let reach = IVec3::splat((buried.reveal_radius / VOXEL_SIZE).ceil() as i32);
+        if sim.air_ratio_in(center - reach, center + reach) < buried.reveal_air_ratio {
+            continue;
+        }
+        commands.entity(entity).insert((
+            Unearthed {
+                rise: Timer::from_seconds(RISE_SECONDS, TimerMode::Once),
+                rest: transform.translation,
+            },
+            Visibility::Inherited,
+        ));
+    }
+}
+
+fn raise_unearthed_items(time: Res<Time>, mut items: Query<(&mut Transform, &mut Unearthed)>) {
+    for (mut transform, mut unearthed) in &mut items {
+        unearthed.rise.tick(time.delta());
+        // Eases out, slowing as it reaches the surface.
+        let t = 1.0 - (1.0 - unearthed.rise.fraction()).powi(2);
+        transform.translation = unearthed.rest - Vec3::Y * RISE_DEPTH * (1.0 - t);
+        transform.scale = Vec3::splat(t.max(0.01));
+        transform.rotate_y(SPIN_SPEED * time.delta_secs());
+    }
+}
+
+fn collect_unearthed_items(
+    mut commands: Commands,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    items: Query<(Entity, &BuriedItem, &Unearthed, &GlobalTransform)>,
+    mut crusts: ResMut<Crusts>,
+) {
+    let Some(player) = player else {
+        return;
+    };
+    let target = player.translation();
+    for (entity, buried, unearthed, transform) in &items {
+        if !unearthed.rise.is_finished()
+            || transform.translation().distance(target) > COLLECT_RADIUS
+        {
+            continue;
+        }
+        if !buried.item.trim().is_empty() {
+            commands.trigger(GiveItem(buried.item.clone()));
+        }
+        if buried.crusts > 0 {
+            crusts.add(buried.crusts);
+            commands.trigger(CrustsRewarded(buried.crusts));
+        }
+        commands.entity(entity).despawn();
+    }
+}
@@ -6,9 +6,11 @@ use bevy_enhanced_input::prelude::*;
 use bevy_trenchbroom::prelude::*;
 
 use crate::{
-    PostPhysicsAppSystems,
+    PostPhysicsAppSystems, game_running,
     gameplay::{
         crosshair::CrosshairState,
+        highlight,
+        mover::MovePlatform,
         player::{camera::PlayerCamera, input::Interact},
     },
     screens::Screen,
@@ -35,7 +37,7 @@ pub fn plugin(app: &mut App) {
             check_looking_at_button
                 .run_if(in_state(Screen::Gameplay))
                 .in_set(PostPhysicsAppSystems::ChangeUi),
-            animate_button_press,
+            animate_button_press.run_if(game_running),
         ),
     );
 }
@@ -134,6 +136,7 @@ impl Default for Button {
 struct LookedAtButton(Option<Entity>);
 
 fn check_looking_at_button(
+    mut commands: Commands,
     player: Single<&GlobalTransform, With<PlayerCamera>>,
     spatial_query: SpatialQuery,
     buttons: Query<(), With<Button>>,
@@ -151,18 +154,19 @@ fn check_looking_at_button(
         &SpatialQueryFilter::from_mask(CollisionLayer::Prop),
     ) {
         if buttons.get(hit.entity).is_ok() {
-            looked_at.0 = Some(hit.entity);
+            highlight::look_at(&mut commands, &mut looked_at.0, Some(hit.entity));
             crosshair.wants_square.insert(system_id);
             return;
         }
     }
 
-    looked_at.0 = None;
+    highlight::look_at(&mut commands, &mut looked_at.0, None);
     crosshair.wants_square.remove(&system_id);
 }
 
 fn interact_with_button(
     _on: On<Start<Interact>>,
+    mut commands: Commands,
     looked_at: Res<LookedAtButton>,
     buttons: Query<&Button>,
     children: Query<&Children>,
@@ -186,6 +190,10 @@ fn interact_with_button(
         return;
     }
     info!("Button pressed: trigger '{}'", button.trigger);
+    if let Some(move_platform) = MovePlatform::parse(&button.trigger) {
+        commands.trigger(move_platform);
+        return;
+    }
     // TODO: parse button.trigger into ScenarioTrigger
 }
 
@@ -0,0 +1,377 @@
+//! Sound captions, for players who can't hear the game. Important sounds trigger a
+//! [`CaptionEvent`] where they're spawned, and the last few show as text at the bottom of the
+//! screen with an arrow toward where the sound came from. Off unless turned on in the settings.
+
+use bevy::prelude::*;
+
+use super::{hud::HudVisibility, npc::shooting::EnemyAlert, player::camera::PlayerCamera};
+use crate::{PostPhysicsAppSystems, screens::Screen, theme::GameFont};
+
+/// Captions on screen at once.
+const MAX_CAPTIONS: usize = 3;
+const CAPTION_SECONDS: f32 = 2.5;
+const CAPTION_FADE_SECONDS: f32 = 0.5;
+/// Space kept under the captions for the inventory.
+const CAPTION_BOTTOM: Val = Val::Percent(18.0);
+/// Where the captions move during a conversation, clear of the dialogue box and just under
+/// the top letterbox bar.
+const DIALOGUE_CAPTION_TOP: Val = Val::Vh(12.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CaptionSettings>();
+    app.init_resource::<ActiveCaptions>();
+    app.add_observer(queue_caption);
+    app.add_observer(caption_enemy_alert);
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_caption_panel);
+    app.add_systems(
+        Update,
+        (tick_captions, update_caption_panel)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct CaptionSettings {
+    pub enabled: bool,
+}
+
+/// The sounds that get a caption.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub(crate) enum Caption {
+    Gunfire,
+    EnemyAlerted,
+    Explosion,
+    Collapse,
+    GraveReward,
+}
+
+impl Caption {
+    /// Caption text, kept in one table so it can be translated later.
+    pub(crate) fn text(self) -> &'static str {
+        match self {
+            Self::Gunfire => "Gunfire",
+            Self::EnemyAlerted => "Enemy shouts",
+            Self::Explosion => "Explosion",
+            Self::Collapse => "Earth collapsing",
+            Self::GraveReward => "Reward chime",
+        }
+    }
+
+    /// With the screen full, a caption pushes out one of equal or lower priority.
+    pub(crate) fn priority(self) -> u8 {
+        match self {
+            Self::GraveReward => 0,
+            Self::Collapse => 1,
+            Self::Gunfire => 2,
+            Self::EnemyAlerted | Self::Explosion => 3,
+        }
+    }
+}
+
+/// Captions a sound as it starts playing. `position` is where it comes from in the world,
+/// `None` for sounds that aren't in any one place.
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct CaptionEvent {
+    pub caption: Caption,
+    pub position: Option<Vec3>,
+}
+
+impl CaptionEvent {
+    pub(crate) fn at(caption: Caption, position: Vec3) -> Self {
+        Self {
+            caption,
+            position: Some(position),
+        }
+    }
+
+    pub(crate) fn everywhere(caption: Caption) -> Self {
+        Self {
+            caption,
+            position: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ActiveCaption {
+    caption: Caption,
+    position: Option<Vec3>,
+    timer: Timer,
+}
+
+/// Captions on screen, oldest first.
+#[derive(Resource, Default, Debug)]
+struct ActiveCaptions(Vec<ActiveCaption>);
+
+impl ActiveCaptions {
+    /// Shows the caption, or refreshes it if it's already up so repeated gunfire doesn't fill
+    /// the screen. Returns whether it made it on screen.
+    fn push(&mut self, caption: Caption, position: Option<Vec3>) -> bool {
+        let timer = Timer::from_seconds(CAPTION_SECONDS, TimerMode::Once);
+        if let Some(active) = self.0.iter_mut().find(|active| active.caption == caption) {
+            active.position = position;
+            active.timer = timer;
+            return true;
+        }
+        if self.0.len() >= MAX_CAPTIONS {
+            // The oldest of the lowest priority goes first.
+            let Some((index, lowest)) = self
+                .0
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, active)| active.caption.priority())
+            else {
+                return false;
+            };
+            if lowest.caption.priority() > caption.priority() {
+                return false;
+            }
+            self.0.remove(index);
+        }
+        self.0.push(ActiveCaption {
+            caption,
+            position,
+            timer,
+        });
+        true
+    }
+
+    fn tick(&mut self, delta: std::time::Duration) {
+        for active in &mut self.0 {
+            active.timer.tick(delta);
+        }
+        self.0.retain(|active| !active.timer.is_finished());
+    }
+}
+
+/// Which way a sound is from where the camera faces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SoundDirection {
+    Ahead,
+    Left,
+    Right,
+    Behind,
+}
+
+impl SoundDirection {
+    /// Only the yaw matters, a sound above or below reads as ahead of or behind the player.
+    fn from_camera(camera: &Transform, position: Vec3) -> Self {
+        let offset = camera.rotation.inverse() * (position - camera.translation);
+        // 0 straight ahead, positive to the right.
+        let angle = offset.x.atan2(-offset.z).to_degrees();
+        match angle {
+            a if a.abs() <= 45.0 => Self::Ahead,
+            a if a.abs() >= 135.0 => Self::Behind,
+            a if a > 0.0 => Self::Right,
+            _ => Self::Left,
+        }
+    }
+
+    fn label(self, text: &str) -> String {
+        match self {
+            Self::Ahead => text.to_string(),
+            Self::Left => format!("< {text}"),
+            Self::Right => format!("{text} >"),
+            Self::Behind => format!("v {text} v"),
+        }
+    }
+}
+
+fn queue_caption(
+    event: On<CaptionEvent>,
+    settings: Res<CaptionSettings>,
+    mut captions: ResMut<ActiveCaptions>,
+) {
+    if !settings.enabled {
+        return;
+    }
+    if !captions.push(event.caption, event.position) {
+        debug!(
+            "{:?} caption dropped for higher priority ones",
+            event.caption
+        );
+    }
+}
+
+/// Enemies don't have an alert bark of their own yet, so the caption goes on the alert itself.
+fn caption_enemy_alert(
+    add: On<Add, EnemyAlert>,
+    mut commands: Commands,
+    enemies: Query<&GlobalTransform>,
+) {
+    if let Ok(transform) = enemies.get(add.entity) {
+        commands.trigger(CaptionEvent::at(
+            Caption::EnemyAlerted,
+            transform.translation(),
+        ));
+    }
+}
+
+#[derive(Component)]
+struct CaptionPanel;
+
+/// One line of the panel, showing the caption at this index of [`ActiveCaptions`].
+#[derive(Component)]
+struct CaptionLine(usize);
+
+fn spawn_caption_panel(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    mut captions: ResMut<ActiveCaptions>,
+) {
+    captions.0.clear();
+    let panel = commands
+        .spawn((
+            Name::new("Caption Panel"),
+            CaptionPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: CAPTION_BOTTOM,
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .id();
+    for index in 0..MAX_CAPTIONS {
+        commands.entity(panel).with_child((
+            CaptionLine(index),
+            Node {
+                display: Display::None,
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                ..default()
+            },
+            Text::default(),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextShadow::default(),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+fn tick_captions(
+    time: Res<Time>,
+    settings: Res<CaptionSettings>,
+    mut captions: ResMut<ActiveCaptions>,
+) {
+    if !settings.enabled {
+        captions.0.clear();
+        return;
+    }
+    captions.tick(time.delta());
+}
+
+fn update_caption_panel(
+    captions: Res<ActiveCaptions>,
+    hud: Res<HudVisibility>,
+    camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    panel: Single<(&mut Node, &mut Visibility), With<CaptionPanel>>,
+    mut lines: Query<
+        (
+            &CaptionLine,
+            &mut Node,
+            &mut Text,
+            &mut TextColor,
+            &mut BackgroundColor,
+        ),
+        Without<CaptionPanel>,
+    >,
+) {
+    let (mut panel_node, mut visibility) = panel.into_inner();
+    visibility.set_if_neq(if captions.0.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    });
+    let (top, bottom) = if hud.in_dialogue {
+        (DIALOGUE_CAPTION_TOP, Val::Auto)
+    } else {
+        (Val::Auto, CAPTION_BOTTOM)
+    };
+    if panel_node.top != top || panel_node.bottom != bottom {
+        panel_node.top = top;
+        panel_node.bottom = bottom;
+    }
+
+    let camera = camera.map(|camera| camera.compute_transform());
+    for (line, mut node, mut text, mut color, mut background) in &mut lines {
+        let Some(active) = captions.0.get(line.0) else {
+            if node.display != Display::None {
+                node.display = Display::None;
+            }
+            continue;
+        };
+        if node.display != Display::Flex {
+            node.display = Display::Flex;
+        }
+        let direction = match (active.position, camera) {
+            (Some(position), Some(camera)) => SoundDirection::from_camera(&camera, position),
+            _ => SoundDirection::Ahead,
+        };
+        let label = direction.label(active.caption.text());
+        if text.0 != label {
+            text.0 = label;
+        }
+        let alpha = (active.timer.remaining_secs() / CAPTION_FADE_SECONDS).min(1.0);
+        color.0 = color.0.with_alpha(alpha);
+        background.0 = background.0.with_alpha(0.6 * alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_screen_evicts_the_oldest_lowest_priority() {
+        let mut captions = ActiveCaptions::default();
+        assert!(captions.push(Caption::Collapse, None));
+        assert!(captions.push(Caption::GraveReward, None));
+        assert!(captions.push(Caption::Gunfire, None));
+        // Already up, so just refreshed.
+        assert!(captions.push(Caption::Collapse, None));
+        assert_eq!(captions.0.len(), 3);
+
+        assert!(captions.push(Caption::Explosion, None));
+        let shown: Vec<_> = captions.0.iter().map(|active| active.caption).collect();
+        assert_eq!(
+            shown,
+            [Caption::Collapse, Caption::Gunfire, Caption::Explosion]
+        );
+
+        // Nothing lower than it left to push out.
+        captions.push(Caption::EnemyAlerted, None);
+        assert!(!captions.push(Caption::GraveReward, None));
+    }
+
+    #[test]
+    fn direction_follows_camera_yaw() {
+        let camera = Transform::from_xyz(0.0, 2.0, 0.0).looking_to(Vec3::NEG_Z, Vec3::Y);
+        let direction = |x, z| SoundDirection::from_camera(&camera, Vec3::new(x, 0.0, z));
+        assert_eq!(direction(0.0, -5.0), SoundDirection::Ahead);
+        assert_eq!(direction(5.0, 0.0), SoundDirection::Right);
+        assert_eq!(direction(-5.0, -1.0), SoundDirection::Left);
+        assert_eq!(direction(1.0, 5.0), SoundDirection::Behind);
+
+        let turned = camera.looking_to(Vec3::X, Vec3::Y);
+        assert_eq!(
+            SoundDirection::from_camera(&turned, Vec3::new(0.0, 0.0, 5.0)),
+            SoundDirection::Right
+        );
+    }
+}
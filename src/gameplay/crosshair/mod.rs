@@ -2,12 +2,24 @@
 //! The crosshair is a UI element that is used to indicate the player's aim. We change the crosshair when the player is looking at a prop or an NPC.
 //! This is done by registering which systems are interested in the crosshair state.
 
-use crate::{PostPhysicsAppSystems, screens::Screen};
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::{
+        dig::VOXEL_SIZE,
+        hud::{HudAnchor, HudSettings, HudSlot},
+        inventory::{DigMode, Inventory, Item},
+        player::camera::{PlayerCamera, WorldModelCamera},
+    },
+    screens::Screen,
+    theme::GameFont,
+    third_party::avian3d::CollisionLayer,
+};
 use assets::{CROSSHAIR_DOT_PATH, CROSSHAIR_SQUARE_PATH};
+use avian3d::prelude::*;
 use bevy::{
     platform::collections::HashSet,
     prelude::*,
-    window::{CursorGrabMode, CursorOptions},
+    window::{CursorGrabMode, CursorOptions, PrimaryWindow, WindowResized},
 };
 
 use std::any::{Any as _, TypeId};
@@ -19,24 +31,33 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         update_crosshair.in_set(PostPhysicsAppSystems::ChangeUi),
     );
+    app.add_systems(
+        Update,
+        size_crosshair_to_tool
+            .in_set(PostPhysicsAppSystems::ChangeUi)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        Update,
+        update_dig_mode_indicator.run_if(resource_changed::<Inventory>),
+    );
+    app.add_systems(Update, regrab_cursor_after_resize);
     app.add_systems(OnEnter(Screen::Gameplay), spawn_crosshair);
 
     app.add_plugins(assets::plugin);
 }
 
 /// Show a crosshair for better aiming
-fn spawn_crosshair(mut commands: Commands, assets: Res<AssetServer>) {
+fn spawn_crosshair(mut commands: Commands, assets: Res<AssetServer>, font: Res<GameFont>) {
     commands
         .spawn((
             Name::new("Crosshair"),
+            HudSlot::new(HudAnchor::Center, 0),
             Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
             },
-            DespawnOnExit(Screen::Gameplay),
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -44,9 +65,51 @@ fn spawn_crosshair(mut commands: Commands, assets: Res<AssetServer>) {
                 CrosshairState::default(),
                 ImageNode::new(assets.load(CROSSHAIR_DOT_PATH)),
             ));
+            parent.spawn((
+                Name::new("Dig Mode Indicator"),
+                DigModeIndicator,
+                Text::new("Flat"),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgba(1.0, 1.0, 1.0, 0.8)),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(50.0),
+                    top: Val::Percent(50.0),
+                    margin: UiRect {
+                        left: Val::Px(16.0),
+                        top: Val::Px(8.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Visibility::Hidden,
+            ));
         });
 }
 
+/// Shown next to the crosshair while the held shovel or bucket is in flat mode.
+#[derive(Component)]
+struct DigModeIndicator;
+
+fn update_dig_mode_indicator(
+    inventory: Res<Inventory>,
+    mut indicator: Single<&mut Visibility, With<DigModeIndicator>>,
+) {
+    let flat = matches!(
+        inventory.active_item(),
+        Some(Item::Shovel(stats) | Item::DirtBucket(stats)) if stats.mode == DigMode::Flat
+    );
+    **indicator = if flat {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
 #[derive(Component, Clone, Default, Reflect)]
 #[reflect(Component, Default)]
 pub(crate) struct CrosshairState {
@@ -99,3 +162,73 @@ fn update_crosshair(
         *visibility = Visibility::Hidden;
     }
 }
+
+/// Some platforms drop the cursor grab when the window changes size or goes fullscreen, so the
+/// current options are applied again after every resize.
+fn regrab_cursor_after_resize(
+    mut resized: MessageReader<WindowResized>,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    if resized.read().count() > 0 {
+        cursor_options.set_changed();
+    }
+}
+
+/// Smallest the crosshair gets, in logical pixels.
+const CROSSHAIR_MIN_SIZE: f32 = 8.0;
+
+/// Size the crosshair to the dig/fill radius where we're aiming. The gun is dead accurate, so it
+/// keeps the plain crosshair.
+fn size_crosshair_to_tool(
+    mut crosshair: Single<&mut Node, With<CrosshairState>>,
+    inventory: Res<Inventory>,
+    player: Single<&GlobalTransform, With<PlayerCamera>>,
+    camera: Single<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    spatial_query: SpatialQuery,
+    hud_settings: Res<HudSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+) {
+    let aim = player.compute_transform();
+    let forward = aim.forward();
+    let (point, world_radius) = match inventory.active_item() {
+        Some(Item::Shovel(stats) | Item::DirtBucket(stats)) => {
+            let distance = spatial_query
+                .cast_ray(
+                    aim.translation,
+                    forward,
+                    stats.distance,
+                    true,
+                    &SpatialQueryFilter::from_mask(CollisionLayer::Level),
+                )
+                .map_or(stats.distance, |hit| hit.distance);
+            (
+                aim.translation + forward * distance,
+                stats.radius * VOXEL_SIZE,
+            )
+        }
+        Some(Item::Gun(_) | Item::Consumable { .. }) | None => {
+            if crosshair.width != Val::Auto {
+                crosshair.width = Val::Auto;
+                crosshair.height = Val::Auto;
+            }
+            return;
+        }
+    };
+
+    let (camera, camera_transform) = *camera;
+    let edge = point + aim.right() * world_radius;
+    let (Ok(center), Ok(edge)) = (
+        camera.world_to_viewport(camera_transform, point),
+        camera.world_to_viewport(camera_transform, edge),
+    ) else {
+        return;
+    };
+
+    // The HUD scale applies on top, so undo it to match the world.
+    let scale = hud_settings.scale_for(&window);
+    let size = Val::Px((center.distance(edge) * 2.0 / scale).max(CROSSHAIR_MIN_SIZE));
+    if crosshair.width != size {
+        crosshair.width = size;
+        crosshair.height = size;
+    }
+}
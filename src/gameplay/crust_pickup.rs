@@ -0,0 +1,131 @@
+//! Crusts dropped in the world that get pulled toward the player and collected.
+
+use bevy::prelude::*;
+
+use crate::{
+    game_running,
+    gameplay::{
+        crusts::{Crusts, CrustsRewarded},
+        player::Player,
+    },
+    screens::Screen,
+};
+
+/// Magnet radius before any upgrades.
+pub(crate) const DEFAULT_MAGNET_RADIUS: f32 = 2.0;
+/// Pickups this close to the player are collected.
+const COLLECT_RADIUS: f32 = 0.6;
+/// How quickly a pickup inside the magnet radius closes the gap to the player.
+const MAGNET_SHARPNESS: f32 = 6.0;
+const PICKUP_SIZE: f32 = 0.25;
+const PICKUP_SPIN_SPEED: f32 = 2.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CrustPickupAssets>();
+    app.add_observer(add_auto_pickup);
+    app.add_observer(spawn_crust_pickup);
+    app.add_systems(
+        Update,
+        (spin_crust_pickups, attract_crust_pickups)
+            .chain()
+            .run_if(game_running),
+    );
+}
+
+/// Pulls crust pickups within `radius` toward the player. Upgraded at the store.
+#[derive(Component, Debug)]
+pub(crate) struct AutoPickup {
+    pub radius: f32,
+}
+
+impl Default for AutoPickup {
+    fn default() -> Self {
+        Self {
+            radius: DEFAULT_MAGNET_RADIUS,
+        }
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct CrustPickup {
+    pub amount: u32,
+}
+
+#[derive(Event)]
+pub(crate) struct SpawnCrustPickup {
+    pub position: Vec3,
+    pub amount: u32,
+}
+
+#[derive(Resource)]
+struct CrustPickupAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for CrustPickupAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(PICKUP_SIZE));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(0.85, 0.6, 0.25),
+                emissive: LinearRgba::rgb(0.8, 0.5, 0.1),
+                ..default()
+            });
+        Self { mesh, material }
+    }
+}
+
+fn add_auto_pickup(add: On<Add, Player>, mut commands: Commands) {
+    commands.entity(add.entity).insert(AutoPickup::default());
+}
+
+fn spawn_crust_pickup(
+    spawn: On<SpawnCrustPickup>,
+    mut commands: Commands,
+    assets: Res<CrustPickupAssets>,
+) {
+    commands.spawn((
+        Name::new("Crust Pickup"),
+        CrustPickup {
+            amount: spawn.amount,
+        },
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material.clone()),
+        Transform::from_translation(spawn.position),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn spin_crust_pickups(time: Res<Time>, mut pickups: Query<&mut Transform, With<CrustPickup>>) {
+    for mut transform in &mut pickups {
+        transform.rotate_y(PICKUP_SPIN_SPEED * time.delta_secs());
+    }
+}
+
+fn attract_crust_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Option<Single<(&GlobalTransform, &AutoPickup), With<Player>>>,
+    mut pickups: Query<(Entity, &CrustPickup, &mut Transform)>,
+    mut crusts: ResMut<Crusts>,
+) {
+    let Some(player) = player else { return };
+    let (player_transform, auto_pickup) = *player;
+    let target = player_transform.translation();
+    let pull = 1.0 - (-MAGNET_SHARPNESS * time.delta_secs()).exp();
+
+    for (entity, pickup, mut transform) in &mut pickups {
+        let distance = transform.translation.distance(target);
+        if distance <= COLLECT_RADIUS {
+            crusts.add(pickup.amount);
+            commands.trigger(CrustsRewarded(pickup.amount));
+            commands.entity(entity).despawn();
+        } else if distance <= auto_pickup.radius {
+            transform.translation = transform.translation.lerp(target, pull);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::iter;
+use std::{f32::consts::PI, iter};
 
 use bevy::{
     camera::{RenderTarget, primitives::Aabb, visibility::RenderLayers},
@@ -8,8 +8,12 @@ use bevy::{
     scene::SceneInstanceReady,
     ui::widget::ViewportNode,
 };
+use bevy_seedling::{prelude::*, sample::AudioSample};
 
-use crate::{RenderLayer, asset_tracking::LoadResource, screens::Screen, theme::GameFont};
+use super::hud::{HudElement, HudSettings};
+use crate::{
+    RenderLayer, asset_tracking::LoadResource, audio::SfxPool, screens::Screen, theme::GameFont,
+};
 
 // hacky shit, should probably just have separate render layers or a closer `far` or something
 const PREVIEW_SPACING: f32 = 100.0;
@@ -191,6 +195,7 @@ fn spin_previews(mut query: Query<(&mut Transform, &SpinningPreview)>, time: Res
 
 pub fn plugin(app: &mut App) {
     app.init_resource::<Crusts>();
+    app.init_resource::<DisplayedCrusts>();
     app.load_resource::<CrustsAssets>();
     app.add_systems(OnEnter(Screen::Gameplay), spawn_crusts_hud);
     app.add_systems(
@@ -198,12 +203,13 @@ pub fn plugin(app: &mut App) {
         (
             spin_previews,
             position_preview_cameras,
-            update_crusts_text.run_if(resource_changed::<Crusts>),
+            count_up_crusts,
             animate_crusts_popups,
+            pulse_crusts_preview,
         ),
     );
     app.add_observer(configure_preview_render_layers);
-    app.add_observer(spawn_crusts_popup);
+    app.add_observer(on_crusts_rewarded);
 }
 
 // TODO: make this a per player thing when we add coop
@@ -236,6 +242,8 @@ impl Crusts {
 struct CrustsAssets {
     #[dependency]
     crab: Handle<Scene>,
+    #[dependency]
+    reward_sound: Handle<AudioSample>,
 }
 
 impl FromWorld for CrustsAssets {
@@ -243,12 +251,21 @@ impl FromWorld for CrustsAssets {
         let assets = world.resource::<AssetServer>();
         Self {
             crab: assets.load("models/crab/scene.gltf#Scene0"),
+            reward_sound: assets.load("audio/sound_effects/button_press.ogg"),
         }
     }
 }
 
-#[derive(Component)]
-pub(crate) struct HudTopLeft;
+/// How long the counter takes to tick up to a new total.
+const COUNT_UP_SECONDS: f32 = 0.5;
+
+/// Crusts shown on the counter, chasing [`Crusts`] after a reward instead of jumping.
+#[derive(Resource, Default)]
+struct DisplayedCrusts {
+    value: f32,
+    /// Crusts per second, so every reward takes about [`COUNT_UP_SECONDS`].
+    rate: f32,
+}
 
 #[derive(Component)]
 struct CrustsCounterText;
@@ -256,14 +273,25 @@ struct CrustsCounterText;
 #[derive(Component)]
 struct CrustsRow;
 
+/// The spinning crab next to the counter, pulses on rewards.
+#[derive(Component)]
+struct CrustsPreview;
+
+#[derive(Component)]
+struct CrustsPulse(Timer);
+
 fn spawn_crusts_hud(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     crusts_assets: Res<CrustsAssets>,
     crusts: Res<Crusts>,
+    mut displayed: ResMut<DisplayedCrusts>,
     font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
 ) {
+    displayed.value = crusts.0 as f32;
+
     let preview = spawn_model_preview(
         &mut commands,
         &mut images,
@@ -298,99 +326,139 @@ fn spawn_crusts_hud(
     commands
         .spawn((
             Name::new("Crusts HUD"),
-            HudTopLeft,
+            CrustsRow,
+            HudElement::Crusts,
+            hud_settings.slot(HudElement::Crusts, 0),
             Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                justify_content: JustifyContent::FlexStart,
-                align_items: AlignItems::FlexStart,
-                padding: UiRect::all(Val::Px(16.0)),
-                row_gap: Val::Px(12.0),
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
                 ..default()
             },
             Pickable::IGNORE,
-            DespawnOnExit(Screen::Gameplay),
         ))
-        .with_children(|parent| {
-            parent
-                .spawn((
-                    CrustsRow,
-                    Node {
-                        align_items: AlignItems::Center,
-                        column_gap: Val::Px(8.0),
-                        ..default()
-                    },
-                ))
-                .with_children(|row| {
-                    row.spawn((
-                        ViewportNode::new(preview.camera),
-                        Node {
-                            width: Val::Px(48.0),
-                            height: Val::Px(48.0),
-                            ..default()
-                        },
-                    ));
-                    row.spawn((
-                        CrustsCounterText,
-                        Text::new(format!("{}", crusts.0)),
-                        TextFont {
-                            font: font.0.clone(),
-                            font_size: 24.0,
-                            ..default()
-                        },
-                        TextColor(Color::WHITE),
-                    ));
-                });
+        .with_children(|row| {
+            row.spawn((
+                CrustsPreview,
+                ViewportNode::new(preview.camera),
+                Node {
+                    width: Val::Px(48.0),
+                    height: Val::Px(48.0),
+                    ..default()
+                },
+                UiTransform::default(),
+            ));
+            row.spawn((
+                CrustsCounterText,
+                Text::new(format!("{}", crusts.0)),
+                TextFont {
+                    font: font.0.clone(),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
         });
 }
 
-fn update_crusts_text(crusts: Res<Crusts>, mut query: Query<&mut Text, With<CrustsCounterText>>) {
+fn count_up_crusts(
+    time: Res<Time>,
+    crusts: Res<Crusts>,
+    mut displayed: ResMut<DisplayedCrusts>,
+    mut query: Query<&mut Text, With<CrustsCounterText>>,
+) {
+    let target = crusts.0 as f32;
+    // Spending snaps straight down, only rewards count up.
+    displayed.value = if displayed.value < target && displayed.rate > 0.0 {
+        (displayed.value + displayed.rate * time.delta_secs()).min(target)
+    } else {
+        target
+    };
+
+    let shown = displayed.value.floor() as u32;
     for mut text in &mut query {
-        **text = format!("{}", crusts.0);
+        text.set_if_neq(Text::new(format!("{shown}")));
     }
 }
 
-
 #[derive(Event)]
 pub(crate) struct CrustsRewarded(pub u32);
 
+/// Floating "+N" next to the counter.
 #[derive(Component)]
 struct CrustsPopup {
     timer: Timer,
+    /// Row the popup starts in, so rewards in quick succession stack instead of overlapping.
+    lane: u32,
 }
 
-const POPUP_DURATION: f32 = 1.2;
+const POPUP_DURATION: f32 = 1.0;
+const POPUP_LANE_HEIGHT: f32 = 22.0;
+const POPUP_RISE: f32 = 20.0;
+const PULSE_SECONDS: f32 = 0.3;
+const PULSE_SCALE: f32 = 0.3;
 
-fn spawn_crusts_popup(
+fn on_crusts_rewarded(
     event: On<CrustsRewarded>,
     mut commands: Commands,
     row: Query<Entity, With<CrustsRow>>,
+    previews: Query<Entity, With<CrustsPreview>>,
+    popups: Query<&CrustsPopup>,
+    crusts: Res<Crusts>,
+    mut displayed: ResMut<DisplayedCrusts>,
+    crusts_assets: Res<CrustsAssets>,
     font: Res<GameFont>,
 ) {
-    let amount = event.0;
     let Ok(row_entity) = row.single() else {
+        // No HUD to show it on yet, just apply the reward.
+        displayed.value = crusts.0 as f32;
         return;
     };
+    displayed.rate = (crusts.0 as f32 - displayed.value).max(1.0) / COUNT_UP_SECONDS;
 
-    for _ in 0..amount {
-        let popup = commands
-            .spawn((
-                CrustsPopup {
-                    timer: Timer::from_seconds(POPUP_DURATION, TimerMode::Once),
-                },
-                Text::new("+1"),
-                TextFont {
-                    font: font.0.clone(),
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::srgba(0.4, 1.0, 0.4, 1.0)),
-            ))
-            .id();
+    let lane = (0..)
+        .find(|lane| popups.iter().all(|popup| popup.lane != *lane))
+        .unwrap_or(0);
+    let popup = commands
+        .spawn((
+            CrustsPopup {
+                timer: Timer::from_seconds(POPUP_DURATION, TimerMode::Once),
+                lane,
+            },
+            Text::new(format!("+{}", event.0)),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgba(0.4, 1.0, 0.4, 1.0)),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Percent(100.0),
+                top: Val::Px(lane as f32 * POPUP_LANE_HEIGHT),
+                margin: UiRect::left(Val::Px(8.0)),
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(row_entity).add_child(popup);
 
-        commands.entity(row_entity).add_child(popup);
+    for preview in &previews {
+        commands
+            .entity(preview)
+            .insert(CrustsPulse(Timer::from_seconds(
+                PULSE_SECONDS,
+                TimerMode::Once,
+            )));
     }
+
+    commands.spawn((
+        SamplePlayer::new(crusts_assets.reward_sound.clone()),
+        PlaybackSettings {
+            speed: 1.5,
+            ..default()
+        },
+        SfxPool,
+    ));
 }
 
 fn animate_crusts_popups(
@@ -402,10 +470,7 @@ fn animate_crusts_popups(
         popup.timer.tick(time.delta());
         let t = popup.timer.fraction();
 
-        // Float upward via negative bottom margin
-        node.margin.top = Val::Px(-20.0 * t);
-
-        // Fade out
+        node.top = Val::Px(popup.lane as f32 * POPUP_LANE_HEIGHT - POPUP_RISE * t);
         color.0 = color.0.with_alpha(1.0 - t);
 
         if popup.timer.just_finished() {
@@ -413,3 +478,19 @@ fn animate_crusts_popups(
         }
     }
 }
+
+fn pulse_crusts_preview(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut previews: Query<(Entity, &mut CrustsPulse, &mut UiTransform)>,
+) {
+    for (entity, mut pulse, mut transform) in &mut previews {
+        pulse.0.tick(time.delta());
+        let scale = 1.0 + PULSE_SCALE * (PI * pulse.0.fraction()).sin();
+        transform.scale = Vec2::splat(scale);
+        if pulse.0.just_finished() {
+            transform.scale = Vec2::ONE;
+            commands.entity(entity).remove::<CrustsPulse>();
+        }
+    }
+}
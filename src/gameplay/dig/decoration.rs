@@ -0,0 +1,360 @@
+//! Grass tufts on exposed dirt and pebbles on exposed sand.
+//!
+//! Each voxel volume gets one decoration mesh per voxel type, with every tuft baked into it at
+//! its offset, so the whole layer draws in a handful of calls. The meshes are rebuilt in the
+//! same pass as the volume's remesh, so digging out a top voxel takes its tuft with it and
+//! newly exposed tops get theirs. Placement is hashed from the voxel position, so tufts that
+//! survive a rebuild stay put.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    light::NotShadowCaster,
+    mesh::{Indices, PrimitiveTopology, VertexAttributeValues},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use super::{VOXEL_SIZE, Voxel, VoxelEntities, VoxelSim};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DecorationSettings>();
+    app.init_resource::<VoxelDecorations>();
+    app.add_observer(add_decoration_children);
+    app.add_systems(
+        Update,
+        apply_decoration_settings
+            .before(super::remesh_voxels)
+            .run_if(resource_changed::<DecorationSettings>),
+    );
+}
+
+/// Graphics setting for the whole decoration layer.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct DecorationSettings {
+    pub enabled: bool,
+}
+
+impl Default for DecorationSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// How the exposed tops of one voxel fill get decorated.
+pub(crate) struct DecorationStyle {
+    /// Chance for each exposed top voxel to get a tuft.
+    pub density: f32,
+    /// Baked once per tuft, with the origin on the surface.
+    pub mesh: Mesh,
+    pub material: Handle<StandardMaterial>,
+    /// Random scale range for each tuft.
+    pub scale: (f32, f32),
+}
+
+/// Decoration for each voxel fill. Fills without an entry stay bare.
+#[derive(Resource)]
+pub(crate) struct VoxelDecorations {
+    pub styles: HashMap<Voxel, DecorationStyle>,
+}
+
+impl FromWorld for VoxelDecorations {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let grass = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.32, 0.45, 0.18),
+            perceptual_roughness: 0.9,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        });
+        let pebble = materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.52, 0.48),
+            perceptual_roughness: 1.0,
+            ..default()
+        });
+
+        let mut styles = HashMap::new();
+        styles.insert(
+            Voxel::Dirt,
+            DecorationStyle {
+                density: 0.35,
+                mesh: grass_tuft_mesh(),
+                material: grass,
+                scale: (0.7, 1.3),
+            },
+        );
+        styles.insert(
+            Voxel::Sand,
+            DecorationStyle {
+                density: 0.04,
+                mesh: Cuboid::new(0.06, 0.03, 0.05).mesh().build(),
+                material: pebble,
+                scale: (0.6, 1.4),
+            },
+        );
+        Self { styles }
+    }
+}
+
+/// Two crossed quads, a voxel wide.
+fn grass_tuft_mesh() -> Mesh {
+    let half = VOXEL_SIZE * 0.5;
+    let height = VOXEL_SIZE * 0.8;
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    for side in [Vec3::X, Vec3::Z] {
+        let normal = side.cross(Vec3::Y);
+        for (corner, uv) in [
+            (-side * half, [0.0, 1.0]),
+            (side * half, [1.0, 1.0]),
+            (side * half + Vec3::Y * height, [1.0, 0.0]),
+            (-side * half + Vec3::Y * height, [0.0, 0.0]),
+        ] {
+            positions.push(corner.to_array());
+            normals.push(normal.to_array());
+            uvs.push(uv);
+        }
+    }
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]))
+}
+
+/// The decoration mesh entity for each decorated voxel type of a volume.
+#[derive(Component, Default)]
+pub(crate) struct DecorationEntities {
+    entities: HashMap<Voxel, Entity>,
+}
+
+fn add_decoration_children(
+    add: On<Add, VoxelEntities>,
+    mut commands: Commands,
+    decorations: Res<VoxelDecorations>,
+    settings: Res<DecorationSettings>,
+) {
+    let mut entities = DecorationEntities::default();
+    for (voxel, style) in &decorations.styles {
+        // No collider, so tufts never block digging, walking or shots.
+        let entity = commands
+            .spawn((
+                Name::new(format!("Voxel Decoration {voxel:?}")),
+                Transform::default(),
+                Mesh3d(default()),
+                MeshMaterial3d(style.material.clone()),
+                NotShadowCaster,
+                layer_visibility(&settings),
+                ChildOf(add.entity),
+            ))
+            .id();
+        entities.entities.insert(*voxel, entity);
+    }
+    commands.entity(add.entity).insert(entities);
+}
+
+fn layer_visibility(settings: &DecorationSettings) -> Visibility {
+    if settings.enabled {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+/// Hides the layer, or shows it and rebuilds it since nothing was kept up to date while off.
+fn apply_decoration_settings(
+    settings: Res<DecorationSettings>,
+    mut sims: Query<(&mut VoxelSim, &DecorationEntities)>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    for (mut sim, entities) in &mut sims {
+        if settings.enabled {
+            sim.needs_remesh = true;
+        }
+        for &entity in entities.entities.values() {
+            if let Ok(mut visibility) = visibilities.get_mut(entity) {
+                visibility.set_if_neq(layer_visibility(&settings));
+            }
+        }
+    }
+}
+
+/// Rebuilds a volume's decoration meshes, called from the volume's remesh.
+pub(super) fn rebuild_decorations(
+    sim: &VoxelSim,
+    entities: &DecorationEntities,
+    decorations: &VoxelDecorations,
+    settings: &DecorationSettings,
+    mesh3ds: &mut Query<&mut Mesh3d>,
+    meshes: &mut Assets<Mesh>,
+) {
+    if !settings.enabled {
+        return;
+    }
+    for (voxel, &entity) in &entities.entities {
+        let Some(style) = decorations.styles.get(voxel) else {
+            continue;
+        };
+        let Ok(mut mesh3d) = mesh3ds.get_mut(entity) else {
+            continue;
+        };
+        let placements = decoration_spots(sim, *voxel, style);
+        mesh3d.0 = meshes.add(bake(&style.mesh, &placements));
+    }
+}
+
+/// Where tufts go on the exposed tops of `voxel`: solid `voxel` with air or the top of the
+/// volume above it.
+fn decoration_spots(sim: &VoxelSim, voxel: Voxel, style: &DecorationStyle) -> Vec<Transform> {
+    let mut spots = Vec::new();
+    for (i, &v) in sim.voxels.iter().enumerate() {
+        if v != voxel {
+            continue;
+        }
+        let pos = sim.delinearize(i);
+        if !matches!(sim.get(pos + IVec3::Y), None | Some(Voxel::Air)) {
+            continue;
+        }
+        let hash = hash_voxel(pos);
+        if unit(hash) >= style.density {
+            continue;
+        }
+        let jitter = Vec2::new(unit(hash >> 8), unit(hash >> 16)) - 0.5;
+        let (min_scale, max_scale) = style.scale;
+        let scale = min_scale + (max_scale - min_scale) * unit(hash >> 24);
+        let base = (pos.as_vec3() + Vec3::new(0.5 + jitter.x * 0.8, 1.0, 0.5 + jitter.y * 0.8))
+            * VOXEL_SIZE;
+        spots.push(
+            Transform::from_translation(base)
+                .with_rotation(Quat::from_rotation_y(
+                    unit(hash.wrapping_mul(0x9e37_79b9) >> 24) * std::f32::consts::TAU,
+                ))
+                .with_scale(Vec3::splat(scale)),
+        );
+    }
+    spots
+}
+
+fn hash_voxel(pos: IVec3) -> u32 {
+    let mut h = (pos.x as u32).wrapping_mul(0x8da6_b343)
+        ^ (pos.y as u32).wrapping_mul(0xd816_3841)
+        ^ (pos.z as u32).wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h
+}
+
+/// The low byte of `bits` as a fraction in 0..1.
+fn unit(bits: u32) -> f32 {
+    (bits & 0xff) as f32 / 256.0
+}
+
+/// One mesh holding a copy of `template` at each placement.
+fn bake(template: &Mesh, placements: &[Transform]) -> Mesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+    if let (
+        Some(VertexAttributeValues::Float32x3(template_positions)),
+        Some(VertexAttributeValues::Float32x3(template_normals)),
+    ) = (
+        template.attribute(Mesh::ATTRIBUTE_POSITION),
+        template.attribute(Mesh::ATTRIBUTE_NORMAL),
+    ) {
+        for placement in placements {
+            let offset = positions.len() as u32;
+            for (position, normal) in template_positions.iter().zip(template_normals) {
+                positions.push(
+                    placement
+                        .transform_point(Vec3::from_array(*position))
+                        .to_array(),
+                );
+                normals.push((placement.rotation * Vec3::from_array(*normal)).to_array());
+            }
+            match template.indices() {
+                Some(template_indices) => {
+                    indices.extend(template_indices.iter().map(|i| offset + i as u32));
+                }
+                None => indices.extend(offset..positions.len() as u32),
+            }
+        }
+    }
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(density: f32) -> DecorationStyle {
+        DecorationStyle {
+            density,
+            mesh: grass_tuft_mesh(),
+            material: default(),
+            scale: (1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn only_exposed_tops_are_decorated() {
+        let mut sim = VoxelSim::new(IVec3::new(4, 4, 4));
+        sim.fill(Voxel::Dirt);
+        let full = decoration_spots(&sim, Voxel::Dirt, &style(1.0));
+        assert_eq!(full.len(), 16);
+        for spot in &full {
+            assert_eq!(spot.translation.y, 4.0 * VOXEL_SIZE);
+        }
+
+        // Digging a top voxel drops its tuft onto the newly exposed one below.
+        sim.set(IVec3::new(1, 3, 1), Voxel::Air);
+        let dug = decoration_spots(&sim, Voxel::Dirt, &style(1.0));
+        assert_eq!(dug.len(), 16);
+        assert_eq!(
+            dug.iter()
+                .filter(|spot| spot.translation.y == 3.0 * VOXEL_SIZE)
+                .count(),
+            1
+        );
+
+        assert!(decoration_spots(&sim, Voxel::Sand, &style(1.0)).is_empty());
+        assert!(decoration_spots(&sim, Voxel::Dirt, &style(0.0)).is_empty());
+    }
+
+    #[test]
+    fn placement_is_stable_across_rebuilds() {
+        let mut sim = VoxelSim::new(IVec3::new(8, 2, 8));
+        sim.fill(Voxel::Dirt);
+        let before = decoration_spots(&sim, Voxel::Dirt, &style(0.5));
+        sim.set(IVec3::new(7, 1, 7), Voxel::Air);
+        let after = decoration_spots(&sim, Voxel::Dirt, &style(0.5));
+        for spot in before
+            .iter()
+            .filter(|spot| spot.translation.x < 7.0 * VOXEL_SIZE)
+        {
+            assert!(after.contains(spot));
+        }
+    }
+
+    #[test]
+    fn baked_mesh_has_a_copy_per_placement() {
+        let template = grass_tuft_mesh();
+        let baked = bake(
+            &template,
+            &[Transform::default(), Transform::from_xyz(1.0, 0.0, 0.0)],
+        );
+        assert_eq!(baked.count_vertices(), template.count_vertices() * 2);
+        assert_eq!(baked.indices().unwrap().len(), 24);
+    }
+}
@@ -0,0 +1,141 @@
+//! Trails worn into the dirt by the player walking over it.
+//!
+//! Every footstep on a voxel volume counts against the column it landed on. Once a column has
+//! been stepped on [`PACK_STEPS`] times its top dirt voxel turns into packed dirt, which looks
+//! smoother and stays put in the sim, so well-walked routes show up as paths over a session.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use super::{Voxel, VoxelSim, world_to_voxel};
+
+/// Steps on a column before its top dirt gets packed.
+const PACK_STEPS: u16 = 12;
+/// Columns tracked per volume before the counts decay to make room.
+const MAX_TRACKED_COLUMNS: usize = 2048;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(add_footstep_wear);
+    app.add_observer(wear_footpaths);
+}
+
+/// The player's foot came down on the voxel containing `point`, just under the surface.
+#[derive(Event, Debug)]
+pub(crate) struct Footstep {
+    pub point: Vec3,
+}
+
+/// Footsteps on each voxel column of a volume, keyed by the column's x and z.
+#[derive(Component, Default, Debug)]
+pub(crate) struct FootstepWear {
+    steps: HashMap<IVec2, u16>,
+}
+
+impl FootstepWear {
+    /// Counts a step on the voxel at `pos`, packing it once its column has been walked on
+    /// enough. Only exposed dirt wears down. Returns whether it was packed.
+    fn step(&mut self, sim: &mut VoxelSim, pos: IVec3) -> bool {
+        if sim.get(pos) != Some(Voxel::Dirt)
+            || !matches!(sim.get(pos + IVec3::Y), None | Some(Voxel::Air))
+        {
+            return false;
+        }
+
+        let column = pos.xz();
+        let steps = self.steps.entry(column).or_default();
+        *steps += 1;
+        if *steps < PACK_STEPS {
+            if self.steps.len() > MAX_TRACKED_COLUMNS {
+                self.decay();
+            }
+            return false;
+        }
+
+        self.steps.remove(&column);
+        sim.set(pos, Voxel::PackedDirt);
+        true
+    }
+
+    /// Halves every count and forgets the columns that drop to zero, so stray steps across a
+    /// huge volume don't pile up.
+    fn decay(&mut self) {
+        self.steps.retain(|_, steps| {
+            *steps /= 2;
+            *steps > 0
+        });
+    }
+}
+
+fn add_footstep_wear(add: On<Add, VoxelSim>, mut commands: Commands) {
+    commands.entity(add.entity).insert(FootstepWear::default());
+}
+
+fn wear_footpaths(
+    step: On<Footstep>,
+    mut sims: Query<(&mut VoxelSim, &mut FootstepWear, &GlobalTransform)>,
+) {
+    for (mut sim, mut wear, transform) in &mut sims {
+        let pos = world_to_voxel(transform, step.point);
+        if sim.in_bounds(pos) {
+            wear.step(&mut sim, pos);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walked_column_packs_its_top_dirt() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 3, 3));
+        sim.fill(Voxel::Dirt);
+        let mut wear = FootstepWear::default();
+        let top = IVec3::new(1, 2, 1);
+
+        for _ in 1..PACK_STEPS {
+            assert!(!wear.step(&mut sim, top));
+        }
+        assert_eq!(sim.get(top), Some(Voxel::Dirt));
+
+        assert!(wear.step(&mut sim, top));
+        assert_eq!(sim.get(top), Some(Voxel::PackedDirt));
+        assert_eq!(sim.get(top - IVec3::Y), Some(Voxel::Dirt));
+        assert!(wear.steps.is_empty());
+
+        // Already packed, nothing left to count.
+        assert!(!wear.step(&mut sim, top));
+        assert!(wear.steps.is_empty());
+    }
+
+    #[test]
+    fn only_exposed_dirt_wears() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 3, 3));
+        sim.fill(Voxel::Sand);
+        sim.set(IVec3::new(1, 1, 1), Voxel::Dirt);
+        let mut wear = FootstepWear::default();
+
+        for _ in 0..PACK_STEPS {
+            wear.step(&mut sim, IVec3::new(1, 1, 1));
+            wear.step(&mut sim, IVec3::new(0, 2, 0));
+        }
+        assert_eq!(sim.get(IVec3::new(1, 1, 1)), Some(Voxel::Dirt));
+        assert_eq!(sim.get(IVec3::new(0, 2, 0)), Some(Voxel::Sand));
+        assert!(wear.steps.is_empty());
+    }
+
+    #[test]
+    fn tracked_columns_stay_bounded() {
+        let size = 64;
+        let mut sim = VoxelSim::new(IVec3::new(size, 1, size));
+        sim.fill(Voxel::Dirt);
+        let mut wear = FootstepWear::default();
+
+        for x in 0..size {
+            for z in 0..size {
+                wear.step(&mut sim, IVec3::new(x, 0, z));
+                assert!(wear.steps.len() <= MAX_TRACKED_COLUMNS);
+            }
+        }
+    }
+}
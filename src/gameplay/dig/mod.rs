@@ -1,4 +1,7 @@
+use crate::game_running;
+use crate::gameplay::captions::{Caption, CaptionEvent};
 use crate::gameplay::tags::Tags;
+use crate::graphics::GraphicsQuality;
 use crate::third_party::avian3d::CollisionLayer;
 use avian3d::prelude::*;
 use bevy::asset::RenderAssetUsages;
@@ -9,28 +12,77 @@ use bevy::prelude::*;
 use bevy_trenchbroom::brush::ConvexHull;
 use bevy_trenchbroom::geometry::{Brushes, BrushesAsset};
 use bevy_trenchbroom::prelude::*;
+use bincode::{Decode, Encode};
 use fast_surface_nets::ndshape::{RuntimeShape, Shape};
 use fast_surface_nets::{SurfaceNetsBuffer, surface_nets};
 use fixedbitset::FixedBitSet;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use terrain::{FillHeights, FillShape, column_heights, seed_from_position};
+
+pub(crate) mod decoration;
+pub(crate) mod footpath;
+pub(crate) mod terrain;
+pub(crate) mod walk_grid;
 
 /// World-space size of a single voxel. 4 voxels per world unit.
 pub const VOXEL_SIZE: f32 = 0.25;
 
 const VOXEL_SIM_HZ: f32 = 30.0;
+/// Sim rate on low graphics quality. Each tick that moves anything remeshes, so this halves
+/// the remeshing too.
+const LOW_QUALITY_SIM_HZ: f32 = 15.0;
+/// Consecutive ticks of a cell flipping before the guard settles it: 30 seconds of sim.
+const MAX_FLIP_STREAK: u32 = 900;
+/// Voxels moved in one sim tick that count as a collapse worth a sound caption.
+const COLLAPSE_CAPTION_MOVES: usize = 400;
 
 pub fn plugin(app: &mut App) {
+    app.add_plugins((decoration::plugin, footpath::plugin, walk_grid::plugin));
     app.insert_resource(VoxelSimTimer(Timer::from_seconds(
         1.0 / VOXEL_SIM_HZ,
         TimerMode::Repeating,
     )));
-    app.add_systems(Update, (voxel_sim, remesh_voxels, init_voxel_volumes));
+    app.init_resource::<SimLimits>();
+    app.add_systems(
+        Update,
+        (
+            voxel_sim.run_if(game_running),
+            remesh_voxels,
+            init_voxel_volumes,
+        ),
+    );
     app.add_observer(add_dirty_buff);
     app.add_observer(add_voxel_children);
+    app.add_observer(on_fill_voxel_volumes);
+    app.add_observer(on_modify_voxels);
 }
 
 #[derive(Resource)]
 struct VoxelSimTimer(Timer);
 
+/// Bounds on how much work the voxel sim does, so a pathological layout or a bug in the
+/// rules can't keep a volume churning forever.
+#[derive(Resource, Clone, Copy, Debug)]
+pub(crate) struct SimLimits {
+    /// Voxel moves per volume per tick. Cells past the cap wait for the next tick.
+    pub max_moves_per_tick: usize,
+    /// Consecutive ticks a cell can keep changing before it's settled in place.
+    pub max_flip_streak: u32,
+    /// Consecutive ticks of moves that bring nothing lower before the volume is settled.
+    pub max_stalled_ticks: u32,
+}
+
+impl Default for SimLimits {
+    fn default() -> Self {
+        Self {
+            max_moves_per_tick: 20_000,
+            max_flip_streak: MAX_FLIP_STREAK,
+            max_stalled_ticks: VOXEL_SIM_HZ as u32,
+        }
+    }
+}
+
 #[derive(FgdType, Reflect, Debug, Clone, Default)]
 #[number_key]
 pub enum VoxelFill {
@@ -45,6 +97,18 @@ pub enum VoxelFill {
 pub(crate) struct VoxelVolume {
     pub fill: VoxelFill,
     pub tags: String,
+    /// Only the player can dig into protected volumes, enemy fire won't.
+    pub protected: bool,
+    /// Bottom layers of indestructible barrier, so nobody can dig out of the level.
+    pub bedrock_layers: u32,
+    /// World units filled from the bottom, with air above. 0 = the whole volume.
+    pub fill_depth: f32,
+    /// Voxels the fill height rolls up and down by, so the ground doesn't start flat.
+    /// 0 = flat.
+    pub surface_noise: f32,
+    /// Grayscale image stretched over the volume's footprint, scaling the fill height of
+    /// each column: white is full height, black is empty. Empty = no heightmap.
+    pub heightmap: String,
 }
 
 /// Relationship from a VoxelAabb collider child to its parent VoxelVolume entity.
@@ -67,16 +131,47 @@ impl Default for VoxelVolume {
         Self {
             fill: VoxelFill::default(),
             tags: String::new(),
+            protected: false,
+            bedrock_layers: 0,
+            fill_depth: 0.0,
+            surface_noise: 0.0,
+            heightmap: String::new(),
         }
     }
 }
 
-fn init_voxel_volumes(
+/// The volume's `heightmap` while it loads.
+#[derive(Component)]
+struct PendingHeightmap(Handle<Image>);
+
+pub(crate) fn init_voxel_volumes(
     mut commands: Commands,
-    volumes: Query<(Entity, &VoxelVolume, &Brushes), Without<VoxelSim>>,
+    volumes: Query<(Entity, &VoxelVolume, &Brushes, Option<&PendingHeightmap>), Without<VoxelSim>>,
     brushes_assets: Res<Assets<BrushesAsset>>,
+    asset_server: Res<AssetServer>,
+    images: Res<Assets<Image>>,
 ) {
-    for (entity, volume, brushes) in &volumes {
+    for (entity, volume, brushes, pending_heightmap) in &volumes {
+        let heightmap_path = volume.heightmap.trim();
+        let heightmap = match pending_heightmap {
+            _ if heightmap_path.is_empty() => None,
+            None => {
+                // Picked back up once it's loaded.
+                commands.entity(entity).insert(PendingHeightmap(
+                    asset_server.load(heightmap_path.to_string()),
+                ));
+                continue;
+            }
+            Some(PendingHeightmap(handle)) => match images.get(handle) {
+                Some(image) => Some(image),
+                None if asset_server.load_state(handle).is_failed() => {
+                    warn!("Couldn't load voxel heightmap {heightmap_path:?}, filling flat");
+                    None
+                }
+                None => continue,
+            },
+        };
+
         let brushes_asset = match brushes {
             Brushes::Owned(asset) => asset,
             Brushes::Shared(handle) => {
@@ -123,14 +218,20 @@ fn init_voxel_volumes(
             VoxelFill::Sand => Voxel::Sand,
         };
 
-        // just fill it
-        for x in 0..bounds.x {
-            for z in 0..bounds.z {
-                for y in 0..bounds.y {
-                    sim.set(IVec3::new(x, y, z), voxel);
-                }
-            }
-        }
+        let depth = if volume.fill_depth > 0.0 {
+            volume.fill_depth / VOXEL_SIZE
+        } else {
+            bounds.y as f32
+        };
+        let shape = FillShape {
+            depth,
+            noise: volume.surface_noise.max(0.0),
+            seed: seed_from_position(((min + max) * 0.5).as_vec3()),
+        };
+        let heights = FillHeights(column_heights(bounds, shape, heightmap));
+
+        sim.fill_columns(voxel, &heights.0);
+        sim.set_bedrock(volume.bedrock_layers);
 
         // Don't let the initial fill trigger a full-volume simulate pass.
         sim.clear_modified();
@@ -144,7 +245,9 @@ fn init_voxel_volumes(
 
         // Strip auto-generated collider from default_solid_scene_hooks
         // so only the voxel collider from remesh_voxels is used.
-        commands.entity(entity).remove::<Collider>();
+        commands
+            .entity(entity)
+            .remove::<(Collider, PendingHeightmap)>();
 
         commands
             .entity(entity)
@@ -159,6 +262,7 @@ fn init_voxel_volumes(
                     max: max.as_vec3(),
                 },
                 VoxelGraves::default(),
+                heights,
             ))
             .with_child((
                 Name::new("VoxelAabb"),
@@ -171,27 +275,178 @@ fn init_voxel_volumes(
     }
 }
 
+/// Refill every voxel volume carrying `tag` with its configured fill.
+#[derive(Event)]
+pub(crate) struct FillVoxelVolumes {
+    pub tag: String,
+}
+
+fn on_fill_voxel_volumes(
+    event: On<FillVoxelVolumes>,
+    mut volumes: Query<(&VoxelVolume, &Tags, &FillHeights, &mut VoxelSim)>,
+) {
+    for (volume, tags, heights, mut sim) in &mut volumes {
+        if !tags.contains(&event.tag) {
+            continue;
+        }
+        let voxel = match volume.fill {
+            VoxelFill::Dirt => Voxel::Dirt,
+            VoxelFill::Sand => Voxel::Sand,
+        };
+        sim.fill_columns(voxel, &heights.0);
+        // Same as the initial fill, the starting shape is left as it is.
+        sim.clear_modified();
+    }
+}
+
+/// Set a sphere of voxels around a world-space point in a voxel volume.
+#[derive(Event)]
+pub(crate) struct ModifyVoxels {
+    pub volume: Entity,
+    pub point: Vec3,
+    /// Radius in voxels.
+    pub radius: f32,
+    pub voxel: Voxel,
+}
+
+fn on_modify_voxels(event: On<ModifyVoxels>, mut sims: Query<(&mut VoxelSim, &GlobalTransform)>) {
+    let Ok((mut sim, sim_transform)) = sims.get_mut(event.volume) else {
+        return;
+    };
+    let center = world_to_voxel(sim_transform, event.point);
+    sim.set_shape(center, event.radius, VoxelShape::Sphere, event.voxel);
+}
+
+/// The player dug into a voxel volume at a world-space point.
+#[derive(Event, Debug)]
+pub(crate) struct VoxelsDug {
+    pub volume: Entity,
+    pub point: Vec3,
+}
+
+/// Which voxels within the radius of an edit get set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VoxelShape {
+    Sphere,
+    /// Leaves a flat surface at `plane_y`: carving clears everything at or above it,
+    /// filling stops at it.
+    FlatTop {
+        plane_y: i32,
+    },
+    /// Horizontal disc of the edit radius, `thickness` voxels tall and centered on the edit.
+    Slab {
+        thickness: u32,
+    },
+    /// Disc of the edit radius stacked `height` voxels upward from the edit.
+    Column {
+        height: u32,
+    },
+}
+
+impl VoxelShape {
+    /// Vertical offsets from the center covered by the shape.
+    fn heights(self, radius: i32) -> RangeInclusive<i32> {
+        match self {
+            VoxelShape::Sphere | VoxelShape::FlatTop { .. } => -radius..=radius,
+            VoxelShape::Slab { thickness } => {
+                let below = thickness.saturating_sub(1) as i32 / 2;
+                -below..=thickness.saturating_sub(1) as i32 - below
+            }
+            VoxelShape::Column { height } => 0..=height.saturating_sub(1) as i32,
+        }
+    }
+
+    fn reaches(self, offset: IVec3, radius: f32) -> bool {
+        let dist_sq = match self {
+            VoxelShape::Sphere | VoxelShape::FlatTop { .. } => offset.length_squared(),
+            VoxelShape::Slab { .. } | VoxelShape::Column { .. } => offset.xz().length_squared(),
+        };
+        dist_sq as f32 <= radius * radius
+    }
+
+    fn includes(self, pos: IVec3, voxel: Voxel) -> bool {
+        match self {
+            VoxelShape::FlatTop { plane_y } if voxel == Voxel::Air => pos.y >= plane_y,
+            VoxelShape::FlatTop { plane_y } => pos.y <= plane_y,
+            _ => true,
+        }
+    }
+}
+
+/// Voxel coordinate of a world-space point inside a voxel volume.
+pub fn world_to_voxel(sim_transform: &GlobalTransform, point: Vec3) -> IVec3 {
+    let local = sim_transform
+        .compute_transform()
+        .compute_affine()
+        .inverse()
+        .transform_point3(point);
+    (local / VOXEL_SIZE).floor().as_ivec3()
+}
+
 fn voxel_sim(
+    mut commands: Commands,
     time: Res<Time>,
     mut timer: ResMut<VoxelSimTimer>,
-    mut sims: Query<(&mut VoxelSim, &mut DirtyBuffer)>,
+    limits: Res<SimLimits>,
+    quality: Res<GraphicsQuality>,
+    mut sims: Query<(
+        &mut VoxelSim,
+        &mut DirtyBuffer,
+        &GlobalTransform,
+        Option<&Name>,
+    )>,
 ) {
+    let hz = match *quality {
+        GraphicsQuality::High => VOXEL_SIM_HZ,
+        GraphicsQuality::Low => LOW_QUALITY_SIM_HZ,
+    };
+    let period = Duration::from_secs_f32(1.0 / hz);
+    if timer.0.duration() != period {
+        timer.0.set_duration(period);
+    }
     timer.0.tick(time.delta());
     if !timer.0.just_finished() {
         return;
     }
-    for (mut sim, mut dirty) in &mut sims {
-        sim.simulate(&mut *dirty);
+    for (mut sim, mut dirty, transform, name) in &mut sims {
+        let name = name.map_or("voxel volume", Name::as_str);
+        let trip = sim.simulate(&mut *dirty, &limits);
+        if sim.last_moves >= COLLAPSE_CAPTION_MOVES {
+            let center = sim.bounds.as_vec3() * VOXEL_SIZE / 2.0;
+            commands.trigger(CaptionEvent::at(
+                Caption::Collapse,
+                transform.transform_point(center),
+            ));
+        }
+        match trip {
+            GuardTrip::None => {}
+            GuardTrip::Flipping(cells) => {
+                warn!(
+                    "{name}: settled {cells} voxels that kept changing for {} ticks",
+                    limits.max_flip_streak
+                );
+            }
+            GuardTrip::Stalled => {
+                warn!("{name}: sim moves stopped bringing anything lower, settled the volume");
+            }
+        }
     }
 }
 
 pub fn remesh_voxels(
     mut commands: Commands,
-    mut sims: Query<(Entity, &mut VoxelSim, &VoxelEntities)>,
+    mut sims: Query<(
+        Entity,
+        &mut VoxelSim,
+        &VoxelEntities,
+        Option<&decoration::DecorationEntities>,
+    )>,
     mut mesh3ds: Query<&mut Mesh3d>,
     mut meshes: ResMut<Assets<Mesh>>,
+    decorations: Res<decoration::VoxelDecorations>,
+    decoration_settings: Res<decoration::DecorationSettings>,
 ) {
-    for (sim_entity, mut sim, entities) in &mut sims {
+    for (sim_entity, mut sim, entities, decoration_entities) in &mut sims {
         if !sim.needs_remesh {
             continue;
         }
@@ -208,6 +463,16 @@ pub fn remesh_voxels(
             let mesh = build_flat_mesh(&buffer);
             mesh3d.0 = meshes.add(mesh);
         }
+        if let Some(decoration_entities) = decoration_entities {
+            decoration::rebuild_decorations(
+                &sim,
+                decoration_entities,
+                &decorations,
+                &decoration_settings,
+                &mut mesh3ds,
+                &mut meshes,
+            );
+        }
 
         // voxel collider from all non-air positions
         let mut voxel_positions: Vec<IVec3> = Vec::new();
@@ -278,9 +543,11 @@ fn build_flat_mesh(buffer: &SurfaceNetsBuffer) -> Mesh {
     mesh
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Encode, Decode)]
 pub enum Voxel {
     Dirt,
+    /// Dirt packed down by footsteps. Diggable, but holds in place like barrier in the sim.
+    PackedDirt,
     Sand,
     Barrier,
     Air,
@@ -360,8 +627,11 @@ impl DirtyBuffer {
         in_bounds(self.bounds, pos)
     }
 
+    /// Marks every modified voxel and its 18-connected neighbors as dirty.
     pub fn dilate_modified(&mut self, modified: &FixedBitSet) {
         for index in modified.ones() {
+            // The voxel itself too, otherwise a lone voxel placed in the air never falls.
+            self.dirty.insert(index);
             let pos = self.delinearize(index);
             for offset in &NEIGHBORS_18 {
                 let neighbor = pos + *offset;
@@ -399,7 +669,7 @@ pub fn add_voxel_children(
         return;
     };
 
-    for voxel in &[Voxel::Sand, Voxel::Dirt] {
+    for voxel in &[Voxel::Sand, Voxel::Dirt, Voxel::PackedDirt, Voxel::Barrier] {
         let material =
             match voxel {
                 Voxel::Dirt => StandardMaterial {
@@ -413,12 +683,28 @@ pub fn add_voxel_children(
                     reflectance: 0.2,
                     ..default()
                 },
+                // Same dirt, lighter and without the normal map so trails read as smoothed over.
+                Voxel::PackedDirt => StandardMaterial {
+                    base_color: Color::linear_rgb(1.6, 1.45, 1.3),
+                    base_color_texture: Some(
+                        assets.load("textures/darkmod/nature/dirt/dirt_002_dark.png"),
+                    ),
+                    perceptual_roughness: 0.7,
+                    reflectance: 0.25,
+                    ..default()
+                },
                 Voxel::Sand => StandardMaterial {
                     base_color: Color::srgb(0.8, 0.8, 0.8),
                     perceptual_roughness: 1.0,
                     reflectance: 0.2,
                     ..default()
                 },
+                Voxel::Barrier => StandardMaterial {
+                    base_color: Color::srgb(0.15, 0.14, 0.13),
+                    perceptual_roughness: 1.0,
+                    reflectance: 0.1,
+                    ..default()
+                },
                 _ => continue,
             };
 
@@ -441,7 +727,68 @@ pub struct VoxelSim {
     bounds: IVec3,
     voxels: Vec<Voxel>,
     modified: FixedBitSet,
+    /// Voxels changed since the walk grid last looked. Unlike `modified`, sim steps don't
+    /// consume it.
+    changed: FixedBitSet,
     needs_remesh: bool,
+    /// Voxels moved on the last sim tick.
+    last_moves: usize,
+    guard: SimGuard,
+}
+
+/// What the sim's divergence guard did on a tick.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum GuardTrip {
+    None,
+    /// This many cells kept changing tick after tick and were settled.
+    Flipping(usize),
+    /// Moves stopped making progress downward and the whole volume was settled.
+    Stalled,
+}
+
+/// Counters [`VoxelSim::simulate`] keeps to notice it isn't converging.
+#[derive(Clone, Default)]
+struct SimGuard {
+    tick: u32,
+    /// Recently changed cells, with how many consecutive ticks they changed on and the last.
+    streaks: HashMap<usize, (u32, u32)>,
+    /// Cells held in place by the guard until something else edits them.
+    settled: FixedBitSet,
+    /// Consecutive ticks whose moves didn't lower anything overall.
+    stalled_ticks: u32,
+}
+
+impl SimGuard {
+    /// Counts a tick in which `cells` changed and returns the ones that have now changed on
+    /// more than `max_streak` ticks in a row. Voxels only ever move down, so a cell changing
+    /// for that long is flipping back and forth.
+    fn record(&mut self, cells: &[usize], max_streak: u32) -> Vec<usize> {
+        self.tick = self.tick.wrapping_add(1);
+        let tick = self.tick;
+        let mut flipping = Vec::new();
+        for &cell in cells {
+            let (streak, last) = self.streaks.entry(cell).or_insert((0, tick));
+            if *last == tick && *streak > 0 {
+                continue;
+            }
+            *streak = if *last == tick.wrapping_sub(1) {
+                *streak + 1
+            } else {
+                1
+            };
+            *last = tick;
+            if *streak > max_streak {
+                flipping.push(cell);
+            }
+        }
+        self.streaks
+            .retain(|_, (_, last)| *last == tick || *last == tick.wrapping_sub(1));
+        for &cell in &flipping {
+            self.streaks.remove(&cell);
+            self.settled.insert(cell);
+        }
+        flipping
+    }
 }
 
 impl VoxelSim {
@@ -451,7 +798,13 @@ impl VoxelSim {
             bounds,
             voxels: vec![Voxel::Air; volume],
             modified: FixedBitSet::with_capacity(volume),
+            changed: FixedBitSet::with_capacity(volume),
             needs_remesh: false,
+            last_moves: 0,
+            guard: SimGuard {
+                settled: FixedBitSet::with_capacity(volume),
+                ..default()
+            },
         }
     }
 
@@ -469,8 +822,31 @@ impl VoxelSim {
         air as f32 / total as f32
     }
 
+    /// Fraction of air in the box from `min` to `max` inclusive, clipped to the volume.
+    /// 0.0 if none of the box is inside.
+    pub fn air_ratio_in(&self, min: IVec3, max: IVec3) -> f32 {
+        let min = min.max(IVec3::ZERO);
+        let max = max.min(self.bounds - 1);
+        if min.cmpgt(max).any() {
+            return 0.0;
+        }
+        let mut air = 0;
+        for z in min.z..=max.z {
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    if self.voxels[self.linearize(IVec3::new(x, y, z))] == Voxel::Air {
+                        air += 1;
+                    }
+                }
+            }
+        }
+        let size = max - min + 1;
+        air as f32 / (size.x * size.y * size.z) as f32
+    }
+
     fn mark_modified(&mut self, index: usize) {
         self.modified.insert(index);
+        self.changed.insert(index);
     }
 
     fn any_modified(&self) -> bool {
@@ -507,9 +883,116 @@ impl VoxelSim {
         let index = self.linearize(pos);
         self.voxels[index] = voxel;
         self.mark_modified(index);
+        // The edit can free whatever the guard settled around it, like a voxel that was held
+        // up over the cell just dug out.
+        self.guard.settled.set(index, false);
+        for offset in &NEIGHBORS_18 {
+            let neighbor = pos + *offset;
+            if self.in_bounds(neighbor) {
+                self.guard.settled.set(self.linearize(neighbor), false);
+            }
+        }
         self.needs_remesh = true;
     }
 
+    /// Set every voxel to `voxel`, leaving barriers alone.
+    pub fn fill(&mut self, voxel: Voxel) {
+        for x in 0..self.bounds.x {
+            for z in 0..self.bounds.z {
+                for y in 0..self.bounds.y {
+                    let pos = IVec3::new(x, y, z);
+                    if self.get(pos) != Some(Voxel::Barrier) {
+                        self.set(pos, voxel);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fill each column up to its height in `heights`, indexed by `x + z * bounds.x`, with air
+    /// above. Leaves barriers alone.
+    pub fn fill_columns(&mut self, voxel: Voxel, heights: &[u32]) {
+        for x in 0..self.bounds.x {
+            for z in 0..self.bounds.z {
+                let height = heights
+                    .get((x + z * self.bounds.x) as usize)
+                    .map_or(self.bounds.y, |&h| h as i32);
+                for y in 0..self.bounds.y {
+                    let pos = IVec3::new(x, y, z);
+                    if self.get(pos) != Some(Voxel::Barrier) {
+                        self.set(pos, if y < height { voxel } else { Voxel::Air });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every voxel in storage order, run-length encoded.
+    pub fn runs(&self) -> Vec<(Voxel, u32)> {
+        let mut runs: Vec<(Voxel, u32)> = Vec::new();
+        for &voxel in &self.voxels {
+            match runs.last_mut() {
+                Some((last, count)) if *last == voxel => *count += 1,
+                _ => runs.push((voxel, 1)),
+            }
+        }
+        runs
+    }
+
+    /// Overwrite the volume with [`VoxelSim::runs`] of one the same size. Only the voxels
+    /// that differ count as changed, and they aren't simulated since the runs were already
+    /// settled. Does nothing and returns false if the runs don't cover the volume exactly.
+    pub fn set_runs(&mut self, runs: &[(Voxel, u32)]) -> bool {
+        let total: usize = runs.iter().map(|&(_, count)| count as usize).sum();
+        if total != self.volume() {
+            return false;
+        }
+        let voxels = runs
+            .iter()
+            .flat_map(|&(voxel, count)| std::iter::repeat_n(voxel, count as usize));
+        for (index, voxel) in voxels.enumerate() {
+            if self.voxels[index] != voxel {
+                self.voxels[index] = voxel;
+                self.changed.insert(index);
+                self.needs_remesh = true;
+            }
+        }
+        true
+    }
+
+    /// Turn the bottom `layers` rows into barrier.
+    pub fn set_bedrock(&mut self, layers: u32) {
+        let layers = (layers as i32).min(self.bounds.y);
+        for x in 0..self.bounds.x {
+            for z in 0..self.bounds.z {
+                for y in 0..layers {
+                    self.set(IVec3::new(x, y, z), Voxel::Barrier);
+                }
+            }
+        }
+    }
+
+    /// Set every voxel within `radius` voxels of `center` that the shape includes,
+    /// leaving barriers alone.
+    pub fn set_shape(&mut self, center: IVec3, radius: f32, shape: VoxelShape, voxel: Voxel) {
+        let r = radius as i32;
+        for dx in -r..=r {
+            for dy in shape.heights(r) {
+                for dz in -r..=r {
+                    let offset = IVec3::new(dx, dy, dz);
+                    if !shape.reaches(offset, radius) {
+                        continue;
+                    }
+                    let pos = center + offset;
+                    if !shape.includes(pos, voxel) || self.get(pos) == Some(Voxel::Barrier) {
+                        continue;
+                    }
+                    self.set(pos, voxel);
+                }
+            }
+        }
+    }
+
     pub fn sample(&self) -> HashMap<Voxel, SurfaceNetsBuffer> {
         // +1 padding on min side, +2 on max side.
         // surface_nets doesn't generate faces on the positive boundary,
@@ -524,7 +1007,7 @@ impl VoxelSim {
         let num_samples = (padded[0] * padded[1] * padded[2]) as usize;
 
         let mut results = HashMap::new();
-        for &voxel_type in &[Voxel::Sand, Voxel::Dirt] {
+        for &voxel_type in &[Voxel::Sand, Voxel::Dirt, Voxel::PackedDirt, Voxel::Barrier] {
             let mut sdf = vec![0.5f32; num_samples];
             for i in 0..self.voxels.len() {
                 if self.voxels[i] == voxel_type {
@@ -548,7 +1031,21 @@ impl VoxelSim {
         results
     }
 
-    pub fn simulate(&mut self, dirty: &mut DirtyBuffer) {
+    /// Change in the summed height of loose voxels since the cells in `before` held what it
+    /// says. Negative when they ended up lower overall.
+    fn displacement(&self, before: &HashMap<usize, Voxel>) -> i64 {
+        let loose = |voxel: Voxel| matches!(voxel, Voxel::Dirt | Voxel::Sand) as i64;
+        before
+            .iter()
+            .map(|(&cell, &old)| {
+                let y = self.delinearize(cell).y as i64;
+                (loose(self.voxels[cell]) - loose(old)) * y
+            })
+            .sum()
+    }
+
+    /// Steps the sim once over the cells around last tick's changes, within `limits`.
+    pub(crate) fn simulate(&mut self, dirty: &mut DirtyBuffer, limits: &SimLimits) -> GuardTrip {
         let y_stride = self.linearize(IVec3::Y);
         let volume = self.volume();
 
@@ -556,9 +1053,20 @@ impl VoxelSim {
         dirty.dilate_modified(&self.modified);
         self.modified.clear();
 
+        let mut moves = 0;
+        // What every cell changed this tick held before it, to measure the net displacement.
+        let mut before: HashMap<usize, Voxel> = HashMap::new();
         for i in dirty.dirty.ones() {
+            if moves >= limits.max_moves_per_tick {
+                // Over the cap, look at it again next tick.
+                self.modified.insert(i);
+                continue;
+            }
+            if self.guard.settled.contains(i) {
+                continue;
+            }
             let voxel = self.voxels[i];
-            // fall
+            // fall, packed dirt and barrier hold in place
             match voxel {
                 Voxel::Dirt | Voxel::Sand => {
                     let below = i.wrapping_sub(y_stride);
@@ -569,6 +1077,9 @@ impl VoxelSim {
                         self.mark_modified(i);
                         self.mark_modified(below);
                         self.needs_remesh = true;
+                        moves += 1;
+                        before.entry(i).or_insert(voxel);
+                        before.entry(below).or_insert(Voxel::Air);
                         continue;
                     }
                 }
@@ -601,6 +1112,9 @@ impl VoxelSim {
                                     self.mark_modified(i);
                                     self.mark_modified(target_idx);
                                     self.needs_remesh = true;
+                                    moves += 1;
+                                    before.entry(i).or_insert(voxel);
+                                    before.entry(target_idx).or_insert(Voxel::Air);
                                     break;
                                 }
                             }
@@ -610,5 +1124,403 @@ impl VoxelSim {
                 _ => {}
             }
         }
+
+        self.last_moves = moves;
+
+        // Every rule moves voxels down, so this only trips if the rules stop converging.
+        if moves > 0 && self.displacement(&before) >= 0 {
+            self.guard.stalled_ticks += 1;
+        } else {
+            self.guard.stalled_ticks = 0;
+        }
+        if self.guard.stalled_ticks > limits.max_stalled_ticks {
+            self.guard.stalled_ticks = 0;
+            self.modified.clear();
+            return GuardTrip::Stalled;
+        }
+
+        let mut changed_cells: Vec<usize> = before.keys().copied().collect();
+        changed_cells.sort_unstable();
+        let flipping = self.guard.record(&changed_cells, limits.max_flip_streak);
+        if flipping.is_empty() {
+            return GuardTrip::None;
+        }
+        for &cell in &flipping {
+            self.modified.set(cell, false);
+        }
+        GuardTrip::Flipping(flipping.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::platform::collections::HashSet;
+
+    use super::*;
+
+    fn simulate(sim: &mut VoxelSim) {
+        let mut dirty = DirtyBuffer::new(sim.bounds);
+        sim.simulate(&mut dirty, &SimLimits::default());
+    }
+
+    #[test]
+    fn linearize_round_trip() {
+        let bounds = IVec3::new(3, 5, 7);
+        let volume = (bounds.x * bounds.y * bounds.z) as usize;
+        for index in 0..volume {
+            assert_eq!(linearize(bounds, delinearize(bounds, index)), index);
+        }
+        for x in 0..bounds.x {
+            for y in 0..bounds.y {
+                for z in 0..bounds.z {
+                    let pos = IVec3::new(x, y, z);
+                    assert_eq!(delinearize(bounds, linearize(bounds, pos)), pos);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn runs_round_trip() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 4, 5));
+        sim.fill(Voxel::Dirt);
+        sim.set_bedrock(1);
+        sim.set(IVec3::new(1, 3, 2), Voxel::Air);
+        sim.set(IVec3::new(2, 3, 4), Voxel::Sand);
+        let runs = sim.runs();
+        assert_eq!(runs.iter().map(|&(_, count)| count).sum::<u32>(), 60);
+
+        let mut restored = VoxelSim::new(IVec3::new(3, 4, 5));
+        assert!(restored.set_runs(&runs));
+        assert_eq!(restored.voxels, sim.voxels);
+        assert!(restored.needs_remesh);
+        assert!(!restored.any_modified());
+
+        // Runs from a different sized volume are refused.
+        let mut smaller = VoxelSim::new(IVec3::new(3, 4, 4));
+        assert!(!smaller.set_runs(&runs));
+        assert!(smaller.voxels.iter().all(|&voxel| voxel == Voxel::Air));
+    }
+
+    #[test]
+    fn linearize_layout() {
+        // z is contiguous, then x, then y. The simulation relies on the y stride.
+        let bounds = IVec3::new(3, 5, 7);
+        assert_eq!(linearize(bounds, IVec3::Z), 1);
+        assert_eq!(linearize(bounds, IVec3::X), 7);
+        assert_eq!(linearize(bounds, IVec3::Y), 21);
+    }
+
+    #[test]
+    fn in_bounds_edges() {
+        let bounds = IVec3::new(3, 5, 7);
+        assert!(in_bounds(bounds, IVec3::ZERO));
+        assert!(in_bounds(bounds, bounds - 1));
+        for axis in [IVec3::X, IVec3::Y, IVec3::Z] {
+            assert!(!in_bounds(bounds, -axis));
+            assert!(!in_bounds(bounds, bounds - 1 + axis));
+        }
+    }
+
+    #[test]
+    fn set_get_and_clear_modified() {
+        let mut sim = VoxelSim::new(IVec3::new(2, 3, 4));
+        let pos = IVec3::new(1, 2, 3);
+        assert_eq!(sim.get(pos), Some(Voxel::Air));
+        assert!(!sim.any_modified());
+
+        sim.set(pos, Voxel::Dirt);
+        assert_eq!(sim.get(pos), Some(Voxel::Dirt));
+        assert!(sim.modified.contains(sim.linearize(pos)));
+        assert!(sim.needs_remesh);
+
+        sim.clear_modified();
+        assert!(!sim.any_modified());
+        assert_eq!(sim.get(pos), Some(Voxel::Dirt));
+        // Clearing the modified set doesn't cancel the pending remesh.
+        assert!(sim.needs_remesh);
+
+        // Out of bounds writes are ignored.
+        sim.set(IVec3::new(2, 0, 0), Voxel::Dirt);
+        assert!(!sim.any_modified());
+        assert_eq!(sim.get(IVec3::new(2, 0, 0)), None);
+        assert_eq!(sim.get(IVec3::new(-1, 0, 0)), None);
+    }
+
+    #[test]
+    fn air_ratio_of_known_fills() {
+        assert_eq!(VoxelSim::new(IVec3::ZERO).air_ratio(), 0.0);
+
+        let mut sim = VoxelSim::new(IVec3::new(2, 2, 2));
+        assert_eq!(sim.air_ratio(), 1.0);
+        sim.fill(Voxel::Dirt);
+        assert_eq!(sim.air_ratio(), 0.0);
+        for x in 0..2 {
+            for z in 0..2 {
+                sim.set(IVec3::new(x, 1, z), Voxel::Air);
+            }
+        }
+        assert_eq!(sim.air_ratio(), 0.5);
+
+        // Only the top layer is air, so the box over both layers is half air.
+        assert_eq!(sim.air_ratio_in(IVec3::ZERO, IVec3::ONE), 0.5);
+        assert_eq!(sim.air_ratio_in(IVec3::Y, IVec3::new(5, 5, 5)), 1.0);
+        assert_eq!(sim.air_ratio_in(IVec3::splat(-3), IVec3::new(1, 0, 1)), 0.0);
+        assert_eq!(sim.air_ratio_in(IVec3::splat(4), IVec3::splat(6)), 0.0);
+    }
+
+    #[test]
+    fn fill_and_set_shape_leave_barriers() {
+        let mut sim = VoxelSim::new(IVec3::new(5, 5, 5));
+        sim.set_bedrock(1);
+        sim.fill(Voxel::Dirt);
+        sim.set_shape(IVec3::new(2, 0, 2), 3.0, VoxelShape::Sphere, Voxel::Air);
+        for x in 0..5 {
+            for z in 0..5 {
+                assert_eq!(sim.get(IVec3::new(x, 0, z)), Some(Voxel::Barrier));
+            }
+        }
+        assert_eq!(sim.get(IVec3::new(2, 1, 2)), Some(Voxel::Air));
+    }
+
+    #[test]
+    fn fill_columns_leaves_air_above_each_height() {
+        let mut sim = VoxelSim::new(IVec3::new(2, 4, 1));
+        sim.set_bedrock(1);
+        sim.fill(Voxel::Dirt);
+        sim.fill_columns(Voxel::Sand, &[3, 0]);
+        assert_eq!(sim.get(IVec3::new(0, 0, 0)), Some(Voxel::Barrier));
+        assert_eq!(sim.get(IVec3::new(0, 2, 0)), Some(Voxel::Sand));
+        assert_eq!(sim.get(IVec3::new(0, 3, 0)), Some(Voxel::Air));
+        assert_eq!(sim.get(IVec3::new(1, 0, 0)), Some(Voxel::Barrier));
+        assert_eq!(sim.get(IVec3::new(1, 1, 0)), Some(Voxel::Air));
+    }
+
+    #[test]
+    fn unsupported_voxel_falls_one_cell_per_step() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 6, 3));
+        sim.set(IVec3::new(1, 5, 1), Voxel::Sand);
+
+        for y in (0..5).rev() {
+            simulate(&mut sim);
+            assert_eq!(sim.get(IVec3::new(1, y, 1)), Some(Voxel::Sand));
+            assert_eq!(sim.get(IVec3::new(1, y + 1, 1)), Some(Voxel::Air));
+        }
+
+        // Resting on the floor.
+        simulate(&mut sim);
+        assert_eq!(sim.get(IVec3::new(1, 0, 1)), Some(Voxel::Sand));
+        assert!(!sim.any_modified());
+    }
+
+    #[test]
+    fn supported_voxel_slides_down_diagonal() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 3, 3));
+        sim.set(IVec3::new(1, 0, 1), Voxel::Dirt);
+        sim.set(IVec3::new(1, 1, 1), Voxel::Dirt);
+        sim.set(IVec3::new(1, 2, 1), Voxel::Dirt);
+
+        simulate(&mut sim);
+
+        // The top of the column slides two down and one over, trying -X first.
+        assert_eq!(sim.get(IVec3::new(1, 2, 1)), Some(Voxel::Air));
+        assert_eq!(sim.get(IVec3::new(0, 0, 1)), Some(Voxel::Dirt));
+        assert_eq!(sim.get(IVec3::new(1, 1, 1)), Some(Voxel::Dirt));
+        assert_eq!(sim.get(IVec3::new(1, 0, 1)), Some(Voxel::Dirt));
+    }
+
+    #[test]
+    fn diagonal_slide_needs_two_cells_of_room() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 2, 3));
+        sim.set(IVec3::new(1, 0, 1), Voxel::Dirt);
+        sim.set(IVec3::new(1, 1, 1), Voxel::Dirt);
+
+        simulate(&mut sim);
+
+        assert_eq!(sim.get(IVec3::new(1, 1, 1)), Some(Voxel::Dirt));
+        assert_eq!(sim.get(IVec3::new(1, 0, 1)), Some(Voxel::Dirt));
+        assert!(!sim.any_modified());
+    }
+
+    #[test]
+    fn packed_dirt_holds_in_place() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 4, 3));
+        sim.set(IVec3::new(1, 2, 1), Voxel::PackedDirt);
+        sim.set(IVec3::new(1, 3, 1), Voxel::Dirt);
+
+        for _ in 0..4 {
+            simulate(&mut sim);
+        }
+
+        // Neither falls into the air below, nor slides off the edge.
+        assert_eq!(sim.get(IVec3::new(1, 2, 1)), Some(Voxel::PackedDirt));
+        assert_eq!(sim.get(IVec3::new(1, 3, 1)), Some(Voxel::Dirt));
+        assert_eq!(sim.air_ratio(), 34.0 / 36.0);
+    }
+
+    #[test]
+    fn packed_dirt_is_still_diggable() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 3, 3));
+        sim.set_bedrock(1);
+        sim.fill(Voxel::PackedDirt);
+        sim.set_shape(IVec3::new(1, 2, 1), 1.0, VoxelShape::Sphere, Voxel::Air);
+
+        assert_eq!(sim.get(IVec3::new(1, 2, 1)), Some(Voxel::Air));
+        assert_eq!(sim.get(IVec3::new(1, 1, 1)), Some(Voxel::Air));
+        assert_eq!(sim.get(IVec3::new(0, 2, 0)), Some(Voxel::PackedDirt));
+        assert_eq!(sim.get(IVec3::new(1, 0, 1)), Some(Voxel::Barrier));
+    }
+
+    #[test]
+    fn moves_over_the_cap_wait_for_the_next_tick() {
+        let mut sim = VoxelSim::new(IVec3::new(3, 2, 3));
+        for x in 0..3 {
+            for z in 0..3 {
+                sim.set(IVec3::new(x, 1, z), Voxel::Sand);
+            }
+        }
+        let limits = SimLimits {
+            max_moves_per_tick: 4,
+            ..default()
+        };
+        let mut dirty = DirtyBuffer::new(sim.bounds);
+        let fallen = |sim: &VoxelSim| {
+            sim.voxels[..9]
+                .iter()
+                .filter(|voxel| **voxel == Voxel::Sand)
+                .count()
+        };
+
+        sim.simulate(&mut dirty, &limits);
+        assert_eq!(fallen(&sim), 4);
+        assert!(sim.any_modified());
+
+        sim.simulate(&mut dirty, &limits);
+        sim.simulate(&mut dirty, &limits);
+        assert_eq!(fallen(&sim), 9);
+    }
+
+    #[test]
+    fn cells_changing_every_tick_get_settled() {
+        let mut guard = SimGuard::default();
+        guard.settled.grow(2);
+        for _ in 0..2 {
+            assert!(guard.record(&[0, 1], 3).is_empty());
+        }
+        // A tick off resets the streak.
+        assert!(guard.record(&[0], 3).is_empty());
+        assert_eq!(guard.record(&[0, 1], 3), vec![0]);
+        assert!(guard.settled.contains(0));
+        assert!(!guard.settled.contains(1));
+    }
+
+    #[test]
+    fn settled_cells_hold_until_edited() {
+        let mut sim = VoxelSim::new(IVec3::new(1, 3, 1));
+        let top = IVec3::new(0, 2, 0);
+        sim.set(top, Voxel::Sand);
+        let index = sim.linearize(top);
+        sim.guard.settled.insert(index);
+
+        simulate(&mut sim);
+        assert_eq!(sim.get(top), Some(Voxel::Sand));
+
+        sim.set(top, Voxel::Dirt);
+        simulate(&mut sim);
+        assert_eq!(sim.get(top), Some(Voxel::Air));
+        assert_eq!(sim.get(top - IVec3::Y), Some(Voxel::Dirt));
+    }
+
+    #[test]
+    fn editing_next_to_a_settled_cell_frees_it() {
+        let mut sim = VoxelSim::new(IVec3::new(1, 3, 1));
+        let top = IVec3::new(0, 2, 0);
+        let middle = IVec3::new(0, 1, 0);
+        sim.set(top, Voxel::Sand);
+        sim.set(middle, Voxel::Dirt);
+        sim.guard.settled.insert(sim.linearize(top));
+
+        sim.set(middle, Voxel::Air);
+        simulate(&mut sim);
+        assert_eq!(sim.get(top), Some(Voxel::Air));
+        assert_eq!(sim.get(middle), Some(Voxel::Sand));
+    }
+
+    #[test]
+    fn displacement_measures_net_height() {
+        let mut sim = VoxelSim::new(IVec3::new(1, 3, 1));
+        let top = sim.linearize(IVec3::new(0, 2, 0));
+        let bottom = sim.linearize(IVec3::new(0, 0, 0));
+        sim.set(IVec3::new(0, 0, 0), Voxel::Dirt);
+
+        // Fell two cells.
+        let fell = HashMap::from([(top, Voxel::Dirt), (bottom, Voxel::Air)]);
+        assert_eq!(sim.displacement(&fell), -2);
+        // Went back up, which no rule should do.
+        sim.set(IVec3::new(0, 0, 0), Voxel::Air);
+        sim.set(IVec3::new(0, 2, 0), Voxel::Dirt);
+        let rose = HashMap::from([(top, Voxel::Air), (bottom, Voxel::Dirt)]);
+        assert_eq!(sim.displacement(&rose), 2);
+    }
+
+    #[test]
+    fn dilation_covers_18_neighborhood() {
+        let bounds = IVec3::new(5, 5, 5);
+        let center = IVec3::new(2, 2, 2);
+        let mut modified = FixedBitSet::with_capacity(125);
+        modified.insert(linearize(bounds, center));
+
+        let mut dirty = DirtyBuffer::new(bounds);
+        dirty.dilate_modified(&modified);
+
+        let mut expected: HashSet<IVec3> = NEIGHBORS_18.iter().map(|o| center + *o).collect();
+        expected.insert(center);
+        let actual: HashSet<IVec3> = dirty.dirty.ones().map(|i| delinearize(bounds, i)).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn dilation_clips_to_bounds() {
+        let bounds = IVec3::new(5, 5, 5);
+        let mut modified = FixedBitSet::with_capacity(125);
+        modified.insert(linearize(bounds, IVec3::ZERO));
+
+        let mut dirty = DirtyBuffer::new(bounds);
+        dirty.dilate_modified(&modified);
+
+        // The corner itself, plus the 3 face and 3 edge neighbors pointing into the volume.
+        assert_eq!(dirty.dirty.count_ones(..), 7);
+    }
+
+    #[test]
+    fn sample_solid_cube() {
+        let mut sim = VoxelSim::new(IVec3::new(4, 4, 4));
+        sim.fill(Voxel::Dirt);
+        let buffers = sim.sample();
+
+        assert!(buffers[&Voxel::Sand].positions.is_empty());
+        let dirt = &buffers[&Voxel::Dirt];
+        assert!(!dirt.positions.is_empty());
+        assert!(!dirt.indices.is_empty());
+
+        let has_vertex = |expected: [f32; 3]| {
+            dirt.positions
+                .iter()
+                .any(|p| Vec3::from(*p).distance(Vec3::from(expected)) < 1e-4)
+        };
+        // Face centers on the min and max sides, scaled into world units.
+        assert!(has_vertex([0.0, VOXEL_SIZE, VOXEL_SIZE]));
+        assert!(has_vertex([
+            4.0 * VOXEL_SIZE,
+            2.0 * VOXEL_SIZE,
+            2.0 * VOXEL_SIZE
+        ]));
+
+        let extent = 4.0 * VOXEL_SIZE;
+        for p in &dirt.positions {
+            for c in p {
+                assert!((-1e-4..=extent + 1e-4).contains(c));
+            }
+        }
     }
 }
@@ -0,0 +1,156 @@
+//! Starting shapes for voxel volumes, so level authors don't have to dig terrain into shape
+//! by hand. Each column of a volume is filled up to its own height, set from `fill_depth`, a
+//! grayscale `heightmap` over the volume's footprint and some rolling `surface_noise`.
+
+use bevy::prelude::*;
+
+/// Voxels across one cell of the noise lattice, so hills are a couple of world units wide.
+const NOISE_CELL: f32 = 8.0;
+
+/// Fill height of every column of a volume, in voxels, indexed by `x + z * bounds.x`.
+/// Kept so refilling the volume gives back the same shape.
+#[derive(Component, Debug, Clone)]
+pub(crate) struct FillHeights(pub Vec<u32>);
+
+/// How a volume's columns are filled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FillShape {
+    /// Fill height in voxels before the heightmap and noise.
+    pub depth: f32,
+    /// Most the noise moves the fill height, in voxels.
+    pub noise: f32,
+    pub seed: u32,
+}
+
+/// A seed that's the same every time the volume at `position` is loaded.
+pub(crate) fn seed_from_position(position: Vec3) -> u32 {
+    position
+        .to_array()
+        .iter()
+        .fold(0x811c_9dc5, |hash: u32, c| {
+            (hash ^ c.to_bits()).wrapping_mul(0x0100_0193)
+        })
+}
+
+/// Fill heights for a volume of `bounds` voxels. The heightmap is stretched over the whole
+/// footprint and scales each column's depth, white being the full `depth`.
+pub(crate) fn column_heights(
+    bounds: IVec3,
+    shape: FillShape,
+    heightmap: Option<&Image>,
+) -> Vec<u32> {
+    let mut heights = Vec::with_capacity((bounds.x * bounds.z).max(0) as usize);
+    for z in 0..bounds.z {
+        for x in 0..bounds.x {
+            let mut height = shape.depth;
+            if let Some(image) = heightmap {
+                let u = (x as f32 + 0.5) / bounds.x as f32;
+                let v = (z as f32 + 0.5) / bounds.z as f32;
+                height *= sample_gray(image, u, v);
+            }
+            if shape.noise > 0.0 {
+                height += shape.noise
+                    * value_noise(shape.seed, x as f32 / NOISE_CELL, z as f32 / NOISE_CELL);
+            }
+            heights.push(height.round().clamp(0.0, bounds.y as f32) as u32);
+        }
+    }
+    heights
+}
+
+/// Nearest pixel at `u`, `v` across the image. Only the red channel is read, which for a
+/// grayscale image is all of it.
+fn sample_gray(image: &Image, u: f32, v: f32) -> f32 {
+    let size = image.size();
+    if size.x == 0 || size.y == 0 {
+        return 1.0;
+    }
+    let x = ((u * size.x as f32) as u32).min(size.x - 1);
+    let y = ((v * size.y as f32) as u32).min(size.y - 1);
+    image
+        .get_color_at(x, y)
+        .map_or(1.0, |color| color.to_srgba().red)
+}
+
+/// Smoothly interpolated noise between -1 and 1, changing over about one unit.
+fn value_noise(seed: u32, x: f32, z: f32) -> f32 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let (tx, tz) = (smooth(x - x0), smooth(z - z0));
+    let (x0, z0) = (x0 as i32, z0 as i32);
+    let corner = |dx, dz| lattice(seed, x0 + dx, z0 + dz);
+    let near = corner(0, 0) + (corner(1, 0) - corner(0, 0)) * tx;
+    let far = corner(0, 1) + (corner(1, 1) - corner(0, 1)) * tx;
+    near + (far - near) * tz
+}
+
+/// A random value between -1 and 1 for each lattice point.
+fn lattice(seed: u32, x: i32, z: i32) -> f32 {
+    let mut hash =
+        seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (z as u32).wrapping_mul(0x1656_67b1);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::RenderAssetUsages,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    use super::*;
+
+    #[test]
+    fn noise_rolls_around_the_fill_depth() {
+        let bounds = IVec3::new(32, 40, 32);
+        let flat = FillShape {
+            depth: 20.0,
+            noise: 0.0,
+            seed: 7,
+        };
+        assert!(column_heights(bounds, flat, None).iter().all(|&h| h == 20));
+
+        let rolling = FillShape { noise: 4.0, ..flat };
+        let heights = column_heights(bounds, rolling, None);
+        assert_eq!(heights.len(), 32 * 32);
+        assert!(heights.iter().all(|&h| (16..=24).contains(&h)));
+        assert!(heights.iter().any(|&h| h != 20));
+        // The same seed always gives the same terrain.
+        assert_eq!(heights, column_heights(bounds, rolling, None));
+
+        // Never above the volume.
+        let tall = FillShape {
+            depth: 50.0,
+            ..rolling
+        };
+        assert!(column_heights(bounds, tall, None).iter().all(|&h| h == 40));
+    }
+
+    #[test]
+    fn heightmap_scales_each_column() {
+        // 2x1: black on the left, white on the right.
+        let image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![0, 0, 0, 255, 255, 255, 255, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        let shape = FillShape {
+            depth: 8.0,
+            noise: 0.0,
+            seed: 0,
+        };
+        let heights = column_heights(IVec3::new(4, 8, 2), shape, Some(&image));
+        assert_eq!(heights, vec![0, 0, 8, 8, 0, 0, 8, 8]);
+    }
+}
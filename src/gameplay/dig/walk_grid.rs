@@ -0,0 +1,365 @@
+//! Coarse walkability grid over each voxel volume, so followers can path through tunnels the
+//! baked navmesh doesn't know about.
+//!
+//! Cells are one world unit across. Each cell lists the floors in its center voxel column with
+//! enough headroom to stand on, and only cells whose voxels changed get rescanned.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{camera::visibility::RenderLayers, platform::collections::HashMap, prelude::*};
+use fixedbitset::FixedBitSet;
+
+use super::{VOXEL_SIZE, Voxel, VoxelSim, remesh_voxels};
+use crate::RenderLayer;
+
+/// Voxels per cell along x and z.
+const CELL_VOXELS: i32 = (1.0 / VOXEL_SIZE) as i32;
+/// Air voxels needed above a solid voxel for it to count as a floor.
+const MIN_HEADROOM: i32 = 2;
+/// Biggest floor height change between neighboring cells that can be walked, in voxels.
+const MAX_CLIMB: i32 = 3;
+/// Searches that expand more nodes than this give up.
+const MAX_SEARCH_NODES: usize = 4096;
+const GIZMO_COLOR: Color = Color::srgb(0.9, 0.6, 0.1);
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_gizmo_config(
+        WalkGridGizmos,
+        GizmoConfig {
+            enabled: false,
+            render_layers: RenderLayers::from(RenderLayer::GIZMO3),
+            ..default()
+        },
+    );
+    app.add_observer(add_walk_grid);
+    app.add_systems(
+        Update,
+        (
+            update_walk_grids.before(remesh_voxels),
+            draw_walk_grids
+                .run_if(|store: Res<GizmoConfigStore>| store.config::<WalkGridGizmos>().0.enabled),
+        ),
+    );
+}
+
+/// Overlay of the walkable cells and their connections, toggled with the landmass debug view.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub(crate) struct WalkGridGizmos;
+
+#[derive(Component, Debug)]
+pub(crate) struct WalkGrid {
+    bounds: IVec3,
+    /// Cells along x and z.
+    size: IVec2,
+    /// Standing heights in voxels for each cell, lowest first.
+    floors: Vec<Vec<i32>>,
+    dirty: FixedBitSet,
+}
+
+/// A floor in a cell, at the height of the first air voxel above it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct WalkNode {
+    pub cell: IVec2,
+    pub floor: i32,
+}
+
+impl WalkGrid {
+    pub fn new(bounds: IVec3) -> Self {
+        let size = ((bounds.xz() + CELL_VOXELS - 1) / CELL_VOXELS).max(IVec2::ONE);
+        let cells = (size.x * size.y) as usize;
+        let mut dirty = FixedBitSet::with_capacity(cells);
+        dirty.insert_range(..);
+        Self {
+            bounds,
+            size,
+            floors: vec![Vec::new(); cells],
+            dirty,
+        }
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        (cell.cmpge(IVec2::ZERO).all() && cell.cmplt(self.size).all())
+            .then(|| (cell.x + cell.y * self.size.x) as usize)
+    }
+
+    /// Voxel column sampled for a cell's floors.
+    fn column(&self, cell: IVec2) -> IVec2 {
+        (cell * CELL_VOXELS + CELL_VOXELS / 2).min(self.bounds.xz() - 1)
+    }
+
+    /// Takes the voxels the sim changed since the last call and rescans the cells they're in.
+    pub fn update(&mut self, sim: &mut VoxelSim) {
+        for index in sim.changed.ones() {
+            let cell = sim.delinearize(index).xz() / CELL_VOXELS;
+            if let Some(cell_index) = self.index(cell) {
+                self.dirty.insert(cell_index);
+            }
+        }
+        sim.changed.clear();
+
+        for index in self.dirty.ones() {
+            let cell = IVec2::new(index as i32 % self.size.x, index as i32 / self.size.x);
+            let floors = column_floors(sim, self.column(cell));
+            self.floors[index] = floors;
+        }
+        self.dirty.clear();
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = WalkNode> + '_ {
+        self.floors
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, floors)| {
+                let cell = IVec2::new(index as i32 % self.size.x, index as i32 / self.size.x);
+                floors.iter().map(move |&floor| WalkNode { cell, floor })
+            })
+    }
+
+    /// Position of a node in the volume's local space, on top of its floor.
+    pub fn node_position(&self, node: WalkNode) -> Vec3 {
+        let column = self.column(node.cell).as_vec2() + 0.5;
+        Vec3::new(column.x, node.floor as f32, column.y) * VOXEL_SIZE
+    }
+
+    /// The node closest to a point in the volume's local space. Points outside the volume use
+    /// the cells along its edge, so a path can start from just outside a tunnel entrance.
+    pub fn nearest_node(&self, local: Vec3) -> Option<WalkNode> {
+        let cell = (local.xz() / (CELL_VOXELS as f32 * VOXEL_SIZE))
+            .floor()
+            .as_ivec2()
+            .clamp(IVec2::ZERO, self.size - 1);
+        let height = local.y / VOXEL_SIZE;
+        (0..=2).find_map(|radius| {
+            let mut closest: Option<(f32, WalkNode)> = None;
+            for dx in -radius..=radius {
+                for dz in -radius..=radius {
+                    let cell = cell + IVec2::new(dx, dz);
+                    let Some(index) = self.index(cell) else {
+                        continue;
+                    };
+                    for &floor in &self.floors[index] {
+                        let distance = (floor as f32 - height).abs() + (dx.abs() + dz.abs()) as f32;
+                        if closest.is_none_or(|(best, _)| distance < best) {
+                            closest = Some((distance, WalkNode { cell, floor }));
+                        }
+                    }
+                }
+            }
+            closest.map(|(_, node)| node)
+        })
+    }
+
+    pub fn neighbors(&self, node: WalkNode) -> impl Iterator<Item = WalkNode> + '_ {
+        [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .into_iter()
+            .filter_map(move |offset| {
+                let cell = node.cell + offset;
+                let index = self.index(cell)?;
+                Some(self.floors[index].iter().filter_map(move |&floor| {
+                    ((floor - node.floor).abs() <= MAX_CLIMB).then_some(WalkNode { cell, floor })
+                }))
+            })
+            .flatten()
+    }
+
+    /// A* from `start` to `goal`, including both ends.
+    pub fn find_path(&self, start: WalkNode, goal: WalkNode) -> Option<Vec<WalkNode>> {
+        let heuristic = |node: WalkNode| (node.cell - goal.cell).abs().element_sum() as u32;
+
+        let mut nodes = vec![start];
+        let mut ids = HashMap::new();
+        ids.insert(start, 0);
+        let mut costs = vec![0u32];
+        let mut came_from: Vec<Option<usize>> = vec![None];
+        let mut open = BinaryHeap::from([Reverse((heuristic(start), 0usize))]);
+
+        while let Some(Reverse((estimate, current))) = open.pop() {
+            let node = nodes[current];
+            if node == goal {
+                let mut path = vec![node];
+                let mut id = current;
+                while let Some(previous) = came_from[id] {
+                    path.push(nodes[previous]);
+                    id = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // Already expanded through a cheaper route.
+            if estimate > costs[current] + heuristic(node) {
+                continue;
+            }
+            if nodes.len() > MAX_SEARCH_NODES {
+                return None;
+            }
+
+            let cost = costs[current] + 1;
+            for neighbor in self.neighbors(node) {
+                let id = match ids.get(&neighbor) {
+                    Some(&id) if costs[id] <= cost => continue,
+                    Some(&id) => {
+                        costs[id] = cost;
+                        came_from[id] = Some(current);
+                        id
+                    }
+                    None => {
+                        let id = nodes.len();
+                        nodes.push(neighbor);
+                        ids.insert(neighbor, id);
+                        costs.push(cost);
+                        came_from.push(Some(current));
+                        id
+                    }
+                };
+                open.push(Reverse((cost + heuristic(neighbor), id)));
+            }
+        }
+        None
+    }
+}
+
+/// Heights with at least [`MIN_HEADROOM`] air voxels above a solid voxel, lowest first.
+/// Anything above the volume counts as open air.
+fn column_floors(sim: &VoxelSim, column: IVec2) -> Vec<i32> {
+    let mut floors = Vec::new();
+    let mut air_above = MIN_HEADROOM;
+    for y in (0..sim.bounds.y).rev() {
+        match sim.get(IVec3::new(column.x, y, column.y)) {
+            Some(Voxel::Air) | None => air_above += 1,
+            Some(_) => {
+                if air_above >= MIN_HEADROOM {
+                    floors.push(y + 1);
+                }
+                air_above = 0;
+            }
+        }
+    }
+    floors.reverse();
+    floors
+}
+
+fn add_walk_grid(add: On<Add, VoxelSim>, mut commands: Commands, sims: Query<&VoxelSim>) {
+    let Ok(sim) = sims.get(add.entity) else {
+        return;
+    };
+    commands
+        .entity(add.entity)
+        .insert(WalkGrid::new(sim.bounds));
+}
+
+fn update_walk_grids(mut sims: Query<(&mut VoxelSim, &mut WalkGrid)>) {
+    for (mut sim, mut grid) in &mut sims {
+        if sim.needs_remesh {
+            grid.update(&mut sim);
+        }
+    }
+}
+
+fn draw_walk_grids(
+    mut gizmos: Gizmos<WalkGridGizmos>,
+    grids: Query<(&WalkGrid, &GlobalTransform)>,
+) {
+    let lift = Vec3::Y * 0.05;
+    for (grid, transform) in &grids {
+        for node in grid.nodes() {
+            let from = transform.transform_point(grid.node_position(node)) + lift;
+            // Each connection once, from the lower cell.
+            for neighbor in grid
+                .neighbors(node)
+                .filter(|n| n.cell.x > node.cell.x || n.cell.y > node.cell.y)
+            {
+                let to = transform.transform_point(grid.node_position(neighbor)) + lift;
+                gizmos.line(from, to, GIZMO_COLOR);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3 cells along x and 1 along z, filled with dirt.
+    fn solid_sim() -> VoxelSim {
+        let mut sim = VoxelSim::new(IVec3::new(3 * CELL_VOXELS, 12, CELL_VOXELS));
+        sim.fill(Voxel::Dirt);
+        sim
+    }
+
+    /// Clears a 3 voxel tall tunnel on the floor at height 3 through the center of cell `x`.
+    fn dig_tunnel(sim: &mut VoxelSim, x: i32) {
+        for vx in x * CELL_VOXELS..(x + 1) * CELL_VOXELS {
+            for y in 3..6 {
+                sim.set(IVec3::new(vx, y, CELL_VOXELS / 2), Voxel::Air);
+            }
+        }
+    }
+
+    fn tunnel_node(x: i32) -> WalkNode {
+        WalkNode {
+            cell: IVec2::new(x, 0),
+            floor: 3,
+        }
+    }
+
+    #[test]
+    fn column_floors_need_headroom() {
+        let mut sim = VoxelSim::new(IVec3::new(1, 8, 1));
+        sim.fill(Voxel::Dirt);
+        sim.set(IVec3::new(0, 2, 0), Voxel::Air);
+        sim.set(IVec3::new(0, 3, 0), Voxel::Air);
+        sim.set(IVec3::new(0, 5, 0), Voxel::Air);
+        // The single air voxel at 5 is too low to stand in.
+        assert_eq!(column_floors(&sim, IVec2::ZERO), vec![2, 8]);
+    }
+
+    #[test]
+    fn path_follows_tunnel_once_dug_through() {
+        let mut sim = solid_sim();
+        dig_tunnel(&mut sim, 0);
+        dig_tunnel(&mut sim, 2);
+        let mut grid = WalkGrid::new(sim.bounds);
+        grid.update(&mut sim);
+        assert_eq!(grid.find_path(tunnel_node(0), tunnel_node(2)), None);
+
+        dig_tunnel(&mut sim, 1);
+        grid.update(&mut sim);
+        assert_eq!(
+            grid.find_path(tunnel_node(0), tunnel_node(2)),
+            Some(vec![tunnel_node(0), tunnel_node(1), tunnel_node(2)])
+        );
+    }
+
+    #[test]
+    fn path_climbs_small_steps_only() {
+        let mut sim = solid_sim();
+        let mut grid = WalkGrid::new(sim.bounds);
+        grid.update(&mut sim);
+        let top = |x| WalkNode {
+            cell: IVec2::new(x, 0),
+            floor: 12,
+        };
+        assert_eq!(grid.find_path(top(0), top(2)).map(|p| p.len()), Some(3));
+
+        // A pit deeper than a step in the middle cell splits the surface.
+        for y in 4..12 {
+            for vx in CELL_VOXELS..2 * CELL_VOXELS {
+                for vz in 0..CELL_VOXELS {
+                    sim.set(IVec3::new(vx, y, vz), Voxel::Air);
+                }
+            }
+        }
+        grid.update(&mut sim);
+        assert_eq!(grid.find_path(top(0), top(2)), None);
+    }
+
+    #[test]
+    fn nearest_node_clamps_to_the_edge() {
+        let mut sim = solid_sim();
+        dig_tunnel(&mut sim, 0);
+        let mut grid = WalkGrid::new(sim.bounds);
+        grid.update(&mut sim);
+        let outside = Vec3::new(-5.0, 3.0 * VOXEL_SIZE, 0.5);
+        assert_eq!(grid.nearest_node(outside), Some(tunnel_node(0)));
+    }
+}
@@ -0,0 +1,305 @@
+//! Per-level ambient light, distance fog and skybox, set from a `world_settings` entity in the map.
+//!
+//! The story can lay a [`LightMood`] over that look, fading the ambient light and fog toward a
+//! color for as long as it's set.
+
+use bevy::{core_pipeline::Skybox, prelude::*};
+use bevy_trenchbroom::prelude::*;
+
+use crate::{gameplay::player::camera::WorldModelCamera, screens::Screen};
+
+/// Matches the skybox the player camera spawns with.
+const DEFAULT_SKYBOX_BRIGHTNESS: f32 = 8.0;
+/// Time to fade a light mood fully in or out.
+const MOOD_FADE_SECONDS: f32 = 1.5;
+/// How far a mood pulls the level's fog toward its color.
+const MOOD_FOG_TINT: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelEnvironment>();
+    app.init_resource::<LightMood>();
+    app.add_observer(read_world_settings);
+    app.add_systems(
+        Update,
+        (apply_level_environment, apply_light_mood)
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_level_environment);
+}
+
+/// Environment overrides for the level. Left empty or zeroed, a setting keeps the game's default look.
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct WorldSettings {
+    /// `R G B` from 0 to 255.
+    pub ambient_color: String,
+    pub ambient_brightness: f32,
+    /// `R G B` from 0 to 255. Empty = no distance fog.
+    pub fog_color: String,
+    /// Distance where the fog starts, in world units.
+    pub fog_start: f32,
+    /// Distance where the fog is fully opaque, has to be past `fog_start`.
+    pub fog_end: f32,
+    /// Cubemap to use as the skybox, e.g. `cubemaps/NightSkyHDRI001_4K-HDR_specular.ktx2`.
+    /// Empty = the level's environment map.
+    pub skybox: String,
+    pub skybox_brightness: f32,
+}
+
+impl Default for WorldSettings {
+    fn default() -> Self {
+        Self {
+            ambient_color: String::new(),
+            ambient_brightness: 0.0,
+            fog_color: String::new(),
+            fog_start: 0.0,
+            fog_end: 0.0,
+            skybox: String::new(),
+            skybox_brightness: DEFAULT_SKYBOX_BRIGHTNESS,
+        }
+    }
+}
+
+/// The current level's parsed [`WorldSettings`], `None` where the default look is kept.
+#[derive(Resource, Default)]
+struct LevelEnvironment {
+    ambient: Option<GlobalAmbientLight>,
+    fog: Option<DistanceFog>,
+    skybox: Option<Skybox>,
+}
+
+/// Ambient light color and brightness for a [`LightMood`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Mood {
+    pub color: Color,
+    pub intensity: f32,
+}
+
+/// A mood over the level's ambient light and fog, e.g. set by objective hooks.
+/// Switching moods fades the old one out before the new one fades in.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct LightMood {
+    target: Option<Mood>,
+    /// The mood being blended in, kept while it fades out.
+    shown: Option<Mood>,
+    /// 0 = the level's own look, 1 = fully `shown`.
+    blend: f32,
+}
+
+impl LightMood {
+    pub(crate) fn set(&mut self, color: Color, intensity: f32) {
+        self.target = Some(Mood { color, intensity });
+    }
+
+    /// Fades back to the level's own look.
+    pub(crate) fn clear(&mut self) {
+        self.target = None;
+    }
+
+    /// Moves the blend toward the target, returns whether there's anything to apply.
+    fn step(&mut self, delta: f32) -> bool {
+        if self.shown.is_none() && self.target.is_none() {
+            return false;
+        }
+        let step = delta / MOOD_FADE_SECONDS;
+        if self.shown == self.target {
+            self.blend = (self.blend + step).min(1.0);
+        } else {
+            self.blend = (self.blend - step).max(0.0);
+            if self.blend == 0.0 {
+                self.shown = self.target;
+            }
+        }
+        true
+    }
+}
+
+/// Parses `R G B` in 0..=255, like TrenchBroom's color picker writes them.
+fn parse_color(value: &str) -> Option<Color> {
+    let channels: Vec<u8> = value
+        .split_whitespace()
+        .map(|channel| channel.parse().ok())
+        .collect::<Option<_>>()?;
+    match channels[..] {
+        [r, g, b] => Some(Color::srgb_u8(r, g, b)),
+        _ => None,
+    }
+}
+
+fn read_world_settings(
+    add: On<Add, WorldSettings>,
+    settings: Query<&WorldSettings>,
+    assets: Res<AssetServer>,
+    mut environment: ResMut<LevelEnvironment>,
+) {
+    let Ok(settings) = settings.get(add.entity) else {
+        return;
+    };
+    *environment = LevelEnvironment::default();
+
+    if settings.ambient_brightness > 0.0 || !settings.ambient_color.is_empty() {
+        let color = if settings.ambient_color.is_empty() {
+            Some(Color::WHITE)
+        } else {
+            parse_color(&settings.ambient_color)
+        };
+        match color {
+            Some(color) => {
+                environment.ambient = Some(GlobalAmbientLight {
+                    color,
+                    brightness: settings.ambient_brightness.max(0.0),
+                    ..default()
+                });
+            }
+            None => warn!(
+                "world_settings has a malformed ambient_color {:?}, keeping the default ambient light",
+                settings.ambient_color
+            ),
+        }
+    }
+
+    if !settings.fog_color.is_empty() {
+        match parse_color(&settings.fog_color) {
+            Some(color) if settings.fog_end > settings.fog_start && settings.fog_start >= 0.0 => {
+                environment.fog = Some(DistanceFog {
+                    color,
+                    falloff: FogFalloff::Linear {
+                        start: settings.fog_start,
+                        end: settings.fog_end,
+                    },
+                    ..default()
+                });
+            }
+            Some(_) => warn!(
+                "world_settings fog_end ({}) has to be past fog_start ({}), leaving fog off",
+                settings.fog_end, settings.fog_start
+            ),
+            None => warn!(
+                "world_settings has a malformed fog_color {:?}, leaving fog off",
+                settings.fog_color
+            ),
+        }
+    }
+
+    if !settings.skybox.is_empty() {
+        environment.skybox = Some(Skybox {
+            image: assets.load(&settings.skybox),
+            brightness: settings.skybox_brightness,
+            ..default()
+        });
+    }
+}
+
+/// Applies the level's environment once both it and the world camera are around.
+/// Only the world model camera gets fog, the view model and preview cameras stay clear.
+fn apply_level_environment(
+    mut commands: Commands,
+    environment: Res<LevelEnvironment>,
+    mut ambient: ResMut<GlobalAmbientLight>,
+    camera: Option<Single<Entity, With<WorldModelCamera>>>,
+    new_camera: Query<(), Added<WorldModelCamera>>,
+) {
+    let Some(camera) = camera else { return };
+    if !environment.is_changed() && new_camera.is_empty() {
+        return;
+    }
+
+    *ambient = environment
+        .ambient
+        .clone()
+        .unwrap_or(GlobalAmbientLight::NONE);
+
+    let mut camera = commands.entity(*camera);
+    match &environment.fog {
+        Some(fog) => camera.insert(fog.clone()),
+        None => camera.remove::<DistanceFog>(),
+    };
+    if let Some(skybox) = &environment.skybox {
+        camera.insert(skybox.clone());
+    }
+}
+
+/// Blends the [`LightMood`] over what [`apply_level_environment`] set. Only touches anything
+/// while a mood is shown or fading out.
+fn apply_light_mood(
+    time: Res<Time>,
+    mut mood: ResMut<LightMood>,
+    environment: Res<LevelEnvironment>,
+    mut ambient: ResMut<GlobalAmbientLight>,
+    mut fogs: Query<&mut DistanceFog, With<WorldModelCamera>>,
+) {
+    if !mood.step(time.delta_secs()) {
+        return;
+    }
+    let base = environment
+        .ambient
+        .clone()
+        .unwrap_or(GlobalAmbientLight::NONE);
+    let Some(shown) = mood.shown else {
+        *ambient = base;
+        if let Some(base_fog) = &environment.fog {
+            for mut fog in &mut fogs {
+                fog.color = base_fog.color;
+            }
+        }
+        return;
+    };
+
+    let blend = mood.blend;
+    ambient.color = base.color.mix(&shown.color, blend);
+    ambient.brightness = base.brightness.lerp(shown.intensity, blend);
+    if let Some(base_fog) = &environment.fog {
+        for mut fog in &mut fogs {
+            fog.color = base_fog.color.mix(&shown.color, blend * MOOD_FOG_TINT);
+        }
+    }
+}
+
+/// Puts the defaults back so the next level doesn't inherit this one's look.
+fn reset_level_environment(
+    mut environment: ResMut<LevelEnvironment>,
+    mut mood: ResMut<LightMood>,
+    mut ambient: ResMut<GlobalAmbientLight>,
+) {
+    *environment = LevelEnvironment::default();
+    *mood = LightMood::default();
+    *ambient = GlobalAmbientLight::NONE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moods_fade_out_before_the_next_fades_in() {
+        let half = MOOD_FADE_SECONDS / 2.0;
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let mut mood = LightMood::default();
+        assert!(!mood.step(half));
+
+        mood.set(red, 80.0);
+        assert!(mood.step(half));
+        assert!(mood.step(half));
+        assert_eq!(mood.shown.map(|shown| shown.color), Some(red));
+        assert_eq!(mood.blend, 0.5);
+        mood.step(MOOD_FADE_SECONDS);
+        assert_eq!(mood.blend, 1.0);
+
+        // Red fades all the way out while blue waits.
+        mood.set(blue, 60.0);
+        mood.step(half);
+        assert_eq!(mood.shown.map(|shown| shown.color), Some(red));
+        assert_eq!(mood.blend, 0.5);
+        mood.step(half);
+        assert_eq!(mood.shown.map(|shown| shown.color), Some(blue));
+        mood.step(half);
+        assert_eq!(mood.blend, 0.5);
+
+        mood.clear();
+        mood.step(MOOD_FADE_SECONDS);
+        assert_eq!(mood.shown, None);
+        assert_eq!(mood.blend, 0.0);
+        assert!(!mood.step(half));
+    }
+}
@@ -0,0 +1,302 @@
+//! Explosions at a point. Anything that blows up triggers an [`Explosion`] and this module
+//! does the rest: damage falling off with distance, knockback, a crater in the voxels, the
+//! particle burst, the boom and camera shake.
+//!
+//! Explosions never set each other off directly. Whatever they catch, like barrels and
+//! grenades, lights its own short fuse and triggers its own [`Explosion`] on a later frame,
+//! so a chain reaction ripples outwards instead of recursing inside one observer.
+
+use avian3d::prelude::*;
+use bevy::{camera::visibility::RenderLayers, ecs::entity::EntityHashSet, prelude::*};
+use bevy_hanabi::prelude::{Gradient as HanabiGradient, *};
+use bevy_seedling::prelude::*;
+
+use crate::{
+    RenderLayer,
+    asset_tracking::LoadResource,
+    audio::SpatialPool,
+    gameplay::{
+        barrel::ExplosiveBarrel,
+        captions::{Caption, CaptionEvent},
+        dig::{ModifyVoxels, VOXEL_SIZE, Voxel, VoxelVolume, VoxelWorldBounds},
+        game_mode::CombatEnabled,
+        npc::{
+            Health, NpcDead,
+            shooting::{Faction, FactionRelations, PLAYER_FACTION},
+        },
+        player::{Invincible, Player, PlayerHealth, camera_shake::CameraShake, hurt_player},
+    },
+    graphics::GraphicsQuality,
+    third_party::avian3d::CollisionLayer,
+};
+
+/// Blast radius the particle burst is made for, it's scaled to match the others.
+const BURST_RADIUS: f32 = 4.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<ExplosionAssets>();
+    app.add_observer(explode);
+}
+
+#[derive(Event, Debug, Clone)]
+pub(crate) struct Explosion {
+    pub position: Vec3,
+    pub radius: f32,
+    /// Damage right at `position`, falling off linearly to nothing at `radius`.
+    pub damage: f32,
+    /// Radius of the crater dug into voxel volumes, 0 for none.
+    pub carve_radius: f32,
+    /// Speed things are thrown away at point blank, falling off like the damage.
+    pub impulse: f32,
+    /// Side of whoever set it off, `None` for things that aren't on anyone's side.
+    pub faction: Option<String>,
+    /// Whatever blew up, left out of its own blast.
+    pub source: Option<Entity>,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct ExplosionAssets {
+    burst: Handle<EffectAsset>,
+    #[dependency]
+    boom: Handle<AudioSample>,
+}
+
+impl FromWorld for ExplosionAssets {
+    fn from_world(world: &mut World) -> Self {
+        let burst = {
+            let mut effects = world.resource_mut::<Assets<EffectAsset>>();
+
+            let mut module = ExprWriter::new().finish();
+
+            let init_pos = SetPositionSphereModifier {
+                center: module.lit(Vec3::ZERO),
+                radius: module.lit(0.5),
+                dimension: ShapeDimension::Volume,
+            };
+
+            let init_vel = SetVelocitySphereModifier {
+                center: module.lit(Vec3::ZERO),
+                speed: module.lit(9.0),
+            };
+
+            let lifetime = SetAttributeModifier::new(Attribute::LIFETIME, module.lit(0.7));
+
+            let drag = LinearDragModifier::new(module.lit(4.0));
+
+            let mut gradient = HanabiGradient::new();
+            gradient.add_key(0.0, Vec4::new(4.0, 2.5, 0.6, 1.0));
+            gradient.add_key(0.3, Vec4::new(1.0, 0.4, 0.1, 0.9));
+            gradient.add_key(1.0, Vec4::new(0.2, 0.2, 0.2, 0.0));
+
+            let mut size_curve = HanabiGradient::new();
+            size_curve.add_key(0.0, Vec3::splat(0.3));
+            size_curve.add_key(1.0, Vec3::splat(0.6));
+
+            let effect = EffectAsset::new(512, SpawnerSettings::once(200.0.into()), module)
+                .with_name("Explosion")
+                .with_alpha_mode(bevy_hanabi::AlphaMode::Add)
+                .init(init_pos)
+                .init(init_vel)
+                .init(lifetime)
+                .update(drag)
+                .render(ColorOverLifetimeModifier {
+                    gradient,
+                    ..default()
+                })
+                .render(SizeOverLifetimeModifier {
+                    gradient: size_curve,
+                    screen_space_size: false,
+                })
+                .render(OrientModifier {
+                    rotation: None,
+                    mode: OrientMode::FaceCameraPosition,
+                });
+
+            effects.add(effect)
+        };
+
+        let assets = world.resource::<AssetServer>();
+        Self {
+            burst,
+            boom: assets.load("audio/sound_effects/explosion.wav"),
+        }
+    }
+}
+
+/// Share of the damage and impulse `distance` away from the center, from all of it at the
+/// center down to none at `radius`.
+fn falloff(distance: f32, radius: f32) -> f32 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - distance / radius).clamp(0.0, 1.0)
+}
+
+/// Whether an explosion set off by `attacker` hurts `target`. Explosions that aren't anyone's,
+/// like barrels, hurt everyone. A side's own explosions spare it unless friendly fire is on,
+/// and the faction relations decide the rest.
+fn hurts(attacker: Option<&str>, target: &str, relations: &FactionRelations) -> bool {
+    let Some(attacker) = attacker else {
+        return true;
+    };
+    if attacker == target && !relations.friendly_fire {
+        return false;
+    }
+    relations.can_hurt(attacker, target)
+}
+
+/// Direction to throw something at `offset` from the center, tipped upwards so it lifts off
+/// the ground instead of sliding along it.
+fn away(offset: Vec3) -> Vec3 {
+    (offset.normalize_or(Vec3::Y) + Vec3::Y * 0.5).normalize()
+}
+
+fn explode(
+    explosion: On<Explosion>,
+    mut commands: Commands,
+    assets: Res<ExplosionAssets>,
+    spatial_query: SpatialQuery,
+    relations: Res<FactionRelations>,
+    mut targets: Query<
+        (
+            &GlobalTransform,
+            &mut Health,
+            Option<&Faction>,
+            Has<ExplosiveBarrel>,
+            Has<NpcDead>,
+        ),
+        Without<Player>,
+    >,
+    mut bodies: Query<(&GlobalTransform, &RigidBody, &mut LinearVelocity), Without<Player>>,
+    player: Option<
+        Single<
+            (
+                Entity,
+                &GlobalTransform,
+                &mut PlayerHealth,
+                &mut LinearVelocity,
+                Option<&Invincible>,
+            ),
+            With<Player>,
+        >,
+    >,
+    volumes: Query<(Entity, &VoxelVolume, &VoxelWorldBounds)>,
+    mut shake: ResMut<CameraShake>,
+    quality: Res<GraphicsQuality>,
+    combat: Res<CombatEnabled>,
+) {
+    let center = explosion.position;
+    let radius = explosion.radius;
+    let attacker = explosion.faction.as_deref();
+
+    let filter = SpatialQueryFilter::from_mask([
+        CollisionLayer::Character,
+        CollisionLayer::Prop,
+        CollisionLayer::Ragdoll,
+    ]);
+    let hits: EntityHashSet = spatial_query
+        .shape_intersections(&Collider::sphere(radius), center, Quat::IDENTITY, &filter)
+        .into_iter()
+        .filter(|&hit| Some(hit) != explosion.source)
+        .collect();
+    for hit in hits {
+        if let Ok((transform, mut health, faction, is_barrel, dead)) = targets.get_mut(hit) {
+            let target = faction.map_or("", |faction| faction.0.as_str());
+            if hurts(attacker, target, &relations) {
+                let distance = transform.translation().distance(center);
+                health.0 -= explosion.damage * falloff(distance, radius);
+                // Barrels light their own fuse once their health changes.
+                if health.0 <= 0.0 && !is_barrel && !dead {
+                    commands.entity(hit).insert(NpcDead);
+                }
+            }
+        }
+        if let Ok((transform, body, mut velocity)) = bodies.get_mut(hit) {
+            if body.is_dynamic() {
+                let offset = transform.translation() - center;
+                velocity.0 += away(offset) * explosion.impulse * falloff(offset.length(), radius);
+            }
+        }
+    }
+
+    if let Some((entity, transform, mut health, mut velocity, invincible)) =
+        player.map(Single::into_inner)
+    {
+        let offset = transform.translation() - center;
+        shake.add_explosion(center, transform.translation());
+        let falloff = falloff(offset.length(), radius);
+        if falloff > 0.0 {
+            if combat.0 && hurts(attacker, PLAYER_FACTION, &relations) {
+                hurt_player(&mut commands, entity, &mut health, invincible, center);
+            }
+            velocity.0 += away(offset) * explosion.impulse * falloff;
+        }
+    }
+
+    if explosion.carve_radius > 0.0 {
+        let carve = explosion.carve_radius;
+        for (volume, voxel_volume, bounds) in &volumes {
+            let overlaps =
+                center.cmpge(bounds.min - carve).all() && center.cmple(bounds.max + carve).all();
+            if overlaps && !voxel_volume.protected {
+                commands.trigger(ModifyVoxels {
+                    volume,
+                    point: center,
+                    radius: carve / VOXEL_SIZE,
+                    voxel: Voxel::Air,
+                });
+            }
+        }
+    }
+
+    if quality.particles() {
+        commands.spawn((
+            ParticleEffect::new(assets.burst.clone()),
+            RenderLayers::from(RenderLayer::DEFAULT),
+            Transform::from_translation(center).with_scale(Vec3::splat(radius / BURST_RADIUS)),
+        ));
+    }
+    commands.spawn((
+        SamplePlayer::new(assets.boom.clone()),
+        SpatialPool,
+        Transform::from_translation(center),
+    ));
+    commands.trigger(CaptionEvent::at(Caption::Explosion, center));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_falls_off_to_nothing_at_the_radius() {
+        assert_eq!(falloff(0.0, 4.0), 1.0);
+        assert_eq!(falloff(1.0, 4.0), 0.75);
+        assert_eq!(falloff(2.0, 4.0), 0.5);
+        assert_eq!(falloff(4.0, 4.0), 0.0);
+        assert_eq!(falloff(10.0, 4.0), 0.0);
+        assert_eq!(falloff(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn explosions_spare_their_own_side_without_friendly_fire() {
+        let mut relations = FactionRelations::default();
+        // Barrels hurt everyone.
+        assert!(hurts(None, "player", &relations));
+        assert!(hurts(None, "enemy", &relations));
+        // The player's own grenades don't.
+        assert!(!hurts(Some("player"), "player", &relations));
+        assert!(hurts(Some("player"), "enemy", &relations));
+        assert!(hurts(Some("player"), "prop", &relations));
+        // Nor do Larry's or the enemies' own.
+        assert!(!hurts(Some("lobster"), "player", &relations));
+        assert!(!hurts(Some("enemy"), "enemy", &relations));
+        assert!(hurts(Some("enemy"), "player", &relations));
+
+        relations.friendly_fire = true;
+        assert!(hurts(Some("player"), "player", &relations));
+        assert!(hurts(Some("enemy"), "enemy", &relations));
+        assert!(!hurts(Some("lobster"), "player", &relations));
+    }
+}
@@ -0,0 +1,59 @@
+//! Which way the level is being played, picked from the main menu.
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GameMode>();
+    app.init_resource::<CombatEnabled>();
+    app.init_resource::<GraveCombos>();
+}
+
+#[derive(Resource, Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) enum GameMode {
+    /// The tutorial and objectives.
+    #[default]
+    Story,
+    /// Story plus dummies and a stats panel for tuning guns and upgrades.
+    Practice,
+    /// Endless enemy waves, no objectives or tutorial dialogue.
+    Sandbox,
+}
+
+impl GameMode {
+    pub(crate) fn has_story(self) -> bool {
+        self != GameMode::Sandbox
+    }
+}
+
+/// Run condition for objective and Yarn systems.
+pub(crate) fn story_active(mode: Res<GameMode>) -> bool {
+    mode.has_story()
+}
+
+/// Run condition for what only the endless mode does, like ramping up threat.
+pub(crate) fn sandbox_active(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Sandbox
+}
+
+/// Off for a peaceful run through the story: enemies still stand around and turn to watch,
+/// but never fire, and nothing hurts or kills the player.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub(crate) struct CombatEnabled(pub bool);
+
+impl Default for CombatEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Run condition for enemy fire and anything that hurts the player.
+pub(crate) fn combat_enabled(combat: Res<CombatEnabled>) -> bool {
+    combat.0
+}
+
+/// Opt-in payout bonus for filling graves back to back. Off by default so crust payouts
+/// stay one per body.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Resource)]
+pub(crate) struct GraveCombos(pub bool);
@@ -1,40 +1,85 @@
+use avian_pickup::prop::HeldProp;
 use avian3d::prelude::*;
 use bevy::math::DVec3;
 use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
 use bevy_trenchbroom::brush::ConvexHull;
 use bevy_trenchbroom::geometry::{Brushes, BrushesAsset};
 use bevy_trenchbroom::prelude::*;
 
-use super::dig::{VoxelGraves, VoxelWorldBounds};
-use super::npc::{Body, NpcRegistry};
+use super::captions::{Caption, CaptionEvent};
+use super::dig::{VoxelGraves, VoxelSim, VoxelWorldBounds};
+use super::hud::{HudElement, HudSettings};
+use super::npc::{
+    Body, NpcPrefab, NpcRegistry,
+    model::{NpcModel, report_unknown_model},
+};
+use super::player::{camera::PlayerCamera, input::Interact};
 use super::tags::Tags;
-use crate::gameplay::crusts::Crusts;
+use crate::game_running;
+use crate::gameplay::crusts::{Crusts, CrustsRewarded};
+use crate::gameplay::game_mode::GraveCombos;
+use crate::gameplay::signpost::SetSignText;
+use crate::screens::Screen;
+use crate::theme::GameFont;
 use crate::third_party::avian3d::CollisionLayer;
 
 /// Maximum air_ratio for a grave to count as "filled" (80% dirt).
 pub(crate) const GRAVE_FILL_THRESHOLD: f32 = 0.2;
+/// Seconds after a grave pays out in which filling another one keeps the combo going.
+const COMBO_WINDOW_SECONDS: f32 = 10.0;
+/// Extra payout fraction for each grave in a combo after the first.
+const COMBO_BONUS_PER_GRAVE: f32 = 0.5;
+/// A body resting within this distance of an unfilled grave's sensor gets buried anyway,
+/// for ragdolls that come to rest on the lip where the sensor never picks them up.
+const REST_SLOT_DISTANCE: f32 = 1.5;
+/// Seconds a body has to rest by a grave before it's buried.
+const REST_SLOT_SECONDS: f32 = 3.0;
+/// Bodies slower than this count as resting.
+const REST_SPEED: f32 = 0.3;
+/// Reach of the Interact nudge on a body.
+const NUDGE_DISTANCE: f32 = 3.0;
+/// Graves further than this from a nudged body aren't aimed for.
+const NUDGE_GRAVE_RANGE: f32 = 8.0;
+/// Velocity a nudge gives a body toward the grave, and upward to get it over the lip.
+const NUDGE_SPEED: f32 = 4.0;
+const NUDGE_LIFT: f32 = 3.0;
 
 pub fn plugin(app: &mut App) {
+    app.init_resource::<GraveCombo>();
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_grave_combo, spawn_combo_hud),
+    );
     app.add_systems(
         Update,
         (
             init_graves,
             link_graves_to_voxels,
             make_grave_colliders_sensors,
-            slot_bodies_in_graves,
-            lerp_slotted_bodies,
-            grave_reward,
-            respawn_fallen_bodies,
+            (
+                slot_bodies_in_graves,
+                slot_resting_bodies,
+                lerp_slotted_bodies,
+                (tick_grave_combo, grave_reward, update_combo_hud).chain(),
+                respawn_fallen_bodies,
+            )
+                .run_if(game_running),
         ),
     );
     app.add_observer(init_body_spawner);
     app.add_observer(on_spawn_body);
+    app.add_observer(nudge_body_toward_grave);
 }
 
 #[solid_class(base(Transform, Visibility))]
 pub(crate) struct Grave {
     pub slots: u32,
     pub tags: String,
+    /// Tag of the signpost to engrave with the name of whatever gets buried here.
+    pub sign: String,
+    /// Scales the crusts paid per buried body.
+    pub reward_multiplier: f32,
 }
 
 impl Default for Grave {
@@ -42,6 +87,8 @@ impl Default for Grave {
         Self {
             slots: 1,
             tags: String::new(),
+            sign: String::new(),
+            reward_multiplier: 1.0,
         }
     }
 }
@@ -52,22 +99,58 @@ pub(crate) struct GraveState {
     pub(crate) slots: u32,
     pub(crate) filled: u32,
     pub(crate) rewarded: u32,
+    pub(crate) reward_multiplier: f32,
 }
 
 impl GraveState {
     pub fn filled(&self) -> bool {
         self.filled >= self.slots
     }
+
+    /// Takes up a slot for a body, unless the grave is full.
+    fn slot(&mut self) -> bool {
+        if self.filled() {
+            return false;
+        }
+        self.filled += 1;
+        true
+    }
+}
+
+/// Graves filled in quick succession, each paying more than the last.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct GraveCombo {
+    /// Graves paid out in the current combo, 0 when none is running.
+    pub count: u32,
+    window: Timer,
+}
+
+impl GraveCombo {
+    /// Counts a grave paying out, returning the payout multiplier for it.
+    fn extend(&mut self) -> f32 {
+        self.count += 1;
+        self.window = Timer::from_seconds(COMBO_WINDOW_SECONDS, TimerMode::Once);
+        combo_multiplier(self.count)
+    }
+}
+
+fn combo_multiplier(count: u32) -> f32 {
+    1.0 + count.saturating_sub(1) as f32 * COMBO_BONUS_PER_GRAVE
+}
+
+/// Crusts paid for `bodies` newly buried in a grave.
+fn grave_payout(bodies: u32, grave_multiplier: f32, combo_multiplier: f32) -> u32 {
+    (bodies as f32 * grave_multiplier * combo_multiplier).round() as u32
 }
 
 #[derive(Component)]
 pub(crate) struct GraveVoxelVolume(pub Entity);
 
 #[derive(Component)]
-struct GraveCenter(Vec3);
+pub(crate) struct GraveCenter(pub(crate) Vec3);
 
 #[derive(Component)]
-struct GraveSensor(Entity);
+pub(crate) struct GraveSensor(pub(crate) Entity);
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
@@ -75,10 +158,30 @@ pub(crate) struct Slotted;
 
 #[derive(Component)]
 struct GraveLerp {
-    target_y: f32,
+    target: Vec3,
+}
+
+/// A body resting next to a grave it didn't land in, see [`slot_resting_bodies`].
+#[derive(Component)]
+struct RestingByGrave {
+    sensor: Entity,
+    seconds: f32,
+}
+
+impl RestingByGrave {
+    /// Counts `dt` more seconds of rest, returning whether it's been long enough to bury.
+    fn rest(&mut self, dt: f32) -> bool {
+        self.seconds += dt;
+        self.seconds >= REST_SLOT_SECONDS
+    }
 }
 
-fn init_graves(
+/// Distance from `point` to the box from `min` to `max`, 0 inside it.
+fn distance_to_aabb(min: Vec3, max: Vec3, point: Vec3) -> f32 {
+    point.clamp(min, max).distance(point)
+}
+
+pub(crate) fn init_graves(
     mut commands: Commands,
     graves: Query<(Entity, &Grave, &Brushes), Without<GraveState>>,
     brushes_assets: Res<Assets<BrushesAsset>>,
@@ -122,6 +225,7 @@ fn init_graves(
                 slots: grave.slots,
                 filled: 0,
                 rewarded: 0,
+                reward_multiplier: grave.reward_multiplier,
             },
             Tags::from_csv(&grave.tags),
             GraveCenter(center),
@@ -213,10 +317,10 @@ impl Default for BodySpawner {
 }
 
 #[derive(Component)]
-struct SpawnerState {
-    queue: Vec<String>,
-    index: usize,
-    spawned: Vec<(Entity, String)>,
+pub(crate) struct SpawnerState {
+    pub queue: Vec<String>,
+    pub index: usize,
+    pub spawned: Vec<(Entity, String)>,
 }
 
 fn init_body_spawner(
@@ -253,13 +357,22 @@ pub(crate) enum SpawnBody {
 
 const BODY_SPAWN_SPEED: f32 = 5.0;
 
-fn body_display_name(model_key: &str) -> String {
-    let mut c = model_key.chars();
-    let capitalized = match c.next() {
-        None => return "Body".to_string(),
-        Some(f) => f.to_uppercase().to_string() + c.as_str(),
-    };
-    format!("{} (Body)", capitalized)
+/// Who a spawned body was, engraved on the sign of the grave it gets buried in.
+#[derive(Component)]
+struct BodyName(String);
+
+impl BodyName {
+    fn from_model(model_key: &str) -> Self {
+        let mut c = model_key.chars();
+        Self(match c.next() {
+            None => "Someone".to_string(),
+            Some(f) => f.to_uppercase().to_string() + c.as_str(),
+        })
+    }
+
+    fn display_name(&self) -> Name {
+        Name::new(format!("{} (Body)", self.0))
+    }
 }
 
 fn on_spawn_body(
@@ -294,17 +407,20 @@ fn on_spawn_body(
             }
         };
 
-        let Some(prefab) = registry.prefabs.get(&npc_name) else {
-            warn!("NPC '{}' not found in registry", npc_name);
-            continue;
-        };
+        let resolved = registry.resolve(&npc_name);
+        let prefab = resolved
+            .prefab()
+            .cloned()
+            .unwrap_or_else(NpcPrefab::fallback);
 
         let mut t = transform.compute_transform();
         t.scale = Vec3::splat(0.5);
 
+        let name = BodyName::from_model(&npc_name);
         let spawned = commands
             .spawn((
-                Name::new(body_display_name(&npc_name)),
+                name.display_name(),
+                name,
                 Body,
                 RigidBody::Dynamic,
                 Collider::capsule(prefab.radius * 0.5, prefab.height * 0.25),
@@ -318,6 +434,9 @@ fn on_spawn_body(
                 prefab.body.model_transform,
             ))
             .id();
+        if let NpcModel::Unknown = resolved {
+            report_unknown_model(&mut commands, spawned, &npc_name);
+        }
 
         state.spawned.push((spawned, npc_name));
     }
@@ -350,17 +469,20 @@ fn respawn_fallen_bodies(
                 commands.entity(entity).despawn();
             }
 
-            let Some(prefab) = registry.prefabs.get(npc_name) else {
-                state.spawned.swap_remove(i);
-                continue;
-            };
+            let resolved = registry.resolve(npc_name);
+            let prefab = resolved
+                .prefab()
+                .cloned()
+                .unwrap_or_else(NpcPrefab::fallback);
 
             let mut t = spawner_transform.compute_transform();
             t.scale = Vec3::splat(0.5);
 
+            let name = BodyName::from_model(npc_name);
             let new_entity = commands
                 .spawn((
-                    Name::new(body_display_name(npc_name)),
+                    name.display_name(),
+                    name,
                     Body,
                     RigidBody::Dynamic,
                     Collider::capsule(prefab.radius * 0.5, prefab.height * 0.25),
@@ -374,6 +496,9 @@ fn respawn_fallen_bodies(
                     prefab.body.model_transform,
                 ))
                 .id();
+            if let NpcModel::Unknown = resolved {
+                report_unknown_model(&mut commands, new_entity, npc_name);
+            }
 
             state.spawned[i] = (new_entity, npc_name.clone());
             i += 1;
@@ -381,20 +506,42 @@ fn respawn_fallen_bodies(
     }
 }
 
+/// Engraves the grave's sign for a body that just took up one of its slots and settles the
+/// body at `target`.
+fn bury_body(
+    commands: &mut Commands,
+    grave: &Grave,
+    body: Entity,
+    name: Option<&BodyName>,
+    target: Vec3,
+) {
+    if !grave.sign.is_empty() {
+        let name = name.map_or("Someone", |name| name.0.as_str());
+        commands.trigger(SetSignText {
+            tag: grave.sign.clone(),
+            text: format!("Here lies {name}"),
+        });
+    }
+    commands
+        .entity(body)
+        .insert((Slotted, RigidBody::Static, GraveLerp { target }))
+        .remove::<RestingByGrave>();
+}
+
 fn slot_bodies_in_graves(
     mut commands: Commands,
     sensors: Query<(&GraveSensor, &CollidingEntities, &Transform)>,
-    mut graves: Query<&mut GraveState>,
-    bodies: Query<Entity, (With<Body>, Without<Slotted>)>,
+    mut graves: Query<(&mut GraveState, &Grave)>,
+    bodies: Query<(Option<&BodyName>, &Transform), (With<Body>, Without<Slotted>)>,
     parents: Query<&ChildOf>,
 ) {
     for (sensor, colliding, sensor_transform) in &sensors {
-        let Ok(mut state) = graves.get_mut(sensor.0) else {
+        let Ok((mut state, grave)) = graves.get_mut(sensor.0) else {
             continue;
         };
 
         for &colliding_entity in colliding.iter() {
-            if state.filled >= state.slots {
+            if state.filled() {
                 break;
             }
 
@@ -404,19 +551,165 @@ fn slot_bodies_in_graves(
             .find(|&e| bodies.get(e).is_ok());
 
             if let Some(body_entity) = body_entity {
-                state.filled += 1;
-                commands.entity(body_entity).insert((
-                    Slotted,
-                    RigidBody::Static,
-                    GraveLerp {
-                        target_y: sensor_transform.translation.y,
-                    },
-                ));
+                let Ok((name, transform)) = bodies.get(body_entity) else {
+                    continue;
+                };
+                state.slot();
+                let target = transform.translation.with_y(sensor_transform.translation.y);
+                bury_body(&mut commands, grave, body_entity, name, target);
             }
         }
     }
 }
 
+/// Buries bodies that came to rest by an unfilled grave without the sensor ever picking them
+/// up. Bodies being carried don't count, and neither do ones with a wall between them and
+/// the grave.
+fn slot_resting_bodies(
+    mut commands: Commands,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    sensors: Query<(Entity, &GraveSensor, &ColliderAabb, &Transform)>,
+    mut graves: Query<(&mut GraveState, &Grave)>,
+    mut bodies: Query<
+        (
+            Entity,
+            &Transform,
+            &LinearVelocity,
+            Option<&BodyName>,
+            Option<&mut RestingByGrave>,
+        ),
+        (With<Body>, Without<Slotted>, Without<HeldProp>),
+    >,
+    voxels: Query<(), With<VoxelSim>>,
+) {
+    for (body, transform, velocity, name, resting) in &mut bodies {
+        let position = transform.translation;
+        let nearby = if velocity.length() < REST_SPEED {
+            sensors.iter().find(|&(_, sensor, aabb, sensor_transform)| {
+                graves.get(sensor.0).is_ok_and(|(state, _)| !state.filled())
+                    && distance_to_aabb(aabb.min, aabb.max, position) <= REST_SLOT_DISTANCE
+                    && in_sight(
+                        &spatial_query,
+                        &voxels,
+                        sensor_transform.translation,
+                        position,
+                    )
+            })
+        } else {
+            None
+        };
+
+        let Some((sensor_entity, sensor, _, sensor_transform)) = nearby else {
+            if resting.is_some() {
+                commands.entity(body).remove::<RestingByGrave>();
+            }
+            continue;
+        };
+        let Some(mut resting) = resting else {
+            commands.entity(body).insert(RestingByGrave {
+                sensor: sensor_entity,
+                seconds: 0.0,
+            });
+            continue;
+        };
+        if resting.sensor != sensor_entity {
+            *resting = RestingByGrave {
+                sensor: sensor_entity,
+                seconds: 0.0,
+            };
+            continue;
+        }
+        if !resting.rest(time.delta_secs()) {
+            continue;
+        }
+
+        let Ok((mut state, grave)) = graves.get_mut(sensor.0) else {
+            continue;
+        };
+        if state.slot() {
+            bury_body(
+                &mut commands,
+                grave,
+                body,
+                name,
+                sensor_transform.translation,
+            );
+        }
+    }
+}
+
+/// Whether nothing but dirt stands between `from` and `to`.
+fn in_sight(
+    spatial_query: &SpatialQuery,
+    voxels: &Query<(), With<VoxelSim>>,
+    from: Vec3,
+    to: Vec3,
+) -> bool {
+    let Ok(direction) = Dir3::new(to - from) else {
+        return true;
+    };
+    spatial_query
+        .cast_ray_predicate(
+            from,
+            direction,
+            from.distance(to),
+            true,
+            &SpatialQueryFilter::from_mask(CollisionLayer::Level),
+            &|entity| !voxels.contains(entity),
+        )
+        .is_none()
+}
+
+/// Interact on a body gives it a shove toward the nearest grave with room, for bodies that
+/// end up somewhere the player can't get them in by pushing.
+fn nudge_body_toward_grave(
+    _on: On<Start<Interact>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    spatial_query: SpatialQuery,
+    parents: Query<&ChildOf>,
+    mut bodies: Query<
+        (&Transform, &mut LinearVelocity),
+        (With<Body>, Without<Slotted>, Without<HeldProp>),
+    >,
+    sensors: Query<(&GraveSensor, &Transform)>,
+    graves: Query<&GraveState>,
+) {
+    let camera = camera.compute_transform();
+    let Some(hit) = spatial_query.cast_ray(
+        camera.translation,
+        camera.forward(),
+        NUDGE_DISTANCE,
+        true,
+        &SpatialQueryFilter::from_mask([CollisionLayer::Prop, CollisionLayer::Ragdoll]),
+    ) else {
+        return;
+    };
+    let Some(body) = std::iter::successors(Some(hit.entity), |&e| parents.get(e).ok().map(|p| p.0))
+        .find(|&e| bodies.contains(e))
+    else {
+        return;
+    };
+    let Ok((transform, mut velocity)) = bodies.get_mut(body) else {
+        return;
+    };
+
+    let position = transform.translation;
+    let grave = sensors
+        .iter()
+        .filter(|(sensor, _)| graves.get(sensor.0).is_ok_and(|state| !state.filled()))
+        .map(|(_, sensor_transform)| sensor_transform.translation)
+        .filter(|center| center.distance(position) <= NUDGE_GRAVE_RANGE)
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+    let Some(grave) = grave else {
+        return;
+    };
+    let Ok(toward) = Dir3::new((grave - position).with_y(0.0)) else {
+        return;
+    };
+    velocity.0 += toward * NUDGE_SPEED + Vec3::Y * NUDGE_LIFT;
+}
+
 const GRAVE_LERP_SPEED: f32 = 5.0;
 
 fn lerp_slotted_bodies(
@@ -425,21 +718,37 @@ fn lerp_slotted_bodies(
     time: Res<Time>,
 ) {
     for (entity, mut transform, lerp) in &mut bodies {
-        let diff = lerp.target_y - transform.translation.y;
-        if diff.abs() < 0.01 {
-            transform.translation.y = lerp.target_y;
+        let diff = lerp.target - transform.translation;
+        if diff.length() < 0.01 {
+            transform.translation = lerp.target;
             commands.entity(entity).remove::<GraveLerp>();
         } else {
-            transform.translation.y += diff * GRAVE_LERP_SPEED * time.delta_secs();
+            transform.translation += diff * GRAVE_LERP_SPEED * time.delta_secs();
         }
     }
 }
 
+fn reset_grave_combo(mut combo: ResMut<GraveCombo>) {
+    *combo = GraveCombo::default();
+}
+
+fn tick_grave_combo(time: Res<Time>, mut combo: ResMut<GraveCombo>) {
+    if combo.count == 0 {
+        return;
+    }
+    combo.window.tick(time.delta());
+    if combo.window.is_finished() {
+        combo.count = 0;
+    }
+}
+
 fn grave_reward(
     mut commands: Commands,
     mut graves: Query<(&mut GraveState, Option<&GraveVoxelVolume>)>,
     voxels: Query<&super::dig::VoxelSim>,
     mut crusts: ResMut<Crusts>,
+    mut combo: ResMut<GraveCombo>,
+    combos: Res<GraveCombos>,
 ) {
     for (mut state, voxel_volume) in &mut graves {
         if state.filled == 0 || state.filled == state.rewarded {
@@ -449,10 +758,169 @@ fn grave_reward(
             .and_then(|v| voxels.get(v.0).ok())
             .is_some_and(|sim| sim.air_ratio() <= GRAVE_FILL_THRESHOLD);
         if filled_enough {
-            let to_give = state.filled.saturating_sub(state.rewarded);
+            let bodies = state.filled.saturating_sub(state.rewarded);
+            state.rewarded += bodies;
+            let combo_multiplier = if combos.0 { combo.extend() } else { 1.0 };
+            let to_give = grave_payout(bodies, state.reward_multiplier, combo_multiplier);
             crusts.add(to_give);
-            state.rewarded += to_give;
-            commands.trigger(super::crusts::CrustsRewarded(to_give));
+            commands.trigger(CrustsRewarded(to_give));
+            // The chime plays on the UI, not at the grave.
+            commands.trigger(CaptionEvent::everywhere(Caption::GraveReward));
         }
     }
 }
+
+#[derive(Component)]
+struct GraveComboText;
+
+/// Sits under the crusts counter, empty unless a combo is running.
+fn spawn_combo_hud(mut commands: Commands, font: Res<GameFont>, hud_settings: Res<HudSettings>) {
+    commands.spawn((
+        Name::new("Grave Combo HUD"),
+        HudElement::Crusts,
+        hud_settings.slot(HudElement::Crusts, 1),
+        Node::default(),
+        Pickable::IGNORE,
+        children![(
+            GraveComboText,
+            Text::default(),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.8, 0.3)),
+        )],
+    ));
+}
+
+fn update_combo_hud(combo: Res<GraveCombo>, mut text: Single<&mut Text, With<GraveComboText>>) {
+    if !combo.is_changed() {
+        return;
+    }
+    let label = if combo.count >= 2 {
+        let remaining = combo.window.remaining_secs().ceil();
+        format!("combo x{} ({remaining}s)", combo.count)
+    } else {
+        String::new()
+    };
+    text.set_if_neq(Text::new(label));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    #[test]
+    fn single_graves_pay_one_crust_per_body() {
+        let mut combo = GraveCombo::default();
+        assert_eq!(grave_payout(3, 1.0, combo.extend()), 3);
+    }
+
+    #[test]
+    fn combos_and_multipliers_scale_payouts() {
+        let mut combo = GraveCombo::default();
+        assert_eq!(grave_payout(2, 1.5, combo.extend()), 3);
+        assert_eq!(grave_payout(2, 1.0, combo.extend()), 3);
+        assert_eq!(grave_payout(2, 1.0, combo.extend()), 4);
+        assert_eq!(combo.count, 3);
+    }
+
+    #[test]
+    fn body_resting_outside_the_sensor_gets_buried() {
+        let mut state = GraveState {
+            slots: 1,
+            filled: 0,
+            rewarded: 0,
+            reward_multiplier: 1.0,
+        };
+        // 1m off the side of a 2m sensor.
+        let body = Vec3::new(2.0, 0.0, 0.0);
+        assert!(distance_to_aabb(Vec3::NEG_ONE, Vec3::ONE, body) <= REST_SLOT_DISTANCE);
+        assert!(distance_to_aabb(Vec3::NEG_ONE, Vec3::ONE, body * 2.0) > REST_SLOT_DISTANCE);
+
+        let mut resting = RestingByGrave {
+            sensor: Entity::PLACEHOLDER,
+            seconds: 0.0,
+        };
+        let steps = (0..).take_while(|_| !resting.rest(0.25)).count();
+        assert_eq!(steps + 1, (REST_SLOT_SECONDS / 0.25) as usize);
+
+        assert!(state.slot());
+        assert_eq!(state.filled, 1);
+        assert!(!state.slot());
+        assert_eq!(state.filled, 1);
+    }
+
+    #[test]
+    fn resting_bodies_in_sight_of_a_grave_get_buried() {
+        const TICK: Duration = Duration::from_micros(15_625);
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .add_systems(Update, slot_resting_bodies);
+
+        let grave = app
+            .world_mut()
+            .spawn((
+                Grave::default(),
+                GraveState {
+                    slots: 2,
+                    filled: 0,
+                    rewarded: 0,
+                    reward_multiplier: 1.0,
+                },
+            ))
+            .id();
+        app.world_mut().spawn((
+            GraveSensor(grave),
+            RigidBody::Static,
+            Collider::cuboid(2.0, 2.0, 2.0),
+            Sensor,
+            CollisionLayers::new(CollisionLayer::Sensor, LayerMask::NONE),
+            Transform::default(),
+        ));
+        // A wall between the sensor and one of the bodies.
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(4.0, 4.0, 0.2),
+            CollisionLayers::new(CollisionLayer::Level, LayerMask::ALL),
+            Transform::from_xyz(0.0, 0.0, 1.5),
+        ));
+        let mut spawn_body = |position: Vec3| {
+            app.world_mut()
+                .spawn((
+                    Body,
+                    LinearVelocity::ZERO,
+                    Transform::from_translation(position),
+                ))
+                .id()
+        };
+        // Each 1m off a side of the sensor.
+        let resting = spawn_body(Vec3::X * 2.0);
+        let held = spawn_body(Vec3::NEG_X * 2.0);
+        let walled_off = spawn_body(Vec3::Z * 2.0);
+        app.world_mut().entity_mut(held).insert(HeldProp);
+
+        let ticks = (REST_SLOT_SECONDS / TICK.as_secs_f32()) as u32 + 10;
+        for _ in 0..ticks {
+            app.update();
+        }
+
+        assert_eq!(app.world().get::<GraveState>(grave).unwrap().filled, 1);
+        assert!(app.world().get::<Slotted>(resting).is_some());
+        assert!(app.world().get::<Slotted>(held).is_none());
+        assert!(app.world().get::<Slotted>(walled_off).is_none());
+    }
+}
@@ -0,0 +1,144 @@
+//! Grenades, bought at the store and thrown from their consumable slot. They arc under
+//! gravity and go off when the fuse runs out, or straight away when they slam into something.
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource, game_running, gameplay::explosion::Explosion, screens::Screen,
+    third_party::avian3d::CollisionLayer,
+};
+
+const FUSE_SECONDS: f32 = 2.0;
+const GRENADE_RADIUS: f32 = 0.12;
+const THROW_SPEED: f32 = 14.0;
+/// Added on top of the throw so it arcs instead of flying flat.
+const THROW_LIFT: f32 = 3.0;
+/// Thrown from this far in front of the camera, clear of the player's own collider.
+const THROW_OFFSET: f32 = 0.8;
+/// Speed lost or gained between two frames that counts as slamming into something rather
+/// than bouncing or rolling.
+const HARD_IMPACT_SPEED: f32 = 10.0;
+/// Fuse left on a grenade caught in another explosion.
+const CHAIN_FUSE_SECONDS: f32 = 0.1;
+const BLAST_RADIUS: f32 = 5.0;
+const BLAST_DAMAGE: f32 = 80.0;
+const BLAST_IMPULSE: f32 = 12.0;
+const CRATER_RADIUS: f32 = 1.5;
+const THROWER_FACTION: &str = "player";
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<GrenadeAssets>();
+    app.add_observer(throw_grenade);
+    app.add_observer(chain_grenades);
+    app.add_systems(Update, detonate_grenades.run_if(game_running));
+}
+
+/// Throws a grenade the way `from` is facing, usually the player camera.
+#[derive(Event, Debug)]
+pub(crate) struct ThrowGrenade {
+    pub from: Transform,
+}
+
+#[derive(Component)]
+struct Grenade {
+    fuse: Timer,
+    /// Velocity last frame, to tell a hard impact from a bounce.
+    last_velocity: Vec3,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct GrenadeAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for GrenadeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Sphere::new(GRENADE_RADIUS));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(0.25, 0.3, 0.15),
+                perceptual_roughness: 0.7,
+                ..default()
+            });
+        Self { mesh, material }
+    }
+}
+
+fn throw_grenade(throw: On<ThrowGrenade>, mut commands: Commands, assets: Res<GrenadeAssets>) {
+    let forward = throw.from.forward();
+    let velocity = forward * THROW_SPEED + Vec3::Y * THROW_LIFT;
+    commands.spawn((
+        Name::new("Grenade"),
+        Grenade {
+            fuse: Timer::from_seconds(FUSE_SECONDS, TimerMode::Once),
+            last_velocity: velocity,
+        },
+        Transform::from_translation(throw.from.translation + forward * THROW_OFFSET),
+        RigidBody::Dynamic,
+        Collider::sphere(GRENADE_RADIUS),
+        CollisionLayers::new(
+            CollisionLayer::Prop,
+            [
+                CollisionLayer::Level,
+                CollisionLayer::Prop,
+                CollisionLayer::Character,
+            ],
+        ),
+        LinearVelocity(velocity),
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material.clone()),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn detonate_grenades(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut grenades: Query<(Entity, &GlobalTransform, &LinearVelocity, &mut Grenade)>,
+) {
+    for (entity, transform, velocity, mut grenade) in &mut grenades {
+        grenade.fuse.tick(time.delta());
+        let impact = (velocity.0 - grenade.last_velocity).length() > HARD_IMPACT_SPEED;
+        grenade.last_velocity = velocity.0;
+        if !grenade.fuse.is_finished() && !impact {
+            continue;
+        }
+        commands.trigger(Explosion {
+            position: transform.translation(),
+            radius: BLAST_RADIUS,
+            damage: BLAST_DAMAGE,
+            carve_radius: CRATER_RADIUS,
+            impulse: BLAST_IMPULSE,
+            faction: Some(THROWER_FACTION.to_string()),
+            source: Some(entity),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Grenades caught in a blast go off a moment later, rather than inside this one.
+fn chain_grenades(
+    explosion: On<Explosion>,
+    mut grenades: Query<(Entity, &GlobalTransform, &mut Grenade)>,
+) {
+    for (entity, transform, mut grenade) in &mut grenades {
+        if Some(entity) == explosion.source
+            || transform.translation().distance(explosion.position) > explosion.radius
+            || grenade.fuse.remaining_secs() <= CHAIN_FUSE_SECONDS
+        {
+            continue;
+        }
+        let duration = grenade.fuse.duration();
+        grenade
+            .fuse
+            .set_elapsed(duration - Duration::from_secs_f32(CHAIN_FUSE_SECONDS));
+    }
+}
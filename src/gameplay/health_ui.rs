@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 
+use super::hud::{HudElement, HudSettings};
 use super::npc::Health;
-use super::player::{PlayerDead, PlayerHealth, camera::PlayerCamera};
-use crate::{screens::Screen, theme::GameFont};
+use super::player::{PlayerDead, PlayerHealth, camera::WorldModelCamera, sprint::Stamina};
+use crate::{game_running, screens::Screen, theme::GameFont};
 
 pub fn plugin(app: &mut App) {
     app.add_observer(spawn_healthbar);
@@ -12,15 +13,18 @@ pub fn plugin(app: &mut App) {
     app.add_systems(
         Update,
         (
-            billboard_healthbars,
-            update_healthbars,
-            update_player_health_bar.run_if(in_state(Screen::Gameplay)),
+            (tick_healthbars.run_if(game_running), layout_healthbars).chain(),
+            (update_player_health_bar, update_player_stamina_bar)
+                .run_if(in_state(Screen::Gameplay)),
         ),
     );
 }
 
+/// World space width of a health bar, projected to pixels every frame so it still shrinks with distance.
 const BAR_WIDTH: f32 = 1.0;
-const BAR_HEIGHT: f32 = 0.08;
+const BAR_HEIGHT_PX: f32 = 6.0;
+/// Far away bars don't get any thinner than this.
+const BAR_MIN_WIDTH_PX: f32 = 24.0;
 const BAR_OFFSET_Y: f32 = 1.8;
 
 /// How long the bar stays fully visible after taking damage.
@@ -28,6 +32,7 @@ const SHOW_DURATION: f32 = 2.0;
 /// How long the bar takes to fade out after SHOW_DURATION expires.
 const FADE_DURATION: f32 = 1.0;
 
+/// Screen space bar over an NPC, only laid out while it's visible.
 #[derive(Component)]
 struct HealthBar {
     target: Entity,
@@ -40,122 +45,52 @@ struct HealthBar {
 #[derive(Component)]
 struct HealthBarFill;
 
-#[derive(Component)]
-struct HealthBarBg;
-
-fn spawn_healthbar(
-    add: On<Add, Health>,
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    health_query: Query<&Health>,
-) {
+fn spawn_healthbar(add: On<Add, Health>, mut commands: Commands, health_query: Query<&Health>) {
     let entity = add.entity;
     let initial_health = health_query.get(entity).map(|h| h.0).unwrap_or(100.0);
 
-    let bg_mesh = meshes.add(Plane3d::new(
-        Vec3::Z,
-        Vec2::new(BAR_WIDTH / 2.0, BAR_HEIGHT / 2.0),
-    ));
-    let fill_mesh = meshes.add(Plane3d::new(
-        Vec3::Z,
-        Vec2::new(BAR_WIDTH / 2.0, BAR_HEIGHT / 2.0),
-    ));
-
-    let bg_mat = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.0, 0.0, 0.0, 0.0),
-        unlit: true,
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    });
-
-    let fill_mat = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.8, 0.1, 0.1, 0.0),
-        unlit: true,
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    });
-
-    commands
-        .spawn((
-            Name::new("Health Bar"),
-            HealthBar {
-                target: entity,
-                max_health: initial_health,
-                prev_health: initial_health,
-                show_timer: 0.0,
-                opacity: 0.0,
+    commands.spawn((
+        Name::new("Health Bar"),
+        HealthBar {
+            target: entity,
+            max_health: initial_health,
+            prev_health: initial_health,
+            show_timer: 0.0,
+            opacity: 0.0,
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(BAR_MIN_WIDTH_PX),
+            height: Val::Px(BAR_HEIGHT_PX),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.0)),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            HealthBarFill,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
             },
-            Transform::from_translation(Vec3::ZERO),
-            Visibility::Inherited,
-        ))
-        .with_children(|parent| {
-            // Background
-            parent.spawn((
-                HealthBarBg,
-                Mesh3d(bg_mesh),
-                MeshMaterial3d(bg_mat),
-                Transform::from_translation(Vec3::new(0.0, 0.0, -0.001)),
-            ));
-
-            // Fill
-            parent.spawn((
-                HealthBarFill,
-                Mesh3d(fill_mesh),
-                MeshMaterial3d(fill_mat),
-                Transform::IDENTITY,
-            ));
-        });
-}
-
-fn billboard_healthbars(
-    camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
-    mut bars: Query<&mut Transform, (With<HealthBar>, Without<PlayerCamera>)>,
-) {
-    let Some(camera) = camera else { return };
-    let cam_pos = camera.translation();
-
-    for mut transform in &mut bars {
-        let dir = cam_pos - transform.translation;
-        let dir_flat = Vec3::new(dir.x, 0.0, dir.z);
-        if dir_flat.length_squared() > 1e-6 {
-            transform.look_to(-dir_flat.normalize(), Vec3::Y);
-        }
-    }
+            BackgroundColor(Color::srgba(0.8, 0.1, 0.1, 0.0)),
+        )],
+    ));
 }
 
-fn update_healthbars(
+/// Shows bars when their target takes damage and fades them out again.
+fn tick_healthbars(
     mut commands: Commands,
-    mut bars: Query<(Entity, &mut HealthBar, &Children)>,
-    mut fills: Query<
-        &mut Transform,
-        (
-            With<HealthBarFill>,
-            Without<HealthBar>,
-            Without<HealthBarBg>,
-        ),
-    >,
-    health_query: Query<(&Health, &GlobalTransform)>,
-    mut bar_transforms: Query<
-        &mut Transform,
-        (
-            With<HealthBar>,
-            Without<HealthBarFill>,
-            Without<HealthBarBg>,
-        ),
-    >,
     time: Res<Time>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    fill_mats: Query<
-        &MeshMaterial3d<StandardMaterial>,
-        (With<HealthBarFill>, Without<HealthBarBg>),
-    >,
-    bg_mats: Query<&MeshMaterial3d<StandardMaterial>, With<HealthBarBg>>,
+    mut bars: Query<(Entity, &mut HealthBar)>,
+    health_query: Query<&Health>,
 ) {
     let dt = time.delta_secs();
 
-    for (bar_entity, mut bar, children) in &mut bars {
-        let Ok((health, target_transform)) = health_query.get(bar.target) else {
+    for (bar_entity, mut bar) in &mut bars {
+        let Ok(health) = health_query.get(bar.target) else {
             commands.entity(bar_entity).despawn();
             continue;
         };
@@ -171,30 +106,74 @@ fn update_healthbars(
         } else if bar.opacity > 0.0 {
             bar.opacity = (bar.opacity - dt / FADE_DURATION).max(0.0);
         }
+    }
+}
 
-        let opacity = bar.opacity;
-        for child in children.iter() {
-            if let Ok(mat_handle) = fill_mats.get(child) {
-                if let Some(mat) = materials.get_mut(&mat_handle.0) {
-                    mat.base_color = Color::srgba(0.8, 0.1, 0.1, opacity);
-                }
-            }
-            if let Ok(mat_handle) = bg_mats.get(child) {
-                if let Some(mat) = materials.get_mut(&mat_handle.0) {
-                    mat.base_color = Color::srgba(0.0, 0.0, 0.0, 0.6 * opacity);
-                }
-            }
-        }
+/// Projects visible bars over their targets. Faded out bars are skipped entirely,
+/// so the per-frame cost only grows with the bars actually on screen.
+fn layout_healthbars(
+    camera: Option<Single<(&Camera, &GlobalTransform), With<WorldModelCamera>>>,
+    mut bars: Query<
+        (
+            &HealthBar,
+            &Children,
+            &mut Node,
+            &mut BackgroundColor,
+            &mut Visibility,
+        ),
+        Without<HealthBarFill>,
+    >,
+    mut fills: Query<(&mut Node, &mut BackgroundColor), With<HealthBarFill>>,
+    targets: Query<(&Health, &GlobalTransform)>,
+) {
+    let Some(camera) = camera else { return };
+    let (camera, camera_transform) = *camera;
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    let half_width = camera_transform.right().as_vec3() * BAR_WIDTH / 2.0;
+
+    for (bar, children, mut node, mut background, mut visibility) in &mut bars {
+        let projected = targets
+            .get(bar.target)
+            .ok()
+            .filter(|_| bar.opacity > 0.0)
+            .and_then(|(health, transform)| {
+                let center = transform.translation() + Vec3::Y * BAR_OFFSET_Y;
+                let left = camera
+                    .world_to_viewport(camera_transform, center - half_width)
+                    .ok()?;
+                let right = camera
+                    .world_to_viewport(camera_transform, center + half_width)
+                    .ok()?;
+                Some((health, left, right))
+            })
+            // Offscreen bars are hidden rather than pinned to the edge of the screen.
+            .filter(|(_, left, right)| {
+                let middle = (*left + *right) / 2.0;
+                left.x.max(right.x) > 0.0
+                    && left.x.min(right.x) < viewport.x
+                    && middle.y > 0.0
+                    && middle.y < viewport.y
+            });
+        let Some((health, left, right)) = projected else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
 
-        if let Ok(mut bar_transform) = bar_transforms.get_mut(bar_entity) {
-            bar_transform.translation = target_transform.translation() + Vec3::Y * BAR_OFFSET_Y;
-        }
+        let middle = (left + right) / 2.0;
+        let width = (right.x - left.x).abs().max(BAR_MIN_WIDTH_PX);
+        node.width = Val::Px(width);
+        node.left = Val::Px(middle.x - width / 2.0);
+        node.top = Val::Px(middle.y - BAR_HEIGHT_PX / 2.0);
+        background.0 = Color::srgba(0.0, 0.0, 0.0, 0.6 * bar.opacity);
 
         let ratio = (health.0 / bar.max_health).clamp(0.0, 1.0);
         for child in children.iter() {
-            if let Ok(mut fill_transform) = fills.get_mut(child) {
-                fill_transform.scale.x = ratio;
-                fill_transform.translation.x = -(1.0 - ratio) * BAR_WIDTH / 2.0;
+            if let Ok((mut fill_node, mut fill_color)) = fills.get_mut(child) {
+                fill_node.width = Val::Percent(ratio * 100.0);
+                fill_color.0 = Color::srgba(0.8, 0.1, 0.1, bar.opacity);
             }
         }
     }
@@ -209,22 +188,57 @@ struct PlayerHealthBarFill;
 #[derive(Component)]
 struct PlayerHealthBarText;
 
-fn spawn_player_health_bar(mut commands: Commands, font: Res<GameFont>) {
+const STAMINA_BAR_HEIGHT: f32 = 6.0;
+
+/// Only shown while stamina isn't full.
+#[derive(Component)]
+struct PlayerStaminaBar;
+
+#[derive(Component)]
+struct PlayerStaminaBarFill;
+
+fn spawn_player_health_bar(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
+) {
     commands
         .spawn((
             Name::new("Player Health Bar"),
+            HudElement::Health,
+            hud_settings.slot(HudElement::Health, 0),
             Node {
-                position_type: PositionType::Absolute,
-                bottom: Val::Px(24.0),
-                left: Val::Px(24.0),
                 flex_direction: FlexDirection::Column,
                 row_gap: Val::Px(4.0),
                 ..default()
             },
             Pickable::IGNORE,
-            DespawnOnExit(Screen::Gameplay),
         ))
         .with_children(|parent| {
+            parent
+                .spawn((
+                    Name::new("Stamina Bar"),
+                    PlayerStaminaBar,
+                    Node {
+                        width: Val::Px(PLAYER_BAR_WIDTH),
+                        height: Val::Px(STAMINA_BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                    Visibility::Hidden,
+                ))
+                .with_children(|bg| {
+                    bg.spawn((
+                        PlayerStaminaBarFill,
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.85, 0.8, 0.3)),
+                    ));
+                });
+
             parent.spawn((
                 PlayerHealthBarText,
                 Text::new("3 / 3"),
@@ -285,6 +299,26 @@ fn update_player_health_bar(
     }
 }
 
+fn update_player_stamina_bar(
+    player: Option<Single<&Stamina>>,
+    mut bar: Query<&mut Visibility, With<PlayerStaminaBar>>,
+    mut fill: Query<&mut Node, With<PlayerStaminaBarFill>>,
+) {
+    let Some(stamina) = player else { return };
+    let ratio = stamina.fraction();
+
+    for mut visibility in &mut bar {
+        visibility.set_if_neq(if ratio < 1.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    for mut node in &mut fill {
+        node.width = Val::Percent(ratio * 100.0);
+    }
+}
+
 
 #[derive(Component)]
 struct DeathOverlay;
@@ -330,3 +364,70 @@ fn despawn_death_overlay(
         commands.entity(entity).despawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(100);
+    const NPC_COUNT: usize = 50;
+
+    fn bars(app: &mut App) -> Vec<(Entity, f32)> {
+        app.world_mut()
+            .query::<&HealthBar>()
+            .iter(app.world())
+            .map(|bar| (bar.target, bar.opacity))
+            .collect()
+    }
+
+    #[test]
+    fn healthbars_follow_damage_and_despawn_with_their_target() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+            .add_observer(spawn_healthbar)
+            .add_systems(Update, tick_healthbars);
+
+        let npcs: Vec<Entity> = (0..NPC_COUNT)
+            .map(|_| app.world_mut().spawn(Health(100.0)).id())
+            .collect();
+        app.update();
+
+        let spawned = bars(&mut app);
+        assert_eq!(spawned.len(), NPC_COUNT);
+        assert!(spawned.iter().all(|&(_, opacity)| opacity == 0.0));
+
+        // Only the damaged NPC's bar shows up.
+        app.world_mut().get_mut::<Health>(npcs[0]).unwrap().0 = 60.0;
+        app.update();
+        let shown: Vec<Entity> = bars(&mut app)
+            .into_iter()
+            .filter(|&(_, opacity)| opacity > 0.0)
+            .map(|(target, _)| target)
+            .collect();
+        assert_eq!(shown, vec![npcs[0]]);
+
+        // Fully faded out once the show and fade durations have passed.
+        let ticks = ((SHOW_DURATION + FADE_DURATION) / TICK.as_secs_f32()).ceil() as usize + 2;
+        for _ in 0..ticks {
+            app.update();
+        }
+        assert!(bars(&mut app).iter().all(|&(_, opacity)| opacity == 0.0));
+
+        for &npc in &npcs[..10] {
+            app.world_mut().despawn(npc);
+        }
+        app.update();
+        let remaining = bars(&mut app);
+        assert_eq!(remaining.len(), NPC_COUNT - 10);
+        assert!(
+            remaining
+                .iter()
+                .all(|(target, _)| !npcs[..10].contains(target))
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Emissive highlight on whatever interactable the player is looking at, next to the
+//! crosshair's square.
+//!
+//! Only the targeted entity's materials are touched: they're swapped for brighter copies
+//! while it's targeted and swapped back after.
+
+use std::iter;
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HighlightSettings>();
+    app.add_observer(highlight_meshes);
+    app.add_observer(unhighlight_meshes);
+}
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct HighlightSettings {
+    pub enabled: bool,
+    /// Base color added to the emissive of highlighted meshes.
+    pub strength: f32,
+}
+
+impl Default for HighlightSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strength: 0.6,
+        }
+    }
+}
+
+/// On the interactable the player is currently looking at.
+#[derive(Component)]
+pub(crate) struct Highlighted;
+
+/// On a highlighted mesh, the material it had before.
+#[derive(Component)]
+pub(crate) struct HighlightOriginal(Handle<StandardMaterial>);
+
+/// Moves the highlight from whatever `looked_at` held to `target`. Called by the
+/// `check_looking_at_*` systems in place of setting their resource directly.
+pub(crate) fn look_at(
+    commands: &mut Commands,
+    looked_at: &mut Option<Entity>,
+    target: Option<Entity>,
+) {
+    if *looked_at == target {
+        return;
+    }
+    if let Some(previous) = looked_at.take() {
+        commands.entity(previous).try_remove::<Highlighted>();
+    }
+    if let Some(target) = target {
+        commands.entity(target).try_insert(Highlighted);
+    }
+    *looked_at = target;
+}
+
+fn highlight_meshes(
+    add: On<Add, Highlighted>,
+    mut commands: Commands,
+    settings: Res<HighlightSettings>,
+    children: Query<&Children>,
+    mut meshes: Query<&mut MeshMaterial3d<StandardMaterial>, Without<HighlightOriginal>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !settings.enabled {
+        return;
+    }
+    for entity in iter::once(add.entity).chain(children.iter_descendants(add.entity)) {
+        let Ok(mut material) = meshes.get_mut(entity) else {
+            continue;
+        };
+        let Some(mut highlighted) = materials.get(&material.0).cloned() else {
+            continue;
+        };
+        highlighted.emissive += highlighted.base_color.to_linear() * settings.strength;
+        commands
+            .entity(entity)
+            .insert(HighlightOriginal(material.0.clone()));
+        material.0 = materials.add(highlighted);
+    }
+}
+
+fn unhighlight_meshes(
+    remove: On<Remove, Highlighted>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    mut meshes: Query<(&mut MeshMaterial3d<StandardMaterial>, &HighlightOriginal)>,
+) {
+    for entity in iter::once(remove.entity).chain(children.iter_descendants(remove.entity)) {
+        let Ok((mut material, original)) = meshes.get_mut(entity) else {
+            continue;
+        };
+        material.0 = original.0.clone();
+        commands.entity(entity).try_remove::<HighlightOriginal>();
+    }
+}
@@ -0,0 +1,567 @@
+//! Layout scaffold and shared visibility toggles for the gameplay HUD.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+use bevy_enhanced_input::prelude::*;
+
+use crate::{screens::Screen, theme::GameFont};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<HudVisibility>();
+    app.init_resource::<HudKeybinds>();
+    app.init_resource::<HudSettings>();
+    app.add_input_context::<HudInputContext>();
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (spawn_hud_scaffold, spawn_hud_input),
+    );
+    app.add_observer(toggle_hud);
+    app.add_observer(toggle_objective_panel);
+    app.add_observer(toggle_minimap);
+    app.add_observer(show_hint);
+    app.add_observer(dismiss_hint);
+    app.add_systems(
+        Update,
+        (
+            (
+                move_hud_slots.run_if(resource_changed::<HudSettings>),
+                attach_hud_slots,
+                relayout_hud_anchors,
+                scale_hud_anchors,
+            )
+                .chain()
+                .run_if(in_state(Screen::Gameplay)),
+            apply_hud_visibility,
+            fade_hints,
+        ),
+    );
+}
+
+/// Padding between the anchors and the screen edge.
+const HUD_PADDING: f32 = 16.0;
+/// Gap between widgets stacked in the same anchor.
+const HUD_GAP: f32 = 12.0;
+pub(crate) const MIN_HUD_SCALE: f32 = 0.5;
+pub(crate) const MAX_HUD_SCALE: f32 = 2.0;
+/// Window height the HUD's pixel sizes are laid out for. Taller windows scale it up to match.
+const REFERENCE_HEIGHT: f32 = 1080.0;
+/// How long a hint stays up, including its fade out.
+const HINT_SECONDS: f32 = 6.0;
+const HINT_FADE_SECONDS: f32 = 1.0;
+
+/// Screen region that HUD widgets stack into.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub(crate) enum HudAnchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    Center,
+}
+
+impl HudAnchor {
+    const ALL: [HudAnchor; 5] = [
+        HudAnchor::TopLeft,
+        HudAnchor::TopRight,
+        HudAnchor::BottomLeft,
+        HudAnchor::BottomCenter,
+        HudAnchor::Center,
+    ];
+
+    fn node(self) -> Node {
+        let node = Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(HUD_PADDING)),
+            row_gap: Val::Px(HUD_GAP),
+            ..default()
+        };
+        match self {
+            HudAnchor::TopLeft => Node {
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                align_items: AlignItems::FlexStart,
+                ..node
+            },
+            HudAnchor::TopRight => Node {
+                top: Val::Px(0.0),
+                right: Val::Px(0.0),
+                align_items: AlignItems::FlexEnd,
+                ..node
+            },
+            HudAnchor::BottomLeft => Node {
+                bottom: Val::Px(0.0),
+                left: Val::Px(0.0),
+                align_items: AlignItems::FlexStart,
+                ..node
+            },
+            HudAnchor::BottomCenter => Node {
+                bottom: Val::Px(0.0),
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                align_items: AlignItems::Center,
+                ..node
+            },
+            HudAnchor::Center => Node {
+                top: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..node
+            },
+        }
+    }
+
+    /// Direction the anchor grows in when scaled, so it stays pinned to its screen edge.
+    fn growth(self) -> Vec2 {
+        match self {
+            HudAnchor::TopLeft => Vec2::new(1.0, 1.0),
+            HudAnchor::TopRight => Vec2::new(-1.0, 1.0),
+            HudAnchor::BottomLeft => Vec2::new(1.0, -1.0),
+            HudAnchor::BottomCenter => Vec2::new(0.0, -1.0),
+            HudAnchor::Center => Vec2::ZERO,
+        }
+    }
+}
+
+/// Moves a widget root into its anchor, stacked by ascending `order`.
+/// The widget is despawned along with the scaffold, so it doesn't need its own `DespawnOnExit`.
+#[derive(Component, Debug, Clone, Copy)]
+pub(crate) struct HudSlot {
+    pub anchor: HudAnchor,
+    pub order: u8,
+}
+
+impl HudSlot {
+    pub fn new(anchor: HudAnchor, order: u8) -> Self {
+        Self { anchor, order }
+    }
+}
+
+/// Size and placement of the HUD widgets.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct HudSettings {
+    /// Multiplier on the size of every HUD widget.
+    pub scale: f32,
+    pub crusts: HudAnchor,
+    pub inventory: HudAnchor,
+    pub health: HudAnchor,
+    pub objectives: HudAnchor,
+    pub minimap: HudAnchor,
+    pub timer: HudAnchor,
+    /// The objective panel lists only the current sub-objective instead of every one so far.
+    pub compact_objectives: bool,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            crusts: HudAnchor::TopLeft,
+            inventory: HudAnchor::BottomCenter,
+            health: HudAnchor::BottomLeft,
+            objectives: HudAnchor::TopLeft,
+            minimap: HudAnchor::TopRight,
+            timer: HudAnchor::TopRight,
+            compact_objectives: false,
+        }
+    }
+}
+
+impl HudSettings {
+    pub fn anchor(&self, element: HudElement) -> HudAnchor {
+        match element {
+            HudElement::Crusts => self.crusts,
+            HudElement::Inventory => self.inventory,
+            HudElement::Health => self.health,
+            HudElement::Objectives => self.objectives,
+            HudElement::Minimap => self.minimap,
+            HudElement::Timer => self.timer,
+        }
+    }
+
+    /// Slot for `element` in its configured anchor.
+    pub fn slot(&self, element: HudElement, order: u8) -> HudSlot {
+        HudSlot::new(self.anchor(element), order)
+    }
+
+    /// Scale the anchors are drawn at in `window`: the player's `scale`, times how far the
+    /// window is from the 1080p the widgets' pixel sizes were picked for.
+    pub fn scale_for(&self, window: &Window) -> f32 {
+        let height = window.height();
+        if height <= 0.0 {
+            // Minimized.
+            return self.scale;
+        }
+        (self.scale * height / REFERENCE_HEIGHT).clamp(MIN_HUD_SCALE, MAX_HUD_SCALE)
+    }
+}
+
+/// Which parts of the HUD are shown. `hidden` overrides every element, for screenshots.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct HudVisibility {
+    pub hidden: bool,
+    pub crusts: bool,
+    pub inventory: bool,
+    pub health: bool,
+    pub objectives: bool,
+    pub minimap: bool,
+    /// The speedrun timer, off unless turned on in the settings.
+    pub timer: bool,
+    /// Set for the length of a conversation, hides the inventory and health behind the
+    /// letterbox without touching the player's own toggles.
+    pub in_dialogue: bool,
+}
+
+impl Default for HudVisibility {
+    fn default() -> Self {
+        Self {
+            hidden: false,
+            crusts: true,
+            inventory: true,
+            health: true,
+            objectives: true,
+            minimap: true,
+            timer: false,
+            in_dialogue: false,
+        }
+    }
+}
+
+impl HudVisibility {
+    pub fn shows(&self, element: HudElement) -> bool {
+        if self.hidden
+            || self.in_dialogue && matches!(element, HudElement::Inventory | HudElement::Health)
+        {
+            return false;
+        }
+        match element {
+            HudElement::Crusts => self.crusts,
+            HudElement::Inventory => self.inventory,
+            HudElement::Health => self.health,
+            HudElement::Objectives => self.objectives,
+            HudElement::Minimap => self.minimap,
+            HudElement::Timer => self.timer,
+        }
+    }
+}
+
+/// Keys for the HUD actions, read when entering gameplay.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct HudKeybinds {
+    /// Hold to show the full objective panel, tap to pin it open or closed.
+    pub objectives: KeyCode,
+    /// Shows or hides the objective panel entirely.
+    pub objective_panel: KeyCode,
+    pub hide_hud: KeyCode,
+    pub minimap: KeyCode,
+    pub dismiss_hint: KeyCode,
+}
+
+impl Default for HudKeybinds {
+    fn default() -> Self {
+        Self {
+            objectives: KeyCode::Tab,
+            objective_panel: KeyCode::KeyO,
+            hide_hud: KeyCode::F1,
+            minimap: KeyCode::KeyM,
+            dismiss_hint: KeyCode::Backspace,
+        }
+    }
+}
+
+/// Root of a HUD element whose visibility follows [`HudVisibility`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub(crate) enum HudElement {
+    Crusts,
+    Inventory,
+    Health,
+    Objectives,
+    Minimap,
+    Timer,
+}
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleObjectives;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+struct ToggleObjectivePanel;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleHud;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleMinimap;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct DismissHint;
+
+/// Kept separate from the player context so the HUD can still be toggled during dialogue.
+#[derive(Debug, Component, Default)]
+struct HudInputContext;
+
+fn spawn_hud_input(mut commands: Commands, keybinds: Res<HudKeybinds>) {
+    commands.spawn((
+        Name::new("HudInput"),
+        HudInputContext,
+        DespawnOnExit(Screen::Gameplay),
+        actions!(HudInputContext[
+            (Action::<ToggleObjectives>::new(), bindings![keybinds.objectives]),
+            (
+                Action::<ToggleObjectivePanel>::new(),
+                bindings![keybinds.objective_panel]
+            ),
+            (Action::<ToggleHud>::new(), bindings![keybinds.hide_hud]),
+            (Action::<ToggleMinimap>::new(), bindings![keybinds.minimap]),
+            (Action::<DismissHint>::new(), bindings![keybinds.dismiss_hint]),
+        ]),
+    ));
+}
+
+fn spawn_hud_scaffold(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("HUD"),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            Pickable::IGNORE,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .with_children(|hud| {
+            for anchor in HudAnchor::ALL {
+                hud.spawn((
+                    Name::new(format!("HUD {anchor:?}")),
+                    anchor,
+                    anchor.node(),
+                    UiTransform::default(),
+                    Pickable::IGNORE,
+                ));
+            }
+        });
+}
+
+fn attach_hud_slots(
+    mut commands: Commands,
+    anchors: Query<(Entity, &HudAnchor, Option<&Children>)>,
+    detached: Query<(Entity, &HudSlot), Without<ChildOf>>,
+    attached: Query<&HudSlot, With<ChildOf>>,
+) {
+    let mut pending: Vec<_> = detached.iter().collect();
+    // Highest order first, so lower ones inserted at the same index end up in front.
+    pending.sort_by_key(|(_, slot)| std::cmp::Reverse(slot.order));
+
+    for (entity, slot) in pending {
+        let Some((anchor_entity, _, children)) = anchors
+            .iter()
+            .find(|(_, anchor, _)| **anchor == slot.anchor)
+        else {
+            continue;
+        };
+        let index = children.map_or(0, |children| {
+            children
+                .iter()
+                .filter(|child| attached.get(*child).is_ok_and(|s| s.order <= slot.order))
+                .count()
+        });
+        commands
+            .entity(anchor_entity)
+            .insert_children(index, &[entity]);
+    }
+}
+
+/// Detach widgets whose anchor setting changed, so `attach_hud_slots` moves them over.
+fn move_hud_slots(
+    mut commands: Commands,
+    settings: Res<HudSettings>,
+    mut slots: Query<(Entity, &HudElement, &mut HudSlot)>,
+) {
+    for (entity, element, mut slot) in &mut slots {
+        let anchor = settings.anchor(*element);
+        if slot.anchor != anchor {
+            slot.anchor = anchor;
+            commands.entity(entity).remove::<ChildOf>();
+        }
+    }
+}
+
+/// Lays the anchors out again after a resize or fullscreen toggle, so they don't keep their
+/// edges from the old window size for a frame.
+fn relayout_hud_anchors(
+    mut resized: MessageReader<WindowResized>,
+    mut anchors: Query<&mut Node, With<HudAnchor>>,
+) {
+    if resized.read().count() == 0 {
+        return;
+    }
+    for mut node in &mut anchors {
+        node.set_changed();
+    }
+}
+
+/// Scales each anchor around its screen edge rather than its center.
+fn scale_hud_anchors(
+    settings: Res<HudSettings>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut anchors: Query<(&HudAnchor, &ComputedNode, &mut UiTransform)>,
+) {
+    let scale = settings.scale_for(&window);
+    for (anchor, computed, mut transform) in &mut anchors {
+        let size = computed.size() * computed.inverse_scale_factor();
+        let offset = (scale - 1.0) * 0.5 * size * anchor.growth();
+        transform.set_if_neq(UiTransform {
+            translation: Val2::px(offset.x, offset.y),
+            scale: Vec2::splat(scale),
+            ..default()
+        });
+    }
+}
+
+/// Shows a short tutorial hint at the top of the screen, replacing any hint already shown.
+#[derive(Event, Debug)]
+pub(crate) struct ShowHint {
+    pub text: String,
+    /// Passed along in [`HintDismissed`], so whoever showed the hint can tell it was dismissed.
+    pub id: Option<&'static str>,
+}
+
+impl ShowHint {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            id: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: &'static str) -> Self {
+        self.id = Some(id);
+        self
+    }
+}
+
+/// The player closed a hint before it faded out.
+#[derive(Event, Debug)]
+pub(crate) struct HintDismissed {
+    pub id: Option<&'static str>,
+}
+
+#[derive(Component)]
+struct Hint {
+    timer: Timer,
+    id: Option<&'static str>,
+}
+
+fn show_hint(
+    hint: On<ShowHint>,
+    mut commands: Commands,
+    existing: Query<Entity, With<Hint>>,
+    font: Res<GameFont>,
+) {
+    for entity in &existing {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        Name::new("Hint"),
+        Hint {
+            timer: Timer::from_seconds(HINT_SECONDS, TimerMode::Once),
+            id: hint.id,
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(20.0),
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            Text::new(hint.text.clone()),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextShadow::default(),
+            Pickable::IGNORE,
+        )],
+    ));
+}
+
+fn dismiss_hint(
+    _on: On<Start<DismissHint>>,
+    mut commands: Commands,
+    hints: Query<(Entity, &Hint)>,
+) {
+    for (entity, hint) in &hints {
+        commands.entity(entity).despawn();
+        commands.trigger(HintDismissed { id: hint.id });
+    }
+}
+
+fn fade_hints(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut hints: Query<(Entity, &mut Hint, &Children)>,
+    mut texts: Query<&mut TextColor>,
+) {
+    for (entity, mut hint, children) in &mut hints {
+        hint.timer.tick(time.delta());
+        if hint.timer.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (hint.timer.remaining_secs() / HINT_FADE_SECONDS).min(1.0);
+        for child in children.iter() {
+            if let Ok(mut color) = texts.get_mut(child) {
+                color.0 = color.0.with_alpha(alpha);
+            }
+        }
+    }
+}
+
+fn toggle_hud(_on: On<Start<ToggleHud>>, mut hud: ResMut<HudVisibility>) {
+    hud.hidden = !hud.hidden;
+}
+
+fn toggle_objective_panel(_on: On<Start<ToggleObjectivePanel>>, mut hud: ResMut<HudVisibility>) {
+    hud.objectives = !hud.objectives;
+}
+
+fn toggle_minimap(_on: On<Start<ToggleMinimap>>, mut hud: ResMut<HudVisibility>) {
+    hud.minimap = !hud.minimap;
+}
+
+fn apply_hud_visibility(
+    hud: Res<HudVisibility>,
+    mut elements: Query<(Ref<HudElement>, &mut Visibility)>,
+) {
+    for (element, mut visibility) in &mut elements {
+        if !hud.is_changed() && !element.is_added() {
+            continue;
+        }
+        *visibility = if hud.shows(*element) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
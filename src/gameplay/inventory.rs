@@ -3,24 +3,40 @@ use std::time::Duration;
 
 use avian3d::prelude::*;
 use bevy::{
-    camera::visibility::RenderLayers, light::NotShadowCaster, prelude::*,
-    scene::SceneInstanceReady, ui::widget::ViewportNode,
+    camera::visibility::RenderLayers, ecs::system::SystemParam,
+    input::mouse::AccumulatedMouseMotion, light::NotShadowCaster, prelude::*,
+    scene::SceneInstanceReady, time::Stopwatch, ui::widget::ViewportNode,
 };
+use bevy_ahoy::{CharacterController, prelude::RotateCamera};
 use bevy_enhanced_input::prelude::*;
 use bevy_hanabi::prelude::{Gradient as HanabiGradient, *};
 use bevy_seedling::prelude::*;
 use bevy_shuffle_bag::ShuffleBag;
 
 use crate::{
-    RenderLayer,
-    asset_tracking::LoadResource,
+    PostPhysicsAppSystems, RenderLayer,
+    asset_tracking::{LoadResource, PickLoaded as _},
     audio::SpatialPool,
+    game_running,
     gameplay::{
-        dig::{VOXEL_SIZE, Voxel, VoxelAabbOf, VoxelSim},
-        npc::{Health, shooting::{AggroConfig, AggroTarget}},
-        player::camera::PlayerCamera,
+        barrel::ExplosiveBarrel,
+        dig::{VOXEL_SIZE, Voxel, VoxelAabbOf, VoxelShape, VoxelSim, VoxelsDug, world_to_voxel},
+        grenade::ThrowGrenade,
+        hud::{HudElement, HudSettings},
+        npc::{
+            Health, NpcDead,
+            shooting::{
+                AggroConfig, AggroTarget, Faction, FactionRelations, PLAYER_FACTION, Provoked,
+            },
+        },
+        player::{
+            Player, PlayerHealth, camera::PlayerCamera, camera_shake::ReduceMotion,
+            input::BlocksInput,
+        },
     },
+    graphics::GraphicsQuality,
     screens::Screen,
+    theme::GameFont,
     third_party::avian3d::CollisionLayer,
 };
 
@@ -28,6 +44,8 @@ pub fn plugin(app: &mut App) {
     app.init_resource::<Inventory>();
     app.init_resource::<DigCooldown>();
     app.init_resource::<GunCooldown>();
+    app.init_resource::<AimAssist>();
+    app.init_resource::<HeldItemSway>();
     app.load_resource::<ToolEffects>();
     app.load_resource::<InventoryAssets>();
     for i in 1..=25 {
@@ -42,16 +60,47 @@ pub fn plugin(app: &mut App) {
         Update,
         update_held_item.run_if(resource_changed::<Inventory>.or(held_item_missing)),
     );
-    app.add_systems(Update, (use_tool, animate_shovel_swing, animate_gun_recoil));
+    app.add_systems(
+        Update,
+        tick_tool_cooldowns
+            .run_if(game_running)
+            .in_set(PostPhysicsAppSystems::TickTimers),
+    );
+    app.add_systems(
+        Update,
+        (
+            use_tool,
+            animate_shovel_swing,
+            animate_gun_recoil,
+            (examine_held_item, sway_held_item, pose_held_item_pivots).chain(),
+            tick_speed_boost,
+        )
+            .run_if(game_running),
+    );
+    app.add_systems(
+        PreUpdate,
+        hold_camera_while_examining
+            .after(EnhancedInputSystems::Update)
+            .run_if(game_running),
+    );
     app.add_observer(on_select_slot::<SelectSlot1, 0>);
     app.add_observer(on_select_slot::<SelectSlot2, 1>);
     app.add_observer(on_select_slot::<SelectSlot3, 2>);
+    app.add_observer(on_select_slot::<SelectSlot4, 3>);
+    app.add_observer(on_select_slot::<SelectSlot5, 4>);
+    app.add_observer(on_select_slot::<SelectSlot6, 5>);
+    app.add_observer(cycle_slot);
+    app.add_observer(quick_swap);
+    app.add_observer(on_use_consumable);
+    app.add_observer(toggle_dig_mode);
 }
 
 #[derive(Resource)]
 pub(crate) struct Inventory {
-    pub slots: [Option<Item>; 3],
+    pub slots: [Option<Item>; 6],
     pub active_slot: usize,
+    /// Slot that was active before the last selection, for [`QuickSwap`].
+    pub previous_slot: usize,
     pub using_hands: bool,
 }
 
@@ -62,8 +111,19 @@ impl Default for Inventory {
                 Some(Item::Shovel(DigStats::default())),
                 Some(Item::Gun(GunStats::default())),
                 Some(Item::DirtBucket(DigStats::default())),
+                Some(Item::Consumable {
+                    kind: ConsumableKind::Bandage,
+                    count: STARTING_BANDAGES,
+                }),
+                Some(Item::Consumable {
+                    kind: ConsumableKind::Tonic,
+                    count: STARTING_TONICS,
+                }),
+                // Bought at the store.
+                None,
             ],
             active_slot: 0,
+            previous_slot: 0,
             using_hands: false,
         }
     }
@@ -77,6 +137,50 @@ impl Inventory {
             self.slots[self.active_slot].as_ref()
         }
     }
+
+    /// Puts `item` in its slot. Consumables stack onto what's there, a tool already held is kept.
+    pub(crate) fn give(&mut self, item: Item) {
+        let slot = item.slot();
+        match (&mut self.slots[slot], item) {
+            (Some(Item::Consumable { count, .. }), Item::Consumable { count: added, .. }) => {
+                *count += added;
+            }
+            (Some(_), _) => {}
+            (empty, item) => *empty = Some(item),
+        }
+    }
+
+    /// Whether [`Self::give`] would add `item` instead of keeping the tool already held.
+    pub(crate) fn has_room_for(&self, item: &Item) -> bool {
+        matches!(item, Item::Consumable { .. }) || self.slots[item.slot()].is_none()
+    }
+
+    /// Removes the active item, whole stack included, and leaves empty hands out.
+    pub(crate) fn take_active(&mut self) -> Option<Item> {
+        if self.using_hands {
+            return None;
+        }
+        let item = self.slots[self.active_slot].take()?;
+        self.using_hands = true;
+        Some(item)
+    }
+
+    /// Takes `slot` out, remembering the slot it replaced.
+    fn select(&mut self, slot: usize) {
+        if slot != self.active_slot {
+            self.previous_slot = self.active_slot;
+            self.active_slot = slot;
+        }
+        self.using_hands = false;
+    }
+
+    /// Next slot holding an item in `direction`, wrapping around.
+    fn next_filled_slot(&self, direction: isize) -> Option<usize> {
+        let len = self.slots.len() as isize;
+        (1..len)
+            .map(|step| (self.active_slot as isize + direction * step).rem_euclid(len) as usize)
+            .find(|&slot| self.slots[slot].is_some())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -84,6 +188,10 @@ pub(crate) struct DigStats {
     pub radius: f32,
     pub distance: f32,
     pub cooldown: f32,
+    pub mode: DigMode,
+    /// Only used by the bucket. [`VoxelShape::Sphere`] follows [`DigStats::mode`], so the
+    /// bucket can still fill flat.
+    pub fill_shape: VoxelShape,
 }
 
 impl Default for DigStats {
@@ -92,6 +200,25 @@ impl Default for DigStats {
             radius: 4.0,
             distance: 6.0,
             cooldown: 0.5,
+            mode: DigMode::Round,
+            fill_shape: VoxelShape::Sphere,
+        }
+    }
+}
+
+/// Shape of the shovel and bucket edits, toggled per item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DigMode {
+    Round,
+    /// Digs down to, or fills up to, the horizontal plane through the hit point.
+    Flat,
+}
+
+impl DigMode {
+    fn shape(self, center: IVec3) -> VoxelShape {
+        match self {
+            DigMode::Round => VoxelShape::Sphere,
+            DigMode::Flat => VoxelShape::FlatTop { plane_y: center.y },
         }
     }
 }
@@ -101,6 +228,8 @@ pub(crate) struct GunStats {
     pub damage: f32,
     pub distance: f32,
     pub cooldown: f32,
+    /// Keep firing while held, otherwise one shot per click.
+    pub auto: bool,
 }
 
 impl Default for GunStats {
@@ -109,6 +238,7 @@ impl Default for GunStats {
             damage: 10.0,
             distance: 50.0,
             cooldown: 0.2,
+            auto: true,
         }
     }
 }
@@ -118,6 +248,82 @@ pub(crate) enum Item {
     Shovel(DigStats),
     Gun(GunStats),
     DirtBucket(DigStats),
+    /// Used up on use, the slot empties when `count` hits zero.
+    Consumable {
+        kind: ConsumableKind,
+        count: u32,
+    },
+}
+
+impl Item {
+    /// Parses the item names used by maps and dialogue: `shovel`, `gun`, `bucket`, `bandage`,
+    /// `tonic`, `grenade`.
+    pub(crate) fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "shovel" => Some(Item::Shovel(DigStats::default())),
+            "gun" => Some(Item::Gun(GunStats::default())),
+            "bucket" => Some(Item::DirtBucket(DigStats::default())),
+            "bandage" => Some(Item::Consumable {
+                kind: ConsumableKind::Bandage,
+                count: STARTING_BANDAGES,
+            }),
+            "tonic" => Some(Item::Consumable {
+                kind: ConsumableKind::Tonic,
+                count: STARTING_TONICS,
+            }),
+            "grenade" => Some(Item::Consumable {
+                kind: ConsumableKind::Grenade,
+                count: 1,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The slot this kind of item always goes in.
+    fn slot(&self) -> usize {
+        match self {
+            Item::Shovel(_) => 0,
+            Item::Gun(_) => 1,
+            Item::DirtBucket(_) => 2,
+            Item::Consumable {
+                kind: ConsumableKind::Bandage,
+                ..
+            } => 3,
+            Item::Consumable {
+                kind: ConsumableKind::Tonic,
+                ..
+            } => 4,
+            Item::Consumable {
+                kind: ConsumableKind::Grenade,
+                ..
+            } => 5,
+        }
+    }
+
+    /// Whether this item has a model held in front of the camera.
+    fn has_view_model(&self) -> bool {
+        !matches!(self, Item::Consumable { .. })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConsumableKind {
+    /// Heals one hit point.
+    Bandage,
+    /// Temporary movement speed boost.
+    Tonic,
+    /// Thrown, explodes after a short fuse.
+    Grenade,
+}
+
+impl ConsumableKind {
+    fn label(self) -> &'static str {
+        match self {
+            ConsumableKind::Bandage => "Bandage",
+            ConsumableKind::Tonic => "Tonic",
+            ConsumableKind::Grenade => "Grenade",
+        }
+    }
 }
 
 #[derive(Debug, InputAction)]
@@ -132,46 +338,221 @@ pub(crate) struct SelectSlot2;
 #[action_output(bool)]
 pub(crate) struct SelectSlot3;
 
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct SelectSlot4;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct SelectSlot5;
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct SelectSlot6;
+
 fn on_select_slot<Action: InputAction, const N: usize>(
     _on: On<Start<Action>>,
     mut inventory: ResMut<Inventory>,
+    mut gun_cooldown: ResMut<GunCooldown>,
 ) {
+    gun_cooldown.queued_shot = false;
     if inventory.active_slot == N && !inventory.using_hands {
         inventory.using_hands = true;
     } else {
-        inventory.active_slot = N;
+        inventory.select(N);
+    }
+}
+
+/// Scroll wheel, positive goes to the next slot.
+#[derive(Debug, InputAction)]
+#[action_output(f32)]
+pub(crate) struct CycleSlot;
+
+/// Swaps back to the previously selected slot.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct QuickSwap;
+
+fn cycle_slot(
+    cycle: On<Fire<CycleSlot>>,
+    mut inventory: ResMut<Inventory>,
+    mut gun_cooldown: ResMut<GunCooldown>,
+) {
+    gun_cooldown.queued_shot = false;
+    // Scrolling off empty hands brings the last item back out.
+    if inventory.using_hands {
         inventory.using_hands = false;
+        return;
+    }
+    // Scrolling down moves along the hotbar, like most shooters.
+    let direction = if cycle.value < 0.0 { 1 } else { -1 };
+    if let Some(slot) = inventory.next_filled_slot(direction) {
+        inventory.select(slot);
+    }
+}
+
+fn quick_swap(
+    _on: On<Start<QuickSwap>>,
+    mut inventory: ResMut<Inventory>,
+    mut gun_cooldown: ResMut<GunCooldown>,
+) {
+    let previous = inventory.previous_slot;
+    if inventory.slots[previous].is_none() {
+        return;
     }
+    gun_cooldown.queued_shot = false;
+    inventory.select(previous);
 }
 
 #[derive(Debug, InputAction)]
 #[action_output(bool)]
 pub(crate) struct UseTool;
 
+/// Switches the shovel and bucket between round and flat digging. Shares right click with grabbing props, which only happens with empty hands.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct ToggleDigMode;
+
+/// Held to pull the held item up to the camera and turn it over with the mouse. Held without
+/// touching anything else, the item is inspected, turning over on its own.
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct Examine;
+
+fn examining(examine: &Query<&Action<Examine>>) -> bool {
+    examine.iter().any(|held| **held)
+}
+
+fn toggle_dig_mode(_on: On<Start<ToggleDigMode>>, mut inventory: ResMut<Inventory>) {
+    if inventory.using_hands {
+        return;
+    }
+    let active_slot = inventory.active_slot;
+    if let Some(Item::Shovel(stats) | Item::DirtBucket(stats)) = &mut inventory.slots[active_slot] {
+        stats.mode = match stats.mode {
+            DigMode::Round => DigMode::Flat,
+            DigMode::Flat => DigMode::Round,
+        };
+    }
+}
+
 const GUN_RECOIL_DURATION: f32 = 0.05;
 const GUN_RECOIL_Z: f32 = 0.3;
 const GUN_RETURN_SPEED: f32 = 20.0;
 const GUN_REST_TRANSLATION: Vec3 = Vec3::new(1.5, -0.3, -2.0);
 
+const DIG_VOLUME_DB: f32 = 32.0;
+/// Minimum time between dig sounds, however low the dig cooldown gets.
+const DIG_SOUND_INTERVAL: f32 = 0.12;
+/// Dig sounds this soon after the last one are played quieter.
+const RAPID_DIG_WINDOW: f32 = 0.3;
+const RAPID_DIG_QUIETER_DB: f32 = 4.0;
+/// Minimum time between "can't dig here" thunks while the shovel is held against a wall.
+const CANT_DIG_SOUND_INTERVAL: f32 = 0.5;
+
 #[derive(Resource)]
-struct DigCooldown {
-    timer: Timer,
-    ready: bool,
+pub(crate) struct DigCooldown {
+    pub(crate) timer: Timer,
+    pub(crate) ready: bool,
+    /// Time since the last dig sound played.
+    pub(crate) since_sound: Stopwatch,
 }
 
 impl Default for DigCooldown {
     fn default() -> Self {
+        let mut since_sound = Stopwatch::new();
+        since_sound.set_elapsed(Duration::from_secs_f32(RAPID_DIG_WINDOW));
         Self {
             timer: Timer::from_seconds(0.5, TimerMode::Once),
             ready: true,
+            since_sound,
         }
     }
 }
 
+/// Optional aim assist for the gun. A shot that narrowly misses an NPC hits the one nearest
+/// the crosshair instead, if it's within `tolerance_degrees` of the shot. Off at 0.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct AimAssist {
+    pub tolerance_degrees: f32,
+}
+
+impl AimAssist {
+    pub(crate) const MAX_DEGREES: f32 = 8.0;
+}
+
+/// NPCs a shot could be assisted onto, leaving out the ones the player can't hurt.
+#[derive(SystemParam)]
+struct AimAssistTargets<'w, 's> {
+    settings: Res<'w, AimAssist>,
+    relations: Res<'w, FactionRelations>,
+    npcs: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            Option<&'static ColliderAabb>,
+            Option<&'static Faction>,
+        ),
+        (With<Health>, Without<ExplosiveBarrel>, Without<NpcDead>),
+    >,
+}
+
+impl AimAssistTargets<'_, '_> {
+    /// The NPC closest to the center of a shot from `origin` along `direction`, if it's
+    /// within the tolerance and `range`.
+    fn target(&self, origin: Vec3, direction: Dir3, range: f32) -> Option<(Entity, Vec3)> {
+        let tolerance = self.settings.tolerance_degrees.to_radians();
+        if tolerance <= 0.0 {
+            return None;
+        }
+        let candidates = self
+            .npcs
+            .iter()
+            .filter(|(_, _, _, faction)| {
+                self.relations
+                    .can_hurt(PLAYER_FACTION, Faction::name_or_enemy(*faction))
+            })
+            // Aim at the middle of the body rather than its feet.
+            .map(|(entity, transform, aabb, _)| {
+                let center = aabb.map_or(transform.translation(), |aabb| aabb.center());
+                (entity, center)
+            });
+        assist_target(origin, direction, tolerance, range, candidates)
+    }
+}
+
+/// The candidate at the smallest angle off `direction`, within `tolerance` radians and
+/// `range` of `origin`.
+fn assist_target(
+    origin: Vec3,
+    direction: Dir3,
+    tolerance: f32,
+    range: f32,
+    candidates: impl Iterator<Item = (Entity, Vec3)>,
+) -> Option<(Entity, Vec3)> {
+    candidates
+        .filter_map(|(entity, position)| {
+            let offset = position - origin;
+            let distance = offset.length();
+            if distance <= f32::EPSILON || distance > range {
+                return None;
+            }
+            let angle = direction.angle_between(offset);
+            (angle <= tolerance).then_some((angle, entity, position))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, entity, position)| (entity, position))
+}
+
 #[derive(Resource)]
-struct GunCooldown {
-    timer: Timer,
-    ready: bool,
+pub(crate) struct GunCooldown {
+    pub(crate) timer: Timer,
+    pub(crate) ready: bool,
+    /// Semi-auto click that landed during the cooldown, fired as soon as it's ready.
+    pub(crate) queued_shot: bool,
 }
 
 impl Default for GunCooldown {
@@ -179,6 +560,7 @@ impl Default for GunCooldown {
         Self {
             timer: Timer::from_seconds(0.2, TimerMode::Once),
             ready: true,
+            queued_shot: false,
         }
     }
 }
@@ -211,6 +593,9 @@ struct ToolEffects {
     dig_sounds: ShuffleBag<Handle<AudioSample>>,
     #[dependency]
     smg_shot: Handle<AudioSample>,
+    /// Played when the shovel hits level geometry that isn't diggable.
+    #[dependency]
+    cant_dig: Handle<AudioSample>,
 }
 
 impl FromWorld for ToolEffects {
@@ -331,34 +716,25 @@ impl FromWorld for ToolEffects {
         .unwrap();
 
         let smg_shot = assets.load("audio/sound_effects/smg_shot.ogg");
+        let cant_dig = assets.load("audio/sound_effects/land/Footsteps_Rock_Jump_Land_01.ogg");
 
         Self {
             dig_particles,
             muzzle_flash,
             dig_sounds,
             smg_shot,
+            cant_dig,
         }
     }
 }
 
-fn use_tool(
+pub(crate) fn tick_tool_cooldowns(
     time: Res<Time>,
-    inventory: Res<Inventory>,
-    mouse: Res<ButtonInput<MouseButton>>,
     mut dig_cooldown: ResMut<DigCooldown>,
     mut gun_cooldown: ResMut<GunCooldown>,
-    player: Single<&GlobalTransform, With<PlayerCamera>>,
-    player_entity: Single<Entity, With<super::player::Player>>,
-    spatial_query: SpatialQuery,
-    mut voxel_sims: Query<(&mut VoxelSim, &GlobalTransform)>,
-    mut shovel: Query<&mut ShovelSwing>,
-    mut gun_recoil: Query<&mut GunRecoil>,
-    mut health_query: Query<(&mut Health, Option<&mut AggroConfig>, Option<&Name>)>,
-    mut commands: Commands,
-    mut tool_effects: ResMut<ToolEffects>,
-    q_aabb_of: Query<&VoxelAabbOf>,
 ) {
     dig_cooldown.timer.tick(time.delta());
+    dig_cooldown.since_sound.tick(time.delta());
     if dig_cooldown.timer.just_finished() {
         dig_cooldown.ready = true;
     }
@@ -366,39 +742,97 @@ fn use_tool(
     if gun_cooldown.timer.just_finished() {
         gun_cooldown.ready = true;
     }
+}
 
-    if !mouse.pressed(MouseButton::Left) {
+fn use_tool(
+    inventory: Res<Inventory>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut dig_cooldown: ResMut<DigCooldown>,
+    mut gun_cooldown: ResMut<GunCooldown>,
+    player: Single<&GlobalTransform, With<PlayerCamera>>,
+    player_entity: Single<Entity, With<super::player::Player>>,
+    spatial_query: SpatialQuery,
+    mut voxel_sims: Query<(&mut VoxelSim, &GlobalTransform)>,
+    mut shovel: Query<&mut ShovelSwing>,
+    mut gun_recoil: Query<&mut GunRecoil>,
+    mut health_query: Query<(
+        &mut Health,
+        Option<&mut AggroConfig>,
+        Option<&Name>,
+        Has<ExplosiveBarrel>,
+    )>,
+    // Grouped to stay under the system parameter limit.
+    (layers, q_aabb_of): (Query<&CollisionLayers>, Query<&VoxelAabbOf>),
+    mut commands: Commands,
+    (mut tool_effects, asset_server, quality): (
+        ResMut<ToolEffects>,
+        Res<AssetServer>,
+        Res<GraphicsQuality>,
+    ),
+    examine: Query<&Action<Examine>>,
+    aim_assist: AimAssistTargets,
+) {
+    if examining(&examine) {
         return;
     }
+    let held = mouse.pressed(MouseButton::Left);
 
     match inventory.active_item() {
         Some(Item::Shovel(stats)) => {
-            if !dig_cooldown.ready {
+            if !held || !dig_cooldown.ready {
                 return;
             }
-            if let Some(hit_point) = dig_voxel(
+            let dig = dig_voxel(
                 &player,
                 &spatial_query,
                 &mut voxel_sims,
                 stats.distance,
                 stats.radius,
-            ) {
-                commands.spawn((
-                    ParticleEffect::new(tool_effects.dig_particles.clone()),
-                    RenderLayers::from(RenderLayer::DEFAULT),
-                    Transform::from_translation(hit_point),
-                ));
-                let rng = &mut rand::rng();
-                let sound = tool_effects.dig_sounds.pick(rng).clone();
-                commands.spawn((
-                    SamplePlayer::new(sound),
-                    SpatialPool,
-                    VolumeNode {
-                        volume: Volume::Decibels(32.0),
-                        ..default()
-                    },
-                    Transform::from_translation(hit_point),
-                ));
+                stats.mode,
+            );
+            if let Some(DigHit::Solid(hit_point)) = dig {
+                if dig_cooldown.since_sound.elapsed_secs() >= CANT_DIG_SOUND_INTERVAL {
+                    commands.spawn((
+                        SamplePlayer::new(tool_effects.cant_dig.clone()),
+                        SpatialPool,
+                        Transform::from_translation(hit_point),
+                    ));
+                    dig_cooldown.since_sound.reset();
+                }
+            }
+            if let Some(DigHit::Dug(volume, hit_point)) = dig {
+                commands.trigger(VoxelsDug {
+                    volume,
+                    point: hit_point,
+                });
+                if quality.particles() {
+                    commands.spawn((
+                        ParticleEffect::new(tool_effects.dig_particles.clone()),
+                        RenderLayers::from(RenderLayer::DEFAULT),
+                        Transform::from_translation(hit_point),
+                    ));
+                }
+                let since_sound = dig_cooldown.since_sound.elapsed_secs();
+                if since_sound >= DIG_SOUND_INTERVAL {
+                    let volume = if since_sound < RAPID_DIG_WINDOW {
+                        DIG_VOLUME_DB - RAPID_DIG_QUIETER_DB
+                    } else {
+                        DIG_VOLUME_DB
+                    };
+                    let rng = &mut rand::rng();
+                    if let Some(sound) = tool_effects.dig_sounds.pick_loaded(rng, &asset_server) {
+                        commands.spawn((
+                            SamplePlayer::new(sound),
+                            SpatialPool,
+                            VolumeNode {
+                                volume: Volume::Decibels(volume),
+                                ..default()
+                            },
+                            Transform::from_translation(hit_point),
+                        ));
+                    }
+                    dig_cooldown.since_sound.reset();
+                }
             }
             dig_cooldown
                 .timer
@@ -411,23 +845,75 @@ fn use_tool(
             }
         }
         Some(Item::Gun(stats)) => {
+            if !stats.auto {
+                if mouse.just_pressed(MouseButton::Left) {
+                    gun_cooldown.queued_shot = true;
+                }
+                if !gun_cooldown.queued_shot {
+                    return;
+                }
+            } else if !held {
+                return;
+            }
             if !gun_cooldown.ready {
                 return;
             }
+            gun_cooldown.queued_shot = false;
 
             let camera_transform = player.compute_transform();
             let origin = camera_transform.translation;
-            let direction = camera_transform.forward();
+            let mut direction = camera_transform.forward();
 
-            let mut gun_filter =
-                SpatialQueryFilter::from_mask([CollisionLayer::Level, CollisionLayer::Character]);
+            let mut gun_filter = SpatialQueryFilter::from_mask([
+                CollisionLayer::Level,
+                CollisionLayer::Character,
+                CollisionLayer::Prop,
+            ]);
             gun_filter.excluded_entities.insert(*player_entity);
-            if let Some(hit) =
-                spatial_query.cast_ray(origin, direction, stats.distance, true, &gun_filter)
-            {
-                if let Ok((mut health, aggro_config, _)) = health_query.get_mut(hit.entity) {
+            // Props only stop the shot when they can be damaged, like explosive barrels.
+            let stops_shot = |entity: Entity| {
+                health_query.contains(entity)
+                    || !layers
+                        .get(entity)
+                        .is_ok_and(|layers| layers.memberships.has_all(CollisionLayer::Prop))
+            };
+            let mut hit = spatial_query.cast_ray_predicate(
+                origin,
+                direction,
+                stats.distance,
+                true,
+                &gun_filter,
+                &stops_shot,
+            );
+            let missed = !hit.is_some_and(|hit| health_query.contains(hit.entity));
+            let assist = if missed {
+                aim_assist.target(origin, direction, stats.distance)
+            } else {
+                None
+            };
+            if let Some((target, position)) = assist {
+                // Only if nothing's in the way of the assisted shot either.
+                if let Ok(assisted_direction) = Dir3::new(position - origin) {
+                    let assisted = spatial_query.cast_ray_predicate(
+                        origin,
+                        assisted_direction,
+                        stats.distance,
+                        true,
+                        &gun_filter,
+                        &stops_shot,
+                    );
+                    if assisted.is_some_and(|assisted| assisted.entity == target) {
+                        hit = assisted;
+                        direction = assisted_direction;
+                    }
+                }
+            }
+            if let Some(hit) = hit {
+                if let Ok((mut health, aggro_config, _, is_barrel)) =
+                    health_query.get_mut(hit.entity)
+                {
                     health.0 -= stats.damage;
-                    if health.0 <= 0.0 {
+                    if health.0 <= 0.0 && !is_barrel {
                         commands.entity(hit.entity).insert(super::npc::NpcDead);
                     }
                     if let Some(mut config) = aggro_config {
@@ -436,17 +922,23 @@ fn use_tool(
                             commands
                                 .entity(hit.entity)
                                 .insert(AggroTarget(*player_entity));
+                            commands.trigger(Provoked {
+                                entity: hit.entity,
+                                attacker: *player_entity,
+                            });
                         }
                     }
                 }
 
                 // Spawn sphere explosion at the hit point
                 let hit_point = origin + *direction * hit.distance;
-                commands.spawn((
-                    ParticleEffect::new(tool_effects.muzzle_flash.clone()),
-                    RenderLayers::from(RenderLayer::DEFAULT),
-                    Transform::from_translation(hit_point),
-                ));
+                if quality.particles() {
+                    commands.spawn((
+                        ParticleEffect::new(tool_effects.muzzle_flash.clone()),
+                        RenderLayers::from(RenderLayer::DEFAULT),
+                        Transform::from_translation(hit_point),
+                    ));
+                }
             }
 
             commands.spawn((
@@ -466,7 +958,7 @@ fn use_tool(
             }
         }
         Some(Item::DirtBucket(stats)) => {
-            if !dig_cooldown.ready {
+            if !held || !dig_cooldown.ready {
                 return;
             }
             if let Some(hit_point) = fill_voxel(
@@ -476,23 +968,28 @@ fn use_tool(
                 &q_aabb_of,
                 stats.distance,
                 stats.radius,
+                stats.mode,
+                stats.fill_shape,
             ) {
-                commands.spawn((
-                    ParticleEffect::new(tool_effects.dig_particles.clone()),
-                    RenderLayers::from(RenderLayer::DEFAULT),
-                    Transform::from_translation(hit_point),
-                ));
+                if quality.particles() {
+                    commands.spawn((
+                        ParticleEffect::new(tool_effects.dig_particles.clone()),
+                        RenderLayers::from(RenderLayer::DEFAULT),
+                        Transform::from_translation(hit_point),
+                    ));
+                }
                 let rng = &mut rand::rng();
-                let sound = tool_effects.dig_sounds.pick(rng).clone();
-                commands.spawn((
-                    SamplePlayer::new(sound),
-                    SpatialPool,
-                    VolumeNode {
-                        volume: Volume::Decibels(10.0),
-                        ..default()
-                    },
-                    Transform::from_translation(hit_point),
-                ));
+                if let Some(sound) = tool_effects.dig_sounds.pick_loaded(rng, &asset_server) {
+                    commands.spawn((
+                        SamplePlayer::new(sound),
+                        SpatialPool,
+                        VolumeNode {
+                            volume: Volume::Decibels(10.0),
+                            ..default()
+                        },
+                        Transform::from_translation(hit_point),
+                    ));
+                }
             }
             dig_cooldown
                 .timer
@@ -504,18 +1001,124 @@ fn use_tool(
                 swing.returning = false;
             }
         }
+        Some(Item::Consumable { .. }) => {
+            // One per click, holding the button shouldn't eat the whole stack.
+            if mouse.just_pressed(MouseButton::Left) {
+                commands.trigger(UseConsumable);
+            }
+        }
         None => {}
     }
 }
 
-/// Returns the world-space hit point if voxels were dug.
+const STARTING_BANDAGES: u32 = 3;
+const STARTING_TONICS: u32 = 2;
+const BANDAGE_HEAL: u32 = 1;
+const TONIC_SPEED_MULTIPLIER: f32 = 1.5;
+const TONIC_DURATION: f32 = 8.0;
+
+/// Use up one of the active consumable.
+#[derive(Event)]
+struct UseConsumable;
+
+/// Movement speed buff from a tonic, restores the controller speed when it runs out.
+/// Multiplies rather than overwriting the speed, so it stacks with sprinting.
+#[derive(Component)]
+struct SpeedBoost {
+    timer: Timer,
+}
+
+fn on_use_consumable(
+    _on: On<UseConsumable>,
+    mut commands: Commands,
+    mut inventory: ResMut<Inventory>,
+    mut player: Single<
+        (
+            Entity,
+            &mut PlayerHealth,
+            &mut CharacterController,
+            Option<&mut SpeedBoost>,
+        ),
+        With<Player>,
+    >,
+    // Only throwing needs it, bandages and tonics work without a camera.
+    camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+) {
+    let Some(&Item::Consumable { kind, .. }) = inventory.active_item() else {
+        return;
+    };
+    let (entity, health, controller, boost) = &mut *player;
+
+    match kind {
+        ConsumableKind::Bandage => {
+            // Don't waste a bandage at full health.
+            if health.current >= health.max {
+                return;
+            }
+            health.current = (health.current + BANDAGE_HEAL).min(health.max);
+        }
+        ConsumableKind::Tonic => match boost {
+            Some(boost) => boost.timer.reset(),
+            None => {
+                controller.speed *= TONIC_SPEED_MULTIPLIER;
+                commands.entity(*entity).insert(SpeedBoost {
+                    timer: Timer::from_seconds(TONIC_DURATION, TimerMode::Once),
+                });
+            }
+        },
+        ConsumableKind::Grenade => {
+            let Some(camera) = camera else {
+                return;
+            };
+            commands.trigger(ThrowGrenade {
+                from: camera.compute_transform(),
+            });
+        }
+    }
+
+    let slot = inventory.active_slot;
+    if let Some(Item::Consumable { count, .. }) = &mut inventory.slots[slot] {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            inventory.slots[slot] = None;
+        }
+    }
+}
+
+fn tick_speed_boost(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut boosted: Query<(Entity, &mut SpeedBoost, &mut CharacterController)>,
+) {
+    for (entity, mut boost, mut controller) in &mut boosted {
+        boost.timer.tick(time.delta());
+        if boost.timer.is_finished() {
+            controller.speed /= TONIC_SPEED_MULTIPLIER;
+            commands.entity(entity).remove::<SpeedBoost>();
+        }
+    }
+}
+
+/// What the shovel hit within reach.
+#[derive(Clone, Copy, Debug)]
+enum DigHit {
+    /// Dug into this voxel volume at the surface point.
+    Dug(Entity, Vec3),
+    /// Level geometry that isn't a voxel volume. It still blocks the shovel, so digging
+    /// can't reach through a wall into a volume behind it.
+    Solid(Vec3),
+}
+
+/// Digs into the voxel volume under the crosshair, if the first level geometry within
+/// `distance` is one.
 fn dig_voxel(
     player: &GlobalTransform,
     spatial_query: &SpatialQuery,
     voxel_sims: &mut Query<(&mut VoxelSim, &GlobalTransform)>,
     distance: f32,
     radius: f32,
-) -> Option<Vec3> {
+    mode: DigMode,
+) -> Option<DigHit> {
     let camera_transform = player.compute_transform();
     let origin = camera_transform.translation;
     let direction = camera_transform.forward();
@@ -528,37 +1131,19 @@ fn dig_voxel(
         &SpatialQueryFilter::from_mask(CollisionLayer::Level),
     )?;
 
+    let surface_point = origin + *direction * hit.distance;
     let Ok((mut sim, sim_transform)) = voxel_sims.get_mut(hit.entity) else {
-        return None;
+        return Some(DigHit::Solid(surface_point));
     };
 
     // push it in a little bit so we aren't at the edge of a voxel
     const BIAS: f32 = 0.1;
     let hit_point = origin + *direction * hit.distance + *direction * BIAS;
-    let surface_point = origin + *direction * hit.distance;
 
-    let local = sim_transform
-        .compute_transform()
-        .compute_affine()
-        .inverse()
-        .transform_point3(hit_point);
-    let center = (local / VOXEL_SIZE).floor().as_ivec3();
-
-    let r = radius as i32;
-    let r_sq = radius * radius;
-    for dx in -r..=r {
-        for dy in -r..=r {
-            for dz in -r..=r {
-                let dist_sq = (dx * dx + dy * dy + dz * dz) as f32;
-                if dist_sq <= r_sq {
-                    let pos = center + IVec3::new(dx, dy, dz);
-                    sim.set(pos, Voxel::Air);
-                }
-            }
-        }
-    }
+    let center = world_to_voxel(sim_transform, hit_point);
+    sim.set_shape(center, radius, mode.shape(center), Voxel::Air);
 
-    Some(surface_point)
+    Some(DigHit::Dug(hit.entity, surface_point))
 }
 
 /// Returns the world-space fill point if voxels were filled with dirt.
@@ -571,6 +1156,8 @@ fn fill_voxel(
     q_aabb_of: &Query<&VoxelAabbOf>,
     distance: f32,
     radius: f32,
+    mode: DigMode,
+    fill_shape: VoxelShape,
 ) -> Option<Vec3> {
     let camera_transform = player.compute_transform();
     let origin = camera_transform.translation;
@@ -635,26 +1222,12 @@ fn fill_voxel(
         return None;
     };
 
-    let local = sim_transform
-        .compute_transform()
-        .compute_affine()
-        .inverse()
-        .transform_point3(world_point);
-    let center = (local / VOXEL_SIZE).floor().as_ivec3();
-
-    let r = radius as i32;
-    let r_sq = radius * radius;
-    for dx in -r..=r {
-        for dy in -r..=r {
-            for dz in -r..=r {
-                let dist_sq = (dx * dx + dy * dy + dz * dz) as f32;
-                if dist_sq <= r_sq {
-                    let pos = center + IVec3::new(dx, dy, dz);
-                    sim.set(pos, Voxel::Dirt);
-                }
-            }
-        }
-    }
+    let center = world_to_voxel(sim_transform, world_point);
+    let shape = match fill_shape {
+        VoxelShape::Sphere => mode.shape(center),
+        shape => shape,
+    };
+    sim.set_shape(center, radius, shape, Voxel::Dirt);
 
     Some(world_point)
 }
@@ -667,10 +1240,17 @@ const INACTIVE_COLOR: Color = Color::srgba(0.3, 0.3, 0.3, 0.4);
 #[derive(Component)]
 struct InventorySlotUi(usize);
 
+/// Name of items without a preview model, and how many are left of stackable ones.
+#[derive(Component)]
+struct InventorySlotText(usize);
+
 fn spawn_inventory_hud(
     mut commands: Commands,
     mut images: ResMut<Assets<Image>>,
     inventory_assets: Res<InventoryAssets>,
+    inventory: Res<Inventory>,
+    font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
 ) {
     use super::crusts::spawn_model_preview;
 
@@ -711,55 +1291,64 @@ fn spawn_inventory_hud(
     commands
         .spawn((
             Name::new("Inventory HUD"),
+            HudElement::Inventory,
+            hud_settings.slot(HudElement::Inventory, 0),
             Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::End,
-                padding: UiRect::bottom(Val::Px(20.0)),
+                column_gap: Val::Px(SLOT_GAP),
                 ..default()
             },
-            DespawnOnExit(Screen::Gameplay),
         ))
-        .with_children(|parent| {
-            parent
-                .spawn(Node {
-                    column_gap: Val::Px(SLOT_GAP),
-                    ..default()
-                })
-                .with_children(|row| {
-                    for i in 0..3 {
-                        let bg = if i == 0 { ACTIVE_COLOR } else { INACTIVE_COLOR };
-                        row.spawn((
-                            Name::new(format!("Slot {}", i + 1)),
-                            InventorySlotUi(i),
-                            Node {
-                                width: Val::Px(SLOT_SIZE),
-                                height: Val::Px(SLOT_SIZE),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                border: UiRect::all(Val::Px(2.0)),
-                                ..default()
-                            },
-                            BackgroundColor(bg),
-                            BorderColor::all(Color::WHITE),
-                        ))
-                        .with_child((
-                            ViewportNode::new(slot_previews[i].camera),
-                            Node {
-                                width: Val::Percent(100.0),
-                                height: Val::Percent(100.0),
-                                ..default()
-                            },
-                        ));
-                    }
-                });
+        .with_children(|row| {
+            for i in 0..inventory.slots.len() {
+                let bg = if i == 0 { ACTIVE_COLOR } else { INACTIVE_COLOR };
+                let mut slot = row.spawn((
+                    Name::new(format!("Slot {}", i + 1)),
+                    InventorySlotUi(i),
+                    Node {
+                        width: Val::Px(SLOT_SIZE),
+                        height: Val::Px(SLOT_SIZE),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(bg),
+                    BorderColor::all(Color::WHITE),
+                ));
+                if let Some(preview) = slot_previews.get(i) {
+                    slot.with_child((
+                        ViewportNode::new(preview.camera),
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                    ));
+                }
+                slot.with_child((
+                    InventorySlotText(i),
+                    Text::new(slot_text(inventory.slots[i].as_ref())),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new_with_justify(Justify::Center),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(2.0),
+                        ..default()
+                    },
+                ));
+            }
         });
 }
 
 fn update_inventory_hud(
     inventory: Res<Inventory>,
     mut slots: Query<(&InventorySlotUi, &mut BackgroundColor)>,
+    mut texts: Query<(&InventorySlotText, &mut Text)>,
 ) {
     for (slot_ui, mut bg) in &mut slots {
         let is_active = slot_ui.0 == inventory.active_slot;
@@ -770,11 +1359,22 @@ fn update_inventory_hud(
         }
         .into();
     }
+    for (slot_text_ui, mut text) in &mut texts {
+        let item = inventory.slots.get(slot_text_ui.0).and_then(Option::as_ref);
+        text.0 = slot_text(item);
+    }
+}
+
+fn slot_text(item: Option<&Item>) -> String {
+    match item {
+        Some(Item::Consumable { kind, count }) => format!("{} x{count}", kind.label()),
+        _ => String::new(),
+    }
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
-struct InventoryAssets {
+pub(crate) struct InventoryAssets {
     #[dependency]
     shovel: Handle<Scene>,
     #[dependency]
@@ -794,16 +1394,40 @@ impl FromWorld for InventoryAssets {
     }
 }
 
+impl InventoryAssets {
+    /// Scene and scale for an item lying in the world. Consumables don't have one.
+    pub(crate) fn world_model(&self, item: &Item) -> Option<(Handle<Scene>, f32)> {
+        match item {
+            Item::Shovel(_) => Some((self.shovel.clone(), 1.0)),
+            Item::Gun(_) => Some((self.gun.clone(), 0.01)),
+            Item::DirtBucket(_) => Some((self.bucket.clone(), 0.01)),
+            Item::Consumable { .. } => None,
+        }
+    }
+}
+
 #[derive(Component)]
 struct HeldItemModel;
 
+/// Sits between the camera and the [`HeldItemModel`]. Sway and examining move this, leaving
+/// the item's own transform to the swing and recoil animations, so neither can knock the
+/// other's rest pose out.
+#[derive(Component)]
+#[require(Transform, Visibility, ExaminedItem, Sway)]
+struct HeldItemPivot {
+    /// Where the item rests in front of the camera, what it turns about.
+    rest: Vec3,
+}
+
 fn held_item_missing(inventory: Res<Inventory>, existing: Query<(), With<HeldItemModel>>) -> bool {
-    inventory.active_item().is_some() && existing.is_empty()
+    inventory.active_item().is_some_and(Item::has_view_model) && existing.is_empty()
 }
 
 const SHOVEL_SWING_X_END: f32 = 0.0;
 const SHOVEL_SWING_X_START: f32 = -1.7;
 const SHOVEL_REST_ROTATION: Vec3 = Vec3::new(SHOVEL_SWING_X_START, 3.00, -1.7);
+const SHOVEL_REST_TRANSLATION: Vec3 = Vec3::new(0.4, -0.2, -0.5);
+const BUCKET_REST_TRANSLATION: Vec3 = Vec3::new(0.7, -0.2, -1.0);
 const SHOVEL_SWING_DURATION: f32 = 0.35;
 const SHOVEL_RETURN_SPEED: f32 = 12.0;
 
@@ -829,7 +1453,7 @@ impl Default for ShovelSwing {
 fn update_held_item(
     mut commands: Commands,
     inventory: Res<Inventory>,
-    existing: Query<Entity, With<HeldItemModel>>,
+    existing: Query<Entity, With<HeldItemPivot>>,
     player_camera: Single<Entity, With<PlayerCamera>>,
     inventory_assets: Res<InventoryAssets>,
     // mut last_held: Local<Option<Item>>,
@@ -849,7 +1473,7 @@ fn update_held_item(
                     ShovelSwing::default(),
                     SceneRoot(inventory_assets.shovel.clone()),
                     Transform {
-                        translation: Vec3::new(0.4, -0.2, -0.5),
+                        translation: SHOVEL_REST_TRANSLATION,
                         rotation: Quat::from_euler(
                             EulerRot::XYZ,
                             SHOVEL_REST_ROTATION.x,
@@ -861,7 +1485,7 @@ fn update_held_item(
                 ))
                 .observe(configure_held_item_view_model)
                 .id();
-            commands.entity(camera_entity).add_child(held);
+            hold_item(&mut commands, camera_entity, held, SHOVEL_REST_TRANSLATION);
         }
         Some(Item::DirtBucket(..)) => {
             let held = commands
@@ -871,7 +1495,7 @@ fn update_held_item(
                     ShovelSwing::default(),
                     SceneRoot(inventory_assets.bucket.clone()),
                     Transform {
-                        translation: Vec3::new(0.7, -0.2, -1.0),
+                        translation: BUCKET_REST_TRANSLATION,
                         rotation: Quat::from_euler(
                             EulerRot::XYZ,
                             SHOVEL_REST_ROTATION.x,
@@ -883,7 +1507,7 @@ fn update_held_item(
                 ))
                 .observe(configure_held_item_view_model)
                 .id();
-            commands.entity(camera_entity).add_child(held);
+            hold_item(&mut commands, camera_entity, held, BUCKET_REST_TRANSLATION);
         }
         Some(Item::Gun(..)) => {
             let held = commands
@@ -900,12 +1524,21 @@ fn update_held_item(
                 ))
                 .observe(configure_held_item_view_model)
                 .id();
-            commands.entity(camera_entity).add_child(held);
+            hold_item(&mut commands, camera_entity, held, GUN_REST_TRANSLATION);
         }
-        None => {}
+        Some(Item::Consumable { .. }) | None => {}
     }
 }
 
+/// Hangs `held` off the camera through a [`HeldItemPivot`] resting at `rest`.
+fn hold_item(commands: &mut Commands, camera: Entity, held: Entity, rest: Vec3) {
+    let pivot = commands
+        .spawn((Name::new("Held Item Pivot"), HeldItemPivot { rest }))
+        .add_child(held)
+        .id();
+    commands.entity(camera).add_child(pivot);
+}
+
 // i love hardcoding animations c:
 fn animate_shovel_swing(time: Res<Time>, mut query: Query<(&mut ShovelSwing, &mut Transform)>) {
     for (mut swing, mut transform) in &mut query {
@@ -971,6 +1604,198 @@ fn animate_gun_recoil(time: Res<Time>, mut query: Query<(&mut GunRecoil, &mut Tr
     }
 }
 
+const EXAMINE_BLEND_SPEED: f32 = 8.0;
+/// Radians the examined item turns per pixel of mouse motion.
+const EXAMINE_SENSITIVITY: f32 = 0.01;
+/// Fraction of its resting depth the examined item is pulled in to.
+const EXAMINE_DEPTH: f32 = 0.6;
+/// Seconds examine has to be held without touching anything else before the item is inspected.
+const INSPECT_HOLD_SECONDS: f32 = 0.5;
+const INSPECT_SECONDS: f32 = 4.0;
+/// Radians per second the item turns itself while inspected.
+const INSPECT_SPIN_SPEED: f32 = 1.2;
+/// Idle drift of the held item, in camera space.
+const SWAY_AMPLITUDE: Vec2 = Vec2::new(0.006, 0.004);
+/// Radians per second of the idle drift on each axis. The 1:2 ratio traces a figure eight.
+const SWAY_FREQUENCY: Vec2 = Vec2::new(0.9, 1.8);
+/// Radians the item trails behind per degree per second of looking around.
+const SWAY_LAG: f32 = 0.0004;
+const SWAY_MAX_LAG: f32 = 0.08;
+/// How quickly the trailing item catches back up.
+const SWAY_RETURN_RATE: f32 = 10.0;
+
+/// How much the held item sways, from 0 for none to 1. Some players find it nauseating.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct HeldItemSway(pub f32);
+
+impl Default for HeldItemSway {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// A held item pulled up for a closer look, blending back to rest once let go. Holding
+/// examine still for a moment inspects it instead, turning it over on its own for a while.
+#[derive(Component, Default)]
+struct ExaminedItem {
+    /// Rotation the player has turned the item to.
+    spin: Quat,
+    /// 0 at rest, 1 fully pulled in.
+    blend: f32,
+    /// Seconds examine has been held without any other input.
+    still: f32,
+    inspect: Option<Timer>,
+    /// Already inspected during this hold of examine, so it doesn't start over.
+    inspected: bool,
+}
+
+/// The held item drifting idly and trailing behind the camera as it turns.
+#[derive(Component, Default)]
+struct Sway {
+    time: f32,
+    /// Yaw and pitch the item trails by.
+    lag: Vec2,
+}
+
+fn examine_held_item(
+    time: Res<Time>,
+    mouse_motion: Res<AccumulatedMouseMotion>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    blocks_input: Res<BlocksInput>,
+    examine: Query<&Action<Examine>>,
+    mut items: Query<&mut ExaminedItem>,
+) {
+    let held = examining(&examine);
+    let moved = mouse_motion.delta != Vec2::ZERO;
+    // Anything pressed besides the examine key itself.
+    let busy = moved
+        || mouse.get_pressed().next().is_some()
+        || keys.get_pressed().count() > usize::from(held);
+    let interrupted = moved
+        || mouse.get_just_pressed().next().is_some()
+        || keys.get_just_pressed().next().is_some()
+        || !blocks_input.is_empty();
+    let dt = time.delta_secs();
+
+    for mut examined in &mut items {
+        if let Some(inspect) = &mut examined.inspect {
+            inspect.tick(time.delta());
+            if inspect.is_finished() || interrupted {
+                examined.inspect = None;
+            } else {
+                examined.spin = Quat::from_rotation_y(INSPECT_SPIN_SPEED * dt) * examined.spin;
+            }
+        } else if held && !busy && !examined.inspected {
+            examined.still += dt;
+            if examined.still >= INSPECT_HOLD_SECONDS {
+                examined.inspect = Some(Timer::from_seconds(INSPECT_SECONDS, TimerMode::Once));
+                examined.inspected = true;
+            }
+        } else {
+            examined.still = 0.0;
+        }
+        if !held {
+            examined.inspected = false;
+        }
+
+        if held {
+            let delta = mouse_motion.delta * EXAMINE_SENSITIVITY;
+            examined.spin =
+                Quat::from_rotation_y(delta.x) * Quat::from_rotation_x(delta.y) * examined.spin;
+        }
+        let step = EXAMINE_BLEND_SPEED * dt;
+        examined.blend = if held || examined.inspect.is_some() {
+            (examined.blend + step).min(1.0)
+        } else {
+            (examined.blend - step).max(0.0)
+        };
+        if examined.blend <= 0.0 {
+            examined.spin = Quat::IDENTITY;
+        }
+    }
+}
+
+/// Held still while dialogue or the death screen is up.
+fn sway_held_item(
+    time: Res<Time>,
+    blocks_input: Res<BlocksInput>,
+    look: Query<&Action<RotateCamera>>,
+    mut sways: Query<&mut Sway>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let frozen = !blocks_input.is_empty();
+    // Degrees per second the camera is turning.
+    let look_speed = look.iter().map(|look| **look).sum::<Vec2>() / dt;
+    let target = if frozen {
+        Vec2::ZERO
+    } else {
+        (look_speed * SWAY_LAG).clamp_length_max(SWAY_MAX_LAG)
+    };
+    for mut sway in &mut sways {
+        if !frozen {
+            sway.time += dt;
+        }
+        sway.lag = sway.lag.lerp(target, 1.0 - (-SWAY_RETURN_RATE * dt).exp());
+    }
+}
+
+/// Idle drift of the held item `time` seconds in.
+fn idle_sway(time: f32) -> Vec3 {
+    let phase = SWAY_FREQUENCY * time;
+    (SWAY_AMPLITUDE * Vec2::new(phase.x.sin(), phase.y.sin())).extend(0.0)
+}
+
+/// Pivot transform that moves an item resting at `rest` by `offset` and turns it by
+/// `rotation` about its own resting point, rather than about the camera.
+fn pivot_about(rest: Vec3, offset: Vec3, rotation: Quat) -> Transform {
+    Transform::from_translation(rest + offset - rotation * rest).with_rotation(rotation)
+}
+
+fn pose_held_item_pivots(
+    settings: Res<HeldItemSway>,
+    reduce_motion: Res<ReduceMotion>,
+    mut pivots: Query<(&HeldItemPivot, &ExaminedItem, &Sway, &mut Transform)>,
+) {
+    let intensity = if reduce_motion.0 {
+        0.0
+    } else {
+        settings.0.clamp(0.0, 1.0)
+    };
+    for (pivot, examined, sway, mut transform) in &mut pivots {
+        let t = examined.blend * examined.blend * (3.0 - 2.0 * examined.blend);
+        // Fades out while the item is pulled in for a look.
+        let sway_weight = intensity * (1.0 - t);
+        let lag = Quat::from_euler(
+            EulerRot::YXZ,
+            sway.lag.x * sway_weight,
+            sway.lag.y * sway_weight,
+            0.0,
+        );
+        let close = Vec3::new(0.0, 0.0, pivot.rest.z * EXAMINE_DEPTH);
+        let offset = (close - pivot.rest) * t + idle_sway(sway.time) * sway_weight;
+        let rotation = Quat::IDENTITY.slerp(examined.spin, t) * lag;
+        transform.set_if_neq(pivot_about(pivot.rest, offset, rotation));
+    }
+}
+
+/// The mouse turns the examined item instead of the camera.
+fn hold_camera_while_examining(
+    examine: Query<&Action<Examine>>,
+    mut look: Query<&mut Action<RotateCamera>>,
+) {
+    if !examining(&examine) {
+        return;
+    }
+    for mut action in &mut look {
+        **action = Vec2::ZERO;
+    }
+}
+
 fn configure_held_item_view_model(
     ready: On<SceneInstanceReady>,
     mut commands: Commands,
@@ -988,3 +1813,223 @@ fn configure_held_item_view_model(
             .insert((RenderLayers::from(RenderLayer::VIEW_MODEL), NotShadowCaster));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aim_assist_picks_the_npc_nearest_the_crosshair() {
+        let mut world = World::new();
+        let [near, far_off, out_of_range] = [(); 3].map(|_| world.spawn_empty().id());
+        let candidates = [
+            (far_off, Vec3::new(0.6, 0.0, -10.0)),
+            (near, Vec3::new(0.2, 0.0, -10.0)),
+            (out_of_range, Vec3::new(0.0, 0.0, -50.0)),
+        ];
+        let tolerance = 5.0_f32.to_radians();
+
+        let target = assist_target(
+            Vec3::ZERO,
+            Dir3::NEG_Z,
+            tolerance,
+            20.0,
+            candidates.into_iter(),
+        );
+        assert_eq!(target.map(|(entity, _)| entity), Some(near));
+
+        let wide_miss = [(far_off, Vec3::new(5.0, 0.0, -10.0))];
+        assert_eq!(
+            assist_target(
+                Vec3::ZERO,
+                Dir3::NEG_Z,
+                tolerance,
+                20.0,
+                wide_miss.into_iter()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn aim_assist_skips_friendlies_and_aims_at_the_body() {
+        use bevy::ecs::system::SystemState;
+
+        let mut world = World::new();
+        world.insert_resource(AimAssist {
+            tolerance_degrees: 5.0,
+        });
+        let mut relations = FactionRelations::default();
+        relations.set(PLAYER_FACTION, "lobster", false);
+        world.insert_resource(relations);
+
+        let mut spawn_npc = |faction: &str, feet: Vec3| {
+            world
+                .spawn((
+                    Health(100.0),
+                    Faction(faction.to_string()),
+                    GlobalTransform::from_translation(feet),
+                    ColliderAabb::from_min_max(
+                        feet - Vec3::new(0.5, 0.0, 0.5),
+                        feet + Vec3::new(0.5, 2.0, 0.5),
+                    ),
+                ))
+                .id()
+        };
+        // The friendly is dead ahead, the enemy a little off to the side.
+        spawn_npc("lobster", Vec3::new(0.0, -1.0, -10.0));
+        let enemy = spawn_npc("enemy", Vec3::new(0.5, -1.0, -10.0));
+
+        let mut targets = SystemState::<AimAssistTargets>::new(&mut world);
+        let target = targets.get(&world).target(Vec3::ZERO, Dir3::NEG_Z, 20.0);
+        assert_eq!(target, Some((enemy, Vec3::new(0.5, 0.0, -10.0))));
+    }
+
+    #[test]
+    fn held_item_turns_about_its_resting_point() {
+        let rest = GUN_REST_TRANSLATION;
+        let offset = Vec3::new(0.0, 0.01, 0.0);
+        let pivot = pivot_about(rest, offset, Quat::from_rotation_y(1.0));
+        // The item's origin only moves by the offset, however it's turned.
+        assert!(pivot.transform_point(rest).abs_diff_eq(rest + offset, 1e-5));
+
+        let resting = pivot_about(rest, Vec3::ZERO, Quat::IDENTITY);
+        assert_eq!(resting, Transform::IDENTITY);
+    }
+
+    #[test]
+    fn idle_sway_stays_within_its_amplitude() {
+        for step in 0..200 {
+            let sway = idle_sway(step as f32 * 0.1);
+            assert!(sway.x.abs() <= SWAY_AMPLITUDE.x && sway.y.abs() <= SWAY_AMPLITUDE.y);
+            assert_eq!(sway.z, 0.0);
+        }
+    }
+
+    #[test]
+    fn bucket_fills_each_of_its_shapes() {
+        use std::time::Duration;
+
+        use bevy::{
+            ecs::system::RunSystemOnce as _, platform::collections::HashSet,
+            time::TimeUpdateStrategy,
+        };
+
+        const TICK: Duration = Duration::from_micros(15_625);
+        const BOUNDS: IVec3 = IVec3::splat(32);
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK));
+
+        let world_size = BOUNDS.as_vec3() * VOXEL_SIZE;
+        let volume = app
+            .world_mut()
+            .spawn((VoxelSim::new(BOUNDS), Transform::default()))
+            .id();
+        app.world_mut().spawn((
+            VoxelAabbOf(volume),
+            Collider::cuboid(world_size.x, world_size.y, world_size.z),
+            Sensor,
+            CollisionLayers::new(CollisionLayer::VoxelAabb, LayerMask::ALL),
+            Transform::from_translation(world_size / 2.0),
+        ));
+        for _ in 0..4 {
+            app.update();
+        }
+
+        // Aimed flat at the middle of the volume's -X face, filling at voxel (0, 8, 16).
+        let aim =
+            GlobalTransform::from(Transform::from_xyz(-2.0, 2.1, 4.1).looking_to(Vec3::X, Vec3::Y));
+        let center = IVec3::new(0, 8, 16);
+        let mut stats = DigStats {
+            radius: 2.0,
+            ..default()
+        };
+
+        /// Fills a fresh volume with `stats`, returning where dirt went relative to `center`.
+        fn fill(
+            app: &mut App,
+            volume: Entity,
+            aim: GlobalTransform,
+            center: IVec3,
+            stats: &DigStats,
+        ) -> HashSet<IVec3> {
+            let stats = stats.clone();
+            app.world_mut()
+                .entity_mut(volume)
+                .insert(VoxelSim::new(BOUNDS));
+            app.world_mut()
+                .run_system_once(
+                    move |spatial_query: SpatialQuery,
+                          mut sims: Query<(&mut VoxelSim, &GlobalTransform)>,
+                          aabbs: Query<&VoxelAabbOf>| {
+                        fill_voxel(
+                            &aim,
+                            &spatial_query,
+                            &mut sims,
+                            &aabbs,
+                            10.0,
+                            stats.radius,
+                            stats.mode,
+                            stats.fill_shape,
+                        )
+                    },
+                )
+                .unwrap()
+                .expect("the fill should land on the volume");
+            let sim = app.world().get::<VoxelSim>(volume).unwrap();
+            (0..BOUNDS.element_product() as usize)
+                .map(|index| sim.delinearize(index))
+                .filter(|&pos| sim.get(pos) == Some(Voxel::Dirt))
+                .map(|pos| pos - center)
+                .collect()
+        }
+
+        // Round: a ball, reaching down and up the radius.
+        let dirt = fill(&mut app, volume, aim, center, &stats);
+        assert!(
+            dirt.contains(&IVec3::ZERO)
+                && dirt.contains(&IVec3::new(0, -2, 0))
+                && dirt.contains(&IVec3::new(0, 2, 0))
+        );
+        assert!(!dirt.contains(&IVec3::new(0, 2, 1)));
+
+        // Flat: the ball cut off level with the hit point.
+        stats.mode = DigMode::Flat;
+        let dirt = fill(&mut app, volume, aim, center, &stats);
+        assert!(dirt.contains(&IVec3::ZERO) && dirt.contains(&IVec3::new(0, -2, 0)));
+        assert!(!dirt.contains(&IVec3::new(0, 1, 0)));
+
+        // Slab: a thin disc the full radius across.
+        stats.mode = DigMode::Round;
+        stats.fill_shape = VoxelShape::Slab { thickness: 2 };
+        let dirt = fill(&mut app, volume, aim, center, &stats);
+        assert!(
+            dirt.contains(&IVec3::ZERO)
+                && dirt.contains(&IVec3::new(0, 1, 0))
+                && dirt.contains(&IVec3::new(2, 0, 0))
+        );
+        assert!(!dirt.contains(&IVec3::new(0, -1, 0)) && !dirt.contains(&IVec3::new(0, 2, 0)));
+
+        // Column: the disc stacked upward from the hit point.
+        stats.fill_shape = VoxelShape::Column { height: 8 };
+        let dirt = fill(&mut app, volume, aim, center, &stats);
+        let top = 7;
+        assert!(
+            dirt.contains(&IVec3::ZERO)
+                && dirt.contains(&IVec3::new(0, top, 0))
+                && dirt.contains(&IVec3::new(2, top, 0))
+        );
+        assert!(
+            !dirt.contains(&IVec3::new(0, -1, 0)) && !dirt.contains(&IVec3::new(0, top + 1, 0))
+        );
+    }
+}
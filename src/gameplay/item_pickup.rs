@@ -0,0 +1,154 @@
+//! Items dropped from the inventory into the world, picked back up by walking over them.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    game_running,
+    gameplay::{
+        inventory::{GunCooldown, Inventory, InventoryAssets, Item},
+        player::{PLAYER_RADIUS, Player, camera::PlayerCamera},
+    },
+    screens::Screen,
+    third_party::avian3d::CollisionLayer,
+};
+
+/// How far in front of the player's center items are dropped, unless a wall is closer.
+const DROP_DISTANCE: f32 = PLAYER_RADIUS + 0.5;
+const PICKUP_HALF_SIZE: f32 = 0.25;
+/// Pickups this close to the player are collected.
+const COLLECT_RADIUS: f32 = 1.0;
+/// Keeps a dropped item from being collected again straight away.
+const COLLECT_DELAY_SECONDS: f32 = 1.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ItemPickupAssets>();
+    app.add_observer(drop_active_item);
+    app.add_systems(Update, collect_item_pickups.run_if(game_running));
+}
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct DropItem;
+
+/// An inventory item lying in the world, with the stats it had when dropped.
+#[derive(Component)]
+pub(crate) struct ItemPickup {
+    pub item: Item,
+    collect_delay: Timer,
+}
+
+/// Stand-in model for items without a world model.
+#[derive(Resource)]
+struct ItemPickupAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for ItemPickupAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(PICKUP_HALF_SIZE * 2.0));
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(0.9, 0.9, 0.85),
+                ..default()
+            });
+        Self { mesh, material }
+    }
+}
+
+fn drop_active_item(
+    _on: On<Start<DropItem>>,
+    mut commands: Commands,
+    mut inventory: ResMut<Inventory>,
+    mut gun_cooldown: ResMut<GunCooldown>,
+    player: Single<(Entity, &GlobalTransform), With<Player>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    spatial_query: SpatialQuery,
+    inventory_assets: Res<InventoryAssets>,
+    pickup_assets: Res<ItemPickupAssets>,
+) {
+    let Some(item) = inventory.take_active() else {
+        return;
+    };
+    gun_cooldown.queued_shot = false;
+
+    let (player, player_transform) = *player;
+    let origin = player_transform.translation();
+    let forward = camera.forward();
+    let direction = Dir3::new(Vec3::new(forward.x, 0.0, forward.z)).unwrap_or(Dir3::NEG_Z);
+    // Stop short of walls so the item doesn't end up inside one.
+    let filter = SpatialQueryFilter::from_mask([CollisionLayer::Level, CollisionLayer::Prop])
+        .with_excluded_entities([player]);
+    let distance = spatial_query
+        .cast_ray(origin, direction, DROP_DISTANCE, true, &filter)
+        .map_or(DROP_DISTANCE, |hit| {
+            (hit.distance - PICKUP_HALF_SIZE).max(0.0)
+        });
+    let transform =
+        Transform::from_translation(origin + direction * distance).looking_to(direction, Vec3::Y);
+    let model = inventory_assets.world_model(&item);
+
+    let pickup = commands
+        .spawn((
+            Name::new("Item Pickup"),
+            ItemPickup {
+                item,
+                collect_delay: Timer::from_seconds(COLLECT_DELAY_SECONDS, TimerMode::Once),
+            },
+            transform,
+            RigidBody::Dynamic,
+            Collider::cuboid(
+                PICKUP_HALF_SIZE * 2.0,
+                PICKUP_HALF_SIZE * 2.0,
+                PICKUP_HALF_SIZE * 2.0,
+            ),
+            // Falls onto the level and props without getting in the player's way.
+            CollisionLayers::new(
+                CollisionLayer::Prop,
+                [CollisionLayer::Level, CollisionLayer::Prop],
+            ),
+            Visibility::default(),
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .id();
+    match model {
+        Some((scene, scale)) => {
+            commands
+                .entity(pickup)
+                .with_child((SceneRoot(scene), Transform::from_scale(Vec3::splat(scale))));
+        }
+        None => {
+            commands.entity(pickup).with_child((
+                Mesh3d(pickup_assets.mesh.clone()),
+                MeshMaterial3d(pickup_assets.material.clone()),
+            ));
+        }
+    }
+}
+
+fn collect_item_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Single<&GlobalTransform, With<Player>>,
+    mut pickups: Query<(Entity, &mut ItemPickup, &GlobalTransform)>,
+    mut inventory: ResMut<Inventory>,
+) {
+    let target = player.translation();
+    for (entity, mut pickup, transform) in &mut pickups {
+        pickup.collect_delay.tick(time.delta());
+        if !pickup.collect_delay.is_finished()
+            || transform.translation().distance(target) > COLLECT_RADIUS
+            // A tool already carried is left on the ground instead of being swallowed.
+            || !inventory.has_room_for(&pickup.item)
+        {
+            continue;
+        }
+        inventory.give(pickup.item.clone());
+        commands.entity(entity).despawn();
+    }
+}
@@ -59,6 +59,17 @@ pub(crate) fn spawn_level(mut commands: Commands, level_assets: Res<LevelAssets>
 #[reflect(Component)]
 pub(crate) struct Level;
 
+/// Dev builds can swap the level with `LOBS_MAP`, e.g. `LOBS_MAP=maps/fog_test.map`.
+fn level_map_path() -> String {
+    const MAIN_MAP: &str = "maps/grave.map";
+    #[cfg(feature = "dev")]
+    if let Ok(path) = std::env::var("LOBS_MAP") {
+        info!("Loading level {path} from LOBS_MAP");
+        return path;
+    }
+    MAIN_MAP.to_string()
+}
+
 /// A [`Resource`] that contains all the assets needed to spawn the level.
 /// We use this to preload assets before the level is spawned.
 #[derive(Resource, Asset, Clone, TypePath)]
@@ -84,7 +95,7 @@ impl FromWorld for LevelAssets {
         Self {
             // Our main level is inspired by the TheDarkMod fan mission [Volta I: The Stone](https://www.thedarkmod.com/missiondetails/?internalName=volta1_3)
             // level: assets.load("maps/volta_i/volta_i.map#Scene"),
-            level: assets.load("maps/grave.map#Scene"),
+            level: assets.load(format!("{}#Scene", level_map_path())),
             // You can regenerate the navmesh by using `bevy_rerecast_editor`
             navmesh: assets.load("maps/volta_i/volta_i.nav"),
             music: assets.load("audio/music/Mark Lingard - bryophyta.ogg"),
@@ -0,0 +1,220 @@
+//! Per-level starting items, upgrade levels and usable upgrade stations, set from a
+//! `starting_loadout` entity in the map. Maps without one start with the full loadout.
+//! Continuing a run keeps what the player had instead.
+
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use crate::{
+    gameplay::{
+        crusts::Crusts,
+        game_mode::GameMode,
+        inventory::{Inventory, Item},
+        save_slot::NewGamePlus,
+        session::Session,
+        store::{ITEM_UPGRADES, UpgradeLevels, apply_upgrade_levels},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelLoadout>();
+    app.add_observer(read_starting_loadout);
+    app.add_observer(give_item);
+    app.add_systems(OnEnter(Screen::Gameplay), apply_default_loadout);
+    app.add_systems(Update, register_give_item_command);
+}
+
+/// What the player starts the level with. Lists are comma-separated.
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct StartingLoadout {
+    /// Any of `shovel`, `gun`, `bucket`, `bandage`, `tonic`.
+    pub items: String,
+    /// `upgrade=level` for the starting tools, e.g. `shovel_radius=2, gun_damage=1`.
+    pub upgrades: String,
+    /// Upgrade kinds whose stations work in this level. Empty = all of them.
+    pub allowed_upgrades: String,
+    /// Keep the crusts from the previous level instead of starting from zero.
+    pub carry_crusts: bool,
+}
+
+impl Default for StartingLoadout {
+    fn default() -> Self {
+        Self {
+            items: "shovel, gun, bucket, bandage, tonic".to_string(),
+            upgrades: String::new(),
+            allowed_upgrades: String::new(),
+            carry_crusts: true,
+        }
+    }
+}
+
+/// The parts of the level's [`StartingLoadout`] that matter after it's applied.
+#[derive(Resource, Default)]
+pub(crate) struct LevelLoadout {
+    allowed_upgrades: Vec<String>,
+}
+
+impl LevelLoadout {
+    fn new(loadout: &StartingLoadout) -> Self {
+        Self {
+            allowed_upgrades: list(&loadout.allowed_upgrades)
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    pub(crate) fn allows_upgrade(&self, upgrade: &str) -> bool {
+        self.allowed_upgrades.is_empty() || self.allowed_upgrades.iter().any(|u| u == upgrade)
+    }
+}
+
+/// Hands the player an item at the current upgrade levels, e.g. from `<<give_item gun>>`.
+#[derive(Event)]
+pub(crate) struct GiveItem(pub(crate) String);
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn apply_loadout(
+    loadout: &StartingLoadout,
+    inventory: &mut Inventory,
+    upgrade_levels: &mut UpgradeLevels,
+    crusts: &mut Crusts,
+    level_loadout: &mut LevelLoadout,
+    new_game_plus: &NewGamePlus,
+) {
+    *upgrade_levels = UpgradeLevels::default();
+    for entry in list(&loadout.upgrades) {
+        let parsed = entry.split_once('=').and_then(|(upgrade, level)| {
+            let upgrade = upgrade.trim();
+            let level: u32 = level.trim().parse().ok()?;
+            ITEM_UPGRADES.contains(&upgrade).then_some((upgrade, level))
+        });
+        match parsed.and_then(|(upgrade, level)| Some((upgrade_levels.level_mut(upgrade)?, level)))
+        {
+            Some((current, level)) => *current = level,
+            None => warn!(
+                "starting_loadout has a malformed upgrade {entry:?}, expected `upgrade=level` with one of {ITEM_UPGRADES:?}"
+            ),
+        }
+    }
+    // New game plus keeps whatever was bought last time, if it's more than the level gives.
+    for upgrade in ITEM_UPGRADES.iter().chain(&["max_hp", "pickup_radius"]) {
+        let carried = new_game_plus.upgrades.level_for(upgrade);
+        if let Some(level) = upgrade_levels.level_mut(upgrade) {
+            *level = (*level).max(carried);
+        }
+    }
+
+    *inventory = Inventory {
+        slots: default(),
+        ..default()
+    };
+    for key in list(&loadout.items) {
+        match Item::from_key(key) {
+            Some(mut item) => {
+                apply_upgrade_levels(&mut item, upgrade_levels);
+                inventory.give(item);
+            }
+            None => warn!("starting_loadout has an unknown item {key:?}"),
+        }
+    }
+    inventory.active_slot = inventory
+        .slots
+        .iter()
+        .position(Option::is_some)
+        .unwrap_or_default();
+
+    *level_loadout = LevelLoadout::new(loadout);
+    // Zero outside of new game plus, which always keeps its crusts.
+    if !loadout.carry_crusts {
+        crusts.0 = new_game_plus.crusts;
+    }
+}
+
+/// Runs before the level spawns, so a map without a `starting_loadout` gets the full one.
+fn apply_default_loadout(
+    mut inventory: ResMut<Inventory>,
+    mut upgrade_levels: ResMut<UpgradeLevels>,
+    mut crusts: ResMut<Crusts>,
+    mut level_loadout: ResMut<LevelLoadout>,
+    new_game_plus: Res<NewGamePlus>,
+    session: Res<Session>,
+) {
+    if session.resuming() {
+        *level_loadout = LevelLoadout::new(&StartingLoadout::default());
+        return;
+    }
+    apply_loadout(
+        &StartingLoadout::default(),
+        &mut inventory,
+        &mut upgrade_levels,
+        &mut crusts,
+        &mut level_loadout,
+        &new_game_plus,
+    );
+}
+
+fn read_starting_loadout(
+    add: On<Add, StartingLoadout>,
+    loadouts: Query<&StartingLoadout>,
+    mode: Res<GameMode>,
+    mut inventory: ResMut<Inventory>,
+    mut upgrade_levels: ResMut<UpgradeLevels>,
+    mut crusts: ResMut<Crusts>,
+    mut level_loadout: ResMut<LevelLoadout>,
+    new_game_plus: Res<NewGamePlus>,
+    session: Res<Session>,
+) {
+    // The practice range and endless waves always get everything.
+    if *mode != GameMode::Story {
+        return;
+    }
+    let Ok(loadout) = loadouts.get(add.entity) else {
+        return;
+    };
+    // Continuing keeps the items and upgrades, only the stations need to know the level's rules.
+    if session.resuming() {
+        *level_loadout = LevelLoadout::new(loadout);
+        return;
+    }
+    apply_loadout(
+        loadout,
+        &mut inventory,
+        &mut upgrade_levels,
+        &mut crusts,
+        &mut level_loadout,
+        &new_game_plus,
+    );
+}
+
+fn give_item(
+    give: On<GiveItem>,
+    mut inventory: ResMut<Inventory>,
+    upgrade_levels: Res<UpgradeLevels>,
+) {
+    let Some(mut item) = Item::from_key(give.0.trim()) else {
+        warn!("Can't give unknown item {:?}", give.0);
+        return;
+    };
+    apply_upgrade_levels(&mut item, &upgrade_levels);
+    inventory.give(item);
+}
+
+fn register_give_item_command(
+    mut runners: Query<&mut DialogueRunner, Added<DialogueRunner>>,
+    mut commands: Commands,
+) {
+    for mut runner in &mut runners {
+        let give = commands.register_system(|In(item): In<String>, mut commands: Commands| {
+            commands.trigger(GiveItem(item));
+        });
+        runner.commands_mut().add_command("give_item", give);
+    }
+}
@@ -0,0 +1,293 @@
+//! Top-down minimap rendered to a texture, with icons for the player, enemies, graves and objectives.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    camera::{RenderTarget, ScalingMode, visibility::RenderLayers},
+    light::NotShadowCaster,
+    prelude::*,
+    render::render_resource::TextureFormat,
+    ui::widget::ViewportNode,
+};
+
+use crate::{
+    RenderLayer,
+    gameplay::{
+        grave::{GraveCenter, GraveState},
+        hud::{HudElement, HudSettings, HudVisibility},
+        npc::{EnemyGunner, NpcDead},
+        player::{Player, camera::PlayerCamera},
+        tags::Tags,
+    },
+    screens::Screen,
+};
+
+const MINIMAP_SIZE: f32 = 180.0;
+const MINIMAP_RESOLUTION: u32 = 256;
+/// World units visible across the minimap.
+const MINIMAP_SPAN: f32 = 40.0;
+/// How far above the player the camera sits, high enough to see over dug-out walls.
+const CAMERA_HEIGHT: f32 = 60.0;
+/// Icons float just under the camera so terrain never covers them.
+const ICON_DEPTH: f32 = 1.0;
+const ICON_RADIUS: f32 = 0.6;
+/// Entities tagged with this get an objective marker.
+const OBJECTIVE_TAG: &str = "objective";
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<MinimapAssets>();
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_minimap);
+    app.add_observer(add_player_icon);
+    app.add_observer(add_enemy_icon);
+    app.add_observer(add_grave_icon);
+    app.add_observer(add_objective_icon);
+    app.add_systems(
+        Update,
+        (follow_player, update_minimap_icons, toggle_minimap_camera)
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Resource)]
+struct MinimapAssets {
+    circle: Handle<Mesh>,
+    arrow: Handle<Mesh>,
+    player: Handle<StandardMaterial>,
+    enemy: Handle<StandardMaterial>,
+    grave: Handle<StandardMaterial>,
+    objective: Handle<StandardMaterial>,
+}
+
+impl FromWorld for MinimapAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let circle = meshes.add(Circle::new(ICON_RADIUS));
+        let arrow = meshes.add(Triangle2d::new(
+            Vec2::new(0.0, ICON_RADIUS * 1.5),
+            Vec2::new(-ICON_RADIUS, -ICON_RADIUS),
+            Vec2::new(ICON_RADIUS, -ICON_RADIUS),
+        ));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut unlit = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            })
+        };
+        Self {
+            circle,
+            arrow,
+            player: unlit(Color::WHITE),
+            enemy: unlit(Color::srgb(0.9, 0.15, 0.1)),
+            grave: unlit(Color::srgb(0.55, 0.4, 0.25)),
+            objective: unlit(Color::srgb(1.0, 0.85, 0.2)),
+        }
+    }
+}
+
+#[derive(Component)]
+struct MinimapCamera;
+
+/// Follows `target` on the minimap, despawned along with it.
+#[derive(Component)]
+struct MinimapIcon {
+    target: Entity,
+}
+
+/// Also turns to match where the player is looking.
+#[derive(Component)]
+struct MinimapPlayerIcon;
+
+fn spawn_minimap(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    hud_settings: Res<HudSettings>,
+) {
+    let image = Image::new_target_texture(
+        MINIMAP_RESOLUTION,
+        MINIMAP_RESOLUTION,
+        TextureFormat::Bgra8UnormSrgb,
+        None,
+    );
+    let image_handle = images.add(image);
+
+    let camera = commands
+        .spawn((
+            Name::new("Minimap Camera"),
+            MinimapCamera,
+            Camera3d::default(),
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: MINIMAP_SPAN,
+                    height: MINIMAP_SPAN,
+                },
+                far: CAMERA_HEIGHT * 2.0,
+                ..OrthographicProjection::default_3d()
+            }),
+            Camera {
+                order: 0,
+                clear_color: ClearColorConfig::Custom(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            Msaa::Off,
+            RenderTarget::Image(image_handle.into()),
+            // North stays up.
+            Transform::from_xyz(0.0, CAMERA_HEIGHT, 0.0).looking_at(Vec3::ZERO, Vec3::NEG_Z),
+            RenderLayers::from(RenderLayer::DEFAULT | RenderLayer::MINIMAP),
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .id();
+
+    commands.spawn((
+        Name::new("Minimap"),
+        HudElement::Minimap,
+        hud_settings.slot(HudElement::Minimap, 0),
+        Node {
+            width: Val::Px(MINIMAP_SIZE),
+            height: Val::Px(MINIMAP_SIZE),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor::all(Color::WHITE),
+        Pickable::IGNORE,
+        children![(
+            ViewportNode::new(camera),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+        )],
+    ));
+}
+
+fn spawn_icon(
+    commands: &mut Commands,
+    target: Entity,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+) -> Entity {
+    commands
+        .spawn((
+            Name::new("Minimap Icon"),
+            MinimapIcon { target },
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::default(),
+            RenderLayers::from(RenderLayer::MINIMAP),
+            NotShadowCaster,
+            DespawnOnExit(Screen::Gameplay),
+        ))
+        .id()
+}
+
+fn add_player_icon(add: On<Add, Player>, mut commands: Commands, assets: Res<MinimapAssets>) {
+    let icon = spawn_icon(
+        &mut commands,
+        add.entity,
+        assets.arrow.clone(),
+        assets.player.clone(),
+    );
+    commands.entity(icon).insert(MinimapPlayerIcon);
+}
+
+fn add_enemy_icon(add: On<Add, EnemyGunner>, mut commands: Commands, assets: Res<MinimapAssets>) {
+    spawn_icon(
+        &mut commands,
+        add.entity,
+        assets.circle.clone(),
+        assets.enemy.clone(),
+    );
+}
+
+fn add_grave_icon(add: On<Add, GraveState>, mut commands: Commands, assets: Res<MinimapAssets>) {
+    spawn_icon(
+        &mut commands,
+        add.entity,
+        assets.circle.clone(),
+        assets.grave.clone(),
+    );
+}
+
+fn add_objective_icon(
+    add: On<Add, Tags>,
+    mut commands: Commands,
+    tags: Query<&Tags>,
+    assets: Res<MinimapAssets>,
+) {
+    if !tags
+        .get(add.entity)
+        .is_ok_and(|tags| tags.contains(OBJECTIVE_TAG))
+    {
+        return;
+    }
+    spawn_icon(
+        &mut commands,
+        add.entity,
+        assets.circle.clone(),
+        assets.objective.clone(),
+    );
+}
+
+fn follow_player(
+    player: Single<&GlobalTransform, With<Player>>,
+    mut camera: Single<&mut Transform, With<MinimapCamera>>,
+) {
+    let position = player.translation();
+    camera.translation = Vec3::new(position.x, position.y + CAMERA_HEIGHT, position.z);
+}
+
+fn update_minimap_icons(
+    mut commands: Commands,
+    camera: Single<&Transform, With<MinimapCamera>>,
+    player_camera: Option<Single<&GlobalTransform, With<PlayerCamera>>>,
+    mut icons: Query<
+        (Entity, &MinimapIcon, &mut Transform, Has<MinimapPlayerIcon>),
+        Without<MinimapCamera>,
+    >,
+    targets: Query<(&GlobalTransform, Option<&GraveCenter>, Has<NpcDead>)>,
+) {
+    let icon_y = camera.translation.y - ICON_DEPTH;
+    // Flat icons face the camera looking straight down.
+    let face_up = Quat::from_rotation_x(-FRAC_PI_2);
+    let player_yaw = player_camera.map_or(0.0, |transform| {
+        transform
+            .compute_transform()
+            .rotation
+            .to_euler(EulerRot::YXZ)
+            .0
+    });
+
+    for (entity, icon, mut transform, is_player) in &mut icons {
+        let Ok((target_transform, grave_center, dead)) = targets.get(icon.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if dead {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Graves are brush entities, their transform sits at the map origin.
+        let position = grave_center.map_or(target_transform.translation(), |center| center.0);
+        transform.translation = Vec3::new(position.x, icon_y, position.z);
+        transform.rotation = if is_player {
+            Quat::from_rotation_y(player_yaw) * face_up
+        } else {
+            face_up
+        };
+    }
+}
+
+fn toggle_minimap_camera(
+    hud: Res<HudVisibility>,
+    mut camera: Single<&mut Camera, With<MinimapCamera>>,
+) {
+    // Don't render the map at all while it's hidden.
+    let active = hud.shows(HudElement::Minimap);
+    if camera.is_active != active {
+        camera.is_active = active;
+    }
+}
@@ -1,22 +1,46 @@
 use bevy::prelude::*;
 
 mod animation;
+pub(crate) mod barrel;
+pub(crate) mod buried_item;
 pub(crate) mod button;
+pub(crate) mod captions;
 pub(crate) mod crosshair;
+pub(crate) mod crust_pickup;
 pub(crate) mod crusts;
 pub(crate) mod dig;
+pub(crate) mod environment;
+pub(crate) mod explosion;
+pub(crate) mod game_mode;
 pub(crate) mod grave;
+pub(crate) mod grenade;
 pub(crate) mod health_ui;
+pub(crate) mod highlight;
+pub(crate) mod hud;
 pub(crate) mod inventory;
+pub(crate) mod item_pickup;
 pub(crate) mod level;
+pub(crate) mod loadout;
+pub(crate) mod minimap;
+pub(crate) mod mover;
 pub(crate) mod npc;
 pub(crate) mod objective;
+pub(crate) mod objective_hints;
 pub(crate) mod player;
+pub(crate) mod practice;
+pub(crate) mod probe;
 pub(crate) mod ragdoll;
+pub(crate) mod run_timer;
+pub(crate) mod sandbox;
+pub(crate) mod save_slot;
 pub(crate) mod scenario;
 pub(crate) mod sensor_area;
+pub(crate) mod session;
+pub(crate) mod signpost;
 pub(crate) mod store;
+pub(crate) mod surface;
 pub(crate) mod tags;
+pub(crate) mod teleporter;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
@@ -27,6 +51,7 @@ pub(super) fn plugin(app: &mut App) {
         grave::plugin,
         health_ui::plugin,
         inventory::plugin,
+        item_pickup::plugin,
         npc::plugin,
         objective::plugin,
         dig::plugin,
@@ -34,8 +59,35 @@ pub(super) fn plugin(app: &mut App) {
         // ragdoll::plugin,
         scenario::plugin,
         sensor_area::plugin,
+    ));
+    app.add_plugins((
+        barrel::plugin,
+        crust_pickup::plugin,
+        environment::plugin,
+        hud::plugin,
+        loadout::plugin,
+        minimap::plugin,
+        mover::plugin,
+        objective_hints::plugin,
+        practice::plugin,
+        probe::plugin,
+        run_timer::plugin,
+        signpost::plugin,
         store::plugin,
+        surface::plugin,
         tags::plugin,
+        teleporter::plugin,
+    ));
+    app.add_plugins((
+        buried_item::plugin,
+        captions::plugin,
+        explosion::plugin,
+        game_mode::plugin,
+        grenade::plugin,
+        highlight::plugin,
+        sandbox::plugin,
+        save_slot::plugin,
+        session::plugin,
     ));
     // This plugin preloads the level,
     // so make sure to add it last.
@@ -0,0 +1,476 @@
+//! Moving platforms and elevators, built from brushes with a `mover_platform` class.
+//!
+//! A platform moves between stops given as offsets from where it's placed, at a constant
+//! speed, and waits `dwell` seconds at each one. Characters standing on it get carried by
+//! moving them along with it before their controller runs; loose props and bodies on top get
+//! its velocity. If it's about to squash a character into the level it turns back.
+//!
+//! To try one out, draw a brush, tie it to `mover_platform` and set `targets` to
+//! `0 0 0; 0 6 0` for an elevator that goes up 6 units and back down. For one that only moves
+//! when asked, set `loop_mode` to triggered and `tag` to something like `lift`, then place a
+//! `button` with the trigger `move_platform lift 1` beside it. From Yarn, the same is
+//! `<<move_platform lift 1>>`.
+
+use avian3d::prelude::*;
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use bevy_ahoy::{CharacterController, prelude::*};
+use bevy_trenchbroom::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use super::tags::{TagIndex, Tags};
+use crate::{game_running, third_party::avian3d::CollisionLayer};
+
+/// How far past the platform's bounds a character counts as in its way.
+const SQUISH_MARGIN: f32 = 0.1;
+/// How far from a character in the platform's way the level has to be to squash them.
+const SQUISH_PROBE_DISTANCE: f32 = 1.2;
+/// How far below the platform's top a loose body can be and still count as on top of it.
+const RIDER_MARGIN: f32 = 0.25;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, (init_platforms, register_move_platform_command));
+    app.add_systems(
+        FixedUpdate,
+        (drive_platforms, reverse_squishing_platforms, carry_riders)
+            .chain()
+            .run_if(game_running),
+    );
+    app.add_observer(move_platform);
+}
+
+#[derive(FgdType, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[number_key]
+pub(crate) enum LoopMode {
+    #[default]
+    /// Back and forth through the stops
+    PingPong = 0,
+    /// Through the stops, then from the last straight back to the first
+    Loop = 1,
+    /// Only moves on a move_platform trigger
+    Triggered = 2,
+}
+
+/// A brush that moves between stops.
+#[solid_class(base(Transform, Visibility))]
+pub(crate) struct MoverPlatform {
+    /// Offsets from where the platform is placed, separated by semicolons, like
+    /// "0 0 0; 0 0 -6". It starts at the first.
+    pub targets: String,
+    /// Units per second.
+    pub speed: f32,
+    pub loop_mode: LoopMode,
+    /// Seconds to wait at each stop.
+    pub dwell: f32,
+    /// Tag for move_platform triggers.
+    pub tag: String,
+}
+
+impl Default for MoverPlatform {
+    fn default() -> Self {
+        Self {
+            targets: "0 0 0".into(),
+            speed: 2.0,
+            loop_mode: LoopMode::PingPong,
+            dwell: 2.0,
+            tag: String::new(),
+        }
+    }
+}
+
+/// Sends platforms tagged `tag` to the stop at `stop_index`, counting from 0.
+/// Ping-pong and looping platforms carry on from there.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub(crate) struct MovePlatform {
+    pub tag: String,
+    pub stop_index: usize,
+}
+
+impl MovePlatform {
+    /// Parses a button trigger like "move_platform lift 1".
+    pub(crate) fn parse(trigger: &str) -> Option<Self> {
+        let mut words = trigger.split_whitespace();
+        if words.next()? != "move_platform" {
+            return None;
+        }
+        let tag = words.next()?.to_string();
+        let stop_index = words.next()?.parse().ok()?;
+        Some(Self { tag, stop_index })
+    }
+}
+
+/// Parses `targets` into offsets, skipping any that aren't three numbers.
+fn parse_targets(targets: &str) -> Vec<Vec3> {
+    targets
+        .split(';')
+        .filter_map(|target| {
+            let numbers: Vec<f32> = target
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .ok()?;
+            match numbers[..] {
+                [x, y, z] => Some(Vec3::new(x, y, z)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Where a platform is going and how long it's waited there.
+#[derive(Component, Debug)]
+pub(crate) struct PlatformMotion {
+    stops: Vec<Vec3>,
+    /// The stop it's moving to, or waiting at once `arrived`.
+    target: usize,
+    /// The stop it last left, to turn back to.
+    from: usize,
+    arrived: bool,
+    /// Whether ping-pong is heading toward the last stop.
+    forward: bool,
+    waited: f32,
+}
+
+impl PlatformMotion {
+    fn new(stops: Vec<Vec3>) -> Self {
+        Self {
+            stops,
+            target: 0,
+            from: 0,
+            arrived: false,
+            forward: true,
+            waited: 0.0,
+        }
+    }
+
+    fn go_to(&mut self, stop: usize) {
+        if stop == self.target {
+            return;
+        }
+        self.from = self.target;
+        self.target = stop;
+        self.arrived = false;
+        self.waited = 0.0;
+    }
+
+    fn reverse(&mut self) {
+        self.forward = !self.forward;
+        self.go_to(self.from);
+    }
+
+    fn next_stop(&mut self, mode: LoopMode) -> usize {
+        let last = self.stops.len().saturating_sub(1);
+        match mode {
+            LoopMode::Loop => (self.target + 1) % self.stops.len().max(1),
+            LoopMode::PingPong => {
+                if (self.forward && self.target >= last) || (!self.forward && self.target == 0) {
+                    self.forward = !self.forward;
+                }
+                if self.forward {
+                    (self.target + 1).min(last)
+                } else {
+                    self.target.saturating_sub(1)
+                }
+            }
+            LoopMode::Triggered => self.target,
+        }
+    }
+
+    /// The velocity to move at for the next `dt` seconds from `position`, landing exactly on
+    /// the stop when it gets there.
+    fn step(&mut self, position: Vec3, platform: &MoverPlatform, dt: f32) -> Vec3 {
+        let Some(&stop) = self.stops.get(self.target) else {
+            return Vec3::ZERO;
+        };
+        if !self.arrived {
+            let offset = stop - position;
+            if offset.length() > platform.speed * dt {
+                return offset.normalize_or_zero() * platform.speed;
+            }
+            self.arrived = true;
+            return offset / dt;
+        }
+        if platform.loop_mode == LoopMode::Triggered {
+            return Vec3::ZERO;
+        }
+        self.waited += dt;
+        if self.waited >= platform.dwell {
+            let next = self.next_stop(platform.loop_mode);
+            self.go_to(next);
+        }
+        Vec3::ZERO
+    }
+}
+
+/// A loose body being carried along, with the platform velocity it was last given.
+#[derive(Component)]
+struct PlatformRider {
+    carried: Vec3,
+}
+
+fn init_platforms(
+    mut commands: Commands,
+    // Waiting on the collider means the scene hooks have already made it static.
+    platforms: Query<
+        (Entity, &MoverPlatform, &Transform),
+        (With<Collider>, Without<PlatformMotion>),
+    >,
+) {
+    for (entity, platform, transform) in &platforms {
+        let stops: Vec<Vec3> = parse_targets(&platform.targets)
+            .into_iter()
+            .map(|offset| transform.translation + offset)
+            .collect();
+        if stops.is_empty() {
+            warn!(
+                "Mover platform has no usable targets: {:?}",
+                platform.targets
+            );
+        }
+        commands.entity(entity).insert((
+            PlatformMotion::new(stops),
+            Tags::from_csv(&platform.tag),
+            RigidBody::Kinematic,
+            CollidingEntities::default(),
+        ));
+    }
+}
+
+fn drive_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(
+        &MoverPlatform,
+        &mut PlatformMotion,
+        &Transform,
+        &mut LinearVelocity,
+    )>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    for (platform, mut motion, transform, mut velocity) in &mut platforms {
+        velocity.0 = motion.step(transform.translation, platform, dt);
+    }
+}
+
+/// Turns platforms back before they push a character into the level.
+fn reverse_squishing_platforms(
+    mut platforms: Query<(Entity, &mut PlatformMotion, &LinearVelocity, &ColliderAabb)>,
+    characters: Query<(&ColliderAabb, &GlobalTransform, &CharacterControllerState)>,
+    parents: Query<&ChildOf>,
+    spatial_query: SpatialQuery,
+) {
+    for (entity, mut motion, velocity, platform_aabb) in &mut platforms {
+        let Ok(direction) = Dir3::new(velocity.0) else {
+            continue;
+        };
+        let min = platform_aabb.min - SQUISH_MARGIN;
+        let max = platform_aabb.max + SQUISH_MARGIN;
+        let center = (platform_aabb.min + platform_aabb.max) * 0.5;
+        let filter =
+            SpatialQueryFilter::from_mask(CollisionLayer::Level).with_excluded_entities([entity]);
+        let squished = characters.iter().any(|(aabb, transform, state)| {
+            let riding = state.grounded.as_ref().is_some_and(|hit| {
+                std::iter::once(hit.entity)
+                    .chain(parents.iter_ancestors(hit.entity))
+                    .any(|ancestor| ancestor == entity)
+            });
+            let position = transform.translation();
+            let touching = aabb.min.cmple(max).all() && aabb.max.cmpge(min).all();
+            !riding
+                && touching
+                && (position - center).dot(*direction) > 0.0
+                && spatial_query
+                    .cast_ray(position, direction, SQUISH_PROBE_DISTANCE, true, &filter)
+                    .is_some()
+        });
+        if squished {
+            motion.reverse();
+        }
+    }
+}
+
+/// Moves characters standing on a platform along with it, ahead of their controllers, and
+/// hands the platform's velocity to loose bodies resting on top.
+fn carry_riders(
+    mut commands: Commands,
+    time: Res<Time>,
+    platforms: Query<(&LinearVelocity, &ColliderAabb, &CollidingEntities), With<PlatformMotion>>,
+    mut characters: Query<(&CharacterControllerState, &mut Transform), With<CharacterController>>,
+    mut bodies: Query<
+        (
+            Entity,
+            &RigidBody,
+            &GlobalTransform,
+            &mut LinearVelocity,
+            Option<&mut PlatformRider>,
+        ),
+        Without<PlatformMotion>,
+    >,
+    parents: Query<&ChildOf>,
+    mut carried: Local<EntityHashSet>,
+) {
+    let dt = time.delta_secs();
+    for (state, mut transform) in &mut characters {
+        let Some(hit) = &state.grounded else {
+            continue;
+        };
+        let platform = std::iter::once(hit.entity)
+            .chain(parents.iter_ancestors(hit.entity))
+            .find_map(|entity| platforms.get(entity).ok());
+        if let Some((velocity, ..)) = platform {
+            transform.translation += velocity.0 * dt;
+        }
+    }
+
+    carried.clear();
+    for (platform_velocity, aabb, colliding) in &platforms {
+        for &entity in colliding.iter() {
+            let Ok((entity, rigid_body, transform, mut velocity, rider)) = bodies.get_mut(entity)
+            else {
+                continue;
+            };
+            if !rigid_body.is_dynamic() || transform.translation().y < aabb.max.y - RIDER_MARGIN {
+                continue;
+            }
+            // Only the change is added, so the body keeps whatever it's doing on its own.
+            match rider {
+                Some(mut rider) => {
+                    velocity.0 += platform_velocity.0 - rider.carried;
+                    rider.carried = platform_velocity.0;
+                }
+                None => {
+                    velocity.0 += platform_velocity.0;
+                    commands.entity(entity).insert(PlatformRider {
+                        carried: platform_velocity.0,
+                    });
+                }
+            }
+            carried.insert(entity);
+        }
+    }
+    // Bodies that stepped off keep their momentum.
+    for (entity, .., rider) in &bodies {
+        if rider.is_some() && !carried.contains(&entity) {
+            commands.entity(entity).remove::<PlatformRider>();
+        }
+    }
+}
+
+fn move_platform(
+    event: On<MovePlatform>,
+    index: Res<TagIndex>,
+    mut platforms: Query<&mut PlatformMotion>,
+) {
+    let Some(entities) = index.get(&event.tag) else {
+        warn!("No mover platforms tagged {:?}", event.tag);
+        return;
+    };
+    for &entity in entities {
+        let Ok(mut motion) = platforms.get_mut(entity) else {
+            continue;
+        };
+        if event.stop_index >= motion.stops.len() {
+            warn!(
+                "Mover platform tagged {:?} has no stop {}",
+                event.tag, event.stop_index
+            );
+            continue;
+        }
+        motion.go_to(event.stop_index);
+    }
+}
+
+fn register_move_platform_command(
+    mut runners: Query<&mut DialogueRunner, Added<DialogueRunner>>,
+    mut commands: Commands,
+) {
+    for mut runner in &mut runners {
+        let system = commands.register_system(
+            |In((tag, stop_index)): In<(String, f32)>, mut commands: Commands| {
+                commands.trigger(MovePlatform {
+                    tag,
+                    stop_index: stop_index.max(0.0) as usize,
+                });
+            },
+        );
+        runner.commands_mut().add_command("move_platform", system);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps the platform until it's waiting at a stop, returning where it ended up.
+    fn run_to_stop(motion: &mut PlatformMotion, platform: &MoverPlatform, position: &mut Vec3) {
+        let dt = 0.25;
+        for _ in 0..1000 {
+            *position += motion.step(*position, platform, dt) * dt;
+            if motion.arrived {
+                return;
+            }
+        }
+        panic!("never got to a stop");
+    }
+
+    #[test]
+    fn targets_parse_into_offsets() {
+        assert_eq!(
+            parse_targets("0 0 0; 0 0 -6;1 2"),
+            vec![Vec3::ZERO, Vec3::new(0.0, 0.0, -6.0)]
+        );
+        assert_eq!(
+            MovePlatform::parse("move_platform lift 1"),
+            Some(MovePlatform {
+                tag: "lift".into(),
+                stop_index: 1,
+            })
+        );
+        assert_eq!(MovePlatform::parse("move_platform lift"), None);
+    }
+
+    #[test]
+    fn ping_pong_goes_back_and_forth_through_the_stops() {
+        let platform = MoverPlatform {
+            speed: 2.0,
+            dwell: 0.5,
+            ..default()
+        };
+        let stops = vec![Vec3::ZERO, Vec3::Y * 3.0, Vec3::Y * 6.0];
+        let mut motion = PlatformMotion::new(stops.clone());
+        let mut position = Vec3::ZERO;
+
+        let mut visited = Vec::new();
+        for _ in 0..5 {
+            run_to_stop(&mut motion, &platform, &mut position);
+            visited.push(motion.target);
+            assert!((position - stops[motion.target]).length() < 1e-4);
+            // Sit out the dwell.
+            while motion.arrived {
+                motion.step(position, &platform, 0.25);
+            }
+        }
+        assert_eq!(visited, vec![0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn reversing_heads_back_to_the_last_stop() {
+        let platform = MoverPlatform {
+            loop_mode: LoopMode::Triggered,
+            ..default()
+        };
+        let mut motion = PlatformMotion::new(vec![Vec3::ZERO, Vec3::NEG_Z * 6.0]);
+        let mut position = Vec3::ZERO;
+        run_to_stop(&mut motion, &platform, &mut position);
+        // Triggered platforms wait where they are.
+        assert_eq!(motion.step(position, &platform, 10.0), Vec3::ZERO);
+
+        motion.go_to(1);
+        position += motion.step(position, &platform, 0.25) * 0.25;
+        motion.reverse();
+        assert_eq!(motion.target, 0);
+        run_to_stop(&mut motion, &platform, &mut position);
+        assert!(position.length() < 1e-4);
+    }
+}
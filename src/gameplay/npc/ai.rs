@@ -1,4 +1,8 @@
 //! NPC AI. In this case, the only AI is the ability to move towards the player.
+//!
+//! The navmesh is baked from the level brushes, so it doesn't know about dug tunnels. When it
+//! can't get a follower to the player near a voxel volume, the follower walks a path over the
+//! volume's [`WalkGrid`] instead and goes back to the navmesh at the end of it.
 
 use avian3d::prelude::*;
 use bevy::prelude::*;
@@ -12,26 +16,38 @@ use bevy_landmass::{
 };
 
 use crate::{
+    game_running,
     gameplay::{
+        dig::{VoxelWorldBounds, walk_grid::WalkGrid},
         npc::NPC_SPEED,
         player::{Player, navmesh_position::LastValidPlayerNavmeshPosition},
     },
-    screens::Screen,
 };
 
 use super::{NPC_FLOAT_HEIGHT, NPC_RADIUS, Npc};
 
+/// Followers further than this from the player when the navmesh says they've arrived are
+/// stuck at the edge of a dug volume.
+const TUNNEL_HANDOFF_DISTANCE: f32 = 5.0;
+/// How far outside a volume's bounds counts as being at its edge.
+const TUNNEL_VOLUME_MARGIN: f32 = 2.0;
+const TUNNEL_WAYPOINT_RADIUS: f32 = 0.5;
+/// Tunnel paths are dropped after this long, so they're replanned if the player moved.
+const TUNNEL_REPLAN_SECONDS: f32 = 3.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         FixedUpdate,
         (
             sync_agent_velocity,
+            plan_tunnel_paths,
             set_controller_velocity,
+            follow_tunnel_paths,
             rotate_npc,
             update_agent_target,
         )
             .chain()
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(game_running),
     );
     app.add_observer(setup_npc_agent);
     app.add_input_context::<NpcInputContext>();
@@ -45,20 +61,7 @@ fn setup_npc_agent(
     archipelago: Single<Entity, With<Archipelago3d>>,
 ) {
     let npc = add.entity;
-    commands.entity(npc).insert((
-        NpcInputContext,
-        actions!(
-            NpcInputContext[(
-                Action::<GlobalMovement>::new(),
-                ActionMock {
-                    state: ActionState::None,
-                    value: Vec3::ZERO.into(),
-                    span: MockSpan::Updates(1),
-                    enabled: false
-                }
-            )]
-        ),
-    ));
+    commands.entity(npc).insert(movement_input());
     commands.spawn((
         Name::new("NPC Agent"),
         Transform::from_translation(Vec3::new(0.0, -NPC_FLOAT_HEIGHT, 0.0)),
@@ -80,7 +83,25 @@ fn setup_npc_agent(
 }
 
 #[derive(Component)]
-struct NpcInputContext;
+pub(super) struct NpcInputContext;
+
+/// Movement action for an NPC's character controller, steered by mocking its input.
+pub(super) fn movement_input() -> impl Bundle {
+    (
+        NpcInputContext,
+        actions!(
+            NpcInputContext[(
+                Action::<GlobalMovement>::new(),
+                ActionMock {
+                    state: ActionState::None,
+                    value: Vec3::ZERO.into(),
+                    span: MockSpan::Updates(1),
+                    enabled: false
+                }
+            )]
+        ),
+    )
+}
 
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
@@ -161,3 +182,111 @@ fn sync_agent_velocity(mut agent_query: Query<(&LinearVelocity, &mut LandmassVel
         landmass_velocity.velocity = avian_velocity.0;
     }
 }
+
+/// Waypoints through a voxel volume, followed instead of the navmesh while present.
+#[derive(Component, Debug)]
+struct TunnelPath {
+    waypoints: Vec<Vec3>,
+    next: usize,
+    expires: Timer,
+}
+
+fn plan_tunnel_paths(
+    mut commands: Commands,
+    agents: Query<(&AgentOf, &AgentState), With<WantsToFollowPlayer>>,
+    npcs: Query<&GlobalTransform, (With<Npc>, Without<TunnelPath>)>,
+    player: Single<&GlobalTransform, With<Player>>,
+    volumes: Query<(&WalkGrid, &GlobalTransform, &VoxelWorldBounds)>,
+) {
+    let target = player.translation();
+    for (agent_of, state) in &agents {
+        let Ok(npc_transform) = npcs.get(**agent_of) else {
+            continue;
+        };
+        let position = npc_transform.translation();
+        let stuck = match state {
+            AgentState::NoPath | AgentState::AgentNotOnNavMesh | AgentState::TargetNotOnNavMesh => {
+                true
+            }
+            AgentState::ReachedTarget => position.distance(target) > TUNNEL_HANDOFF_DISTANCE,
+            _ => false,
+        };
+        if !stuck {
+            continue;
+        }
+        if let Some(path) = tunnel_path(position, target, &volumes) {
+            commands.entity(**agent_of).insert(path);
+        }
+    }
+}
+
+/// Grid path from `from` to `to` through the first volume either of them is at.
+fn tunnel_path(
+    from: Vec3,
+    to: Vec3,
+    volumes: &Query<(&WalkGrid, &GlobalTransform, &VoxelWorldBounds)>,
+) -> Option<TunnelPath> {
+    let near = |bounds: &VoxelWorldBounds, point: Vec3| {
+        point.cmpge(bounds.min - TUNNEL_VOLUME_MARGIN).all()
+            && point.cmple(bounds.max + TUNNEL_VOLUME_MARGIN).all()
+    };
+    volumes
+        .iter()
+        .filter(|(_, _, bounds)| near(bounds, to) || near(bounds, from))
+        .find_map(|(grid, transform, _)| {
+            let to_local = transform.affine().inverse();
+            let start = grid.nearest_node(to_local.transform_point3(from))?;
+            let goal = grid.nearest_node(to_local.transform_point3(to))?;
+            let nodes = grid.find_path(start, goal)?;
+            Some(TunnelPath {
+                waypoints: nodes
+                    .into_iter()
+                    .map(|node| transform.transform_point(grid.node_position(node)))
+                    .collect(),
+                next: 0,
+                expires: Timer::from_seconds(TUNNEL_REPLAN_SECONDS, TimerMode::Once),
+            })
+        })
+}
+
+/// Steers followers along their tunnel path, overriding the navmesh velocity.
+fn follow_tunnel_paths(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut npcs: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut TunnelPath,
+        &Actions<NpcInputContext>,
+    )>,
+    mut action_mocks: Query<&mut ActionMock, With<Action<GlobalMovement>>>,
+) {
+    for (entity, transform, mut path, actions) in &mut npcs {
+        path.expires.tick(time.delta());
+        let position = transform.translation();
+        while path
+            .waypoints
+            .get(path.next)
+            .is_some_and(|waypoint| (*waypoint - position).xz().length() < TUNNEL_WAYPOINT_RADIUS)
+        {
+            path.next += 1;
+        }
+        let Some(waypoint) = path.waypoints.get(path.next) else {
+            commands.entity(entity).remove::<TunnelPath>();
+            continue;
+        };
+        if path.expires.is_finished() {
+            commands.entity(entity).remove::<TunnelPath>();
+            continue;
+        }
+
+        let to_waypoint = *waypoint - position;
+        let Ok(direction) = Dir3::new(Vec3::new(to_waypoint.x, 0.0, to_waypoint.z)) else {
+            continue;
+        };
+        let mut iter = action_mocks.iter_many_mut(actions);
+        if let Some(mut mock) = iter.fetch_next() {
+            *mock = ActionMock::once(ActionState::Fired, *direction);
+        }
+    }
+}
@@ -9,21 +9,47 @@ use bevy_ahoy::CharacterControllerState;
 use crate::{
     PostPhysicsAppSystems,
     animation::{AnimationState, AnimationStateTransition},
-    gameplay::animation::AnimationPlayers,
+    gameplay::animation::{AnimationPlayerAncestor, AnimationPlayers},
     screens::Screen,
 };
 
-use super::assets::NpcAssets;
+use super::{NpcAggro, NpcDead, NpcPrefab, assets::NpcAssets};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         Update,
-        play_animations
+        (play_animations, play_idle_animations)
             .run_if(in_state(Screen::Gameplay))
             .in_set(PostPhysicsAppSystems::PlayAnimations),
     );
 }
 
+/// Loops a named animation from the NPC's glTF while it isn't aggroed.
+#[derive(Component, Debug)]
+#[require(AnimationPlayerAncestor)]
+pub(super) struct NpcIdleAnimation {
+    gltf: Handle<Gltf>,
+    name: String,
+}
+
+impl NpcIdleAnimation {
+    pub(super) fn from_prefab(prefab: &NpcPrefab, assets: &AssetServer) -> Option<Self> {
+        let name = prefab.idle_animation.clone()?;
+        let path = prefab
+            .scene
+            .split_once('#')
+            .map_or(prefab.scene.as_str(), |(path, _label)| path);
+        Some(Self {
+            gltf: assets.load(path.to_string()),
+            name,
+        })
+    }
+}
+
+/// The idle clip's node in the graph given to an NPC's animation player.
+#[derive(Component, Debug)]
+struct IdleAnimationNode(AnimationNodeIndex);
+
 #[derive(Component, Debug, Reflect)]
 #[reflect(Component)]
 struct NpcAnimations {
@@ -153,3 +179,47 @@ fn play_animations(
         }
     }
 }
+
+fn play_idle_animations(
+    mut commands: Commands,
+    npcs: Query<(
+        Entity,
+        &NpcIdleAnimation,
+        &AnimationPlayers,
+        Has<NpcAggro>,
+        Has<NpcDead>,
+    )>,
+    gltfs: Res<Assets<Gltf>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    mut players: Query<(&mut AnimationPlayer, Option<&IdleAnimationNode>)>,
+) {
+    for (entity, idle, anim_players, aggro, dead) in &npcs {
+        for anim_player in anim_players.iter() {
+            let Ok((mut player, node)) = players.get_mut(anim_player) else {
+                continue;
+            };
+            let Some(IdleAnimationNode(node)) = node else {
+                let Some(gltf) = gltfs.get(&idle.gltf) else {
+                    continue;
+                };
+                let Some(clip) = gltf.named_animations.get(idle.name.as_str()) else {
+                    warn!("NPC {entity} has no idle animation named {:?}", idle.name);
+                    commands.entity(entity).remove::<NpcIdleAnimation>();
+                    continue;
+                };
+                let (graph, node) = AnimationGraph::from_clip(clip.clone());
+                commands.entity(anim_player).insert((
+                    AnimationGraphHandle(graphs.add(graph)),
+                    IdleAnimationNode(node),
+                ));
+                continue;
+            };
+
+            if aggro || dead {
+                player.stop(*node);
+            } else if !player.is_playing_animation(*node) {
+                player.play(*node).repeat();
+            }
+        }
+    }
+}
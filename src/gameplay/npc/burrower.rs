@@ -0,0 +1,439 @@
+//! Enemies that dig through voxel volumes towards the player instead of walking the navmesh.
+//! A burrower lies still until the player comes near, then sinks under them, carving a tunnel
+//! with [`ModifyVoxels`] wherever dirt is in the way, surfaces next to them for a bite and dives
+//! again.
+//!
+//! Barriers, protected volumes and the level's brushes can't be dug, so a burrower slides along
+//! them where it can and otherwise stops dead until the player moves somewhere it can reach.
+
+use std::time::Duration;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use super::{
+    BodyConfig, DEFAULT_NPC_HEALTH, Health, NpcDead, NpcRegistry, Tags, model, npc_display_name,
+    shooting::Faction, threat::Threat,
+};
+use crate::{
+    game_running,
+    gameplay::{
+        dig::{
+            ModifyVoxels, VOXEL_SIZE, Voxel, VoxelSim, VoxelVolume, VoxelWorldBounds,
+            world_to_voxel,
+        },
+        game_mode::CombatEnabled,
+        player::{Invincible, Player, PlayerHealth, hurt_player},
+    },
+    third_party::{avian3d::CollisionLayer, bevy_trenchbroom::LoadTrenchbroomModel as _},
+};
+
+/// Radius of the body and of the tunnel it leaves behind.
+const BURROWER_RADIUS: f32 = 0.6;
+/// A new stretch of tunnel is carved every time the burrower moves this far.
+const CARVE_STEP: f32 = 0.3;
+/// Close enough to bite.
+const BITE_REACH: f32 = 1.5;
+/// Speed multiplier in the open, where there's nothing to dig.
+const OPEN_SPEED_MULTIPLIER: f32 = 2.0;
+/// Seconds spent diving away after a bite before tunneling again.
+const DIVE_SECONDS: f32 = 2.0;
+/// How far below the body the ground can be before it starts falling.
+const GROUND_SNAP: f32 = 0.2;
+/// Falling speed off ledges, in world units per second.
+const FALL_SPEED: f32 = 8.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_add_burrower);
+    app.add_systems(Update, burrow.run_if(game_running));
+}
+
+#[point_class(base(Transform, Visibility), model("models/crab/scene.gltf"))]
+pub(crate) struct Burrower {
+    /// Comma-separated tags for identification/objectives.
+    pub tag: String,
+    /// Registry key for the model prefab.
+    pub model: String,
+    /// Starting health. 0 = use default.
+    pub health: f32,
+    /// Speed through dirt, in world units per second. Twice as fast in the open.
+    pub dig_speed: f32,
+    /// Starts digging once the player is this close.
+    pub detect_radius: f32,
+    /// Surfaces once it's this close to the player, measured along the ground.
+    pub emerge_range: f32,
+    /// How far under the player it tunnels.
+    pub depth: f32,
+    /// Crusts dropped on death. 0 = the model's.
+    pub reward_crusts: u32,
+    /// Sub-objective that goes up by one on death. Empty = the model's.
+    pub reward_objective: String,
+}
+
+impl Default for Burrower {
+    fn default() -> Self {
+        Self {
+            tag: String::new(),
+            model: "crab".into(),
+            health: 0.0,
+            dig_speed: 3.0,
+            detect_radius: 25.0,
+            emerge_range: 4.0,
+            depth: 1.5,
+            reward_crusts: 0,
+            reward_objective: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BurrowState {
+    /// Waiting for the player to come within `detect_radius`.
+    Dormant,
+    /// Heading for the spot `depth` under the player.
+    Tunneling,
+    /// Coming up at the player for a bite.
+    Emerging,
+    /// Going back down after a bite.
+    Diving(Timer),
+}
+
+#[derive(Component, Debug)]
+struct Burrow {
+    dig_speed: f32,
+    detect_radius: f32,
+    emerge_range: f32,
+    depth: f32,
+    state: BurrowState,
+    /// Where the tunnel was last carved.
+    last_carve: Option<Vec3>,
+}
+
+impl Burrow {
+    fn from_burrower(burrower: &Burrower) -> Self {
+        Self {
+            dig_speed: burrower.dig_speed,
+            detect_radius: burrower.detect_radius,
+            emerge_range: burrower.emerge_range,
+            depth: burrower.depth,
+            state: BurrowState::Dormant,
+            last_carve: None,
+        }
+    }
+
+    /// Moves the state along for the burrower at `position`. Returns whether it bit the
+    /// player.
+    fn update(&mut self, position: Vec3, player: Vec3, delta: Duration) -> bool {
+        let along_ground = (player - position).xz().length();
+        match &mut self.state {
+            BurrowState::Dormant => {
+                if position.distance(player) <= self.detect_radius {
+                    self.state = BurrowState::Tunneling;
+                }
+            }
+            BurrowState::Tunneling => {
+                if along_ground <= self.emerge_range {
+                    self.state = BurrowState::Emerging;
+                }
+            }
+            BurrowState::Emerging => {
+                if position.distance(player) <= BITE_REACH {
+                    self.state =
+                        BurrowState::Diving(Timer::from_seconds(DIVE_SECONDS, TimerMode::Once));
+                    return true;
+                }
+                // Lost them, go back under.
+                if along_ground > self.emerge_range * 2.0 {
+                    self.state = BurrowState::Tunneling;
+                }
+            }
+            BurrowState::Diving(timer) => {
+                if timer.tick(delta).is_finished() {
+                    self.state = BurrowState::Tunneling;
+                }
+            }
+        }
+        false
+    }
+
+    /// Where the burrower is heading, `None` while it lies still.
+    fn target(&self, position: Vec3, player: Vec3) -> Option<Vec3> {
+        match self.state {
+            BurrowState::Dormant => None,
+            BurrowState::Tunneling => Some(player - Vec3::Y * self.depth),
+            BurrowState::Emerging => Some(player),
+            BurrowState::Diving(_) => {
+                let away = (position - player).with_y(0.0).normalize_or_zero();
+                Some(position + (away - Vec3::Y) * self.depth)
+            }
+        }
+    }
+}
+
+fn on_add_burrower(
+    add: On<Add, Burrower>,
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    burrowers: Query<&Burrower>,
+    registry: Res<NpcRegistry>,
+    threat: Res<Threat>,
+) {
+    let entity = add.entity;
+    let Ok(burrower) = burrowers.get(entity) else {
+        return;
+    };
+    let tags = Tags::from_csv(&burrower.tag);
+    let model_key = burrower.model.trim();
+    let health = if burrower.health > 0.0 {
+        burrower.health
+    } else {
+        DEFAULT_NPC_HEALTH
+    } * threat.health_multiplier();
+
+    let prefab = model::resolve_npc_model(&mut commands, &registry, entity, model_key);
+    let prefab = prefab.as_deref();
+    let body_config = prefab.map(|p| p.body.clone()).unwrap_or_default();
+    let reward = prefab
+        .map(|p| p.reward.clone())
+        .unwrap_or_default()
+        .with_overrides(burrower.reward_crusts, &burrower.reward_objective);
+    let (scene, model_transform) = match prefab {
+        Some(prefab) => (assets.load(&prefab.scene), prefab.body.model_transform),
+        None => (
+            assets.load_trenchbroom_model::<Burrower>(),
+            body_config.model_transform,
+        ),
+    };
+
+    commands.entity(entity).insert((
+        Name::new(npc_display_name(model_key, "Burrower", &tags)),
+        Burrow::from_burrower(burrower),
+        // Moves itself, so it passes through the dirt it's digging instead of being stopped.
+        RigidBody::Kinematic,
+        Collider::sphere(BURROWER_RADIUS),
+        CollisionLayers::new(CollisionLayer::Character, [CollisionLayer::Prop]),
+        Health(health),
+        body_config,
+        tags,
+        reward,
+        Faction("enemy".to_string()),
+    ));
+    commands
+        .entity(entity)
+        .with_child((Name::new("Npc Model"), SceneRoot(scene), model_transform));
+}
+
+fn burrow(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut burrowers: Query<
+        (
+            &GlobalTransform,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut Burrow,
+        ),
+        Without<NpcDead>,
+    >,
+    player: Option<
+        Single<
+            (
+                Entity,
+                &GlobalTransform,
+                &mut PlayerHealth,
+                Option<&Invincible>,
+            ),
+            With<Player>,
+        >,
+    >,
+    volumes: Query<(
+        Entity,
+        &VoxelVolume,
+        &VoxelSim,
+        &GlobalTransform,
+        &VoxelWorldBounds,
+    )>,
+    combat: Res<CombatEnabled>,
+    spatial_query: SpatialQuery,
+) {
+    let Some((player_entity, player_transform, mut health, invincible)) =
+        player.map(Single::into_inner)
+    else {
+        return;
+    };
+    let player = player_transform.translation();
+    let level = SpatialQueryFilter::from_mask(CollisionLayer::Level);
+    // Voxel colliders live on the volumes themselves and get dug through, what's left are brushes.
+    let brushes = level
+        .clone()
+        .with_excluded_entities(volumes.iter().map(|(volume, ..)| volume));
+
+    for (global_transform, mut transform, mut velocity, mut burrow) in &mut burrowers {
+        let position = global_transform.translation();
+        // Still surfaces and snaps at the player in a peaceful run, it just doesn't hurt.
+        let bit = burrow.update(position, player, time.delta());
+        if bit && combat.0 {
+            hurt_player(
+                &mut commands,
+                player_entity,
+                &mut health,
+                invincible,
+                position,
+            );
+        }
+        let Some(target) = burrow.target(position, player) else {
+            velocity.0 = Vec3::ZERO;
+            continue;
+        };
+        let heading = (target - position).normalize_or_zero();
+        let ahead = position + heading * BURROWER_RADIUS;
+
+        let volume = volumes
+            .iter()
+            .find(|(.., bounds)| ahead.cmpge(bounds.min).all() && ahead.cmple(bounds.max).all());
+        let blocking = volume.and_then(|(volume, voxel_volume, sim, sim_transform, _)| {
+            let voxel = sim.get(world_to_voxel(sim_transform, ahead))?;
+            (voxel != Voxel::Air).then_some((volume, voxel_volume.protected, voxel))
+        });
+
+        velocity.0 = match blocking {
+            // Can't dig through, wait for the player to move.
+            Some((_, true, _)) | Some((_, _, Voxel::Barrier)) => Vec3::ZERO,
+            Some((volume, false, _)) => {
+                let carved_nearby = burrow
+                    .last_carve
+                    .is_some_and(|carve| carve.distance(position) < CARVE_STEP);
+                if !carved_nearby {
+                    commands.trigger(ModifyVoxels {
+                        volume,
+                        point: ahead,
+                        radius: BURROWER_RADIUS / VOXEL_SIZE,
+                        voxel: Voxel::Air,
+                    });
+                    burrow.last_carve = Some(position);
+                }
+                heading * burrow.dig_speed
+            }
+            // In the open it keeps to the ground, unless it's lunging at the player.
+            None if burrow.state == BurrowState::Emerging => {
+                heading * burrow.dig_speed * OPEN_SPEED_MULTIPLIER
+            }
+            None => {
+                let grounded = spatial_query
+                    .cast_ray(
+                        position,
+                        Dir3::NEG_Y,
+                        BURROWER_RADIUS + GROUND_SNAP,
+                        true,
+                        &level,
+                    )
+                    .is_some();
+                let fall = if grounded { 0.0 } else { -FALL_SPEED };
+                (heading.with_y(0.0) * burrow.dig_speed * OPEN_SPEED_MULTIPLIER).with_y(fall)
+            }
+        };
+        velocity.0 = avoid_brushes(
+            &spatial_query,
+            &brushes,
+            position,
+            velocity.0,
+            time.delta_secs(),
+        );
+
+        if let Ok(facing) = Dir3::new(heading.with_y(0.0)) {
+            let target_rotation = transform.looking_to(facing, Vec3::Y).rotation;
+            transform.rotation = transform
+                .rotation
+                .slerp(target_rotation, (10.0 * time.delta_secs()).min(1.0));
+        }
+    }
+}
+
+/// Slides `velocity` along any brush it would run into this frame, or stops it if sliding runs
+/// into one too.
+fn avoid_brushes(
+    spatial_query: &SpatialQuery,
+    brushes: &SpatialQueryFilter,
+    position: Vec3,
+    velocity: Vec3,
+    delta: f32,
+) -> Vec3 {
+    let shape = Collider::sphere(BURROWER_RADIUS);
+    let hit = |velocity: Vec3| {
+        let (direction, speed) = Dir3::new_and_length(velocity).ok()?;
+        let config = ShapeCastConfig {
+            ignore_origin_penetration: true,
+            ..ShapeCastConfig::from_max_distance(speed * delta)
+        };
+        spatial_query
+            .cast_shape(
+                &shape,
+                position,
+                Quat::IDENTITY,
+                direction,
+                &config,
+                brushes,
+            )
+            // Resting on a floor or moving away from a wall isn't running into it.
+            .filter(|hit| velocity.dot(hit.normal2) < 0.0)
+    };
+    let Some(wall) = hit(velocity) else {
+        return velocity;
+    };
+    let slide = velocity.reject_from_normalized(wall.normal2);
+    if hit(slide).is_some() {
+        Vec3::ZERO
+    } else {
+        slide
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_burrow() -> Burrow {
+        Burrow::from_burrower(&Burrower::default())
+    }
+
+    #[test]
+    fn burrower_wakes_tunnels_under_and_surfaces_at_the_player() {
+        let mut burrow = default_burrow();
+        let player = Vec3::new(0.0, 1.0, 0.0);
+        let step = Duration::from_secs_f32(0.1);
+
+        let far = Vec3::new(40.0, 0.0, 0.0);
+        assert!(!burrow.update(far, player, step));
+        assert_eq!(burrow.state, BurrowState::Dormant);
+        assert_eq!(burrow.target(far, player), None);
+
+        let near = Vec3::new(10.0, 0.0, 0.0);
+        burrow.update(near, player, step);
+        assert_eq!(burrow.state, BurrowState::Tunneling);
+        assert_eq!(burrow.target(near, player), Some(player - Vec3::Y * 1.5));
+
+        let below = Vec3::new(3.0, -0.5, 0.0);
+        burrow.update(below, player, step);
+        assert_eq!(burrow.state, BurrowState::Emerging);
+        assert_eq!(burrow.target(below, player), Some(player));
+
+        assert!(burrow.update(Vec3::new(1.0, 0.5, 0.0), player, step));
+        assert!(matches!(burrow.state, BurrowState::Diving(_)));
+    }
+
+    #[test]
+    fn burrower_dives_away_then_tunnels_again() {
+        let mut burrow = default_burrow();
+        burrow.state = BurrowState::Diving(Timer::from_seconds(DIVE_SECONDS, TimerMode::Once));
+        let player = Vec3::ZERO;
+        let position = Vec3::new(1.0, 0.0, 0.0);
+
+        let target = burrow.target(position, player).unwrap();
+        assert!(target.x > position.x);
+        assert!(target.y < position.y);
+
+        burrow.update(position, player, Duration::from_secs_f32(DIVE_SECONDS));
+        assert_eq!(burrow.state, BurrowState::Tunneling);
+    }
+}
@@ -0,0 +1,199 @@
+//! NPCs the story can't go on without, like Larry. Whatever kills one, objective or not, ends
+//! the run: time slows for a moment, then the failure screen offers a retry from the last
+//! session snapshot, or from the start of the level without one.
+//!
+//! Scenes that kill a critical NPC on purpose clear the marker first with [`SetCritical`], or
+//! `<<set_critical larry false>>` from yarn.
+
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use super::NpcDead;
+use crate::{
+    Pause,
+    gameplay::{save_slot::leave_slot, tags::TagIndex},
+    menus::Menu,
+    screens::Screen,
+};
+
+/// Game speed while the death plays out.
+const FAILURE_TIME_SCALE: f32 = 0.25;
+/// Real time between the death and the failure screen.
+const FAILURE_DELAY_SECONDS: f32 = 1.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CriticalFailure>();
+    app.add_observer(fail_on_critical_death);
+    app.add_observer(set_critical);
+    app.add_systems(
+        Update,
+        (
+            register_set_critical_command,
+            show_failure_screen.run_if(in_state(Screen::Gameplay).and(critical_npc_died)),
+        ),
+    );
+    // After the session has seen the failure and skipped its snapshot.
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        reset_critical_failure.after(leave_slot),
+    );
+}
+
+/// On an NPC whose death ends the run.
+#[derive(Component, Debug)]
+pub(crate) struct Critical;
+
+/// Puts [`Critical`] on or takes it off every NPC tagged `tag`.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct SetCritical {
+    pub tag: String,
+    pub critical: bool,
+}
+
+/// The critical NPC that died this run, if one did.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct CriticalFailure {
+    npc: Option<String>,
+    delay: Timer,
+}
+
+impl CriticalFailure {
+    pub(crate) fn npc(&self) -> Option<&str> {
+        self.npc.as_deref()
+    }
+}
+
+/// A critical NPC died, so respawning and saving the run over the snapshot hold off.
+pub(crate) fn critical_npc_died(failure: Res<CriticalFailure>) -> bool {
+    failure.npc.is_some()
+}
+
+fn fail_on_critical_death(
+    add: On<Add, NpcDead>,
+    npcs: Query<Option<&Name>, With<Critical>>,
+    mut failure: ResMut<CriticalFailure>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let Ok(name) = npcs.get(add.entity) else {
+        return;
+    };
+    if failure.npc.is_some() {
+        return;
+    }
+    let name = name.map_or("someone important", Name::as_str);
+    info!("{name} died, ending the run");
+    *failure = CriticalFailure {
+        npc: Some(name.to_string()),
+        delay: Timer::from_seconds(FAILURE_DELAY_SECONDS, TimerMode::Once),
+    };
+    time.set_relative_speed(FAILURE_TIME_SCALE);
+}
+
+fn show_failure_screen(
+    real_time: Res<Time<Real>>,
+    mut failure: ResMut<CriticalFailure>,
+    mut time: ResMut<Time<Virtual>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if failure.delay.is_finished() {
+        return;
+    }
+    failure.delay.tick(real_time.delta());
+    if failure.delay.just_finished() {
+        time.set_relative_speed(1.0);
+        next_pause.set(Pause(true));
+        next_menu.set(Menu::CriticalFailure);
+    }
+}
+
+fn reset_critical_failure(mut failure: ResMut<CriticalFailure>, mut time: ResMut<Time<Virtual>>) {
+    *failure = default();
+    time.set_relative_speed(1.0);
+}
+
+fn set_critical(set: On<SetCritical>, mut commands: Commands, tag_index: Res<TagIndex>) {
+    let Some(entities) = tag_index.get(&set.tag) else {
+        warn!("No NPC tagged {:?} to set critical on", set.tag);
+        return;
+    };
+    for &entity in entities {
+        if set.critical {
+            commands.entity(entity).insert(Critical);
+        } else {
+            commands.entity(entity).remove::<Critical>();
+        }
+    }
+}
+
+fn register_set_critical_command(
+    mut runners: Query<&mut DialogueRunner, Added<DialogueRunner>>,
+    mut commands: Commands,
+) {
+    for mut runner in &mut runners {
+        let system = commands.register_system(
+            |In((tag, critical)): In<(String, bool)>, mut commands: Commands| {
+                commands.trigger(SetCritical { tag, critical });
+            },
+        );
+        runner.commands_mut().add_command("set_critical", system);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::tags::{self, Tags};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(tags::plugin);
+        app.init_resource::<CriticalFailure>();
+        app.add_observer(fail_on_critical_death);
+        app.add_observer(set_critical);
+        app
+    }
+
+    #[test]
+    fn critical_death_fails_the_run() {
+        let mut app = app();
+        let bystander = app.world_mut().spawn(Name::new("Crab")).id();
+        app.world_mut().entity_mut(bystander).insert(NpcDead);
+        assert!(app.world().resource::<CriticalFailure>().npc().is_none());
+
+        let larry = app.world_mut().spawn((Name::new("Larry"), Critical)).id();
+        app.world_mut().entity_mut(larry).insert(NpcDead);
+        let world = app.world();
+        assert_eq!(world.resource::<CriticalFailure>().npc(), Some("Larry"));
+        assert_eq!(
+            world.resource::<Time<Virtual>>().relative_speed(),
+            FAILURE_TIME_SCALE
+        );
+    }
+
+    #[test]
+    fn cleared_marker_lets_a_scene_kill_the_npc() {
+        let mut app = app();
+        let larry = app
+            .world_mut()
+            .spawn((Name::new("Larry"), Critical, Tags::from_csv("larry")))
+            .id();
+        app.world_mut().trigger(SetCritical {
+            tag: "larry".into(),
+            critical: false,
+        });
+        app.world_mut().flush();
+        assert!(app.world().get::<Critical>(larry).is_none());
+
+        app.world_mut().entity_mut(larry).insert(NpcDead);
+        assert!(app.world().resource::<CriticalFailure>().npc().is_none());
+
+        app.world_mut().trigger(SetCritical {
+            tag: "larry".into(),
+            critical: true,
+        });
+        app.world_mut().flush();
+        assert!(app.world().get::<Critical>(larry).is_some());
+    }
+}
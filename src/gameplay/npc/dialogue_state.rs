@@ -0,0 +1,184 @@
+//! Which yarn node an NPC's conversation starts from.
+//!
+//! Objectives push `condition → node` entries onto the NPC's [`DialogueState`] instead of
+//! overwriting its [`YarnNode`]. The effective node is copied into [`YarnNode`] only while that
+//! NPC isn't in a conversation, so a hook firing mid-dialogue can't desync the runner.
+
+use bevy::prelude::*;
+use bevy_yarnspinner::events::DialogueCompleted;
+
+use crate::{PostPhysicsAppSystems, third_party::bevy_yarnspinner::YarnNode};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(add_dialogue_state);
+    app.add_observer(end_conversations);
+    app.add_systems(
+        Update,
+        sync_yarn_nodes.before(PostPhysicsAppSystems::ChangeUi),
+    );
+}
+
+/// The node an NPC was placed with, plus the entries objectives have pushed on top of it.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DialogueState {
+    base: String,
+    /// `(condition, node)` in the order they were pushed, the last one wins.
+    entries: Vec<(String, String)>,
+}
+
+impl DialogueState {
+    pub(crate) fn new(base: impl Into<String>) -> Self {
+        Self {
+            base: base.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Starts conversations from `node` until the entry is popped or something is pushed on top.
+    /// Pushing a condition that's already there moves it to the top with the new node.
+    pub(crate) fn push(&mut self, condition: impl Into<String>, node: impl Into<String>) {
+        let condition = condition.into();
+        self.remove(&condition);
+        self.entries.push((condition, node.into()));
+    }
+
+    /// Removes the most recently pushed entry, returning `(condition, node)`.
+    pub(crate) fn pop(&mut self) -> Option<(String, String)> {
+        self.entries.pop()
+    }
+
+    /// Removes the entry for `condition` wherever it is, returning its node.
+    pub(crate) fn remove(&mut self, condition: &str) -> Option<String> {
+        let index = self
+            .entries
+            .iter()
+            .position(|(existing, _)| existing == condition)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Drops every entry, going back to the base node.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn effective_node(&self) -> &str {
+        self.entries
+            .last()
+            .map_or(self.base.as_str(), |(_, node)| node.as_str())
+    }
+}
+
+/// On the NPC the player is currently talking to.
+#[derive(Component)]
+pub(crate) struct InConversation;
+
+fn add_dialogue_state(
+    add: On<Add, YarnNode>,
+    mut commands: Commands,
+    nodes: Query<&YarnNode, Without<DialogueState>>,
+) {
+    let Ok(node) = nodes.get(add.entity) else {
+        return;
+    };
+    commands
+        .entity(add.entity)
+        .insert(DialogueState::new(node.yarn_node.clone()));
+}
+
+fn end_conversations(
+    _complete: On<DialogueCompleted>,
+    mut commands: Commands,
+    speakers: Query<Entity, With<InConversation>>,
+) {
+    for speaker in &speakers {
+        commands.entity(speaker).remove::<InConversation>();
+    }
+}
+
+fn sync_yarn_nodes(mut npcs: Query<(&DialogueState, &mut YarnNode), Without<InConversation>>) {
+    for (state, mut node) in &mut npcs {
+        if node.yarn_node != state.effective_node() {
+            node.yarn_node = state.effective_node().to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_node_is_the_latest_entry_or_the_base() {
+        let mut state = DialogueState::new("Start");
+        assert_eq!(state.effective_node(), "Start");
+
+        state.push("after_dig_3", "3_Dug");
+        state.push("after_body_3", "3_Slotted");
+        assert_eq!(state.effective_node(), "3_Slotted");
+
+        // Re-pushing moves the condition back on top.
+        state.push("after_dig_3", "3_Dug_Again");
+        assert_eq!(state.effective_node(), "3_Dug_Again");
+        assert_eq!(state.entries.len(), 2);
+
+        assert_eq!(
+            state.pop(),
+            Some(("after_dig_3".to_string(), "3_Dug_Again".to_string()))
+        );
+        assert_eq!(state.effective_node(), "3_Slotted");
+
+        state.clear();
+        assert_eq!(state.effective_node(), "Start");
+        assert_eq!(state.pop(), None);
+    }
+
+    #[test]
+    fn removing_an_entry_falls_back_to_the_one_below() {
+        let mut state = DialogueState::new("Start");
+        state.push("after_dirt_3", "3_Done");
+        state.push("under_attack", "Under_Attack");
+        state.push("after_help_larry", "Relief");
+
+        // Removing from the middle leaves the top alone.
+        assert_eq!(
+            state.remove("under_attack"),
+            Some("Under_Attack".to_string())
+        );
+        assert_eq!(state.effective_node(), "Relief");
+        assert_eq!(state.remove("under_attack"), None);
+
+        assert_eq!(state.remove("after_help_larry"), Some("Relief".to_string()));
+        assert_eq!(state.effective_node(), "3_Done");
+    }
+
+    #[test]
+    fn yarn_node_only_changes_outside_a_conversation() {
+        let mut app = App::new();
+        app.add_systems(Update, sync_yarn_nodes);
+        let larry = app
+            .world_mut()
+            .spawn((
+                YarnNode::new("Start"),
+                DialogueState::new("Start"),
+                InConversation,
+            ))
+            .id();
+
+        app.world_mut()
+            .get_mut::<DialogueState>(larry)
+            .unwrap()
+            .push("after_dig_3", "3_Dug");
+        app.update();
+        assert_eq!(
+            app.world().get::<YarnNode>(larry).unwrap().yarn_node,
+            "Start"
+        );
+
+        app.world_mut().entity_mut(larry).remove::<InConversation>();
+        app.update();
+        assert_eq!(
+            app.world().get::<YarnNode>(larry).unwrap().yarn_node,
+            "3_Dug"
+        );
+    }
+}
@@ -3,15 +3,22 @@
 use std::f32::consts::PI;
 
 use avian3d::prelude::*;
-use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use bevy::{
+    ecs::{entity::EntityHashSet, lifecycle::HookContext, world::DeferredWorld},
+    prelude::*,
+};
 
 use bevy_ahoy::CharacterController;
 use bevy_trenchbroom::prelude::*;
 
 use bevy::platform::collections::HashMap;
 
+use reward::DeathReward;
+
 use crate::{
     asset_tracking::LoadResource,
+    game_running,
+    gameplay::barrel::{BARREL_SPAWN_KEY, ExplosiveBarrel},
     third_party::{
         avian3d::CollisionLayer,
         bevy_trenchbroom::{GetTrenchbroomModelPath, LoadTrenchbroomModel as _},
@@ -22,16 +29,31 @@ use crate::{
 pub(crate) mod ai;
 mod animation;
 mod assets;
-pub(super) mod shooting;
+pub(crate) mod burrower;
+pub(crate) mod critical;
+pub(crate) mod dialogue_state;
+pub(crate) mod model;
+mod mods;
+pub(crate) mod pattern_preview;
+pub(crate) mod reward;
+pub(crate) mod shooting;
 mod sound;
+pub(crate) mod threat;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         ai::plugin,
         animation::plugin,
         assets::plugin,
+        burrower::plugin,
+        critical::plugin,
+        dialogue_state::plugin,
+        mods::plugin,
+        pattern_preview::plugin,
+        reward::plugin,
         shooting::plugin,
         sound::plugin,
+        threat::plugin,
     ));
     app.load_asset::<Gltf>(Npc::model_path());
     app.load_asset::<Gltf>("models/crab/scene.gltf");
@@ -41,6 +63,7 @@ pub(super) fn plugin(app: &mut App) {
     app.load_asset::<Gltf>("models/Seal.glb");
     app.load_asset::<Gltf>("models/Octopus.glb");
     app.load_asset::<Gltf>("models/tommy_gun.glb");
+    app.add_observer(unparent_npc);
     app.add_observer(on_add);
     app.add_observer(on_add_enemy_gunner);
     app.add_observer(on_npc_aggro);
@@ -51,9 +74,11 @@ pub(super) fn plugin(app: &mut App) {
     app.add_observer(on_spawn_enemy);
     app.add_systems(
         Update,
-        (respawn_fallen_npcs, respawn_fallen_enemies, unparent_npcs),
+        (respawn_fallen_npcs, respawn_fallen_enemies).run_if(game_running),
     );
     app.init_resource::<NpcRegistry>();
+    #[cfg(feature = "dev")]
+    app.add_observer(model::spawn_validation_billboard);
 }
 
 #[derive(Component)]
@@ -63,10 +88,11 @@ pub(crate) struct NpcDead;
 pub(crate) struct NpcAggro;
 
 #[derive(Component)]
-struct NpcAggroGun;
+struct NpcAggroAccessory;
 
+/// Scenes attached to an NPC while it's aggroed, copied from its prefab.
 #[derive(Component)]
-struct GunOffset(Vec3);
+struct AggroAccessories(Vec<NpcAccessory>);
 
 #[derive(Component, Clone)]
 pub(crate) struct BodyConfig {
@@ -91,11 +117,38 @@ pub(crate) struct NpcPrefab {
     pub radius: f32,
     pub height: f32,
     pub body: BodyConfig,
-    pub gun_offset: Vec3,
+    /// Child scenes attached on aggro and removed on death.
+    pub aggro_accessories: Vec<NpcAccessory>,
+    /// Named animation in the prefab's glTF, looped while the NPC isn't alerted.
+    pub idle_animation: Option<String>,
+    /// What killing it as an enemy is worth, unless the gunner overrides it.
+    pub reward: DeathReward,
+}
+
+/// A scene attached to an NPC, placed relative to the NPC's root.
+#[derive(Clone)]
+pub(crate) struct NpcAccessory {
+    pub scene: String,
+    pub transform: Transform,
+}
+
+impl NpcAccessory {
+    fn tommy_gun(offset: Vec3) -> Self {
+        Self {
+            scene: "models/tommy_gun.glb#Scene0".into(),
+            transform: Transform::from_translation(offset)
+                .with_rotation(Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2))
+                .with_scale(Vec3::splat(0.01)),
+        }
+    }
 }
 
 const DEFAULT_GUN_OFFSET: Vec3 = Vec3::new(0.7, 0.3, -0.4);
 
+fn default_aggro_accessories() -> Vec<NpcAccessory> {
+    vec![NpcAccessory::tommy_gun(DEFAULT_GUN_OFFSET)]
+}
+
 #[derive(Resource)]
 pub(crate) struct NpcRegistry {
     pub prefabs: HashMap<String, NpcPrefab>,
@@ -111,7 +164,9 @@ impl Default for NpcRegistry {
                 radius: NPC_RADIUS,
                 height: NPC_HEIGHT,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -121,7 +176,9 @@ impl Default for NpcRegistry {
                 radius: 0.5,
                 height: 0.8,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -131,7 +188,9 @@ impl Default for NpcRegistry {
                 radius: NPC_RADIUS,
                 height: NPC_HEIGHT,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -148,7 +207,9 @@ impl Default for NpcRegistry {
                     },
                     ..default()
                 },
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -158,7 +219,9 @@ impl Default for NpcRegistry {
                 radius: NPC_RADIUS,
                 height: NPC_HEIGHT,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -168,7 +231,9 @@ impl Default for NpcRegistry {
                 radius: NPC_RADIUS,
                 height: NPC_HEIGHT,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         prefabs.insert(
@@ -178,7 +243,9 @@ impl Default for NpcRegistry {
                 radius: 0.8,
                 height: 3.0,
                 body: BodyConfig::default(),
-                gun_offset: DEFAULT_GUN_OFFSET,
+                aggro_accessories: default_aggro_accessories(),
+                idle_animation: None,
+                reward: DeathReward::default(),
             },
         );
         Self { prefabs }
@@ -195,6 +262,8 @@ pub(crate) struct Npc {
     pub yarn_node: String,
     pub model: String,
     pub health: f32,
+    /// The story can't go on without this NPC, its death ends the run.
+    pub critical: bool,
 }
 
 impl Default for Npc {
@@ -204,6 +273,7 @@ impl Default for Npc {
             yarn_node: String::new(),
             model: String::new(),
             health: 0.0,
+            critical: false,
         }
     }
 }
@@ -219,7 +289,7 @@ pub(crate) struct EnemyGunner {
     pub model: String,
     /// Starting health. 0 = use default.
     pub health: f32,
-    /// Firing pattern: "radial", "spread", etc.
+    /// Firing pattern: "radial", "spread" or "ricochet".
     pub pattern: String,
     /// Shots per second.
     pub fire_rate: f32,
@@ -233,6 +303,26 @@ pub(crate) struct EnemyGunner {
     pub target_tag: String,
     /// Radius for player proximity aggro swap.
     pub aggro_radius: f32,
+    /// Seconds of warning ring before a radial burst fires. 0 = no warning.
+    pub telegraph: f32,
+    /// Seconds the player has to stay hidden and out of `aggro_radius` before the enemy
+    /// goes back to its `target_tag`. 0 = never.
+    pub deescalate: f32,
+    /// "auto" aggros on sight, "on_hit" stays passive until something damages it.
+    pub aggro_mode: String,
+    /// Times "ricochet" projectiles bounce off level geometry before they stop.
+    pub bounces: u32,
+    /// Tilts "radial" bursts towards the target's height, so they reach players above or
+    /// below instead of staying level.
+    pub aim_vertical: bool,
+    /// What projectiles do on a hit: "damage", "heal" (the enemy's allies) or "slow".
+    pub effect: String,
+    /// Damage or healing per hit, or seconds of slow. 0 = default for the effect.
+    pub effect_amount: f32,
+    /// Crusts dropped on death. 0 = the model's.
+    pub reward_crusts: u32,
+    /// Sub-objective that goes up by one on death. Empty = the model's.
+    pub reward_objective: String,
 }
 
 impl Default for EnemyGunner {
@@ -248,6 +338,15 @@ impl Default for EnemyGunner {
             range: 20.0,
             target_tag: String::new(),
             aggro_radius: 15.0,
+            telegraph: 0.4,
+            deescalate: 30.0,
+            aggro_mode: "auto".into(),
+            bounces: 2,
+            aim_vertical: false,
+            effect: "damage".into(),
+            effect_amount: 0.0,
+            reward_crusts: 0,
+            reward_objective: String::new(),
         }
     }
 }
@@ -258,14 +357,29 @@ pub(crate) use super::tags::Tags;
 pub(crate) struct Body;
 
 #[derive(Component)]
+#[component(on_add = Health::on_add)]
 pub(crate) struct Health(pub f32);
 
+impl Health {
+    fn on_add(mut world: DeferredWorld, ctx: HookContext) {
+        let health = world.get::<Health>(ctx.entity).unwrap().0;
+        world
+            .commands()
+            .entity(ctx.entity)
+            .insert(MaxHealth(health));
+    }
+}
+
+/// The [`Health`] an entity spawned with, which heals can't go past.
+#[derive(Component, Debug)]
+pub(crate) struct MaxHealth(pub f32);
+
 pub(crate) const NPC_RADIUS: f32 = 1.0;
 pub(crate) const NPC_HEIGHT: f32 = 6.0;
 const NPC_HALF_HEIGHT: f32 = NPC_HEIGHT / 2.0;
 const NPC_FLOAT_HEIGHT: f32 = NPC_HALF_HEIGHT + 0.01;
 const NPC_SPEED: f32 = 7.0;
-const DEFAULT_NPC_HEALTH: f32 = 100.0;
+pub(crate) const DEFAULT_NPC_HEALTH: f32 = 100.0;
 
 fn npc_display_name(model_key: &str, kind: &str, tags: &Tags) -> String {
     let model = if model_key.is_empty() {
@@ -321,11 +435,8 @@ fn on_add(
         })
         .unwrap_or(DEFAULT_NPC_HEALTH);
 
-    let prefab = if !model_key.is_empty() {
-        registry.prefabs.get(&model_key)
-    } else {
-        None
-    };
+    let prefab = model::resolve_npc_model(&mut commands, &registry, add.entity, &model_key);
+    let prefab = prefab.as_deref();
 
     let mut self_hashset = EntityHashSet::new();
     self_hashset.insert(add.entity);
@@ -335,7 +446,9 @@ fn on_add(
     };
 
     let body_config = prefab.map(|p| p.body.clone()).unwrap_or_default();
-    let gun_offset = prefab.map(|p| p.gun_offset).unwrap_or(DEFAULT_GUN_OFFSET);
+    let aggro_accessories = prefab
+        .map(|p| p.aggro_accessories.clone())
+        .unwrap_or_else(default_aggro_accessories);
 
     let display_name = npc_display_name(&model_key, "", &npc_tags);
 
@@ -356,7 +469,7 @@ fn on_add(
         ),
         Health(health),
         body_config.clone(),
-        GunOffset(gun_offset),
+        AggroAccessories(aggro_accessories),
         npc_tags.clone(),
         shooting::Faction("lobster".to_string()),
     ));
@@ -364,6 +477,12 @@ fn on_add(
     if !yarn_node.is_empty() {
         entity_commands.insert(YarnNode::new(&yarn_node));
     }
+    if npc.is_some_and(|npc| npc.critical) {
+        entity_commands.insert(critical::Critical);
+    }
+    if let Some(idle) = prefab.and_then(|p| animation::NpcIdleAnimation::from_prefab(p, &assets)) {
+        entity_commands.insert(idle);
+    }
 
     let (scene, model_transform) = if let Some(prefab) = prefab {
         (assets.load(&prefab.scene), prefab.body.model_transform)
@@ -383,6 +502,7 @@ fn on_add_enemy_gunner(
     assets: Res<AssetServer>,
     gunners: Query<&EnemyGunner>,
     registry: Res<NpcRegistry>,
+    threat: Res<threat::Threat>,
 ) {
     let entity = add.entity;
     let gunner = gunners.get(entity).ok();
@@ -400,16 +520,14 @@ fn on_add_enemy_gunner(
                 DEFAULT_NPC_HEALTH
             }
         })
-        .unwrap_or(DEFAULT_NPC_HEALTH);
+        .unwrap_or(DEFAULT_NPC_HEALTH)
+        * threat.health_multiplier();
 
-    let prefab = if !model_key.is_empty() {
-        registry.prefabs.get(&model_key)
-    } else {
-        None
-    };
+    let prefab = model::resolve_npc_model(&mut commands, &registry, entity, &model_key);
+    let prefab = prefab.as_deref();
 
     let shooter = gunner
-        .map(|g| shooting::NpcShooter::from_gunner(g))
+        .map(|g| shooting::NpcShooter::from_gunner(g, &threat))
         .unwrap_or_default();
 
     let mut self_hashset = EntityHashSet::new();
@@ -420,20 +538,32 @@ fn on_add_enemy_gunner(
     };
 
     let body_config = prefab.map(|p| p.body.clone()).unwrap_or_default();
-    let gun_offset = prefab.map(|p| p.gun_offset).unwrap_or(DEFAULT_GUN_OFFSET);
+    let aggro_accessories = prefab
+        .map(|p| p.aggro_accessories.clone())
+        .unwrap_or_else(default_aggro_accessories);
 
     let display_name = npc_display_name(&model_key, "Gunner", &npc_tags);
 
+    let reward = prefab.map(|p| p.reward.clone()).unwrap_or_default();
+    let reward = match gunner {
+        Some(g) => reward.with_overrides(g.reward_crusts, &g.reward_objective),
+        None => reward,
+    };
+
     let aggro_config = gunner
         .map(|g| shooting::AggroConfig {
             target_tag: g.target_tag.trim().to_string(),
             aggro_radius: g.aggro_radius,
             swapped_to_player: false,
+            deescalate_after: g.deescalate,
+            unseen: 0.0,
         })
         .unwrap_or(shooting::AggroConfig {
             target_tag: String::new(),
             aggro_radius: 15.0,
             swapped_to_player: false,
+            deescalate_after: 0.0,
+            unseen: 0.0,
         });
 
     commands.entity(entity).insert((
@@ -450,16 +580,33 @@ fn on_add_enemy_gunner(
             CollisionLayer::Character,
             [CollisionLayer::Level, CollisionLayer::Prop],
         ),
+        // Only steered while stepping around a friendly in the line of fire.
+        ai::movement_input(),
         Health(health),
         body_config.clone(),
-        GunOffset(gun_offset),
-        NpcAggro,
+        AggroAccessories(aggro_accessories),
         shooter,
         aggro_config,
         npc_tags,
+        reward,
         shooting::Faction("enemy".to_string()),
     ));
 
+    match gunner.map_or("auto", |g| g.aggro_mode.trim()) {
+        "on_hit" => {
+            commands.entity(entity).insert(shooting::AggroOnHit);
+        }
+        mode => {
+            if mode != "auto" {
+                warn!("Unknown aggro_mode {mode:?} on {entity}, using \"auto\"");
+            }
+            commands.entity(entity).insert(NpcAggro);
+        }
+    }
+    if let Some(idle) = prefab.and_then(|p| animation::NpcIdleAnimation::from_prefab(p, &assets)) {
+        commands.entity(entity).insert(idle);
+    }
+
     let (scene, model_transform) = if let Some(prefab) = prefab {
         (assets.load(&prefab.scene), prefab.body.model_transform)
     } else {
@@ -478,22 +625,21 @@ fn on_npc_aggro(
     aggro: On<Add, NpcAggro>,
     mut commands: Commands,
     assets: Res<AssetServer>,
-    gun_offsets: Query<&GunOffset>,
+    accessories: Query<&AggroAccessories>,
 ) {
     let entity = aggro.entity;
-    let offset = gun_offsets
-        .get(entity)
-        .map(|g| g.0)
-        .unwrap_or(DEFAULT_GUN_OFFSET);
-
-    commands.entity(entity).with_child((
-        Name::new("Aggro Gun"),
-        NpcAggroGun,
-        SceneRoot(assets.load("models/tommy_gun.glb#Scene0")),
-        Transform::from_translation(offset)
-            .with_rotation(Quat::from_rotation_y(-std::f32::consts::FRAC_PI_2))
-            .with_scale(Vec3::splat(0.01)),
-    ));
+    let Ok(AggroAccessories(accessories)) = accessories.get(entity) else {
+        return;
+    };
+
+    for accessory in accessories {
+        commands.entity(entity).with_child((
+            Name::new("Aggro Accessory"),
+            NpcAggroAccessory,
+            SceneRoot(assets.load(&accessory.scene)),
+            accessory.transform,
+        ));
+    }
 }
 
 fn on_npc_death(
@@ -502,7 +648,7 @@ fn on_npc_death(
     npc_entity: Query<(Entity, &Transform, Option<&BodyConfig>, Option<&Name>)>,
     children: Query<&Children>,
     agents: Query<(), With<ai::WantsToFollowPlayer>>,
-    aggro_guns: Query<(), With<NpcAggroGun>>,
+    aggro_accessories: Query<(), With<NpcAggroAccessory>>,
 ) {
     let Ok((entity, transform, body_config, name)) = npc_entity.get(add.entity) else {
         warn!("npc death didnt have transform");
@@ -536,11 +682,19 @@ fn on_npc_death(
             bevy_ahoy::prelude::WaterState,
             CustomPositionIntegration,
             Health,
-            YarnNode,
-            shooting::NpcShooter,
-            shooting::EnemyAlert,
-            shooting::AggroTarget,
-            shooting::AggroConfig,
+            MaxHealth,
+            (
+                YarnNode,
+                dialogue_state::DialogueState,
+                dialogue_state::InConversation,
+            ),
+            (
+                shooting::NpcShooter,
+                shooting::EnemyAlert,
+                shooting::EnemyMemory,
+                shooting::AggroTarget,
+                shooting::AggroConfig,
+            ),
         )>()
         .insert((
             Name::new(dead_name),
@@ -559,20 +713,41 @@ fn on_npc_death(
 
     if let Ok(children) = children.get(entity) {
         for child in children.iter() {
-            if agents.get(child).is_ok() || aggro_guns.get(child).is_ok() {
+            if agents.get(child).is_ok() || aggro_accessories.get(child).is_ok() {
                 commands.entity(child).despawn();
             }
         }
     }
 }
 
-fn unparent_npcs(
-    mut commands: Commands,
-    npcs: Query<Entity, (With<ChildOf>, Or<(Added<Npc>, Added<EnemyGunner>)>)>,
-) {
-    for entity in &npcs {
-        commands.entity(entity).remove::<ChildOf>();
+/// NPCs placed in the map spawn as children of the level scene. Detach them in the same flush as
+/// their physics components, keeping where they are in the world, and fill in `GlobalTransform`
+/// so nothing sees a parent-relative or identity transform for a frame.
+fn unparent_npc(add: On<Add, (Npc, EnemyGunner, burrower::Burrower)>, mut commands: Commands) {
+    let entity = add.entity;
+    // Queued so the scene has finished writing the NPC's transform and parent.
+    commands.queue(move |world: &mut World| {
+        let Some(transform) = world_transform(world, entity) else {
+            return;
+        };
+        world
+            .entity_mut(entity)
+            .remove::<ChildOf>()
+            .insert((transform, GlobalTransform::from(transform)));
+    });
+}
+
+/// Composes `Transform`s up the hierarchy, since `GlobalTransform` isn't propagated yet.
+fn world_transform(world: &World, entity: Entity) -> Option<Transform> {
+    let mut transform = *world.get::<Transform>(entity)?;
+    let mut current = entity;
+    while let Some(parent) = world.get::<ChildOf>(current).map(ChildOf::parent) {
+        if let Some(parent_transform) = world.get::<Transform>(parent) {
+            transform = parent_transform.mul_transform(transform);
+        }
+        current = parent;
     }
+    Some(transform)
 }
 
 #[point_class(base(Transform, Visibility))]
@@ -599,10 +774,10 @@ impl Default for NpcSpawner {
 }
 
 #[derive(Component)]
-struct NpcSpawnerState {
-    queue: Vec<String>,
-    index: usize,
-    spawned: Vec<(Entity, String)>,
+pub(crate) struct NpcSpawnerState {
+    pub queue: Vec<String>,
+    pub index: usize,
+    pub spawned: Vec<(Entity, String)>,
 }
 
 fn init_npc_spawner(
@@ -692,6 +867,7 @@ fn on_spawn_npc(
                     yarn_node: overrides.yarn_node.clone().unwrap_or_default(),
                     model: model_key.clone(),
                     health: overrides.health.unwrap_or(0.0),
+                    critical: false,
                 },
                 t,
                 Visibility::default(),
@@ -738,6 +914,7 @@ fn respawn_fallen_npcs(
                         yarn_node: String::new(),
                         model: model_key.clone(),
                         health: 0.0,
+                        critical: false,
                     },
                     t,
                     Visibility::default(),
@@ -760,6 +937,7 @@ pub(crate) struct EnemySpawner {
     /// Default model prefab key when queue is empty.
     pub model: String,
     /// Comma-separated model keys to cycle through on each spawn.
+    /// `barrel` places an explosive barrel instead of an enemy.
     pub queue: String,
     /// Firing pattern passed to spawned EnemyGunners.
     pub pattern: String,
@@ -775,6 +953,26 @@ pub(crate) struct EnemySpawner {
     pub target_tag: String,
     /// Radius for player proximity aggro swap for spawned enemies.
     pub aggro_radius: f32,
+    /// Seconds of warning before radial bursts from spawned enemies.
+    pub telegraph: f32,
+    /// Seconds hidden before spawned enemies lose interest in the player. 0 = never.
+    pub deescalate: f32,
+    /// "auto" or "on_hit" for spawned enemies.
+    pub aggro_mode: String,
+    /// Level bounces for "ricochet" projectiles from spawned enemies.
+    pub bounces: u32,
+    /// Spawned enemies tilt "radial" bursts towards the target's height.
+    pub aim_vertical: bool,
+    /// Projectile effect for spawned enemies: "damage", "heal" or "slow".
+    pub effect: String,
+    /// Damage or healing per hit, or seconds of slow, for spawned enemies. 0 = default.
+    pub effect_amount: f32,
+    /// Crusts dropped by spawned enemies. 0 = the model's.
+    pub reward_crusts: u32,
+    /// Sub-objective that goes up by one per spawned enemy killed. Empty = the model's.
+    pub reward_objective: String,
+    /// Spawned enemies come out already alerted on their target, for ambushes.
+    pub spawn_alerted: bool,
 }
 
 impl Default for EnemySpawner {
@@ -791,15 +989,25 @@ impl Default for EnemySpawner {
             range: 20.0,
             target_tag: String::new(),
             aggro_radius: 15.0,
+            telegraph: 0.4,
+            deescalate: 30.0,
+            aggro_mode: "auto".into(),
+            bounces: 2,
+            aim_vertical: false,
+            effect: "damage".into(),
+            effect_amount: 0.0,
+            reward_crusts: 0,
+            reward_objective: String::new(),
+            spawn_alerted: false,
         }
     }
 }
 
 #[derive(Component)]
-struct EnemySpawnerState {
-    queue: Vec<String>,
-    index: usize,
-    spawned: Vec<(Entity, String)>,
+pub(crate) struct EnemySpawnerState {
+    pub queue: Vec<String>,
+    pub index: usize,
+    pub spawned: Vec<(Entity, String)>,
 }
 
 fn init_enemy_spawner(
@@ -823,10 +1031,19 @@ fn init_enemy_spawner(
     });
 }
 
+/// Spawns an enemy at the named spawner. `alerted` spawns it already alerted on its target,
+/// on top of the spawner's own `spawn_alerted`.
 #[derive(Event)]
 pub(crate) enum SpawnEnemy {
-    Queue { spawner_name: String },
-    Direct { spawner_name: String, model: String },
+    Queue {
+        spawner_name: String,
+        alerted: bool,
+    },
+    Direct {
+        spawner_name: String,
+        model: String,
+        alerted: bool,
+    },
 }
 
 fn on_spawn_enemy(
@@ -834,12 +1051,16 @@ fn on_spawn_enemy(
     mut commands: Commands,
     mut spawners: Query<(&EnemySpawner, &GlobalTransform, &mut EnemySpawnerState)>,
 ) {
-    let (target_spawner, target_model): (&str, Option<&str>) = match &*event {
-        SpawnEnemy::Queue { spawner_name } => (spawner_name.as_str(), None),
+    let (target_spawner, target_model, alerted): (&str, Option<&str>, bool) = match &*event {
+        SpawnEnemy::Queue {
+            spawner_name,
+            alerted,
+        } => (spawner_name.as_str(), None, *alerted),
         SpawnEnemy::Direct {
             spawner_name,
             model,
-        } => (spawner_name.as_str(), Some(model.as_str())),
+            alerted,
+        } => (spawner_name.as_str(), Some(model.as_str()), *alerted),
     };
 
     for (spawner, transform, mut state) in &mut spawners {
@@ -862,6 +1083,12 @@ fn on_spawn_enemy(
 
         let t = transform.compute_transform();
 
+        // Trap barrels aren't tracked, so they stay gone once they've exploded.
+        if model_key == BARREL_SPAWN_KEY {
+            commands.spawn((ExplosiveBarrel, t, Visibility::default()));
+            continue;
+        }
+
         let spawned = commands
             .spawn((
                 EnemyGunner {
@@ -875,12 +1102,24 @@ fn on_spawn_enemy(
                     range: spawner.range,
                     target_tag: spawner.target_tag.clone(),
                     aggro_radius: spawner.aggro_radius,
+                    telegraph: spawner.telegraph,
+                    deescalate: spawner.deescalate,
+                    aggro_mode: spawner.aggro_mode.clone(),
+                    bounces: spawner.bounces,
+                    aim_vertical: spawner.aim_vertical,
+                    effect: spawner.effect.clone(),
+                    effect_amount: spawner.effect_amount,
+                    reward_crusts: spawner.reward_crusts,
+                    reward_objective: spawner.reward_objective.clone(),
                 },
                 t,
                 Visibility::default(),
             ))
             .id();
 
+        if alerted || spawner.spawn_alerted {
+            commands.entity(spawned).insert(shooting::SpawnAlerted);
+        }
         state.spawned.push((spawned, model_key));
     }
 }
@@ -923,14 +1162,64 @@ fn respawn_fallen_enemies(
                         range: spawner.range,
                         target_tag: spawner.target_tag.clone(),
                         aggro_radius: spawner.aggro_radius,
+                        telegraph: spawner.telegraph,
+                        deescalate: spawner.deescalate,
+                        aggro_mode: spawner.aggro_mode.clone(),
+                        bounces: spawner.bounces,
+                        aim_vertical: spawner.aim_vertical,
+                        effect: spawner.effect.clone(),
+                        effect_amount: spawner.effect_amount,
+                        reward_crusts: spawner.reward_crusts,
+                        reward_objective: spawner.reward_objective.clone(),
                     },
                     t,
                     Visibility::default(),
                 ))
                 .id();
 
+            if spawner.spawn_alerted {
+                commands.entity(new_entity).insert(shooting::SpawnAlerted);
+            }
             state.spawned[i] = (new_entity, model_key.clone());
             i += 1;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn npcs_keep_their_world_position_when_unparented() {
+        let mut app = App::new();
+        app.add_observer(unparent_npc);
+
+        let parent_transform = Transform::from_xyz(10.0, 2.0, -5.0)
+            .with_rotation(Quat::from_rotation_y(PI / 2.0))
+            .with_scale(Vec3::splat(2.0));
+        let local = Transform::from_xyz(1.0, 0.5, 0.0);
+        let expected = parent_transform.mul_transform(local);
+
+        let parent = app.world_mut().spawn(parent_transform).id();
+        let npc = app
+            .world_mut()
+            .spawn((Npc::default(), local, ChildOf(parent)))
+            .id();
+        let gunner = app
+            .world_mut()
+            .spawn((EnemyGunner::default(), local, ChildOf(parent)))
+            .id();
+        app.update();
+
+        for entity in [npc, gunner] {
+            let entity = app.world().entity(entity);
+            assert!(!entity.contains::<ChildOf>());
+            let transform = entity.get::<Transform>().unwrap();
+            let global = entity.get::<GlobalTransform>().unwrap();
+            assert!(transform.translation.distance(expected.translation) < 1e-4);
+            assert!(global.translation().distance(expected.translation) < 1e-4);
+            assert!(transform.rotation.angle_between(expected.rotation) < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Resolving the `model` string level authors put on NPCs, gunners and spawners.
+//!
+//! A registry key is tried first, then anything that looks like an asset path is loaded
+//! directly. Everything else falls back to the default model with a warning, and in dev
+//! builds a red label over the NPC so the typo is easy to find.
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use super::{
+    BodyConfig, NPC_HEIGHT, NPC_RADIUS, Npc, NpcPrefab, NpcRegistry, default_aggro_accessories,
+    reward::DeathReward,
+};
+use crate::third_party::bevy_trenchbroom::GetTrenchbroomModelPath;
+
+/// What a `model` string resolved to.
+pub(crate) enum NpcModel<'a> {
+    /// No model given, the entity's default is used.
+    Unset,
+    /// A registered prefab, or an ad-hoc one for a scene path.
+    Prefab(Cow<'a, NpcPrefab>),
+    /// Neither a registry key nor a scene path.
+    Unknown,
+}
+
+impl NpcModel<'_> {
+    pub(crate) fn prefab(&self) -> Option<&NpcPrefab> {
+        match self {
+            Self::Prefab(prefab) => Some(prefab),
+            Self::Unset | Self::Unknown => None,
+        }
+    }
+}
+
+impl NpcRegistry {
+    pub(crate) fn resolve(&self, key: &str) -> NpcModel<'_> {
+        let key = key.trim();
+        if key.is_empty() {
+            return NpcModel::Unset;
+        }
+        if let Some(prefab) = self.prefabs.get(key) {
+            return NpcModel::Prefab(Cow::Borrowed(prefab));
+        }
+        if is_scene_path(key) {
+            return NpcModel::Prefab(Cow::Owned(NpcPrefab::from_scene(scene_path(key))));
+        }
+        NpcModel::Unknown
+    }
+}
+
+impl NpcPrefab {
+    /// Default dimensions and accessories around `scene`.
+    pub(crate) fn from_scene(scene: String) -> Self {
+        Self {
+            scene,
+            radius: NPC_RADIUS,
+            height: NPC_HEIGHT,
+            body: BodyConfig::default(),
+            aggro_accessories: default_aggro_accessories(),
+            idle_animation: None,
+            reward: DeathReward::default(),
+        }
+    }
+
+    /// The lobster, for anything that needs a prefab but didn't resolve to one.
+    pub(crate) fn fallback() -> Self {
+        Self::from_scene(Npc::scene_path())
+    }
+}
+
+fn is_scene_path(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    key.contains('/') || lower.ends_with(".glb") || lower.ends_with(".gltf")
+}
+
+pub(super) fn scene_path(path: &str) -> String {
+    if path.contains('#') {
+        path.to_string()
+    } else {
+        format!("{path}#Scene0")
+    }
+}
+
+/// On an entity whose `model` didn't resolve, holds the bad key.
+#[derive(Component, Debug)]
+pub(crate) struct UnknownModel(pub(crate) String);
+
+/// Warns that `key` on `entity` didn't resolve to a model.
+pub(crate) fn report_unknown_model(commands: &mut Commands, entity: Entity, key: &str) {
+    warn!(
+        "Unknown NPC model {key:?} on {entity}, expected a registry key or a scene path like \
+         \"models/Seal.glb\". Using the default model"
+    );
+    commands
+        .entity(entity)
+        .insert(UnknownModel(key.to_string()));
+}
+
+/// Resolves `key` for the NPC `entity`, reporting it if it's unknown. `None` means the
+/// entity's default model should be used.
+pub(crate) fn resolve_npc_model<'a>(
+    commands: &mut Commands,
+    registry: &'a NpcRegistry,
+    entity: Entity,
+    key: &str,
+) -> Option<Cow<'a, NpcPrefab>> {
+    match registry.resolve(key) {
+        NpcModel::Prefab(prefab) => Some(prefab),
+        NpcModel::Unset => None,
+        NpcModel::Unknown => {
+            report_unknown_model(commands, entity, key);
+            None
+        }
+    }
+}
+
+#[cfg(feature = "dev")]
+pub(super) fn spawn_validation_billboard(
+    add: On<Add, UnknownModel>,
+    mut commands: Commands,
+    unknown: Query<&UnknownModel>,
+    font: Res<crate::theme::GameFont>,
+) {
+    use bevy_mod_billboard::prelude::*;
+
+    let Ok(unknown) = unknown.get(add.entity) else {
+        return;
+    };
+    commands.spawn((
+        Name::new("Model Validation Billboard"),
+        BillboardText::new(format!("unknown model {:?}", unknown.0)),
+        TextFont {
+            font: font.0.clone(),
+            font_size: 32.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.15, 0.15)),
+        TextLayout::new_with_justify(Justify::Center),
+        Transform::from_translation(Vec3::Y * (NPC_HEIGHT * 0.5 + 0.5))
+            .with_scale(Vec3::splat(0.01)),
+        ChildOf(add.entity),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(model: NpcModel) -> String {
+        model.prefab().expect("resolved to a prefab").scene.clone()
+    }
+
+    #[test]
+    fn registry_keys_win() {
+        let registry = NpcRegistry::default();
+        let seal = &registry.prefabs["seal"];
+        assert!(matches!(
+            registry.resolve("seal"),
+            NpcModel::Prefab(Cow::Borrowed(prefab)) if std::ptr::eq(prefab, seal)
+        ));
+        assert_eq!(scene(registry.resolve("  seal ")), seal.scene);
+    }
+
+    #[test]
+    fn scene_paths_get_an_ad_hoc_prefab() {
+        let registry = NpcRegistry::default();
+        assert_eq!(
+            scene(registry.resolve("models/Seal.glb")),
+            "models/Seal.glb#Scene0"
+        );
+        assert_eq!(scene(registry.resolve("Crab.GLTF")), "Crab.GLTF#Scene0");
+        assert_eq!(
+            scene(registry.resolve("models/fox/Fox.gltf#Scene1")),
+            "models/fox/Fox.gltf#Scene1"
+        );
+        assert_eq!(scene(registry.resolve("models/fox")), "models/fox#Scene0");
+
+        let prefab = NpcPrefab::from_scene(String::new());
+        let NpcModel::Prefab(adhoc) = registry.resolve("models/Seal.glb") else {
+            panic!("expected a prefab");
+        };
+        assert_eq!(adhoc.radius, prefab.radius);
+        assert_eq!(adhoc.height, prefab.height);
+        assert_eq!(
+            adhoc.aggro_accessories.len(),
+            prefab.aggro_accessories.len()
+        );
+        assert!(adhoc.idle_animation.is_none());
+    }
+
+    #[test]
+    fn anything_else_is_unset_or_unknown() {
+        let registry = NpcRegistry::default();
+        assert!(matches!(registry.resolve(""), NpcModel::Unset));
+        assert!(matches!(registry.resolve("   "), NpcModel::Unset));
+        assert!(matches!(registry.resolve("sael"), NpcModel::Unknown));
+        assert!(matches!(registry.resolve("Seal"), NpcModel::Unknown));
+        assert!(matches!(registry.resolve("seal.png"), NpcModel::Unknown));
+    }
+}
@@ -0,0 +1,232 @@
+//! Extra NPC models from `assets/mods/npcs/`. Every `.ron` manifest there describes one model,
+//! registered into [`NpcRegistry`] at startup so its key works anywhere a built-in key does.
+//! Native only, the web build has no folder to scan.
+//!
+//! ```ron
+//! (
+//!     key: "baby_octopus",
+//!     scene: "models/Octopus.glb",
+//!     radius: 0.5,
+//!     height: 1.5,
+//!     rotation: -90.0,
+//!     density: 800.0,
+//!     gun_offset: [0.4, 0.2, -0.2],
+//! )
+//! ```
+//!
+//! Everything but `key` and `scene` can be left out. A broken manifest or a missing model
+//! only skips that one manifest, and a key a built-in model already has keeps the built-in.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    BodyConfig, DEFAULT_GUN_OFFSET, NPC_HEIGHT, NPC_RADIUS, NpcAccessory, NpcPrefab, NpcRegistry,
+    model::scene_path,
+};
+use crate::asset_tracking::LoadResource;
+
+/// Where manifests go, relative to the assets folder.
+const MOD_NPC_DIR: &str = "mods/npcs";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<NpcRegistry>();
+    #[cfg(feature = "native")]
+    register_mod_npcs(
+        app,
+        &bevy::asset::io::file::FileAssetReader::get_base_path().join("assets"),
+    );
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+struct NpcModManifest {
+    /// What level authors put in `model`.
+    key: String,
+    /// Asset path of the glTF, `#Scene0` unless it names a scene.
+    scene: String,
+    radius: f32,
+    height: f32,
+    /// Yaw of the model in its body, in degrees.
+    rotation: f32,
+    density: f32,
+    /// Where the tommy gun sits while aggroed.
+    gun_offset: [f32; 3],
+}
+
+impl Default for NpcModManifest {
+    fn default() -> Self {
+        let body = BodyConfig::default();
+        Self {
+            key: String::new(),
+            scene: String::new(),
+            radius: NPC_RADIUS,
+            height: NPC_HEIGHT,
+            rotation: body
+                .model_transform
+                .rotation
+                .to_euler(EulerRot::YXZ)
+                .0
+                .to_degrees(),
+            density: body.density,
+            gun_offset: DEFAULT_GUN_OFFSET.to_array(),
+        }
+    }
+}
+
+impl NpcModManifest {
+    fn into_prefab(self, assets_root: &Path) -> anyhow::Result<(String, NpcPrefab)> {
+        let key = self.key.trim();
+        if key.is_empty() {
+            anyhow::bail!("no `key`");
+        }
+        let gltf = gltf_path(&self.scene);
+        if gltf.is_empty() {
+            anyhow::bail!("no `scene`");
+        }
+        if !assets_root.join(gltf).is_file() {
+            anyhow::bail!("{gltf} doesn't exist");
+        }
+        let prefab = NpcPrefab {
+            radius: self.radius,
+            height: self.height,
+            body: BodyConfig {
+                model_transform: Transform::from_rotation(Quat::from_rotation_y(
+                    self.rotation.to_radians(),
+                )),
+                density: self.density,
+            },
+            aggro_accessories: vec![NpcAccessory::tommy_gun(Vec3::from_array(self.gun_offset))],
+            ..NpcPrefab::from_scene(scene_path(&self.scene))
+        };
+        Ok((key.to_string(), prefab))
+    }
+}
+
+/// The scene path without its `#` label.
+fn gltf_path(scene: &str) -> &str {
+    scene
+        .split_once('#')
+        .map_or(scene, |(path, _label)| path)
+        .trim()
+}
+
+fn read_manifest(path: &Path, assets_root: &Path) -> anyhow::Result<(String, NpcPrefab)> {
+    let text = std::fs::read_to_string(path)?;
+    let manifest: NpcModManifest = ron::from_str(&text)?;
+    manifest.into_prefab(assets_root)
+}
+
+/// Registers every manifest in the mods folder under `assets_root` and preloads its model.
+fn register_mod_npcs(app: &mut App, assets_root: &Path) {
+    let dir = assets_root.join(MOD_NPC_DIR);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        // No mods installed.
+        return;
+    };
+    let mut manifests: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .collect();
+    manifests.sort();
+
+    for path in manifests {
+        let (key, prefab) = match read_manifest(&path, assets_root) {
+            Ok(npc) => npc,
+            Err(error) => {
+                warn!("Skipping NPC mod {}: {error}", path.display());
+                continue;
+            }
+        };
+        let mut registry = app.world_mut().resource_mut::<NpcRegistry>();
+        if registry.prefabs.contains_key(&key) {
+            warn!(
+                "NPC mod {} uses the key {key:?}, which is already taken. Keeping the other model",
+                path.display()
+            );
+            continue;
+        }
+        let gltf = gltf_path(&prefab.scene).to_string();
+        registry.prefabs.insert(key.clone(), prefab);
+        info!("Registered NPC mod {key:?} from {}", path.display());
+        app.load_asset::<Gltf>(gltf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use avian3d::prelude::*;
+
+    use super::*;
+    use crate::{
+        asset_tracking::ResourceHandles,
+        gameplay::npc::{Npc, model::UnknownModel, on_add},
+    };
+
+    #[test]
+    fn mod_manifest_registers_a_spawnable_npc() {
+        let root = std::env::temp_dir().join(format!("lob_npc_mods_{}", std::process::id()));
+        let dir = root.join(MOD_NPC_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        // Never rendered, so the model doesn't have to be a real glTF.
+        std::fs::write(dir.join("jellyfish.glb"), []).unwrap();
+        let manifests = [
+            (
+                "jellyfish",
+                r#"(key: "jellyfish", scene: "mods/npcs/jellyfish.glb", radius: 0.8, density: 500.0)"#,
+            ),
+            ("crab", r#"(key: "crab", scene: "mods/npcs/jellyfish.glb")"#),
+            ("ghost", r#"(key: "ghost", scene: "mods/npcs/ghost.glb")"#),
+            ("broken", "(key: "),
+        ];
+        for (name, manifest) in manifests {
+            std::fs::write(dir.join(format!("{name}.ron")), manifest).unwrap();
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: root.to_string_lossy().into_owned(),
+                ..default()
+            },
+            TransformPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<Gltf>()
+        .init_asset::<Scene>()
+        .init_resource::<ResourceHandles>()
+        .init_resource::<NpcRegistry>()
+        .add_observer(on_add);
+        register_mod_npcs(&mut app, &root);
+
+        let registry = app.world().resource::<NpcRegistry>();
+        let jellyfish = &registry.prefabs["jellyfish"];
+        assert_eq!(jellyfish.scene, "mods/npcs/jellyfish.glb#Scene0");
+        assert_eq!(jellyfish.radius, 0.8);
+        assert_eq!(jellyfish.height, NPC_HEIGHT);
+        // The built-in crab wins over the mod's.
+        assert_eq!(
+            registry.prefabs["crab"].scene,
+            "models/crab/scene.gltf#Scene0"
+        );
+        assert!(!registry.prefabs.contains_key("ghost"));
+
+        let npc = app
+            .world_mut()
+            .spawn(Npc {
+                model: "jellyfish".into(),
+                ..default()
+            })
+            .id();
+        app.update();
+        let npc = app.world().entity(npc);
+        assert!(!npc.contains::<UnknownModel>());
+        assert_eq!(npc.get::<BodyConfig>().unwrap().density, 500.0);
+
+        std::fs::remove_dir_all(root).ok();
+    }
+}
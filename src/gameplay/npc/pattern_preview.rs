@@ -0,0 +1,99 @@
+//! Gizmo previews of enemy firing patterns, for a shooting range and for tuning. A
+//! `pattern_preview` draws where a volley would go from where it stands, using the same
+//! direction math as [`npc_shoot`](super::shooting), and never spawns a projectile.
+
+use bevy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use super::shooting::{GUN_HEIGHT, SPREAD_HALF_ANGLE, radial_directions, spread_directions};
+use crate::{gameplay::player::Player, screens::Screen};
+
+/// Previews further than this from the player aren't drawn, to bound the gizmo cost.
+const PREVIEW_DISTANCE: f32 = 30.0;
+/// Line segments each arcing trajectory is sampled into.
+const ARC_SEGMENTS: usize = 24;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        draw_pattern_previews.run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Draws the trajectories of one volley with an enemy gunner's numbers. Spreads aim along the
+/// entity's forward direction.
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct PatternPreview {
+    /// Firing pattern: "radial", "spread" or "ricochet". Ricochets are drawn up to the first
+    /// bounce.
+    pub pattern: String,
+    /// Projectiles per burst.
+    pub projectile_count: u32,
+    /// Projectile travel speed.
+    pub projectile_speed: f32,
+    /// Distance the trajectories are drawn out to.
+    pub range: f32,
+    /// Downward pull for arcing shots, in units per second squared. 0 = straight shots.
+    pub gravity: f32,
+}
+
+impl Default for PatternPreview {
+    fn default() -> Self {
+        Self {
+            pattern: "radial".into(),
+            projectile_count: 12,
+            projectile_speed: 5.0,
+            range: 20.0,
+            gravity: 0.0,
+        }
+    }
+}
+
+impl PatternPreview {
+    fn directions(&self, forward: Vec3) -> Vec<Vec3> {
+        match self.pattern.as_str() {
+            "spread" | "ricochet" => {
+                spread_directions(forward, self.projectile_count, SPREAD_HALF_ANGLE)
+            }
+            _ => radial_directions(self.projectile_count),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.pattern.as_str() {
+            "spread" => Color::srgb(1.0, 0.85, 0.2),
+            "ricochet" => Color::srgb(0.3, 0.9, 1.0),
+            _ => Color::srgb(1.0, 0.45, 0.15),
+        }
+    }
+}
+
+fn draw_pattern_previews(
+    previews: Query<(&PatternPreview, &GlobalTransform)>,
+    player: Option<Single<&GlobalTransform, With<Player>>>,
+    mut gizmos: Gizmos,
+) {
+    let Some(player) = player else { return };
+    for (preview, transform) in &previews {
+        let origin = transform.translation();
+        if origin.distance_squared(player.translation()) > PREVIEW_DISTANCE * PREVIEW_DISTANCE {
+            continue;
+        }
+        let start = origin + Vec3::Y * GUN_HEIGHT;
+        let color = preview.color();
+        for direction in preview.directions(transform.forward().as_vec3()) {
+            if preview.gravity <= 0.0 || preview.projectile_speed <= 0.0 {
+                gizmos.line(start, start + direction * preview.range, color);
+                continue;
+            }
+            // Sampled out to the time it takes to cover `range` horizontally.
+            let duration = preview.range / preview.projectile_speed;
+            let velocity = direction * preview.projectile_speed;
+            let points = (0..=ARC_SEGMENTS).map(|i| {
+                let t = duration * i as f32 / ARC_SEGMENTS as f32;
+                start + velocity * t + Vec3::NEG_Y * (0.5 * preview.gravity * t * t)
+            });
+            gizmos.linestrip(points, color);
+        }
+    }
+}
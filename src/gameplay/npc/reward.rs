@@ -0,0 +1,93 @@
+//! What killing an enemy is worth: crusts dropped where it died, and optionally a step of
+//! progress on a sub-objective. The model's registry entry sets the defaults, and a gunner or
+//! enemy spawner can override both.
+
+use bevy::prelude::*;
+
+use super::NpcDead;
+use crate::gameplay::{crust_pickup::SpawnCrustPickup, objective::Objectives};
+
+/// Crusts dropped by a killed enemy whose model doesn't say.
+pub(crate) const DEFAULT_CRUST_DROP: u32 = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(grant_death_reward);
+}
+
+/// Paid out once when the enemy dies.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub(crate) struct DeathReward {
+    pub crusts: u32,
+    /// Sub-objective that goes up by one, empty for none.
+    pub objective: String,
+}
+
+impl Default for DeathReward {
+    fn default() -> Self {
+        Self {
+            crusts: DEFAULT_CRUST_DROP,
+            objective: String::new(),
+        }
+    }
+}
+
+impl DeathReward {
+    /// The model's reward with a gunner's overrides on top: `crusts` of 0 and an empty
+    /// `objective` keep the model's.
+    pub(crate) fn with_overrides(&self, crusts: u32, objective: &str) -> Self {
+        let objective = objective.trim();
+        Self {
+            crusts: if crusts > 0 { crusts } else { self.crusts },
+            objective: if objective.is_empty() {
+                self.objective.clone()
+            } else {
+                objective.to_string()
+            },
+        }
+    }
+}
+
+fn grant_death_reward(
+    add: On<Add, NpcDead>,
+    mut commands: Commands,
+    rewards: Query<(&DeathReward, &GlobalTransform)>,
+    mut objectives: ResMut<Objectives>,
+) {
+    let Ok((reward, transform)) = rewards.get(add.entity) else {
+        return;
+    };
+    if reward.crusts > 0 {
+        commands.trigger(SpawnCrustPickup {
+            position: transform.translation() + Vec3::Y,
+            amount: reward.crusts,
+        });
+    }
+    if !reward.objective.is_empty() && !objectives.add_progress(&reward.objective, 1) {
+        debug!(
+            "Kill didn't count toward {:?}, it isn't running",
+            reward.objective
+        );
+    }
+    commands.entity(add.entity).remove::<DeathReward>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gunner_overrides_replace_the_model_reward() {
+        let model = DeathReward {
+            crusts: 3,
+            objective: "help_larry".into(),
+        };
+        assert_eq!(model.with_overrides(0, " "), model);
+        assert_eq!(
+            model.with_overrides(5, "dock_fight"),
+            DeathReward {
+                crusts: 5,
+                objective: "dock_fight".into(),
+            }
+        );
+    }
+}
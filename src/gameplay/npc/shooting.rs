@@ -1,49 +1,80 @@
 //! Enemy projectile system — bullet-hell style slow-moving orbs.
 
 use avian3d::prelude::*;
-use bevy::prelude::*;
+use bevy::{camera::visibility::RenderLayers, platform::collections::HashMap, prelude::*};
+use bevy_ahoy::{CharacterController, input::GlobalMovement};
+use bevy_enhanced_input::prelude::{Action, ActionMock, ActionState, Actions};
+use bevy_hanabi::prelude::{Gradient as HanabiGradient, *};
 use bevy_seedling::prelude::*;
 use bevy_seedling::sample::AudioSample;
+use rand::Rng as _;
 use std::f32::consts::{PI, TAU};
 
 use crate::{
+    RenderLayer,
     audio::SpatialPool,
+    game_running,
     gameplay::{
-        player::{Invincible, Player, PlayerHealth, hurt_player},
-        tags::TagIndex,
+        barrel::ExplosiveBarrel,
+        captions::{Caption, CaptionEvent},
+        dig::{ModifyVoxels, Voxel, VoxelSim, VoxelVolume},
+        game_mode::combat_enabled,
+        player::{Invincible, Player, PlayerDead, PlayerHealth, crouch::Crouched, hurt_player},
+        tags::{TagIndex, TagResolutionCache},
     },
+    graphics::GraphicsQuality,
     screens::Screen,
     third_party::avian3d::CollisionLayer,
 };
 
-use super::{EnemyGunner, Health, NpcAggro, NpcDead};
+use super::threat::Threat;
+use super::{EnemyGunner, Health, MaxHealth, NPC_SPEED, NpcAggro, NpcDead, ai::NpcInputContext};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(
         FixedUpdate,
         (
             resolve_aggro_targets,
+            alert_spawned_enemies,
             aggro_swap,
+            deescalate_aggro,
             enemy_detection,
             rotate_alert_enemies,
-            npc_shoot,
+            strafe_repositioning_enemies,
+            npc_shoot.run_if(combat_enabled),
             move_projectiles,
-            projectile_hit_player,
+            projectile_hit_player.run_if(combat_enabled),
             projectile_hit_npc,
             projectile_hit_level,
         )
             .chain()
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(game_running),
     );
+    app.add_systems(Update, expand_telegraphs.run_if(game_running));
+    app.add_systems(FixedUpdate, tick_slowed.run_if(game_running));
+    app.init_resource::<FactionRelations>();
     app.add_observer(init_projectile_assets);
+    app.add_observer(provoke);
+    app.add_observer(slow_controller);
+    app.add_observer(restore_controller);
 }
 
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 struct ProjectileAssets {
     mesh: Handle<Mesh>,
     material: Handle<StandardMaterial>,
+    heal_material: Handle<StandardMaterial>,
+    slow_material: Handle<StandardMaterial>,
     gunshot: Handle<AudioSample>,
+    /// Ring flashed around a shooter just before a radial burst.
+    telegraph_mesh: Handle<Mesh>,
+    telegraph_material: Handle<StandardMaterial>,
+    /// Puff for projectiles hitting voxel terrain.
+    impact_dust: Handle<EffectAsset>,
+    /// Sparks for projectiles hitting brush geometry.
+    impact_sparks: Handle<EffectAsset>,
+    impact_sound: Handle<AudioSample>,
 }
 
 fn init_projectile_assets(
@@ -51,6 +82,7 @@ fn init_projectile_assets(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut effects: ResMut<Assets<EffectAsset>>,
     asset_server: Res<AssetServer>,
     existing: Option<Res<ProjectileAssets>>,
 ) {
@@ -65,27 +97,154 @@ fn init_projectile_assets(
             unlit: true,
             ..default()
         }),
+        heal_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.2, 1.0, 0.3),
+            emissive: LinearRgba::new(0.8, 6.0, 1.0, 1.0),
+            unlit: true,
+            ..default()
+        }),
+        slow_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.2, 0.5, 1.0),
+            emissive: LinearRgba::new(0.6, 2.0, 6.0, 1.0),
+            unlit: true,
+            ..default()
+        }),
         gunshot: asset_server.load("audio/sound_effects/smg_shot.ogg"),
+        telegraph_mesh: meshes.add(Torus::new(0.9, 1.0)),
+        telegraph_material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.5, 0.1),
+            emissive: LinearRgba::new(4.0, 1.0, 0.1, 1.0),
+            unlit: true,
+            ..default()
+        }),
+        impact_dust: effects.add(impact_effect(
+            "ProjectileImpactDust",
+            [
+                Vec4::new(0.5, 0.4, 0.3, 0.9),
+                Vec4::new(0.35, 0.28, 0.2, 0.0),
+            ],
+            1.5,
+            0.5,
+        )),
+        impact_sparks: effects.add(
+            impact_effect(
+                "ProjectileImpactSparks",
+                [Vec4::new(1.0, 0.8, 0.3, 1.0), Vec4::new(1.0, 0.3, 0.0, 0.0)],
+                4.0,
+                0.25,
+            )
+            .with_alpha_mode(bevy_hanabi::AlphaMode::Add),
+        ),
+        impact_sound: asset_server.load("audio/sound_effects/dig/dig-3.ogg"),
     });
 }
 
+/// Small burst of particles flying out from the impact point, fading from `colors[0]` to `colors[1]`.
+fn impact_effect(name: &str, colors: [Vec4; 2], speed: f32, lifetime: f32) -> EffectAsset {
+    let mut module = ExprWriter::new().finish();
+
+    let init_pos = SetPositionSphereModifier {
+        center: module.lit(Vec3::ZERO),
+        radius: module.lit(0.05),
+        dimension: ShapeDimension::Volume,
+    };
+
+    let init_vel = SetVelocitySphereModifier {
+        center: module.lit(Vec3::ZERO),
+        speed: module.lit(speed),
+    };
+
+    let lifetime = SetAttributeModifier::new(Attribute::LIFETIME, module.lit(lifetime));
+
+    let accel = AccelModifier::new(module.lit(Vec3::new(0.0, -9.8, 0.0)));
+
+    let mut gradient = HanabiGradient::new();
+    gradient.add_key(0.0, colors[0]);
+    gradient.add_key(1.0, colors[1]);
+
+    let mut size_curve = HanabiGradient::new();
+    size_curve.add_key(0.0, Vec3::splat(0.05));
+    size_curve.add_key(1.0, Vec3::splat(0.01));
+
+    EffectAsset::new(64, SpawnerSettings::once(8.0.into()), module)
+        .with_name(name)
+        .init(init_pos)
+        .init(init_vel)
+        .init(lifetime)
+        .update(accel)
+        .render(ColorOverLifetimeModifier {
+            gradient,
+            ..default()
+        })
+        .render(SizeOverLifetimeModifier {
+            gradient: size_curve,
+            screen_space_size: false,
+        })
+        .render(OrientModifier {
+            rotation: None,
+            mode: OrientMode::FaceCameraPosition,
+        })
+}
+
+
+/// The player's side in the [`FactionRelations`] matrix.
+pub(crate) const PLAYER_FACTION: &str = "player";
 
 #[derive(Component, Clone, Debug)]
 pub(crate) struct Faction(pub String);
 
 impl Faction {
     /// Returns true if a projectile from `self` faction is allowed to hurt `target` faction.
-    pub fn can_hurt(&self, target: &Faction) -> bool {
-        match (self.0.as_str(), target.0.as_str()) {
-            // Player can hurt everyone
-            ("player", _) => true,
-            // Lobster (larry) shouldn't hurt the player
-            ("lobster", "player") => false,
-            // Enemies shouldn't hurt other enemies
-            ("enemy", "enemy") => false,
-            // Everything else is fair game
-            _ => true,
+    pub fn can_hurt(&self, target: &str, relations: &FactionRelations) -> bool {
+        relations.can_hurt(&self.0, target)
+    }
+
+    /// The faction's name, NPCs without one are enemies.
+    pub fn name_or_enemy(faction: Option<&Faction>) -> &str {
+        faction.map_or("enemy", |faction| faction.0.as_str())
+    }
+}
+
+/// Which factions can damage which. Pairs that aren't listed are fair game.
+#[derive(Resource, Debug)]
+pub(crate) struct FactionRelations {
+    /// Attacker to target to whether it can hurt them, keyed so lookups don't allocate.
+    hurts: HashMap<String, HashMap<String, bool>>,
+    /// Lets factions hurt themselves regardless of the matrix.
+    pub friendly_fire: bool,
+}
+
+impl Default for FactionRelations {
+    fn default() -> Self {
+        let mut relations = Self {
+            hurts: HashMap::new(),
+            friendly_fire: false,
+        };
+        // Lobster (larry) shouldn't hurt the player
+        relations.set("lobster", PLAYER_FACTION, false);
+        // Enemies shouldn't hurt other enemies
+        relations.set("enemy", "enemy", false);
+        relations
+    }
+}
+
+impl FactionRelations {
+    pub fn set(&mut self, attacker: &str, target: &str, can_hurt: bool) {
+        self.hurts
+            .entry(attacker.to_string())
+            .or_default()
+            .insert(target.to_string(), can_hurt);
+    }
+
+    pub fn can_hurt(&self, attacker: &str, target: &str) -> bool {
+        if self.friendly_fire && attacker == target {
+            return true;
         }
+        self.hurts
+            .get(attacker)
+            .and_then(|targets| targets.get(target))
+            .copied()
+            .unwrap_or(true)
     }
 }
 
@@ -96,6 +255,117 @@ pub(crate) struct EnemyProjectile;
 struct Projectile {
     velocity: Vec3,
     lifetime: Timer,
+    /// Usually the shooter, passed through for the first [`SHOOTER_GRACE_SECONDS`]
+    /// so the projectile doesn't hit them (or their corpse) on the way out.
+    ignore: Option<Entity>,
+    /// Times left to bounce off level geometry. At zero the next hit stops it.
+    bounces: u32,
+    effect: ProjectileEffect,
+}
+
+impl Projectile {
+    fn ignores(&self, entity: Entity) -> bool {
+        self.ignore == Some(entity) && self.lifetime.elapsed_secs() < SHOOTER_GRACE_SECONDS
+    }
+}
+
+/// What a projectile does to whatever it hits. The player's health is counted in hearts, so
+/// damage and heals on them are always a single heart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ProjectileEffect {
+    /// Takes this much health.
+    Damage(f32),
+    /// Gives this much health back.
+    Heal(f32),
+    /// Slows the target's movement for this many seconds.
+    Slow(f32),
+}
+
+impl Default for ProjectileEffect {
+    fn default() -> Self {
+        Self::Damage(PROJECTILE_DAMAGE)
+    }
+}
+
+impl ProjectileEffect {
+    /// `"damage"`, `"heal"` or `"slow"` as written in the map, with `amount` as the damage,
+    /// healing or seconds of slow. An `amount` of 0 uses the effect's default.
+    pub(crate) fn from_stats(name: &str, amount: f32) -> Self {
+        let amount = |default| if amount > 0.0 { amount } else { default };
+        match name.trim() {
+            "" | "damage" => Self::Damage(amount(PROJECTILE_DAMAGE)),
+            "heal" => Self::Heal(amount(PROJECTILE_HEAL)),
+            "slow" => Self::Slow(amount(SLOW_SECONDS)),
+            other => {
+                warn!("Unknown projectile effect {other:?}, using \"damage\"");
+                Self::Damage(amount(PROJECTILE_DAMAGE))
+            }
+        }
+    }
+
+    /// Whether a projectile from `attacker` stops on `target`. Harmful effects hit whoever
+    /// the attacker can hurt, heals hit the attacker's own side.
+    fn affects(self, attacker: &Faction, target: &str, relations: &FactionRelations) -> bool {
+        match self {
+            Self::Heal(_) => attacker.0 == target || !attacker.can_hurt(target, relations),
+            Self::Damage(_) | Self::Slow(_) => attacker.can_hurt(target, relations),
+        }
+    }
+}
+
+/// Movement slowed by a projectile. The controller speed is scaled while it's present, so
+/// a second slow landing before the first is inserted doesn't stack.
+#[derive(Component)]
+struct Slowed(Timer);
+
+/// Slows `entity`, or extends its slow if it's already slowed.
+fn slow(commands: &mut Commands, entity: Entity, seconds: f32, slowed: Option<Mut<Slowed>>) {
+    match slowed {
+        Some(mut slowed) => {
+            let remaining = slowed.0.remaining_secs().max(seconds);
+            slowed.0 = Timer::from_seconds(remaining, TimerMode::Once);
+        }
+        None => {
+            commands
+                .entity(entity)
+                .insert(Slowed(Timer::from_seconds(seconds, TimerMode::Once)));
+        }
+    }
+}
+
+fn slow_controller(add: On<Add, Slowed>, mut controllers: Query<&mut CharacterController>) {
+    if let Ok(mut controller) = controllers.get_mut(add.entity) {
+        controller.speed *= SLOW_MULTIPLIER;
+    }
+}
+
+fn restore_controller(
+    remove: On<Remove, Slowed>,
+    mut controllers: Query<&mut CharacterController>,
+) {
+    if let Ok(mut controller) = controllers.get_mut(remove.entity) {
+        controller.speed /= SLOW_MULTIPLIER;
+    }
+}
+
+fn tick_slowed(mut commands: Commands, time: Res<Time>, mut slowed: Query<(Entity, &mut Slowed)>) {
+    for (entity, mut slowed) in &mut slowed {
+        slowed.0.tick(time.delta());
+        if slowed.0.is_finished() {
+            commands.entity(entity).remove::<Slowed>();
+        }
+    }
+}
+
+/// Suppression fire that carves into voxel cover when it hits the level.
+#[derive(Component)]
+struct TerrainDestructive;
+
+/// What a projectile's sweep ran into this tick, resolved by the hit systems.
+/// The projectile itself is moved up to the point of impact.
+#[derive(Component)]
+struct ProjectileImpact {
+    entity: Entity,
 }
 
 #[derive(Component)]
@@ -105,6 +375,20 @@ pub(crate) struct NpcShooter {
     range: f32,
     projectile_speed: f32,
     projectile_count: u32,
+    /// Half the cone aimed volleys are spread over, in radians.
+    spread_half_angle: f32,
+    /// Limits how often this shooter's volleys can dig into cover.
+    cover_bust_cooldown: Timer,
+    /// Seconds of warning before a radial burst.
+    telegraph: f32,
+    /// The warning for the upcoming burst has been shown. Cleared whenever `fire_rate` is
+    /// rewound, so the next approach to firing warns again.
+    telegraphing: bool,
+    /// Level bounces each projectile gets, only for ricochet volleys.
+    bounces: u32,
+    /// Radial bursts tilt towards the target's height.
+    aim_vertical: bool,
+    effect: ProjectileEffect,
 }
 
 impl Default for NpcShooter {
@@ -115,29 +399,99 @@ impl Default for NpcShooter {
             range: 20.0,
             projectile_speed: 5.0,
             projectile_count: 12,
+            spread_half_angle: SPREAD_HALF_ANGLE,
+            cover_bust_cooldown: Timer::from_seconds(COVER_BUST_COOLDOWN, TimerMode::Once),
+            telegraph: 0.4,
+            telegraphing: false,
+            bounces: 0,
+            aim_vertical: false,
+            effect: ProjectileEffect::default(),
         }
     }
 }
 
 impl NpcShooter {
-    pub fn from_gunner(g: &EnemyGunner) -> Self {
+    pub fn from_gunner(g: &EnemyGunner, threat: &Threat) -> Self {
         let pattern = match g.pattern.as_str() {
             "spread" => FiringPattern::AimedSpread,
+            "ricochet" => FiringPattern::Ricochet,
             _ => FiringPattern::RadialBurst,
         };
+        let bounces = match pattern {
+            FiringPattern::Ricochet => g.bounces,
+            _ => 0,
+        };
+        let projectile_count =
+            (g.projectile_count as f32 * threat.projectile_multiplier()).round() as u32;
         Self {
             pattern,
-            fire_rate: Timer::from_seconds(g.fire_rate, TimerMode::Repeating),
+            fire_rate: Timer::from_seconds(
+                g.fire_rate / threat.fire_rate_multiplier(),
+                TimerMode::Repeating,
+            ),
             range: g.range,
             projectile_speed: g.projectile_speed,
-            projectile_count: g.projectile_count,
+            projectile_count,
+            spread_half_angle: SPREAD_HALF_ANGLE,
+            cover_bust_cooldown: Timer::from_seconds(COVER_BUST_COOLDOWN, TimerMode::Once),
+            telegraph: g.telegraph,
+            telegraphing: false,
+            bounces,
+            aim_vertical: g.aim_vertical,
+            effect: ProjectileEffect::from_stats(&g.effect, g.effect_amount),
+        }
+    }
+
+    /// `"radial"`, `"spread"` or `"ricochet"`, as written in the map.
+    pub(crate) fn pattern_name(&self) -> &'static str {
+        match self.pattern {
+            FiringPattern::RadialBurst => "radial",
+            FiringPattern::AimedSpread => "spread",
+            FiringPattern::Ricochet => "ricochet",
         }
     }
+
+    pub(crate) fn tuning(&self) -> ShooterTuning {
+        ShooterTuning {
+            fire_interval: self.fire_rate.duration().as_secs_f32(),
+            projectile_speed: self.projectile_speed,
+            projectile_count: self.projectile_count,
+            range: self.range,
+            spread_half_angle: self.spread_half_angle,
+        }
+    }
+
+    /// Applies tuned numbers live. The fire timer keeps how far through its interval it was,
+    /// so the volley rhythm doesn't restart.
+    pub(crate) fn set_tuning(&mut self, tuning: ShooterTuning) {
+        let fraction = self.fire_rate.fraction();
+        let mut fire_rate = Timer::from_seconds(tuning.fire_interval, TimerMode::Repeating);
+        fire_rate.set_elapsed(fire_rate.duration().mul_f32(fraction));
+        self.fire_rate = fire_rate;
+        self.telegraphing = false;
+        self.projectile_speed = tuning.projectile_speed;
+        self.projectile_count = tuning.projectile_count;
+        self.range = tuning.range;
+        self.spread_half_angle = tuning.spread_half_angle;
+    }
+}
+
+/// The numbers of an [`NpcShooter`] that can be tuned while playing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ShooterTuning {
+    /// Seconds between volleys, after threat scaling.
+    pub fire_interval: f32,
+    pub projectile_speed: f32,
+    pub projectile_count: u32,
+    pub range: f32,
+    pub spread_half_angle: f32,
 }
 
 enum FiringPattern {
     RadialBurst,
     AimedSpread,
+    /// An aimed spread whose projectiles bounce off the level.
+    Ricochet,
 }
 
 /// Tracks that an enemy has detected the player and is actively engaging.
@@ -146,33 +500,119 @@ pub(crate) struct EnemyAlert {
     last_seen_position: Vec3,
     /// Counts down after losing sight; enemy stays alert briefly.
     lose_sight_timer: Timer,
+    /// Voxel volume and point that blocked the last sight check, if any.
+    cover: Option<(Entity, Vec3)>,
+}
+
+impl EnemyAlert {
+    fn lost_sight(&self) -> bool {
+        !self.lose_sight_timer.elapsed().is_zero()
+    }
+}
+
+/// Short-term memory of where the target was last seen, kept for a while after the alert runs out.
+/// The enemy keeps aiming at the spot, and if the target shows up again it pre-fires there
+/// instead of re-acquiring from scratch.
+#[derive(Component)]
+pub(crate) struct EnemyMemory {
+    last_seen_position: Vec3,
+    memory_timer: Timer,
 }
 
 #[derive(Component)]
 pub(crate) struct AggroTarget(pub Entity);
 
+/// Put on an enemy spawned for an ambush, so it comes out already alerted on its target
+/// instead of waiting to spot it.
 #[derive(Component)]
+pub(crate) struct SpawnAlerted;
+
+/// Stepping sideways out from behind a friendly that was blocking the shot.
+#[derive(Component)]
+pub(crate) struct Repositioning {
+    direction: Vec3,
+    timer: Timer,
+}
+
+/// Passive enemy (`aggro_mode "on_hit"`) that ignores everyone until it takes damage.
+#[derive(Component)]
+pub(crate) struct AggroOnHit;
+
+/// Damage dealt to an enemy by `attacker`, turning an [`AggroOnHit`] enemy hostile towards them.
+#[derive(EntityEvent)]
+pub(crate) struct Provoked {
+    pub(crate) entity: Entity,
+    pub(crate) attacker: Entity,
+}
+
+#[derive(Component)]
+#[require(ResolveAggroTarget)]
 pub(crate) struct AggroConfig {
     pub target_tag: String,
     pub aggro_radius: f32,
     pub swapped_to_player: bool,
+    /// Seconds [`unseen`](Self::unseen) has to reach before a swapped enemy gives up on the player.
+    /// 0 = never.
+    pub deescalate_after: f32,
+    /// Seconds the player has been out of sight and out of `aggro_radius`.
+    pub unseen: f32,
 }
 
 
 const PROJECTILE_LIFETIME: f32 = 6.0;
-const SPREAD_HALF_ANGLE: f32 = PI / 6.0; // 30 degrees total cone
-/// Half of the 120° FOV detection cone (in radians).
+const PROJECTILE_DAMAGE: f32 = 10.0;
+const PROJECTILE_HEAL: f32 = 10.0;
+const SLOW_SECONDS: f32 = 2.0;
+/// Movement speed multiplier while slowed.
+const SLOW_MULTIPLIER: f32 = 0.5;
+const SHOOTER_GRACE_SECONDS: f32 = 0.25;
+pub(crate) const SPREAD_HALF_ANGLE: f32 = PI / 6.0; // 30 degrees total cone
+/// Height above an enemy's origin its projectiles spawn at, roughly where the gun is.
+pub(crate) const GUN_HEIGHT: f32 = 0.8;
+/// Fraction of their range enemies can spot a crouched player from.
+const CROUCHED_RANGE_MULTIPLIER: f32 = 0.75;
+/// Half the width of the detection cone (in radians).
 const DETECTION_HALF_ANGLE: f32 = PI / 3.0; // 60°
+/// Half the height of the detection cone (in radians), narrower than its width.
+const DETECTION_HALF_PITCH: f32 = PI / 4.0; // 45°
+/// Steepest enemies aim up or down, so volleys still read as gunfire.
+const MAX_AIM_PITCH: f32 = PI / 3.0; // 60°
 /// How long an enemy stays alert after losing sight of the player.
 const LOSE_SIGHT_DURATION: f32 = 3.0;
+/// How long an enemy remembers where it last saw the target once it stops being alert.
+const MEMORY_DURATION: f32 = 6.0;
+/// Fraction of the fire interval an enemy with memory waits before pre-firing on a re-peek.
+/// Enemies spotting the target for the first time keep their fire timer where it was.
+const MEMORY_REACQUIRE_FRACTION: f32 = 0.3;
+/// Fire rate multiplier for suppression volleys at the last seen position.
+const SUPPRESSION_RATE: f32 = 0.5;
+/// How far through its first fire interval an enemy spawned alerted starts.
+const SPAWN_ALERTED_FIRE_FRACTION: f32 = 0.75;
+const COVER_BUST_COOLDOWN: f32 = 4.0;
+/// Crater radius in voxels for each destructive projectile.
+const COVER_BUST_RADIUS: f32 = 1.0;
+/// Global cap on projectile impact sounds, so a volley into a wall doesn't stack up.
+const IMPACT_SOUNDS_PER_SECOND: f32 = 10.0;
+const IMPACT_VOLUME: f32 = 0.4;
+const IMPACT_PITCH_RANGE: std::ops::RangeInclusive<f64> = 0.9..=1.1;
+/// Aimed volleys are held when a living friendly is this close in the line of fire.
+const FRIENDLY_BLOCK_DISTANCE: f32 = 2.0;
+const REPOSITION_SECONDS: f32 = 1.0;
+const REPOSITION_SPEED: f32 = 3.0;
+
 
+/// On enemies that need their [`AggroTarget`] picked from their tag: new ones, and ones
+/// [`deescalate_aggro`] sent back to their original target.
+#[derive(Component, Default)]
+struct ResolveAggroTarget;
 
 fn resolve_aggro_targets(
     mut commands: Commands,
     tag_index: Res<TagIndex>,
+    mut cache: Local<TagResolutionCache>,
     mut enemies: Query<
         (Entity, &mut AggroConfig),
-        (With<NpcAggro>, Without<AggroTarget>),
+        (With<NpcAggro>, With<ResolveAggroTarget>, Without<AggroTarget>),
     >,
     dead: Query<(), With<NpcDead>>,
     player: Option<Single<Entity, With<Player>>>,
@@ -181,16 +621,14 @@ fn resolve_aggro_targets(
     let player_entity = *player;
 
     for (entity, mut config) in &mut enemies {
+        commands.entity(entity).remove::<ResolveAggroTarget>();
         if config.target_tag.is_empty() {
             commands.entity(entity).insert(AggroTarget(player_entity));
             config.swapped_to_player = true;
             continue;
         }
 
-        let target = tag_index
-            .get(&config.target_tag)
-            .and_then(|set| set.iter().find(|e| dead.get(**e).is_err()))
-            .copied();
+        let target = cache.resolve(&tag_index, &config.target_tag, |e| dead.get(e).is_err());
 
         match target {
             Some(t) => {
@@ -204,8 +642,58 @@ fn resolve_aggro_targets(
     }
 }
 
+/// Skips detection for [`SpawnAlerted`] enemies once their target is resolved. A missing tag,
+/// a dead target or no player yet leaves them to spot it the usual way.
+fn alert_spawned_enemies(
+    mut commands: Commands,
+    mut enemies: Query<
+        (
+            Entity,
+            &mut NpcShooter,
+            &AggroConfig,
+            Option<&AggroTarget>,
+            Has<NpcAggro>,
+        ),
+        With<SpawnAlerted>,
+    >,
+    targets: Query<&GlobalTransform, (Without<NpcDead>, Without<PlayerDead>)>,
+) {
+    for (entity, mut shooter, config, target, aggro) in &mut enemies {
+        commands.entity(entity).remove::<SpawnAlerted>();
+        // `resolve_aggro_targets` falls back to the player when the tag has no living match.
+        let missing_tag = !config.target_tag.is_empty() && config.swapped_to_player;
+        if !aggro || missing_tag {
+            continue;
+        }
+        let Some(target_transform) = target.and_then(|target| targets.get(target.0).ok()) else {
+            continue;
+        };
+
+        commands.entity(entity).insert(EnemyAlert {
+            last_seen_position: target_transform.translation(),
+            lose_sight_timer: Timer::from_seconds(LOSE_SIGHT_DURATION, TimerMode::Once),
+            cover: None,
+        });
+        let interval = shooter.fire_rate.duration();
+        shooter
+            .fire_rate
+            .set_elapsed(interval.mul_f32(SPAWN_ALERTED_FIRE_FRACTION));
+        shooter.telegraphing = false;
+    }
+}
+
 fn aggro_swap(
-    mut enemies: Query<(&GlobalTransform, &mut AggroTarget, &mut AggroConfig), With<NpcAggro>>,
+    mut commands: Commands,
+    mut enemies: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&mut AggroTarget>,
+            &mut AggroConfig,
+            Has<EnemyAlert>,
+        ),
+        With<NpcAggro>,
+    >,
     player: Option<Single<(Entity, &GlobalTransform), With<Player>>>,
     dead: Query<(), With<NpcDead>>,
 ) {
@@ -213,11 +701,22 @@ fn aggro_swap(
     let (player_entity, player_transform) = *player;
     let player_pos = player_transform.translation();
 
-    for (npc_transform, mut target, mut config) in &mut enemies {
+    for (entity, npc_transform, target, mut config, alert) in &mut enemies {
         if config.swapped_to_player {
             continue;
         }
 
+        let Some(mut target) = target else {
+            // Untagged enemies that lost interest have no target until the player comes close
+            // or is spotted again. Tagged ones are still waiting on `resolve_aggro_targets`.
+            let near = npc_transform.translation().distance(player_pos) < config.aggro_radius;
+            if config.target_tag.is_empty() && (alert || near) {
+                commands.entity(entity).insert(AggroTarget(player_entity));
+                config.swapped_to_player = true;
+            }
+            continue;
+        };
+
         if dead.get(target.0).is_ok() {
             target.0 = player_entity;
             config.swapped_to_player = true;
@@ -232,6 +731,71 @@ fn aggro_swap(
     }
 }
 
+/// Wakes up an [`AggroOnHit`] enemy, aimed straight at whoever hurt it.
+fn provoke(
+    provoked: On<Provoked>,
+    mut commands: Commands,
+    mut enemies: Query<&mut AggroConfig, (With<AggroOnHit>, Without<NpcDead>)>,
+    player: Option<Single<Entity, With<Player>>>,
+) {
+    let Ok(mut config) = enemies.get_mut(provoked.entity) else {
+        return;
+    };
+    config.swapped_to_player = player.is_some_and(|player| *player == provoked.attacker);
+    commands
+        .entity(provoked.entity)
+        .remove::<AggroOnHit>()
+        .insert((NpcAggro, AggroTarget(provoked.attacker)));
+}
+
+/// Lets swapped enemies lose interest in a player that stayed hidden long enough,
+/// so `resolve_aggro_targets` sends them back to their original target. Enemies without one
+/// are left with no target at all.
+fn deescalate_aggro(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemies: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &AggroTarget,
+            &mut AggroConfig,
+            Has<EnemyAlert>,
+        ),
+        With<NpcAggro>,
+    >,
+    player: Option<Single<(Entity, &GlobalTransform), With<Player>>>,
+) {
+    let Some(player) = player else { return };
+    let (player_entity, player_transform) = *player;
+    let player_pos = player_transform.translation();
+
+    for (entity, npc_transform, target, mut config, alert) in &mut enemies {
+        if !config.swapped_to_player || config.deescalate_after <= 0.0 || target.0 != player_entity
+        {
+            continue;
+        }
+        let near = npc_transform.translation().distance(player_pos) < config.aggro_radius;
+        if alert || near {
+            config.unseen = 0.0;
+            continue;
+        }
+
+        config.unseen += time.delta_secs();
+        if config.unseen < config.deescalate_after {
+            continue;
+        }
+        config.unseen = 0.0;
+        config.swapped_to_player = false;
+        let mut enemy = commands.entity(entity);
+        enemy.remove::<(AggroTarget, EnemyMemory)>();
+        // Untagged enemies have nothing to go back to, `aggro_swap` picks the player up again.
+        if !config.target_tag.is_empty() {
+            enemy.insert(ResolveAggroTarget);
+        }
+    }
+}
+
 fn enemy_detection(
     mut commands: Commands,
     time: Res<Time>,
@@ -239,20 +803,24 @@ fn enemy_detection(
     mut enemies: Query<
         (
             Entity,
-            &NpcShooter,
+            &mut NpcShooter,
             &GlobalTransform,
             Option<&AggroTarget>,
             Option<&mut EnemyAlert>,
+            Option<&mut EnemyMemory>,
         ),
         With<NpcAggro>,
     >,
-    player: Option<Single<&GlobalTransform, With<Player>>>,
+    player: Option<Single<(Entity, &GlobalTransform, Has<Crouched>), With<Player>>>,
     transforms: Query<&GlobalTransform>,
+    volumes: Query<&VoxelVolume, With<VoxelSim>>,
 ) {
     let Some(player) = player else { return };
-    let player_pos = player.translation();
+    let (player, player_transform, crouched) = player.into_inner();
+    let player_pos = player_transform.translation();
 
-    for (entity, shooter, npc_transform, aggro_target, alert) in &mut enemies {
+    for (entity, mut shooter, npc_transform, aggro_target, alert, memory) in &mut enemies {
+        let mut cover = None;
         let target_pos = aggro_target
             .and_then(|at| transforms.get(at.0).ok())
             .map(|gt| gt.translation())
@@ -262,30 +830,34 @@ fn enemy_detection(
         let to_target = target_pos - npc_pos;
         let distance = to_target.length();
 
-        let to_target_hz = Vec3::new(to_target.x, 0.0, to_target.z);
         let forward = npc_transform.forward().as_vec3();
-        let forward_hz = Vec3::new(forward.x, 0.0, forward.z);
 
-        let can_see = if distance < 0.01 || distance > shooter.range {
+        // Crouching keeps the player hidden from further away.
+        let targets_player = aggro_target.is_none_or(|at| at.0 == player);
+        let range = if crouched && targets_player {
+            shooter.range * CROUCHED_RANGE_MULTIPLIER
+        } else {
+            shooter.range
+        };
+
+        let can_see = if distance < 0.01 || distance > range {
             false
-        } else if let (Ok(to_dir), Ok(fwd_dir)) = (Dir3::new(to_target_hz), Dir3::new(forward_hz)) {
-            let dot = to_dir.dot(*fwd_dir);
-            let in_fov = dot >= DETECTION_HALF_ANGLE.cos(); // cos(60°) = 0.5
-
-            if in_fov {
-                // LOS check
-                let direction = Dir3::new(to_target).unwrap();
-                let los_hit = spatial_query.cast_ray(
-                    npc_pos,
-                    direction,
-                    distance,
-                    true,
-                    &SpatialQueryFilter::from_mask(CollisionLayer::Level),
-                );
-                los_hit.is_none()
-            } else {
-                false
+        } else if in_detection_cone(forward, to_target) {
+            // LOS check
+            let direction = Dir3::new(to_target).unwrap();
+            let los_hit = spatial_query.cast_ray(
+                npc_pos,
+                direction,
+                distance,
+                true,
+                &SpatialQueryFilter::from_mask(CollisionLayer::Level),
+            );
+            if let Some(hit) = los_hit {
+                if volumes.get(hit.entity).is_ok_and(|v| !v.protected) {
+                    cover = Some((hit.entity, npc_pos + direction * hit.distance));
+                }
             }
+            los_hit.is_none()
         } else {
             false
         };
@@ -294,31 +866,82 @@ fn enemy_detection(
             Some(mut alert) if can_see => {
                 alert.last_seen_position = target_pos;
                 alert.lose_sight_timer.reset();
+                alert.cover = None;
             }
             Some(mut alert) => {
                 // Lost sight — tick the timer
+                if cover.is_some() {
+                    alert.cover = cover;
+                }
                 alert.lose_sight_timer.tick(time.delta());
                 if alert.lose_sight_timer.just_finished() {
-                    commands.entity(entity).remove::<EnemyAlert>();
+                    commands
+                        .entity(entity)
+                        .remove::<EnemyAlert>()
+                        .insert(EnemyMemory {
+                            last_seen_position: alert.last_seen_position,
+                            memory_timer: Timer::from_seconds(MEMORY_DURATION, TimerMode::Once),
+                        });
                 }
             }
             None if can_see => {
                 commands.entity(entity).insert(EnemyAlert {
                     last_seen_position: target_pos,
                     lose_sight_timer: Timer::from_seconds(LOSE_SIGHT_DURATION, TimerMode::Once),
+                    cover: None,
                 });
+                // The memory stays until `npc_shoot` pre-fires at it.
+                if memory.is_some() {
+                    let interval = shooter.fire_rate.duration();
+                    shooter
+                        .fire_rate
+                        .set_elapsed(interval.mul_f32(1.0 - MEMORY_REACQUIRE_FRACTION));
+                    shooter.telegraphing = false;
+                }
+            }
+            None => {
+                if let Some(mut memory) = memory {
+                    memory.memory_timer.tick(time.delta());
+                    if memory.memory_timer.just_finished() {
+                        commands.entity(entity).remove::<EnemyMemory>();
+                    }
+                }
             }
-            None => {}
         }
     }
 }
 
+/// Whether `to_target` is inside the detection cone around `forward`. The cone is elliptical,
+/// wider than it is tall, and measured from the horizontal, so a target in a pit below is seen
+/// as long as it's not too steep.
+fn in_detection_cone(forward: Vec3, to_target: Vec3) -> bool {
+    let forward_hz = Vec3::new(forward.x, 0.0, forward.z);
+    let to_target_hz = Vec3::new(to_target.x, 0.0, to_target.z);
+    if forward_hz.length_squared() < 1e-6 || to_target_hz.length_squared() < 1e-6 {
+        return false;
+    }
+    let yaw = forward_hz.angle_between(to_target_hz) / DETECTION_HALF_ANGLE;
+    let pitch = to_target.y.atan2(to_target_hz.length()) / DETECTION_HALF_PITCH;
+    yaw * yaw + pitch * pitch <= 1.0
+}
+
+/// Turns only around Y, so models stay upright. Aiming up or down is left to the volleys.
 fn rotate_alert_enemies(
-    mut enemies: Query<(&mut Transform, &EnemyAlert), With<EnemyGunner>>,
+    mut enemies: Query<
+        (&mut Transform, Option<&EnemyAlert>, Option<&EnemyMemory>),
+        (With<EnemyGunner>, Or<(With<EnemyAlert>, With<EnemyMemory>)>),
+    >,
     time: Res<Time>,
 ) {
-    for (mut transform, alert) in &mut enemies {
-        let to_target = alert.last_seen_position - transform.translation;
+    for (mut transform, alert, memory) in &mut enemies {
+        // Remembering enemies keep their gun trained on where the target was.
+        let Some(last_seen_position) = alert
+            .map(|alert| alert.last_seen_position)
+            .or(memory.map(|memory| memory.last_seen_position))
+        else {
+            continue;
+        };
+        let to_target = last_seen_position - transform.translation;
         let to_target_hz = Vec3::new(to_target.x, 0.0, to_target.z);
         let Ok(target_dir) = Dir3::new(to_target_hz) else {
             continue;
@@ -337,9 +960,11 @@ fn npc_shoot(
     assets: Option<Res<ProjectileAssets>>,
     mut shooters: Query<
         (
+            Entity,
             &mut NpcShooter,
             &GlobalTransform,
             &EnemyAlert,
+            Option<&EnemyMemory>,
             Option<&AggroTarget>,
             Option<&Faction>,
         ),
@@ -347,70 +972,133 @@ fn npc_shoot(
     >,
     player: Option<Single<&GlobalTransform, With<Player>>>,
     transforms: Query<&GlobalTransform>,
+    spatial_query: SpatialQuery,
+    factions: Query<Option<&Faction>, (With<Health>, Without<NpcDead>)>,
 ) {
     let Some(assets) = assets else { return };
     let Some(player) = player else { return };
     let player_pos = player.translation();
 
-    for (mut shooter, npc_transform, _alert, aggro_target, faction) in &mut shooters {
-        let faction = faction
-            .cloned()
-            .unwrap_or(Faction("enemy".to_string()));
-        shooter.fire_rate.tick(time.delta());
+    for (entity, mut shooter, npc_transform, alert, memory, aggro_target, faction) in &mut shooters
+    {
+        let faction = Faction::name_or_enemy(faction);
+        shooter.cover_bust_cooldown.tick(time.delta());
+
+        // Without sight of the target, keep up slower suppression fire where it was last seen.
+        let suppressing = alert.lost_sight();
+        let rate = if suppressing { SUPPRESSION_RATE } else { 1.0 };
+        shooter.fire_rate.tick(time.delta().mul_f32(rate));
+
+        let npc_pos = npc_transform.translation();
+        let spawn_pos = npc_pos + Vec3::Y * GUN_HEIGHT;
+
         if !shooter.fire_rate.just_finished() {
+            // Warn slightly ahead of radial bursts so they can be dodged.
+            let until_fire = shooter.fire_rate.remaining_secs() / rate;
+            if matches!(shooter.pattern, FiringPattern::RadialBurst)
+                && !shooter.telegraphing
+                && until_fire <= shooter.telegraph
+            {
+                shooter.telegraphing = true;
+                commands.spawn((
+                    Name::new("Burst Telegraph"),
+                    BurstTelegraph {
+                        timer: Timer::from_seconds(until_fire, TimerMode::Once),
+                    },
+                    Mesh3d(assets.telegraph_mesh.clone()),
+                    MeshMaterial3d(assets.telegraph_material.clone()),
+                    Transform::from_translation(spawn_pos)
+                        .with_scale(Vec3::splat(TELEGRAPH_START_SCALE)),
+                    DespawnOnExit(Screen::Gameplay),
+                ));
+            }
             continue;
         }
+        shooter.telegraphing = false;
 
-        let npc_pos = npc_transform.translation();
-
-        let target_pos = aggro_target
-            .and_then(|at| transforms.get(at.0).ok())
-            .map(|gt| gt.translation())
-            .unwrap_or(player_pos);
+        let target_pos = if let Some(memory) = memory {
+            // Pre-fire at the remembered spot, rewarding players who peek from somewhere new.
+            commands.entity(entity).remove::<EnemyMemory>();
+            memory.last_seen_position
+        } else if suppressing {
+            alert.last_seen_position
+        } else {
+            aggro_target
+                .and_then(|at| transforms.get(at.0).ok())
+                .map(|gt| gt.translation())
+                .unwrap_or(player_pos)
+        };
         let to_target = target_pos - npc_pos;
 
-        // Spawn projectiles
-        let spawn_pos = npc_pos + Vec3::Y * 0.8; // roughly gun height
+        // Radial bursts go everywhere anyway, only aimed volleys care about who's in the way.
+        let blocker = match shooter.pattern {
+            FiringPattern::AimedSpread | FiringPattern::Ricochet => blocking_friendly(
+                &spatial_query,
+                &factions,
+                &transforms,
+                entity,
+                spawn_pos,
+                to_target,
+                faction,
+            ),
+            FiringPattern::RadialBurst => None,
+        };
+        if let Some(blocker) = blocker {
+            let side = Vec3::new(to_target.x, 0.0, to_target.z)
+                .cross(Vec3::Y)
+                .normalize_or_zero();
+            // Step away from the side the blocker is on.
+            let direction = if side.dot(blocker - npc_pos) > 0.0 {
+                -side
+            } else {
+                side
+            };
+            commands.entity(entity).insert(Repositioning {
+                direction,
+                timer: Timer::from_seconds(REPOSITION_SECONDS, TimerMode::Once),
+            });
+            continue;
+        }
+
+        let destructive =
+            suppressing && alert.cover.is_some() && shooter.cover_bust_cooldown.is_finished();
+        if destructive {
+            shooter.cover_bust_cooldown.reset();
+        }
+
         let count = shooter.projectile_count;
         let speed = shooter.projectile_speed;
+        let spread_half_angle = shooter.spread_half_angle;
+        let bounces = shooter.bounces;
+        let effect = shooter.effect;
 
-        match shooter.pattern {
-            FiringPattern::RadialBurst => {
-                for i in 0..count {
-                    let angle = (i as f32 / count as f32) * TAU;
-                    let dir = Vec3::new(angle.cos(), 0.0, angle.sin());
-                    spawn_projectile(
-                        &mut commands,
-                        &assets,
-                        spawn_pos,
-                        dir * speed,
-                        faction.clone(),
-                    );
-                }
+        // From the gun rather than the feet, so shots land on targets above or below.
+        let aim = target_pos - spawn_pos;
+        let directions = match shooter.pattern {
+            FiringPattern::RadialBurst if shooter.aim_vertical => {
+                tilt_toward(radial_directions(count), aim)
             }
-            FiringPattern::AimedSpread => {
-                let forward_hz = Vec3::new(to_target.x, 0.0, to_target.z).normalize_or_zero();
-                if forward_hz.length_squared() < 0.01 {
-                    continue;
-                }
-                for i in 0..count {
-                    let t = if count <= 1 {
-                        0.0
-                    } else {
-                        (i as f32 / (count - 1) as f32) * 2.0 - 1.0 // -1..1
-                    };
-                    let angle = t * SPREAD_HALF_ANGLE;
-                    let rot = Quat::from_rotation_y(angle);
-                    let dir = rot * forward_hz;
-                    spawn_projectile(
-                        &mut commands,
-                        &assets,
-                        spawn_pos,
-                        dir * speed,
-                        faction.clone(),
-                    );
-                }
+            FiringPattern::RadialBurst => radial_directions(count),
+            FiringPattern::AimedSpread | FiringPattern::Ricochet => {
+                spread_directions(aim, count, spread_half_angle)
             }
+        };
+        // Straight above or below, there's no way to aim a flat spread.
+        if directions.is_empty() {
+            continue;
+        }
+        for dir in directions {
+            spawn_projectile(
+                &mut commands,
+                &assets,
+                entity,
+                spawn_pos,
+                dir * speed,
+                Faction(faction.to_string()),
+                destructive,
+                bounces,
+                effect,
+            );
         }
 
         // Gunshot sound at the enemy's position
@@ -419,26 +1107,194 @@ fn npc_shoot(
             SpatialPool,
             Transform::from_translation(npc_pos),
         ));
+        commands.trigger(CaptionEvent::at(Caption::Gunfire, npc_pos));
+    }
+}
+
+/// Directions of a radial burst: `count` evenly spaced around the horizontal plane, the first
+/// along +X.
+pub(crate) fn radial_directions(count: u32) -> Vec<Vec3> {
+    (0..count)
+        .map(|i| {
+            let angle = (i as f32 / count as f32) * TAU;
+            Vec3::new(angle.cos(), 0.0, angle.sin())
+        })
+        .collect()
+}
+
+/// Horizontal heading towards `to_target` and the rotation pitching it up or down to the
+/// target's height, at most [`MAX_AIM_PITCH`]. `None` when `to_target` is (nearly) vertical.
+fn aim_pitch(to_target: Vec3) -> Option<(Vec3, Quat)> {
+    let forward_hz = Vec3::new(to_target.x, 0.0, to_target.z).normalize_or_zero();
+    if forward_hz.length_squared() < 0.01 {
+        return None;
+    }
+    let horizontal = Vec3::new(to_target.x, 0.0, to_target.z).length();
+    let pitch = to_target
+        .y
+        .atan2(horizontal)
+        .clamp(-MAX_AIM_PITCH, MAX_AIM_PITCH);
+    let right = forward_hz.cross(Vec3::Y);
+    Some((forward_hz, Quat::from_axis_angle(right, pitch)))
+}
+
+/// Directions of an aimed volley: `count` evenly spread over `half_angle` to either side of
+/// `forward`, pitched up or down to it. A single shot goes straight ahead. Empty when `forward`
+/// is (nearly) vertical.
+pub(crate) fn spread_directions(forward: Vec3, count: u32, half_angle: f32) -> Vec<Vec3> {
+    let Some((forward_hz, pitch)) = aim_pitch(forward) else {
+        return Vec::new();
+    };
+    let aim = pitch * forward_hz;
+    let up = pitch * Vec3::Y;
+    (0..count)
+        .map(|i| {
+            let t = if count <= 1 {
+                0.0
+            } else {
+                (i as f32 / (count - 1) as f32) * 2.0 - 1.0 // -1..1
+            };
+            Quat::from_axis_angle(up, t * half_angle) * aim
+        })
+        .collect()
+}
+
+/// Tilts a flat ring of `directions` so the side facing `to_target` pitches up or down to it.
+/// Left flat when the target is (nearly) straight above or below.
+fn tilt_toward(directions: Vec<Vec3>, to_target: Vec3) -> Vec<Vec3> {
+    let Some((_, pitch)) = aim_pitch(to_target) else {
+        return directions;
+    };
+    directions
+        .into_iter()
+        .map(|direction| pitch * direction)
+        .collect()
+}
+
+/// Position of a living same-faction NPC right in front of the shooter, if there is one.
+fn blocking_friendly(
+    spatial_query: &SpatialQuery,
+    factions: &Query<Option<&Faction>, (With<Health>, Without<NpcDead>)>,
+    transforms: &Query<&GlobalTransform>,
+    shooter: Entity,
+    origin: Vec3,
+    to_target: Vec3,
+    faction: &str,
+) -> Option<Vec3> {
+    let direction = Dir3::new(to_target).ok()?;
+    let filter =
+        SpatialQueryFilter::from_mask(CollisionLayer::Character).with_excluded_entities([shooter]);
+    let hit = spatial_query.cast_ray(origin, direction, FRIENDLY_BLOCK_DISTANCE, true, &filter)?;
+    let hit_faction = factions.get(hit.entity).ok()?;
+    let same_faction = Faction::name_or_enemy(hit_faction) == faction;
+    if !same_faction {
+        return None;
+    }
+    transforms.get(hit.entity).ok().map(|t| t.translation())
+}
+
+/// Steps through the controller like any other NPC movement, so the strafe collides and
+/// interpolates instead of teleporting the body.
+fn strafe_repositioning_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut enemies: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Repositioning,
+        &Actions<NpcInputContext>,
+    )>,
+    mut action_mocks: Query<&mut ActionMock, With<Action<GlobalMovement>>>,
+) {
+    for (entity, transform, mut repositioning, actions) in &mut enemies {
+        repositioning.timer.tick(time.delta());
+        let step = REPOSITION_SPEED * time.delta_secs();
+        let filter = SpatialQueryFilter::from_mask([
+            CollisionLayer::Level,
+            CollisionLayer::Prop,
+            CollisionLayer::Character,
+        ])
+        .with_excluded_entities([entity]);
+        let Ok(direction) = Dir3::new(repositioning.direction) else {
+            commands.entity(entity).remove::<Repositioning>();
+            continue;
+        };
+        let blocked = spatial_query
+            .cast_ray(
+                transform.translation(),
+                direction,
+                step + super::NPC_RADIUS,
+                true,
+                &filter,
+            )
+            .is_some();
+        if repositioning.timer.is_finished() || blocked {
+            commands.entity(entity).remove::<Repositioning>();
+            continue;
+        }
+        if let Some(mut mock) = action_mocks.iter_many_mut(actions).fetch_next() {
+            *mock = ActionMock::once(
+                ActionState::Fired,
+                direction * (REPOSITION_SPEED / NPC_SPEED),
+            );
+        }
+    }
+}
+
+/// Ring that grows out from a shooter until its burst fires.
+#[derive(Component)]
+struct BurstTelegraph {
+    timer: Timer,
+}
+
+const TELEGRAPH_START_SCALE: f32 = 0.2;
+const TELEGRAPH_END_SCALE: f32 = 1.5;
+
+fn expand_telegraphs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut telegraphs: Query<(Entity, &mut BurstTelegraph, &mut Transform)>,
+) {
+    for (entity, mut telegraph, mut transform) in &mut telegraphs {
+        telegraph.timer.tick(time.delta());
+        let scale = TELEGRAPH_START_SCALE.lerp(TELEGRAPH_END_SCALE, telegraph.timer.fraction());
+        transform.scale = Vec3::splat(scale);
+        if telegraph.timer.is_finished() {
+            commands.entity(entity).despawn();
+        }
     }
 }
 
 fn spawn_projectile(
     commands: &mut Commands,
     assets: &ProjectileAssets,
+    shooter: Entity,
     pos: Vec3,
     velocity: Vec3,
     faction: Faction,
+    destructive: bool,
+    bounces: u32,
+    effect: ProjectileEffect,
 ) {
-    commands.spawn((
+    let material = match effect {
+        ProjectileEffect::Damage(_) => &assets.material,
+        ProjectileEffect::Heal(_) => &assets.heal_material,
+        ProjectileEffect::Slow(_) => &assets.slow_material,
+    };
+    let mut projectile = commands.spawn((
         Name::new("Enemy Projectile"),
         EnemyProjectile,
         faction,
         Projectile {
             velocity,
             lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+            ignore: Some(shooter),
+            bounces,
+            effect,
         },
         Mesh3d(assets.mesh.clone()),
-        MeshMaterial3d(assets.material.clone()),
+        MeshMaterial3d(material.clone()),
         Transform::from_translation(pos),
         RigidBody::Kinematic,
         Collider::sphere(0.1),
@@ -448,16 +1304,90 @@ fn spawn_projectile(
             [CollisionLayer::Character, CollisionLayer::Level],
         ),
     ));
+    if destructive {
+        projectile.insert(TerrainDestructive);
+    }
 }
 
+/// Max number of shape cast hits to sift through for something the projectile actually stops on.
+const PROJECTILE_SWEEP_MAX_HITS: u32 = 8;
+
+/// Sweeps projectiles along their velocity so fast ones can't tunnel through
+/// thin walls or the player between ticks. Projectiles with bounces left reflect off level
+/// geometry instead of stopping on it.
 fn move_projectiles(
     mut commands: Commands,
     time: Res<Time>,
-    mut projectiles: Query<(Entity, &mut Transform, &mut Projectile)>,
+    spatial_query: SpatialQuery,
+    mut projectiles: Query<(Entity, &mut Transform, &mut Projectile, &Collider, &Faction)>,
+    player: Option<Single<Entity, With<Player>>>,
+    targets: Query<Option<&Faction>, (With<Health>, Without<Player>)>,
+    layers: Query<&CollisionLayers>,
+    relations: Res<FactionRelations>,
 ) {
     let dt = time.delta_secs();
-    for (entity, mut transform, mut proj) in &mut projectiles {
-        transform.translation += proj.velocity * dt;
+    let player_entity = player.map(|p| *p);
+    // Props only stop projectiles when they can be damaged, like explosive barrels.
+    let filter = SpatialQueryFilter::from_mask([
+        CollisionLayer::Character,
+        CollisionLayer::Prop,
+        CollisionLayer::Level,
+    ]);
+
+    for (entity, mut transform, mut proj, collider, faction) in &mut projectiles {
+        let step = proj.velocity * dt;
+        let impact = Dir3::new(step).ok().and_then(|direction| {
+            let config = ShapeCastConfig {
+                ignore_origin_penetration: true,
+                ..ShapeCastConfig::from_max_distance(step.length())
+            };
+            let mut hits = spatial_query.shape_hits(
+                collider,
+                transform.translation,
+                transform.rotation,
+                direction,
+                PROJECTILE_SWEEP_MAX_HITS,
+                &config,
+                &filter,
+            );
+            hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+            hits.into_iter()
+                .find(|hit| {
+                    if proj.ignores(hit.entity) {
+                        false
+                    } else if Some(hit.entity) == player_entity {
+                        proj.effect.affects(faction, PLAYER_FACTION, &relations)
+                    } else if let Ok(target_faction) = targets.get(hit.entity) {
+                        let target_faction = Faction::name_or_enemy(target_faction);
+                        proj.effect.affects(faction, target_faction, &relations)
+                    } else {
+                        layers
+                            .get(hit.entity)
+                            .is_ok_and(|l| l.memberships.has_all(CollisionLayer::Level))
+                    }
+                })
+                .map(|hit| (direction, hit))
+        });
+
+        match impact {
+            Some((direction, hit)) => {
+                transform.translation += direction * hit.distance;
+                let level = !targets.contains(hit.entity)
+                    && layers
+                        .get(hit.entity)
+                        .is_ok_and(|l| l.memberships.has_all(CollisionLayer::Level));
+                if level && proj.bounces > 0 {
+                    proj.bounces -= 1;
+                    proj.velocity = reflect(proj.velocity, hit.normal1);
+                } else {
+                    commands
+                        .entity(entity)
+                        .insert(ProjectileImpact { entity: hit.entity });
+                }
+            }
+            None => transform.translation += step,
+        }
+
         proj.lifetime.tick(time.delta());
         if proj.lifetime.just_finished() {
             commands.entity(entity).despawn();
@@ -465,33 +1395,83 @@ fn move_projectiles(
     }
 }
 
+/// `velocity` mirrored about the surface with `normal`.
+fn reflect(velocity: Vec3, normal: Vec3) -> Vec3 {
+    velocity - 2.0 * velocity.dot(normal) * normal
+}
+
 fn projectile_hit_player(
     mut commands: Commands,
     spatial_query: SpatialQuery,
-    projectiles: Query<(Entity, &GlobalTransform, &Collider, &Faction), With<EnemyProjectile>>,
-    mut player: Query<(Entity, &mut PlayerHealth, Option<&Invincible>), With<Player>>,
+    projectiles: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &Faction,
+            &Projectile,
+            Option<&ProjectileImpact>,
+        ),
+        With<EnemyProjectile>,
+    >,
+    mut player: Query<
+        (
+            Entity,
+            &mut PlayerHealth,
+            Option<&Invincible>,
+            Option<&mut Slowed>,
+        ),
+        With<Player>,
+    >,
+    relations: Res<FactionRelations>,
 ) {
-    let Ok((player_entity, mut health, invincible)) = player.single_mut() else {
+    let Ok((player_entity, mut health, invincible, mut slowed)) = player.single_mut() else {
         return;
     };
 
-    let player_faction = Faction("player".to_string());
-
-    for (proj_entity, proj_transform, proj_collider, proj_faction) in &projectiles {
-        if !proj_faction.can_hurt(&player_faction) {
+    for (proj_entity, proj_transform, proj_collider, proj_faction, projectile, impact) in
+        &projectiles
+    {
+        if !projectile
+            .effect
+            .affects(proj_faction, PLAYER_FACTION, &relations)
+        {
             continue;
         }
 
-        let hits = spatial_query.shape_intersections(
-            proj_collider,
-            proj_transform.translation(),
-            proj_transform.to_isometry().rotation,
-            &SpatialQueryFilter::from_mask(CollisionLayer::Character),
-        );
+        // Swept hits were already resolved in `move_projectiles`, overlap is
+        // the fallback for projectiles that spawned inside something.
+        let hits = match impact {
+            Some(impact) => vec![impact.entity],
+            None => spatial_query.shape_intersections(
+                proj_collider,
+                proj_transform.translation(),
+                proj_transform.to_isometry().rotation,
+                &SpatialQueryFilter::from_mask(CollisionLayer::Character),
+            ),
+        };
 
         for hit_entity in &hits {
             if *hit_entity == player_entity {
-                hurt_player(&mut commands, player_entity, &mut health, invincible);
+                match projectile.effect {
+                    ProjectileEffect::Damage(_) => {
+                        // Back along the projectile's path, towards whoever fired it.
+                        let source = proj_transform.translation() - projectile.velocity;
+                        hurt_player(
+                            &mut commands,
+                            player_entity,
+                            &mut health,
+                            invincible,
+                            source,
+                        );
+                    }
+                    ProjectileEffect::Heal(_) => {
+                        health.current = (health.current + 1).min(health.max);
+                    }
+                    ProjectileEffect::Slow(seconds) => {
+                        slow(&mut commands, player_entity, seconds, slowed.take());
+                    }
+                }
                 commands.entity(proj_entity).despawn();
                 break;
             }
@@ -502,42 +1482,93 @@ fn projectile_hit_player(
 fn projectile_hit_npc(
     mut commands: Commands,
     spatial_query: SpatialQuery,
-    projectiles: Query<(Entity, &GlobalTransform, &Collider, &Faction), With<EnemyProjectile>>,
+    projectiles: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Collider,
+            &Faction,
+            &Projectile,
+            Option<&ProjectileImpact>,
+        ),
+        With<EnemyProjectile>,
+    >,
     player: Option<Single<Entity, With<Player>>>,
-    mut health_query: Query<(&mut Health, Option<&Faction>), Without<Player>>,
+    mut health_query: Query<
+        (
+            &mut Health,
+            Option<&MaxHealth>,
+            Option<&Faction>,
+            Has<ExplosiveBarrel>,
+            Option<&mut Slowed>,
+        ),
+        Without<Player>,
+    >,
+    relations: Res<FactionRelations>,
 ) {
     let player_entity = player.map(|p| *p);
 
-    for (proj_entity, proj_transform, proj_collider, proj_faction) in &projectiles {
+    for (proj_entity, proj_transform, proj_collider, proj_faction, projectile, impact) in
+        &projectiles
+    {
         if commands.get_entity(proj_entity).is_err() {
             continue;
         }
 
-        let hits = spatial_query.shape_intersections(
-            proj_collider,
-            proj_transform.translation(),
-            proj_transform.to_isometry().rotation,
-            &SpatialQueryFilter::from_mask(CollisionLayer::Character),
-        );
+        let hits = match impact {
+            Some(impact) => vec![impact.entity],
+            None => spatial_query.shape_intersections(
+                proj_collider,
+                proj_transform.translation(),
+                proj_transform.to_isometry().rotation,
+                &SpatialQueryFilter::from_mask(CollisionLayer::Character),
+            ),
+        };
 
         for hit_entity in &hits {
-            if player_entity == Some(*hit_entity) {
+            if player_entity == Some(*hit_entity) || projectile.ignores(*hit_entity) {
                 continue;
             }
 
-            let Ok((mut health, target_faction)) = health_query.get_mut(*hit_entity) else {
+            let Ok((mut health, max_health, target_faction, is_barrel, slowed)) =
+                health_query.get_mut(*hit_entity)
+            else {
                 continue;
             };
-            let target_faction = target_faction
-                .cloned()
-                .unwrap_or(Faction("enemy".to_string()));
-            if !proj_faction.can_hurt(&target_faction) {
+            let target_faction = Faction::name_or_enemy(target_faction);
+            if !projectile
+                .effect
+                .affects(proj_faction, target_faction, &relations)
+            {
                 continue;
             }
 
-            health.0 -= 10.0;
-            if health.0 <= 0.0 {
-                commands.entity(*hit_entity).insert(NpcDead);
+            match projectile.effect {
+                ProjectileEffect::Damage(amount) => {
+                    health.0 -= amount;
+                    if health.0 <= 0.0 && !is_barrel {
+                        commands.entity(*hit_entity).insert(NpcDead);
+                    }
+                }
+                // Corpses stay dead, and barrels aren't anyone's to patch up.
+                ProjectileEffect::Heal(amount) if health.0 > 0.0 && !is_barrel => {
+                    let max = max_health.map_or(f32::INFINITY, |max| max.0);
+                    health.0 = (health.0 + amount).min(max.max(health.0));
+                }
+                ProjectileEffect::Heal(_) => {}
+                ProjectileEffect::Slow(seconds) => {
+                    slow(&mut commands, *hit_entity, seconds, slowed);
+                }
+            }
+            // Projectiles only know their shooter through `ignore`.
+            if let (Some(shooter), false) = (
+                projectile.ignore,
+                matches!(projectile.effect, ProjectileEffect::Heal(_)),
+            ) {
+                commands.trigger(Provoked {
+                    entity: *hit_entity,
+                    attacker: shooter,
+                });
             }
             commands.entity(proj_entity).despawn();
             break;
@@ -545,21 +1576,644 @@ fn projectile_hit_npc(
     }
 }
 
+/// Refills at [`IMPACT_SOUNDS_PER_SECOND`], impacts past that are silent.
+struct ImpactSoundBudget(f32);
+
+impl Default for ImpactSoundBudget {
+    fn default() -> Self {
+        Self(IMPACT_SOUNDS_PER_SECOND)
+    }
+}
+
 fn projectile_hit_level(
     mut commands: Commands,
+    time: Res<Time>,
+    assets: Option<Res<ProjectileAssets>>,
+    mut sound_budget: Local<ImpactSoundBudget>,
     spatial_query: SpatialQuery,
-    projectiles: Query<(Entity, &GlobalTransform, &Collider), With<EnemyProjectile>>,
+    projectiles: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &Transform,
+            &Collider,
+            Option<&ProjectileImpact>,
+            Has<TerrainDestructive>,
+        ),
+        With<EnemyProjectile>,
+    >,
+    volumes: Query<&VoxelVolume, With<VoxelSim>>,
+    quality: Res<GraphicsQuality>,
 ) {
-    for (proj_entity, proj_transform, proj_collider) in &projectiles {
-        let hits = spatial_query.shape_intersections(
-            proj_collider,
-            proj_transform.translation(),
-            proj_transform.to_isometry().rotation,
-            &SpatialQueryFilter::from_mask(CollisionLayer::Level),
+    sound_budget.0 = (sound_budget.0 + time.delta_secs() * IMPACT_SOUNDS_PER_SECOND)
+        .min(IMPACT_SOUNDS_PER_SECOND);
+
+    for (proj_entity, proj_transform, transform, proj_collider, impact, destructive) in &projectiles
+    {
+        // Anything the sweep stopped on that the player/npc systems didn't
+        // consume is level geometry.
+        let hit = match impact {
+            Some(impact) => Some(impact.entity),
+            None => spatial_query
+                .shape_intersections(
+                    proj_collider,
+                    proj_transform.translation(),
+                    proj_transform.to_isometry().rotation,
+                    &SpatialQueryFilter::from_mask(CollisionLayer::Level),
+                )
+                .first()
+                .copied(),
+        };
+        let Some(hit) = hit else {
+            continue;
+        };
+
+        let volume = volumes.get(hit).ok();
+        if destructive && volume.is_some_and(|v| !v.protected) {
+            commands.trigger(ModifyVoxels {
+                volume: hit,
+                point: transform.translation,
+                radius: COVER_BUST_RADIUS,
+                voxel: Voxel::Air,
+            });
+        }
+
+        // The sweep already moved the projectile onto the impact point.
+        if let Some(assets) = &assets {
+            let effect = if volume.is_some() {
+                &assets.impact_dust
+            } else {
+                &assets.impact_sparks
+            };
+            if quality.particles() {
+                commands.spawn((
+                    ParticleEffect::new(effect.clone()),
+                    RenderLayers::from(RenderLayer::DEFAULT),
+                    Transform::from_translation(transform.translation),
+                ));
+            }
+
+            if sound_budget.0 >= 1.0 {
+                sound_budget.0 -= 1.0;
+                commands.spawn((
+                    SamplePlayer::new(assets.impact_sound.clone())
+                        .with_volume(Volume::Linear(IMPACT_VOLUME)),
+                    PlaybackSettings {
+                        speed: rand::rng().random_range(IMPACT_PITCH_RANGE),
+                        ..default()
+                    },
+                    SpatialPool,
+                    Transform::from_translation(transform.translation),
+                ));
+            }
+        }
+        commands.entity(proj_entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{
+        Pause, game_running,
+        gameplay::inventory::{DigCooldown, GunCooldown, tick_tool_cooldowns},
+        pause_virtual_time,
+        screens::Screen,
+        unpause_virtual_time,
+    };
+
+    const TICK: Duration = Duration::from_micros(15_625); // 64hz
+
+    /// Headless app stepping physics a fixed tick per update, for tests to add their systems to.
+    fn physics_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            PhysicsPlugins::default(),
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(Time::<Fixed>::from_duration(TICK))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TICK))
+        .init_resource::<FactionRelations>();
+        app
+    }
+
+    #[test]
+    fn fast_projectile_hits_thin_wall() {
+        let mut app = physics_test_app();
+        app.add_systems(
+            FixedUpdate,
+            (move_projectiles, projectile_hit_level).chain(),
         );
 
-        if !hits.is_empty() {
-            commands.entity(proj_entity).despawn();
+        // 5cm thick wall, way thinner than the ~0.94 units a speed 60 projectile covers in a tick.
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(0.05, 4.0, 4.0),
+            CollisionLayers::new(CollisionLayer::Level, LayerMask::ALL),
+            Transform::from_xyz(2.0, 0.0, 0.0),
+        ));
+        let projectile = app
+            .world_mut()
+            .spawn((
+                EnemyProjectile,
+                Faction("enemy".to_string()),
+                Projectile {
+                    velocity: Vec3::X * 60.0,
+                    lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                    ignore: None,
+                    bounces: 0,
+                    effect: ProjectileEffect::default(),
+                },
+                Transform::default(),
+                RigidBody::Kinematic,
+                Collider::sphere(0.1),
+                Sensor,
+                CollisionLayers::new(
+                    CollisionLayer::Projectile,
+                    [CollisionLayer::Character, CollisionLayer::Level],
+                ),
+            ))
+            .id();
+
+        for _ in 0..8 {
+            app.update();
+            let Some(transform) = app.world().get::<Transform>(projectile) else {
+                break;
+            };
+            assert!(
+                transform.translation.x < 2.0,
+                "projectile tunneled through the wall to {}",
+                transform.translation
+            );
+        }
+
+        assert!(
+            app.world().get_entity(projectile).is_err(),
+            "projectile should have been despawned by the wall"
+        );
+    }
+
+    #[test]
+    fn projectile_with_bounces_reflects_off_wall() {
+        let mut app = physics_test_app();
+        app.add_systems(
+            FixedUpdate,
+            (move_projectiles, projectile_hit_level).chain(),
+        );
+
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(0.05, 4.0, 4.0),
+            CollisionLayers::new(CollisionLayer::Level, LayerMask::ALL),
+            Transform::from_xyz(2.0, 0.0, 0.0),
+        ));
+        let projectile = app
+            .world_mut()
+            .spawn((
+                EnemyProjectile,
+                Faction("enemy".to_string()),
+                Projectile {
+                    velocity: Vec3::X * 60.0,
+                    lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                    ignore: None,
+                    bounces: 1,
+                    effect: ProjectileEffect::default(),
+                },
+                Transform::default(),
+                RigidBody::Kinematic,
+                Collider::sphere(0.1),
+                Sensor,
+                CollisionLayers::new(
+                    CollisionLayer::Projectile,
+                    [CollisionLayer::Character, CollisionLayer::Level],
+                ),
+            ))
+            .id();
+
+        for _ in 0..8 {
+            app.update();
         }
+
+        let transform = app
+            .world()
+            .get::<Transform>(projectile)
+            .expect("projectile should survive its first bounce");
+        assert!(
+            transform.translation.x < 2.0,
+            "projectile went through the wall to {}",
+            transform.translation
+        );
+        let proj = app.world().get::<Projectile>(projectile).unwrap();
+        assert!(proj.velocity.x < 0.0, "projectile should head back");
+        assert_eq!(proj.bounces, 0);
+    }
+
+    #[test]
+    fn spawn_alerted_enemy_fires_without_line_of_sight() {
+        let mut app = physics_test_app();
+        app.init_resource::<TagIndex>()
+            .init_resource::<ProjectileAssets>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    resolve_aggro_targets,
+                    alert_spawned_enemies,
+                    enemy_detection,
+                    npc_shoot,
+                )
+                    .chain(),
+            );
+
+        // Behind the enemy's back and a wall, so it never gets a look at the player.
+        app.world_mut().spawn((
+            RigidBody::Static,
+            Collider::cuboid(4.0, 4.0, 0.5),
+            CollisionLayers::new(CollisionLayer::Level, LayerMask::ALL),
+            Transform::from_xyz(0.0, 0.0, 3.0),
+        ));
+        app.world_mut()
+            .spawn((Player, Transform::from_xyz(0.0, 0.0, 6.0)));
+        let enemy = app
+            .world_mut()
+            .spawn((
+                NpcShooter::default(),
+                AggroConfig {
+                    target_tag: String::new(),
+                    aggro_radius: 0.0,
+                    swapped_to_player: false,
+                    deescalate_after: 0.0,
+                    unseen: 0.0,
+                },
+                NpcAggro,
+                SpawnAlerted,
+                Transform::default(),
+            ))
+            .id();
+
+        let mut projectiles = app
+            .world_mut()
+            .query_filtered::<(), With<EnemyProjectile>>();
+        let ticks = (2.0 / TICK.as_secs_f32()) as u32;
+        let first_volley = (1..=ticks).find(|_| {
+            app.update();
+            projectiles.iter(app.world()).next().is_some()
+        });
+
+        let first_volley = first_volley.expect("alerted enemy should fire within two seconds");
+        assert!(first_volley > 4, "first volley shouldn't be instant");
+        let alert = app.world().get::<EnemyAlert>(enemy).unwrap();
+        assert!(
+            alert.lost_sight(),
+            "enemy should never have seen the player"
+        );
+        assert!(app.world().get::<SpawnAlerted>(enemy).is_none());
+    }
+
+    #[test]
+    fn projectile_passes_through_its_shooter() {
+        let mut app = physics_test_app();
+        app.add_systems(FixedUpdate, (move_projectiles, projectile_hit_npc).chain());
+
+        // A faction that can hurt itself, so only the grace keeps the shooter safe.
+        let faction = || Faction("lobster".to_string());
+        let mut spawn_target = |position: Vec3| {
+            app.world_mut()
+                .spawn((
+                    Health(100.0),
+                    faction(),
+                    RigidBody::Static,
+                    Collider::sphere(0.5),
+                    CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
+                    Transform::from_translation(position),
+                ))
+                .id()
+        };
+        let shooter = spawn_target(Vec3::ZERO);
+        let bystander = spawn_target(Vec3::Z * 10.0);
+
+        // Both start on the edge of a target and fly through it.
+        for (position, velocity, ignore) in [
+            (Vec3::ZERO, Vec3::X * 4.0, Some(shooter)),
+            (Vec3::Z * 10.5, Vec3::NEG_Z * 4.0, None),
+        ] {
+            app.world_mut().spawn((
+                EnemyProjectile,
+                faction(),
+                Projectile {
+                    velocity,
+                    lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                    ignore,
+                    bounces: 0,
+                    effect: ProjectileEffect::default(),
+                },
+                Transform::from_translation(position),
+                RigidBody::Kinematic,
+                Collider::sphere(0.1),
+                Sensor,
+            ));
+        }
+
+        for _ in 0..4 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world().get::<Health>(shooter).unwrap().0,
+            100.0,
+            "projectile hit its own shooter"
+        );
+        assert!(
+            app.world().get::<Health>(bystander).unwrap().0 < 100.0,
+            "projectile without a shooter to ignore should hit"
+        );
+    }
+
+    #[test]
+    fn heals_stop_at_max_health_and_skip_barrels() {
+        let mut app = physics_test_app();
+        app.add_systems(FixedUpdate, (move_projectiles, projectile_hit_npc).chain());
+
+        let faction = || Faction("lobster".to_string());
+        let mut spawn_target = |position: Vec3, barrel: bool| {
+            let mut target = app.world_mut().spawn((
+                Health(100.0),
+                faction(),
+                RigidBody::Static,
+                Collider::sphere(0.5),
+                CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
+                Transform::from_translation(position),
+            ));
+            if barrel {
+                target.insert(ExplosiveBarrel);
+            }
+            target.get_mut::<Health>().unwrap().0 = 90.0;
+            target.id()
+        };
+        let ally = spawn_target(Vec3::ZERO, false);
+        let barrel = spawn_target(Vec3::Z * 10.0, true);
+
+        for position in [Vec3::NEG_X * 0.5, Vec3::Z * 10.0 + Vec3::NEG_X * 0.5] {
+            app.world_mut().spawn((
+                EnemyProjectile,
+                faction(),
+                Projectile {
+                    velocity: Vec3::X * 4.0,
+                    lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                    ignore: None,
+                    bounces: 0,
+                    effect: ProjectileEffect::Heal(50.0),
+                },
+                Transform::from_translation(position),
+                RigidBody::Kinematic,
+                Collider::sphere(0.1),
+                Sensor,
+            ));
+        }
+
+        for _ in 0..4 {
+            app.update();
+        }
+
+        assert_eq!(app.world().get::<Health>(ally).unwrap().0, 100.0);
+        assert_eq!(app.world().get::<Health>(barrel).unwrap().0, 90.0);
+    }
+
+    #[test]
+    fn pause_freezes_projectiles_and_cooldowns() {
+        let mut app = physics_test_app();
+        app.add_plugins(StatesPlugin)
+            .init_resource::<DigCooldown>()
+            .init_resource::<GunCooldown>()
+            .insert_state(Screen::Gameplay)
+            .init_state::<Pause>()
+            .add_systems(OnEnter(Pause(true)), pause_virtual_time)
+            .add_systems(OnExit(Pause(true)), unpause_virtual_time)
+            .add_systems(Update, tick_tool_cooldowns.run_if(game_running))
+            .add_systems(FixedUpdate, move_projectiles.run_if(game_running));
+
+        let projectile = app
+            .world_mut()
+            .spawn((
+                EnemyProjectile,
+                Faction("enemy".to_string()),
+                Projectile {
+                    velocity: Vec3::X,
+                    lifetime: Timer::from_seconds(PROJECTILE_LIFETIME, TimerMode::Once),
+                    ignore: None,
+                    bounces: 0,
+                    effect: ProjectileEffect::default(),
+                },
+                Transform::default(),
+                RigidBody::Kinematic,
+                Collider::sphere(0.1),
+                Sensor,
+            ))
+            .id();
+        // Start a dig cooldown like `use_tool` does.
+        {
+            let mut cooldown = app.world_mut().resource_mut::<DigCooldown>();
+            cooldown.timer.reset();
+            cooldown.ready = false;
+        }
+
+        for _ in 0..4 {
+            app.update();
+        }
+        app.world_mut()
+            .resource_mut::<NextState<Pause>>()
+            .set(Pause(true));
+        app.update();
+
+        let paused_at = app
+            .world()
+            .get::<Transform>(projectile)
+            .unwrap()
+            .translation;
+        let paused_elapsed = app.world().resource::<DigCooldown>().timer.elapsed();
+        assert!(
+            paused_at.x > 0.0,
+            "projectile should have moved before pausing"
+        );
+
+        for _ in 0..64 {
+            app.update();
+        }
+        assert_eq!(
+            app.world()
+                .get::<Transform>(projectile)
+                .unwrap()
+                .translation,
+            paused_at,
+            "projectile moved while paused"
+        );
+        let cooldown = app.world().resource::<DigCooldown>();
+        assert_eq!(cooldown.timer.elapsed(), paused_elapsed);
+        assert!(!cooldown.ready, "dig cooldown elapsed while paused");
+
+        app.world_mut()
+            .resource_mut::<NextState<Pause>>()
+            .set(Pause(false));
+        for _ in 0..4 {
+            app.update();
+        }
+        assert!(
+            app.world()
+                .get::<Transform>(projectile)
+                .unwrap()
+                .translation
+                .x
+                > paused_at.x,
+            "projectile should move again after unpausing"
+        );
+    }
+
+    #[test]
+    fn projectile_effects_parse_with_defaults() {
+        assert_eq!(
+            ProjectileEffect::from_stats("", 0.0),
+            ProjectileEffect::default()
+        );
+        assert_eq!(
+            ProjectileEffect::from_stats("heal", 25.0),
+            ProjectileEffect::Heal(25.0)
+        );
+        assert_eq!(
+            ProjectileEffect::from_stats(" slow ", 0.0),
+            ProjectileEffect::Slow(SLOW_SECONDS)
+        );
+        assert_eq!(
+            ProjectileEffect::from_stats("freeze", 5.0),
+            ProjectileEffect::Damage(5.0)
+        );
+    }
+
+    #[test]
+    fn radial_burst_spaces_shots_evenly_around() {
+        let directions = radial_directions(8);
+        assert_eq!(directions.len(), 8);
+        assert!(directions[0].abs_diff_eq(Vec3::X, 1e-5));
+        for (i, dir) in directions.iter().enumerate() {
+            assert!((dir.length() - 1.0).abs() < 1e-5);
+            assert_eq!(dir.y, 0.0);
+            let next = directions[(i + 1) % directions.len()];
+            assert!((dir.angle_between(next) - TAU / 8.0).abs() < 1e-4);
+        }
+        assert!(radial_directions(0).is_empty());
+    }
+
+    #[test]
+    fn spread_is_symmetric_about_the_aim() {
+        let forward = Vec3::new(0.0, 0.0, -2.0);
+        let directions = spread_directions(forward, 5, SPREAD_HALF_ANGLE);
+        assert_eq!(directions.len(), 5);
+        let aim = Vec3::NEG_Z;
+        // Middle shot straight at the target, the rest mirrored in equal steps out to the edge.
+        assert!(directions[2].abs_diff_eq(aim, 1e-5));
+        for i in 0..2 {
+            let left = directions[i].angle_between(aim);
+            let right = directions[4 - i].angle_between(aim);
+            assert!((left - right).abs() < 1e-5);
+            assert!(directions[i].x * directions[4 - i].x < 0.0);
+        }
+        assert!((directions[0].angle_between(aim) - SPREAD_HALF_ANGLE).abs() < 1e-4);
+        assert!((directions[1].angle_between(aim) - SPREAD_HALF_ANGLE / 2.0).abs() < 1e-4);
+
+        assert_eq!(spread_directions(forward, 1, SPREAD_HALF_ANGLE).len(), 1);
+        assert!(spread_directions(Vec3::Y, 5, SPREAD_HALF_ANGLE).is_empty());
+    }
+
+    #[test]
+    fn spread_pitches_towards_the_target_up_to_a_limit() {
+        let below = Vec3::new(0.0, -3.0, -4.0);
+        let directions = spread_directions(below, 5, SPREAD_HALF_ANGLE);
+        assert!(directions[2].abs_diff_eq(below.normalize(), 1e-5));
+        // The edges keep the same angle from the aim as on flat ground.
+        let aim = directions[2];
+        assert!((directions[0].angle_between(aim) - SPREAD_HALF_ANGLE).abs() < 1e-4);
+        assert!((directions[4].angle_between(aim) - SPREAD_HALF_ANGLE).abs() < 1e-4);
+
+        let steep = spread_directions(Vec3::new(0.0, 10.0, -1.0), 1, SPREAD_HALF_ANGLE);
+        assert!((steep[0].y - MAX_AIM_PITCH.sin()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn tilted_ring_reaches_the_targets_height() {
+        let to_target = Vec3::new(4.0, 3.0, 0.0);
+        let ring = tilt_toward(radial_directions(8), to_target);
+        // The first shot heads along +X, straight at the target.
+        assert!(ring[0].abs_diff_eq(to_target.normalize(), 1e-5));
+        // The far side dips, the sides stay level.
+        assert!(ring[4].y < 0.0);
+        assert!(ring[2].y.abs() < 1e-5);
+        assert!(ring[6].y.abs() < 1e-5);
+        assert_eq!(
+            tilt_toward(radial_directions(8), Vec3::Y),
+            radial_directions(8)
+        );
+    }
+
+    #[test]
+    fn detection_cone_is_wider_than_it_is_tall() {
+        let forward = Vec3::NEG_Z;
+        assert!(in_detection_cone(forward, Vec3::new(0.0, -3.0, -5.0)));
+        assert!(in_detection_cone(forward, Vec3::new(-5.0, 0.0, -5.0)));
+        // 50° to the side is in, 50° down isn't.
+        let angle = 50f32.to_radians();
+        assert!(in_detection_cone(
+            forward,
+            Vec3::new(angle.sin(), 0.0, -angle.cos())
+        ));
+        assert!(!in_detection_cone(
+            forward,
+            Vec3::new(0.0, -angle.sin(), -angle.cos())
+        ));
+        assert!(!in_detection_cone(forward, Vec3::new(0.0, -3.0, 5.0)));
+        assert!(!in_detection_cone(forward, Vec3::NEG_Y));
+    }
+
+    #[test]
+    fn spread_volley_aims_down_at_a_player_in_a_pit() {
+        let mut app = physics_test_app();
+        app.init_resource::<ProjectileAssets>()
+            .add_systems(FixedUpdate, (enemy_detection, npc_shoot).chain());
+
+        // 3m down and a few ahead of where the enemy faces.
+        let player = Vec3::new(0.0, -3.0, -5.0);
+        app.world_mut()
+            .spawn((Player, Transform::from_translation(player)));
+        app.world_mut().spawn((
+            NpcShooter {
+                pattern: FiringPattern::AimedSpread,
+                projectile_count: 3,
+                ..default()
+            },
+            NpcAggro,
+            Transform::default(),
+        ));
+
+        let mut projectiles = app.world_mut().query::<&Projectile>();
+        let ticks = (3.0 / TICK.as_secs_f32()) as u32;
+        let fired = (1..=ticks).any(|_| {
+            app.update();
+            projectiles.iter(app.world()).next().is_some()
+        });
+        assert!(fired, "enemy should see the player in the pit and fire");
+
+        let expected =
+            (player - Vec3::Y * GUN_HEIGHT).normalize() * NpcShooter::default().projectile_speed;
+        assert!(expected.y < 0.0);
+        assert!(
+            projectiles
+                .iter(app.world())
+                .any(|projectile| projectile.velocity.abs_diff_eq(expected, 1e-3)),
+            "the middle shot should head down at the player"
+        );
     }
 }
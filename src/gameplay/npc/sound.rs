@@ -1,7 +1,9 @@
 //! NPC sound handling. The only sound is a step sound that plays when the NPC is walking.
 
 use super::{Npc, assets::NpcAssets};
-use crate::{PostPhysicsAppSystems, audio::SpatialPool, screens::Screen};
+use crate::{
+    PostPhysicsAppSystems, asset_tracking::PickLoaded as _, audio::SpatialPool, screens::Screen,
+};
 use avian3d::prelude::LinearVelocity;
 use bevy::prelude::*;
 use bevy_ahoy::CharacterControllerState;
@@ -22,6 +24,7 @@ fn play_step_sound(
     mut commands: Commands,
     npc: Single<(Entity, &CharacterControllerState, &LinearVelocity), With<Npc>>,
     mut npc_assets: ResMut<NpcAssets>,
+    asset_server: Res<AssetServer>,
     time: Res<Time>,
     mut timer: Local<Option<Timer>>,
 ) {
@@ -47,7 +50,9 @@ fn play_step_sound(
     let factor = 1.0 - (speed - speed_to_half_duration) / speed_to_half_duration;
     timer.set_duration(Duration::from_millis((base_millis as f32 * factor) as u64));
     let rng = &mut rand::rng();
-    let sound_effect = npc_assets.steps.pick(rng).clone();
+    let Some(sound_effect) = npc_assets.steps.pick_loaded(rng, &asset_server) else {
+        return;
+    };
 
     commands.entity(entity).with_child((
         Transform::default(),
@@ -0,0 +1,92 @@
+//! Global threat that ramps up over a sandbox run, making newly spawned enemies tougher. The
+//! story and tutorial stay at the level they start at.
+
+use bevy::prelude::*;
+
+use super::{EnemyGunner, NpcDead};
+use crate::{
+    game_running,
+    gameplay::{
+        game_mode::{GameMode, sandbox_active},
+        save_slot::NewGamePlus,
+    },
+    screens::Screen,
+};
+
+/// Threat gained per second of gameplay.
+const THREAT_PER_SECOND: f32 = 1.0 / 60.0;
+/// Threat gained per enemy killed.
+const THREAT_PER_KILL: f32 = 0.25;
+const MAX_THREAT: f32 = 10.0;
+
+/// Extra health per point of threat, as a fraction of base health.
+const HEALTH_PER_THREAT: f32 = 0.15;
+/// Extra shots per point of threat, as a fraction of base fire rate.
+const FIRE_RATE_PER_THREAT: f32 = 0.08;
+/// Extra projectiles per point of threat, as a fraction of the base count.
+const PROJECTILES_PER_THREAT: f32 = 0.1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Threat>();
+    app.add_observer(on_enemy_killed);
+    app.add_systems(OnEnter(Screen::Gameplay), reset_threat);
+    app.add_systems(Update, ramp_threat.run_if(game_running.and(sandbox_active)));
+}
+
+/// Only read when an enemy spawns, so already spawned enemies keep their stats.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct Threat {
+    pub level: f32,
+    /// Scales enemy health and fire rate on top of the threat level, raised by new game plus.
+    pub difficulty: f32,
+}
+
+impl Default for Threat {
+    fn default() -> Self {
+        Self {
+            level: 0.0,
+            difficulty: 1.0,
+        }
+    }
+}
+
+impl Threat {
+    pub fn raise(&mut self, amount: f32) {
+        self.level = (self.level + amount).min(MAX_THREAT);
+    }
+
+    pub fn health_multiplier(&self) -> f32 {
+        (1.0 + self.level * HEALTH_PER_THREAT) * self.difficulty
+    }
+
+    pub fn fire_rate_multiplier(&self) -> f32 {
+        (1.0 + self.level * FIRE_RATE_PER_THREAT) * self.difficulty
+    }
+
+    pub fn projectile_multiplier(&self) -> f32 {
+        1.0 + self.level * PROJECTILES_PER_THREAT
+    }
+}
+
+fn reset_threat(mut threat: ResMut<Threat>, new_game_plus: Res<NewGamePlus>) {
+    *threat = Threat {
+        difficulty: new_game_plus.difficulty_multiplier(),
+        ..default()
+    };
+}
+
+fn ramp_threat(time: Res<Time>, mut threat: ResMut<Threat>) {
+    threat.raise(time.delta_secs() * THREAT_PER_SECOND);
+}
+
+fn on_enemy_killed(
+    add: On<Add, NpcDead>,
+    enemies: Query<(), With<EnemyGunner>>,
+    mut threat: ResMut<Threat>,
+    mode: Res<GameMode>,
+) {
+    if *mode == GameMode::Sandbox && enemies.contains(add.entity) {
+        threat.raise(THREAT_PER_KILL);
+    }
+}
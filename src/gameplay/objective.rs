@@ -1,30 +1,65 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use bevy::ecs::system::IntoSystem;
 use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_seedling::sample::SamplePlayer;
 use bevy_yarnspinner::prelude::*;
-
-use super::crusts::HudTopLeft;
-use super::dig::{VoxelGraves, VoxelSim};
+use bincode::{Decode, Encode};
+
+use super::crusts::{Crusts, CrustsRewarded};
+use super::dig::{FillVoxelVolumes, VoxelGraves, VoxelSim};
+use super::environment::LightMood;
+use super::hud::{HudElement, HudSettings, ShowHint, ToggleObjectives};
+use crate::audio::MusicPool;
+use crate::gameplay::game_mode::story_active;
 use crate::gameplay::grave::{GraveState, Slotted, SpawnBody, GRAVE_FILL_THRESHOLD};
+use crate::gameplay::loadout::GiveItem;
+use crate::gameplay::npc::dialogue_state::DialogueState;
 use crate::gameplay::npc::{Health, NpcDead, SpawnEnemy, SpawnNpc};
 use crate::gameplay::sensor_area::player_in_sensor;
+use crate::gameplay::session::resuming_session;
 use crate::gameplay::tags::Tags;
 use crate::props::specific::light::FlickerLight;
 use crate::screens::Screen;
 use crate::theme::GameFont;
-use crate::third_party::bevy_yarnspinner::YarnNode;
+
+/// Default rate of progress hooks, fast enough that counts still look immediate.
+const PROGRESS_HOOK_HZ: f32 = 10.0;
 
 pub fn plugin(app: &mut App) {
     app.init_resource::<Objectives>();
-    app.add_observer(spawn_objectives_ui);
+    app.init_resource::<ObjectivePanelToggle>();
+    app.init_resource::<ProgressHookRate>();
+    app.insert_resource(ProgressHookTimer(Timer::from_seconds(
+        1.0 / PROGRESS_HOOK_HZ,
+        TimerMode::Repeating,
+    )));
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_objectives_ui.run_if(story_active),
+    );
+    app.add_observer(on_skip_tutorial);
+    app.add_observer(press_toggle_objectives);
+    app.add_observer(release_toggle_objectives);
     app.add_systems(
         Update,
         (
             register_objective_command,
-            run_progress_hooks.run_if(in_state(Screen::Gameplay)),
-            update_objective_ui.run_if(resource_changed::<Objectives>),
+            run_progress_hooks.run_if(
+                in_state(Screen::Gameplay)
+                    .and(story_active)
+                    .and(not(resuming_session)),
+            ),
+            rebuild_objective_panel.run_if(resource_changed::<Objectives>),
+            update_objective_ui
+                .run_if(resource_changed::<Objectives>.or(resource_changed::<HudSettings>)),
             animate_objective_completion,
+            update_objective_title.run_if(
+                resource_changed::<Objectives>.or(resource_changed::<ObjectivePanelToggle>),
+            ),
+            slide_objective_body,
         ),
     );
 }
@@ -55,6 +90,209 @@ impl Objectives {
             obj.complete(sub_id);
         }
     }
+
+    /// Adds `amount` to the sub-objective `sub_id`, whichever objective it's in, for progress
+    /// that's counted as it happens instead of by a hook. A hook on the same sub-objective
+    /// would overwrite it. Sub-objectives that haven't started yet or are already done are
+    /// left alone, and so is an unknown id. Returns whether the progress changed.
+    pub fn add_progress(&mut self, sub_id: &str, amount: u32) -> bool {
+        let Some(item) = self
+            .objectives
+            .values_mut()
+            .flat_map(|objective| &mut objective.items)
+            .find(|item| item.id == sub_id)
+        else {
+            return false;
+        };
+        if !item.started || item.completed || amount == 0 {
+            return false;
+        }
+        match &mut item.target {
+            ObjectiveTarget::Binary { done } => *done = true,
+            ObjectiveTarget::Tracked { current, .. } => *current = current.saturating_add(amount),
+        }
+        item.completed = item.target.is_complete();
+        info!("Objective '{}': {}", item.id, item.target.debug_value());
+        true
+    }
+
+    /// The story is over: the active objective is the last one and has nothing left to do.
+    pub fn all_complete(&self) -> bool {
+        self.active()
+            .is_some_and(|obj| obj.next.is_none() && obj.current_item().is_none())
+    }
+
+    /// Whether the objective or sub-objective `id` is done, `None` if there's no such id.
+    pub fn is_done(&self, id: &str) -> Option<bool> {
+        if let Some(objective) = self.objectives.get(id) {
+            return Some(objective.items.iter().all(|item| item.completed));
+        }
+        self.objectives
+            .values()
+            .flat_map(|objective| &objective.items)
+            .find(|item| item.id == id)
+            .map(|item| item.completed)
+    }
+
+    /// The title of the objective `id`, or the label of the sub-objective `id`.
+    pub fn title_for(&self, id: &str) -> Option<&str> {
+        if let Some(objective) = self.objectives.get(id) {
+            return Some(&objective.title);
+        }
+        self.objectives
+            .values()
+            .flat_map(|objective| &objective.items)
+            .find(|item| item.id == id)
+            .map(|item| item.label.as_str())
+    }
+
+    /// Where every objective has got to, without the hooks.
+    pub fn progress(&self) -> ObjectiveProgress {
+        let mut progress = ObjectiveProgress {
+            active: self.active.clone(),
+            ..default()
+        };
+        for objective in self.objectives.values() {
+            progress
+                .current
+                .push((objective.id.clone(), objective.current as u32));
+            for item in &objective.items {
+                let value = match item.target {
+                    ObjectiveTarget::Binary { done } => done as u32,
+                    ObjectiveTarget::Tracked { current, .. } => current,
+                };
+                progress.items.push(SubObjectiveProgress {
+                    id: item.id.clone(),
+                    started: item.started,
+                    completed: item.completed,
+                    value,
+                });
+            }
+        }
+        progress
+    }
+
+    /// Puts back progress from [`Objectives::progress`] without running any hooks, so started
+    /// sub-objectives don't spawn their encounters again. Unknown ids are skipped.
+    pub fn restore_progress(&mut self, progress: &ObjectiveProgress) {
+        if self.objectives.contains_key(&progress.active) {
+            self.active = progress.active.clone();
+        }
+        for (id, current) in &progress.current {
+            if let Some(objective) = self.objectives.get_mut(id) {
+                objective.current = (*current as usize).min(objective.items.len());
+            }
+        }
+        let items = self
+            .objectives
+            .values_mut()
+            .flat_map(|objective| &mut objective.items);
+        for item in items {
+            let Some(saved) = progress.items.iter().find(|saved| saved.id == item.id) else {
+                continue;
+            };
+            item.started = saved.started;
+            item.completed = saved.completed;
+            match &mut item.target {
+                ObjectiveTarget::Binary { done } => *done = saved.value > 0,
+                ObjectiveTarget::Tracked { current, .. } => *current = saved.value,
+            }
+        }
+    }
+
+    /// Marks every remaining sub-objective of `objective_id` complete, in order,
+    /// and then moves on to its `next` objective if it was the active one.
+    ///
+    /// Sub-objectives that already started won't have their `on_start` hooks re-run,
+    /// and ones that already completed won't have their `on_complete` hooks re-run.
+    pub fn force_complete_all(world: &mut World, objective_id: &str, run_hooks: HookPolicy) {
+        let Some(mut objectives) = world.remove_resource::<Objectives>() else {
+            warn!("Objectives resource missing, can't force complete '{objective_id}'");
+            return;
+        };
+
+        let Some(objective) = objectives.objectives.get_mut(objective_id) else {
+            warn!("Objective '{objective_id}' not found");
+            world.insert_resource(objectives);
+            return;
+        };
+
+        let start = objective.current;
+        for item in objective.items.iter_mut().skip(start) {
+            if !item.started {
+                item.started = true;
+                if run_hooks == HookPolicy::All {
+                    info!("Objective '{}' started", item.id);
+                    for hook in &mut item.on_start_hooks {
+                        hook(world);
+                    }
+                }
+            }
+
+            match &mut item.target {
+                ObjectiveTarget::Binary { done } => *done = true,
+                ObjectiveTarget::Tracked { current, target } => *current = *target,
+            }
+            item.completed = true;
+
+            info!("Objective '{}' force completed", item.id);
+            if run_hooks != HookPolicy::None {
+                for hook in &mut item.on_complete_hooks {
+                    hook(world);
+                }
+            }
+            world.trigger(SubObjectiveCompleted {
+                objective: objective_id.to_string(),
+                sub_objective: item.id.clone(),
+                forced: true,
+            });
+        }
+        objective.current = objective.items.len();
+        if start < objective.items.len() {
+            world.trigger(ObjectiveCompleted {
+                objective: objective_id.to_string(),
+            });
+        }
+
+        let next = objective.next.clone();
+        if objectives.active == objective_id {
+            if let Some(next) = next {
+                info!("Objective '{objective_id}' done, switching to '{next}'");
+                objectives.active = next;
+            }
+        }
+
+        world.insert_resource(objectives);
+    }
+}
+
+/// Objective progress from [`Objectives::progress`], for picking a session back up.
+#[derive(Encode, Decode, Clone, Default, Debug)]
+pub(crate) struct ObjectiveProgress {
+    active: String,
+    /// The `current` sub-objective of each objective, by objective id.
+    current: Vec<(String, u32)>,
+    items: Vec<SubObjectiveProgress>,
+}
+
+#[derive(Encode, Decode, Clone, Debug)]
+struct SubObjectiveProgress {
+    id: String,
+    started: bool,
+    completed: bool,
+    /// `current` of a tracked target, 1 for a done binary one.
+    value: u32,
+}
+
+/// Which lifecycle hooks [`Objectives::force_complete_all`] should still run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum HookPolicy {
+    /// Run both `on_start` and `on_complete` hooks.
+    All,
+    /// Only run `on_complete` hooks, so intermediate steps don't spawn their encounters.
+    CompleteOnly,
+    /// Don't run any hooks, just mark everything done.
+    None,
 }
 
 impl Default for Objectives {
@@ -66,13 +304,19 @@ impl Default for Objectives {
                 id: "the_molt".to_string(),
                 title: "The Molt".to_string(),
                 current: 0,
+                next: Some("the_job".to_string()),
                 items: vec![
-                    SubObjective::tracked("dig_3", "dig 3 graves", 3)
+                    SubObjective::tracked(TUTORIAL_DIG_GRAVES, "dig 3 graves", 3)
+                        .on_start(|mut commands: Commands| {
+                            commands.trigger(ShowHint::new(
+                                "Can't find a grave? Press R with the shovel out to probe the ground",
+                            ));
+                        })
                         .hook(|voxels: Query<(&VoxelSim, &Tags)>| -> u32 {
                             voxels
                                 .iter()
                                 .filter(|(sim, tags)| {
-                                    tags.contains("tutorial") && sim.air_ratio() >= 0.8
+                                    tags.contains(TUTORIAL_TAG) && sim.air_ratio() >= 0.8
                                 })
                                 .count() as u32
                         })
@@ -83,29 +327,15 @@ impl Default for Objectives {
                                 });
                             }
                         })
-                        .on_complete(|mut yarn_nodes: Query<(&Tags, &mut YarnNode)>| {
-                            for (tags, mut node) in &mut yarn_nodes {
-                                if !tags.contains("larry") {
-                                    continue;
-                                }
-                                node.yarn_node = "3_Dug".to_string();
-                            }
-                        }),
+                        .on_complete(push_larry_dialogue("after_dig_3", "3_Dug")),
                     SubObjective::tracked("body_3", "put bodies in the graves", 3)
                         .hook(|graves: Query<(&GraveState, &Tags)>| -> u32 {
                             graves
                                 .iter()
-                                .filter(|(grave, tags)| tags.contains("tutorial") && grave.filled())
+                                .filter(|(grave, tags)| tags.contains(TUTORIAL_TAG) && grave.filled())
                                 .count() as u32
                         })
-                        .on_complete(|mut yarn_nodes: Query<(&Tags, &mut YarnNode)>| {
-                            for (tags, mut node) in &mut yarn_nodes {
-                                if !tags.contains("larry") {
-                                    continue;
-                                }
-                                node.yarn_node = "3_Slotted".to_string();
-                            }
-                        }),
+                        .on_complete(push_larry_dialogue("after_body_3", "3_Slotted")),
                     SubObjective::tracked("dirt_3", "put dirt in the graves", 3)
                         .hook(
                             |voxels: Query<(&VoxelSim, &Tags, &VoxelGraves)>,
@@ -114,7 +344,7 @@ impl Default for Objectives {
                                 voxels
                                     .iter()
                                     .filter(|(sim, tags, voxel_graves)| {
-                                        tags.contains("tutorial")
+                                        tags.contains(TUTORIAL_TAG)
                                             && sim.air_ratio() <= GRAVE_FILL_THRESHOLD
                                             && voxel_graves
                                                 .0
@@ -124,17 +354,11 @@ impl Default for Objectives {
                                     .count() as u32
                             },
                         )
-                        .on_complete(|mut yarn_nodes: Query<(&Tags, &mut YarnNode)>| {
-                            for (tags, mut node) in &mut yarn_nodes {
-                                if !tags.contains("larry") {
-                                    continue;
-                                }
-                                node.yarn_node = "3_Done".to_string();
-                            }
-                        }),
+                        .on_complete(push_larry_dialogue("after_dirt_3", "3_Done")),
                     SubObjective::tracked("store_hit", "shoot the whale in the store", 1)
+                        .flicker_lights("tutorial_hallway")
+                        .light_mood(Color::srgb(0.45, 0.55, 0.8), 60.0)
                         .on_start(|mut commands: Commands| {
-                            commands.trigger(FlickerLight::new("tutorial_hallway"));
                             commands.trigger(SpawnNpc::Queue {
                                 spawner_name: "tutorial_whale".to_string(),
                                 overrides: default(),
@@ -147,27 +371,25 @@ impl Default for Objectives {
                         .on_complete(|mut commands: Commands| {
                             commands.trigger(SpawnEnemy::Queue {
                                 spawner_name: "tutorial_octopus".to_string(),
+                                alerted: false,
                             });
                         }),
                     SubObjective::binary("bury_whale", "bury the whale")
                         .hook(player_in_sensor(&["tutorial_hallway"])),
                     SubObjective::tracked("help_larry", "help larry, shoot the octopi", 2)
-                        .on_start(|mut yarn_nodes: Query<(&Tags, &mut YarnNode)>| {
-                            for (tags, mut node) in &mut yarn_nodes {
-                                if tags.contains("larry") {
-                                    node.yarn_node = "Under_Attack".to_string();
-                                }
-                            }
-                        })
+                        .light_mood(Color::srgb(0.8, 0.25, 0.2), 80.0)
+                        .play_sting("audio/music/under_attack_sting.wav")
+                        .on_start(push_larry_dialogue("under_attack", "Under_Attack"))
                         .hook(|dead: Query<&Tags, With<NpcDead>>| -> u32 {
                             dead.iter()
                                 .filter(|tags| tags.contains("tutorial_octopus"))
                                 .count() as u32
                         })
-                        .on_complete(|mut yarn_nodes: Query<(&Tags, &mut YarnNode)>| {
-                            for (tags, mut node) in &mut yarn_nodes {
+                        .on_complete(|mut npcs: Query<(&Tags, &mut DialogueState)>| {
+                            for (tags, mut state) in &mut npcs {
                                 if tags.contains("larry") {
-                                    node.yarn_node = "Relief".to_string();
+                                    state.remove("under_attack");
+                                    state.push("after_help_larry", "Relief");
                                 }
                             }
                         }),
@@ -176,6 +398,7 @@ impl Default for Objectives {
                         "bury the whale... and the octopi",
                         3,
                     )
+                    .clear_light_mood()
                     .hook(
                         |voxels: Query<(&VoxelSim, &Tags, &VoxelGraves)>,
                          graves: Query<&GraveState>|
@@ -183,7 +406,7 @@ impl Default for Objectives {
                             let total = voxels
                                 .iter()
                                 .filter(|(sim, tags, voxel_graves)| {
-                                    tags.contains("tutorial")
+                                    tags.contains(TUTORIAL_TAG)
                                         && sim.air_ratio() <= GRAVE_FILL_THRESHOLD
                                         && voxel_graves
                                             .0
@@ -193,11 +416,8 @@ impl Default for Objectives {
                                 .count() as u32;
                             total.saturating_sub(3)
                         },
-                    )
-                    .on_complete(|mut commands: Commands| {
-                        // complete `the_molt` and
-                        // swap to `the_job` objective.
-                    }),
+                    ),
+                    // `the_molt` swaps over to `next` once this completes.
                 ],
             },
         );
@@ -208,6 +428,7 @@ impl Default for Objectives {
                 id: "the_job".to_string(),
                 title: "The Job".to_string(),
                 current: 0,
+                next: None,
                 items: vec![],
             },
         );
@@ -223,10 +444,26 @@ pub(crate) struct Objective {
     pub id: String,
     pub title: String,
     pub current: usize,
+    /// Objective to activate once every item is complete.
+    pub next: Option<String>,
     pub items: Vec<SubObjective>,
 }
 
 impl Objective {
+    /// "The Molt — 3/7", shown while the panel is collapsed.
+    pub fn summary(&self) -> String {
+        if self.items.is_empty() {
+            return self.title.clone();
+        }
+        let done = self.items.iter().filter(|item| item.completed).count();
+        format!("{} — {}/{}", self.title, done, self.items.len())
+    }
+
+    /// The sub-objective being worked on, `None` once all are done.
+    pub fn current_item(&self) -> Option<&SubObjective> {
+        self.items.get(self.current)
+    }
+
     pub fn set_progress(&mut self, sub_id: &str, value: u32) {
         if let Some(item) = self.items.iter_mut().find(|i| i.id == sub_id) {
             if let ObjectiveTarget::Tracked { current, target } = &mut item.target {
@@ -248,6 +485,23 @@ impl Objective {
     }
 }
 
+/// A sub-objective was just completed, after its `on_complete` hooks ran.
+#[derive(Event, Debug)]
+pub(crate) struct SubObjectiveCompleted {
+    pub objective: String,
+    pub sub_objective: String,
+    /// Marked done by [`Objectives::force_complete_all`], like skipping the tutorial, instead
+    /// of being played through.
+    pub forced: bool,
+}
+
+/// Every sub-objective of an objective is complete, triggered after the last
+/// [`SubObjectiveCompleted`] and before switching to its `next` objective.
+#[derive(Event, Debug)]
+pub(crate) struct ObjectiveCompleted {
+    pub objective: String,
+}
+
 type ProgressHookFn = Box<dyn FnMut(&mut ObjectiveTarget, &mut World) + Send + Sync>;
 type LifecycleHookFn = Box<dyn FnMut(&mut World) + Send + Sync>;
 
@@ -348,6 +602,35 @@ impl SubObjective {
         }));
         self
     }
+
+    /// Fades the level's ambient light and fog toward `color` at `intensity` once this starts.
+    /// The mood stays until another sub-objective sets or clears it.
+    pub fn light_mood(self, color: Color, intensity: f32) -> Self {
+        self.on_start(move |mut mood: ResMut<LightMood>| mood.set(color, intensity))
+    }
+
+    /// Fades back to the level's own lighting once this starts.
+    pub fn clear_light_mood(self) -> Self {
+        self.on_start(|mut mood: ResMut<LightMood>| mood.clear())
+    }
+
+    /// Flickers the lights tagged `tag` once this starts.
+    pub fn flicker_lights(self, tag: &'static str) -> Self {
+        self.on_start(move |mut commands: Commands| {
+            commands.trigger(FlickerLight::new(tag));
+        })
+    }
+
+    /// Plays the sound at `path` once this starts, on the music bus.
+    pub fn play_sting(self, path: &'static str) -> Self {
+        self.on_start(move |mut commands: Commands, assets: Res<AssetServer>| {
+            commands.spawn((
+                Name::new("Objective Sting"),
+                SamplePlayer::new(assets.load(path)),
+                MusicPool,
+            ));
+        })
+    }
 }
 
 pub(crate) enum ObjectiveTarget {
@@ -391,7 +674,54 @@ impl ProgressUpdate for bool {
     }
 }
 
+/// Has Larry start his conversations from `node` once the hook runs.
+fn push_larry_dialogue(
+    condition: &'static str,
+    node: &'static str,
+) -> impl FnMut(Query<(&Tags, &mut DialogueState)>) + Send + Sync {
+    move |mut npcs: Query<(&Tags, &mut DialogueState)>| {
+        for (tags, mut state) in &mut npcs {
+            if tags.contains("larry") {
+                state.push(condition, node);
+            }
+        }
+    }
+}
+
+/// How many times a second the current sub-objective's progress hooks run, which can query a
+/// lot of entities. 0 runs them every frame. Starting and completing sub-objectives aren't
+/// held back by it.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub(crate) struct ProgressHookRate(pub f32);
+
+impl Default for ProgressHookRate {
+    fn default() -> Self {
+        Self(PROGRESS_HOOK_HZ)
+    }
+}
+
+#[derive(Resource)]
+struct ProgressHookTimer(Timer);
+
+/// Ticks the throttle, true on the frames progress hooks should run.
+fn progress_hooks_due(world: &mut World) -> bool {
+    let rate = world.resource::<ProgressHookRate>().0;
+    if rate <= 0.0 {
+        return true;
+    }
+    let delta = world.resource::<Time>().delta();
+    let mut timer = world.resource_mut::<ProgressHookTimer>();
+    let period = Duration::from_secs_f32(1.0 / rate);
+    if timer.0.duration() != period {
+        timer.0.set_duration(period);
+    }
+    timer.0.tick(delta);
+    timer.0.just_finished()
+}
+
 fn run_progress_hooks(world: &mut World) {
+    let hooks_due = progress_hooks_due(world);
     let Some(mut objectives) = world.remove_resource::<Objectives>() else {
         warn!("Objectives resource missing, skipping hooks");
         return;
@@ -416,7 +746,7 @@ fn run_progress_hooks(world: &mut World) {
         }
     }
 
-    if !item.completed && !item.progress_hooks.is_empty() {
+    if hooks_due && !item.completed && !item.progress_hooks.is_empty() {
         let before = item.target.debug_value();
         for hook in &mut item.progress_hooks {
             hook(&mut item.target, world);
@@ -433,6 +763,11 @@ fn run_progress_hooks(world: &mut World) {
         for hook in &mut item.on_complete_hooks {
             hook(world);
         }
+        world.trigger(SubObjectiveCompleted {
+            objective: active.id.clone(),
+            sub_objective: item.id.clone(),
+            forced: false,
+        });
         active.current += 1;
 
         if let Some(next) = active.items.get_mut(active.current) {
@@ -443,12 +778,69 @@ fn run_progress_hooks(world: &mut World) {
                     hook(world);
                 }
             }
+        } else {
+            world.trigger(ObjectiveCompleted {
+                objective: active.id.clone(),
+            });
+            if let Some(next) = active.next.clone() {
+                info!("Objective '{}' done, switching to '{}'", active.id, next);
+                objectives.active = next;
+            }
         }
     }
 
     world.insert_resource(objectives);
 }
 
+pub(crate) const TUTORIAL_OBJECTIVE: &str = "the_molt";
+/// Tag on the tutorial's graves and their voxel volumes.
+pub(crate) const TUTORIAL_TAG: &str = "tutorial";
+/// The tutorial's sub-objective for digging out its graves.
+pub(crate) const TUTORIAL_DIG_GRAVES: &str = "dig_3";
+/// Items the tutorial hands out with `<<give_item>>`, on top of the map's starting shovel.
+const TUTORIAL_ITEMS: [&str; 4] = ["bucket", "gun", "bandage", "tonic"];
+
+/// Fast-forwards the tutorial objective to its completed end state.
+#[derive(Event)]
+pub(crate) struct SkipTutorial;
+
+fn on_skip_tutorial(_on: On<SkipTutorial>, mut commands: Commands, objectives: Res<Objectives>) {
+    if objectives.active != TUTORIAL_OBJECTIVE {
+        return;
+    }
+
+    commands.queue(|world: &mut World| {
+        // Skip the ambush `on_start`s, but keep the body spawns, yarn nodes and
+        // enemy spawns from `on_complete` so larry & co end up where they should.
+        Objectives::force_complete_all(world, TUTORIAL_OBJECTIVE, HookPolicy::CompleteOnly);
+
+        world.trigger(FillVoxelVolumes {
+            tag: TUTORIAL_TAG.to_string(),
+        });
+        // Everything larry would have handed out along the way.
+        for item in TUTORIAL_ITEMS {
+            world.trigger(GiveItem(item.to_string()));
+        }
+
+        // Pay out the graves as if they had been filled and buried.
+        let mut owed = 0;
+        let mut graves = world.query::<(&mut GraveState, &Tags)>();
+        for (mut grave, tags) in graves.iter_mut(world) {
+            if !tags.contains(TUTORIAL_TAG) {
+                continue;
+            }
+            grave.filled = grave.slots;
+            owed += grave.slots.saturating_sub(grave.rewarded);
+            grave.rewarded = grave.slots;
+        }
+
+        if owed > 0 {
+            world.resource_mut::<Crusts>().add(owed);
+            world.trigger(CrustsRewarded(owed));
+        }
+    });
+}
+
 fn register_objective_command(
     mut runners: Query<&mut DialogueRunner, Added<DialogueRunner>>,
     mut commands: Commands,
@@ -462,6 +854,11 @@ fn register_objective_command(
             },
         );
         runner.commands_mut().add_command("objective", system);
+
+        let skip = commands.register_system(|mut commands: Commands| {
+            commands.trigger(SkipTutorial);
+        });
+        runner.commands_mut().add_command("skip_tutorial", skip);
     }
 }
 
@@ -477,8 +874,49 @@ struct ObjectiveProgress(usize);
 #[derive(Component)]
 struct ObjectiveStrike(usize);
 
+/// Holds the id of the objective the panel was built for.
+#[derive(Component)]
+struct ObjectivePanel(String);
+
+#[derive(Component)]
+struct ObjectiveTitle;
+
+/// Divider and rows, clipped away while the panel is collapsed.
 #[derive(Component)]
-struct ObjectivePanel;
+struct ObjectiveBody {
+    /// 0 is collapsed, 1 is fully expanded.
+    expand: f32,
+}
+
+/// Whether the panel shows every row or just the title and a progress summary.
+#[derive(Resource, Debug)]
+struct ObjectivePanelToggle {
+    /// Stays expanded without holding the key.
+    pinned: bool,
+    /// Real time the toggle key went down, while it's held.
+    pressed_at: Option<f32>,
+}
+
+impl Default for ObjectivePanelToggle {
+    fn default() -> Self {
+        Self {
+            pinned: true,
+            pressed_at: None,
+        }
+    }
+}
+
+impl ObjectivePanelToggle {
+    fn expanded(&self) -> bool {
+        self.pinned || self.pressed_at.is_some()
+    }
+}
+
+/// Releasing the key within this long pins or unpins the panel instead of just peeking.
+const TOGGLE_TAP_SECONDS: f32 = 0.25;
+const SLIDE_DURATION: f32 = 0.15;
+/// Upper bound for the body height, the slide animates `max_height` up to this.
+const BODY_MAX_HEIGHT: f32 = 400.0;
 
 #[derive(Component)]
 struct WasCompleted(bool);
@@ -489,21 +927,82 @@ struct ObjectiveCompleteAnim(Timer);
 const COMPLETE_ANIM_DURATION: f32 = 0.6;
 const COMPLETED_COLOR: Color = Color::srgba(0.6, 0.6, 0.6, 1.0);
 
+/// Completed rows and the current one are shown, future ones hidden. Compact mode hides the
+/// completed rows too, once their strikethrough has finished playing.
+fn row_visibility(row: usize, current: usize, compact: bool, animating: bool) -> Visibility {
+    if row == current || (row < current && (!compact || animating)) {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
 fn spawn_objectives_ui(
-    add: On<Add, HudTopLeft>,
     mut commands: Commands,
     objectives: Res<Objectives>,
+    toggle: Res<ObjectivePanelToggle>,
     font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
 ) {
-    let hud_root = add.entity;
+    let Some(active) = objectives.active() else {
+        return;
+    };
 
+    spawn_objective_panel(
+        &mut commands,
+        active,
+        toggle.expanded(),
+        &font,
+        &hud_settings,
+    );
+}
+
+/// Swap the panel out when the active objective changes.
+fn rebuild_objective_panel(
+    mut commands: Commands,
+    objectives: Res<Objectives>,
+    panels: Query<(Entity, &ObjectivePanel)>,
+    toggle: Res<ObjectivePanelToggle>,
+    font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
+) {
     let Some(active) = objectives.active() else {
         return;
     };
 
-    let panel = commands
+    for (entity, panel) in &panels {
+        if panel.0 == active.id {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        spawn_objective_panel(
+            &mut commands,
+            active,
+            toggle.expanded(),
+            &font,
+            &hud_settings,
+        );
+    }
+}
+
+fn spawn_objective_panel(
+    commands: &mut Commands,
+    active: &Objective,
+    expanded: bool,
+    font: &GameFont,
+    hud_settings: &HudSettings,
+) {
+    let (title, expand) = if expanded {
+        (active.title.clone(), 1.0)
+    } else {
+        (active.summary(), 0.0)
+    };
+
+    commands
         .spawn((
-            ObjectivePanel,
+            ObjectivePanel(active.id.clone()),
+            HudElement::Objectives,
+            hud_settings.slot(HudElement::Objectives, 1),
             Node {
                 flex_direction: FlexDirection::Column,
                 ..default()
@@ -512,7 +1011,8 @@ fn spawn_objectives_ui(
         .with_children(|panel| {
             // Title
             panel.spawn((
-                Text::new(&active.title),
+                ObjectiveTitle,
+                Text::new(title),
                 TextFont {
                     font: font.0.clone(),
                     font_size: 28.0,
@@ -521,107 +1021,132 @@ fn spawn_objectives_ui(
                 TextColor(Color::WHITE),
             ));
 
-            // Divider
-            panel.spawn((
+            panel
+                .spawn((ObjectiveBody { expand }, body_node(expand)))
+                .with_children(|body| {
+                    spawn_objective_rows(body, active, hud_settings.compact_objectives, font);
+                });
+        });
+}
+
+fn body_node(expand: f32) -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        overflow: Overflow::clip(),
+        max_height: Val::Px(expand * BODY_MAX_HEIGHT),
+        display: if expand > 0.0 {
+            Display::Flex
+        } else {
+            Display::None
+        },
+        ..default()
+    }
+}
+
+fn spawn_objective_rows(
+    panel: &mut ChildSpawnerCommands,
+    active: &Objective,
+    compact: bool,
+    font: &GameFont,
+) {
+    // Divider
+    panel.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Px(1.0),
+            margin: UiRect::vertical(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE),
+    ));
+
+    let current = active.current;
+    for (i, item) in active.items.iter().enumerate() {
+        let is_completed = item.completed;
+        let row_visible = row_visibility(i, current, compact, false);
+
+        let progress = match &item.target {
+            ObjectiveTarget::Tracked { current, target } => {
+                format!("{}/{}", current, target)
+            }
+            ObjectiveTarget::Binary { .. } => String::new(),
+        };
+
+        panel
+            .spawn((
+                ObjectiveRow(i),
+                WasCompleted(is_completed),
                 Node {
+                    position_type: PositionType::Relative,
                     width: Val::Percent(100.0),
-                    height: Val::Px(1.0),
-                    margin: UiRect::vertical(Val::Px(4.0)),
+                    justify_content: JustifyContent::SpaceBetween,
                     ..default()
                 },
-                BackgroundColor(Color::WHITE),
-            ));
-
-            // Sub-objectives: show completed + current, hide future
-            let current = active.current;
-            for (i, item) in active.items.iter().enumerate() {
-                let is_completed = item.completed;
-                let is_current = i == current;
-                let row_visible = if is_completed || is_current {
-                    Visibility::Inherited
+                row_visible,
+            ))
+            .with_children(|row| {
+                let text_color = if is_completed {
+                    Color::srgba(0.6, 0.6, 0.6, 1.0)
                 } else {
-                    Visibility::Hidden
+                    Color::WHITE
                 };
-
-                let progress = match &item.target {
-                    ObjectiveTarget::Tracked { current, target } => {
-                        format!("{}/{}", current, target)
-                    }
-                    ObjectiveTarget::Binary { .. } => String::new(),
-                };
-
-                panel
-                    .spawn((
-                        ObjectiveRow(i),
-                        WasCompleted(is_completed),
-                        Node {
-                            position_type: PositionType::Relative,
-                            width: Val::Percent(100.0),
-                            justify_content: JustifyContent::SpaceBetween,
+                row.spawn((
+                    ObjectiveText(i),
+                    Text::new(&item.label),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(text_color),
+                ));
+
+                if !progress.is_empty() {
+                    row.spawn((
+                        ObjectiveProgress(i),
+                        Text::new(progress),
+                        TextFont {
+                            font: font.0.clone(),
+                            font_size: 20.0,
                             ..default()
                         },
-                        row_visible,
-                    ))
-                    .with_children(|row| {
-                        let text_color = if is_completed {
-                            Color::srgba(0.6, 0.6, 0.6, 1.0)
-                        } else {
-                            Color::WHITE
-                        };
-                        row.spawn((
-                            ObjectiveText(i),
-                            Text::new(&item.label),
-                            TextFont {
-                                font: font.0.clone(),
-                                font_size: 20.0,
-                                ..default()
-                            },
-                            TextColor(text_color),
-                        ));
-
-                        if !progress.is_empty() {
-                            row.spawn((
-                                ObjectiveProgress(i),
-                                Text::new(progress),
-                                TextFont {
-                                    font: font.0.clone(),
-                                    font_size: 20.0,
-                                    ..default()
-                                },
-                                TextColor(text_color),
-                            ));
-                        }
-
-                        let (strike_visible, strike_width) = if is_completed {
-                            (Visibility::Inherited, Val::Percent(100.0))
-                        } else {
-                            (Visibility::Hidden, Val::Percent(0.0))
-                        };
-                        row.spawn((
-                            ObjectiveStrike(i),
-                            Node {
-                                position_type: PositionType::Absolute,
-                                height: Val::Px(1.0),
-                                width: strike_width,
-                                top: Val::Percent(50.0),
-                                left: Val::Px(0.0),
-                                ..default()
-                            },
-                            BackgroundColor(COMPLETED_COLOR),
-                            strike_visible,
-                        ));
-                    });
-            }
-        })
-        .id();
+                        TextColor(text_color),
+                    ));
+                }
 
-    commands.entity(hud_root).add_child(panel);
+                let (strike_visible, strike_width) = if is_completed {
+                    (Visibility::Inherited, Val::Percent(100.0))
+                } else {
+                    (Visibility::Hidden, Val::Percent(0.0))
+                };
+                row.spawn((
+                    ObjectiveStrike(i),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        height: Val::Px(1.0),
+                        width: strike_width,
+                        top: Val::Percent(50.0),
+                        left: Val::Px(0.0),
+                        ..default()
+                    },
+                    BackgroundColor(COMPLETED_COLOR),
+                    strike_visible,
+                ));
+            });
+    }
 }
 
 fn update_objective_ui(
     mut commands: Commands,
     objectives: Res<Objectives>,
-    mut row_query: Query<(Entity, &ObjectiveRow, &mut Visibility, &mut WasCompleted)>,
+    hud_settings: Res<HudSettings>,
+    mut row_query: Query<(
+        Entity,
+        &ObjectiveRow,
+        &mut Visibility,
+        &mut WasCompleted,
+        Has<ObjectiveCompleteAnim>,
+    )>,
     mut text_query: Query<(&ObjectiveText, &mut Text, &mut TextColor), Without<ObjectiveProgress>>,
     mut progress_query: Query<
         (&ObjectiveProgress, &mut Text, &mut TextColor),
@@ -636,20 +1161,22 @@ fn update_objective_ui(
     let current = active.current;
 
     // Detect newly completed rows and start animations
-    for (entity, row, mut vis, mut was_completed) in &mut row_query {
+    for (entity, row, mut vis, mut was_completed, animating) in &mut row_query {
         let i = row.0;
         let Some(item) = active.items.get(i) else {
             continue;
         };
 
-        *vis = if i <= current {
-            Visibility::Inherited
-        } else {
-            Visibility::Hidden
-        };
+        let newly_completed = item.completed && !was_completed.0;
+        *vis = row_visibility(
+            i,
+            current,
+            hud_settings.compact_objectives,
+            animating || newly_completed,
+        );
 
         // Transition: not completed → completed — start animation
-        if item.completed && !was_completed.0 {
+        if newly_completed {
             was_completed.0 = true;
             commands
                 .entity(entity)
@@ -701,12 +1228,20 @@ fn update_objective_ui(
 fn animate_objective_completion(
     mut commands: Commands,
     time: Res<Time>,
-    mut rows: Query<(Entity, &ObjectiveRow, &Children, &mut ObjectiveCompleteAnim)>,
+    objectives: Res<Objectives>,
+    hud_settings: Res<HudSettings>,
+    mut rows: Query<(
+        Entity,
+        &ObjectiveRow,
+        &Children,
+        &mut ObjectiveCompleteAnim,
+        &mut Visibility,
+    )>,
     mut texts: Query<&mut TextColor, With<ObjectiveText>>,
     mut progress_texts: Query<&mut TextColor, (With<ObjectiveProgress>, Without<ObjectiveText>)>,
     mut strikes: Query<(&mut Node, &mut BackgroundColor), With<ObjectiveStrike>>,
 ) {
-    for (entity, _row, children, mut anim) in &mut rows {
+    for (entity, row, children, mut anim, mut visibility) in &mut rows {
         anim.0.tick(time.delta());
         let t = anim.0.fraction();
         // Ease-out for a quick slash feel
@@ -731,6 +1266,124 @@ fn animate_objective_completion(
 
         if anim.0.just_finished() {
             commands.entity(entity).remove::<ObjectiveCompleteAnim>();
+            if let Some(active) = objectives.active() {
+                *visibility = row_visibility(
+                    row.0,
+                    active.current,
+                    hud_settings.compact_objectives,
+                    false,
+                );
+            }
         }
     }
 }
+
+fn press_toggle_objectives(
+    _on: On<Start<ToggleObjectives>>,
+    time: Res<Time<Real>>,
+    mut toggle: ResMut<ObjectivePanelToggle>,
+) {
+    toggle.pressed_at = Some(time.elapsed_secs());
+}
+
+fn release_toggle_objectives(
+    _on: On<Complete<ToggleObjectives>>,
+    time: Res<Time<Real>>,
+    mut toggle: ResMut<ObjectivePanelToggle>,
+) {
+    let Some(pressed_at) = toggle.pressed_at.take() else {
+        return;
+    };
+    if time.elapsed_secs() - pressed_at <= TOGGLE_TAP_SECONDS {
+        toggle.pinned = !toggle.pinned;
+    }
+}
+
+fn update_objective_title(
+    objectives: Res<Objectives>,
+    toggle: Res<ObjectivePanelToggle>,
+    mut titles: Query<&mut Text, With<ObjectiveTitle>>,
+) {
+    let Some(active) = objectives.active() else {
+        return;
+    };
+    let title = if toggle.expanded() {
+        active.title.clone()
+    } else {
+        active.summary()
+    };
+    for mut text in &mut titles {
+        **text = title.clone();
+    }
+}
+
+fn slide_objective_body(
+    time: Res<Time<Real>>,
+    toggle: Res<ObjectivePanelToggle>,
+    mut bodies: Query<(&mut ObjectiveBody, &mut Node)>,
+) {
+    let target = if toggle.expanded() { 1.0 } else { 0.0 };
+    let step = time.delta_secs() / SLIDE_DURATION;
+    for (mut body, mut node) in &mut bodies {
+        if body.expand == target {
+            continue;
+        }
+        body.expand = if target > body.expand {
+            (body.expand + step).min(target)
+        } else {
+            (body.expand - step).max(target)
+        };
+        // Ease-out so the rows settle rather than stop dead
+        let eased = 1.0 - (1.0 - body.expand) * (1.0 - body.expand);
+        *node = body_node(eased);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_progress_only_counts_started_sub_objectives() {
+        let mut objectives = Objectives::default();
+        assert!(!objectives.add_progress("help_larry", 1));
+        assert!(!objectives.add_progress("no_such_objective", 1));
+
+        let item = objectives
+            .objectives
+            .values_mut()
+            .flat_map(|objective| &mut objective.items)
+            .find(|item| item.id == "help_larry")
+            .unwrap();
+        item.started = true;
+        assert!(objectives.add_progress("help_larry", 1));
+        assert_eq!(objectives.is_done("help_larry"), Some(false));
+        assert!(objectives.add_progress("help_larry", 1));
+        assert_eq!(objectives.is_done("help_larry"), Some(true));
+        assert!(!objectives.add_progress("help_larry", 1));
+    }
+
+    #[test]
+    fn progress_hooks_run_at_the_configured_rate() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.insert_resource(ProgressHookRate(10.0));
+        world.insert_resource(ProgressHookTimer(Timer::from_seconds(
+            1.0 / PROGRESS_HOOK_HZ,
+            TimerMode::Repeating,
+        )));
+        let step = |world: &mut World, millis| {
+            world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(millis));
+            progress_hooks_due(world)
+        };
+        assert!(!step(&mut world, 60));
+        assert!(step(&mut world, 60));
+        assert!(!step(&mut world, 60));
+
+        world.insert_resource(ProgressHookRate(0.0));
+        assert!(step(&mut world, 1));
+        assert!(step(&mut world, 1));
+    }
+}
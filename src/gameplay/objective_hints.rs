@@ -0,0 +1,88 @@
+//! Nudges for players who are stuck on an objective, like digging holes away from the graves.
+
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        dig::{VoxelGraves, VoxelWorldBounds, VoxelsDug},
+        hud::{HintDismissed, ShowHint},
+        objective::{Objective, Objectives, TUTORIAL_DIG_GRAVES, TUTORIAL_TAG},
+        tags::Tags,
+    },
+    screens::Screen,
+};
+
+/// Sub-objectives that want digging in specific graves, and the tag their voxel volumes carry.
+const DIG_TARGETS: &[(&str, &str)] = &[(TUTORIAL_DIG_GRAVES, TUTORIAL_TAG)];
+/// Digs outside the target volumes before the hint shows up.
+const MISSES_BEFORE_HINT: u32 = 6;
+/// Slack around the target volumes, so digging right at the rim still counts.
+const TARGET_MARGIN: f32 = 0.5;
+const WRONG_SPOT_HINT: &str = "dig_wrong_spot";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DigHints>();
+    app.add_systems(OnEnter(Screen::Gameplay), reset_dig_hints);
+    app.add_observer(track_dig_misses);
+    app.add_observer(silence_dig_hints);
+}
+
+#[derive(Resource, Default, Debug)]
+struct DigHints {
+    /// Digs in a row that missed the target volumes.
+    misses: u32,
+    /// The player closed the hint, don't show it again this run.
+    dismissed: bool,
+}
+
+fn reset_dig_hints(mut hints: ResMut<DigHints>) {
+    *hints = DigHints::default();
+}
+
+fn track_dig_misses(
+    dug: On<VoxelsDug>,
+    mut commands: Commands,
+    mut hints: ResMut<DigHints>,
+    objectives: Res<Objectives>,
+    volumes: Query<(Entity, &Tags, &VoxelWorldBounds, &VoxelGraves)>,
+) {
+    if hints.dismissed {
+        return;
+    }
+    let Some(item) = objectives.active().and_then(Objective::current_item) else {
+        return;
+    };
+    let Some(&(_, tag)) = DIG_TARGETS.iter().find(|(id, _)| *id == item.id) else {
+        return;
+    };
+
+    let mut targets = volumes
+        .iter()
+        .filter(|(_, tags, _, graves)| tags.contains(tag) && !graves.0.is_empty())
+        .peekable();
+    // Nothing to compare against, so we can't tell a wrong spot from a right one.
+    if targets.peek().is_none() {
+        return;
+    }
+    let on_target = targets.any(|(entity, _, bounds, _)| {
+        entity == dug.volume
+            || (dug.point.cmpge(bounds.min - TARGET_MARGIN).all()
+                && dug.point.cmple(bounds.max + TARGET_MARGIN).all())
+    });
+    if on_target {
+        hints.misses = 0;
+        return;
+    }
+
+    hints.misses += 1;
+    if hints.misses >= MISSES_BEFORE_HINT {
+        hints.misses = 0;
+        commands.trigger(ShowHint::new("Dig in the marked graves").with_id(WRONG_SPOT_HINT));
+    }
+}
+
+fn silence_dig_hints(dismissed: On<HintDismissed>, mut hints: ResMut<DigHints>) {
+    if dismissed.id == Some(WRONG_SPOT_HINT) {
+        hints.dismissed = true;
+    }
+}
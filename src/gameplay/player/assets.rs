@@ -1,11 +1,16 @@
 //! Assets for the player.
 
-use bevy::{asset::RenderAssetUsages, gltf::GltfLoaderSettings, prelude::*};
+use bevy::{
+    asset::{RenderAssetUsages, VisitAssetDependencies},
+    gltf::GltfLoaderSettings,
+    prelude::*,
+};
 use bevy_seedling::sample::AudioSample;
 use bevy_shuffle_bag::ShuffleBag;
 
 use crate::{
-    asset_tracking::LoadResource, third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
+    asset_tracking::LoadResource, gameplay::surface::Surface,
+    third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _,
 };
 
 use super::Player;
@@ -29,12 +34,51 @@ pub(crate) struct PlayerAssets {
     pub(crate) land_sounds: ShuffleBag<Handle<AudioSample>>,
     #[dependency]
     pub(crate) jump_start_sounds: ShuffleBag<Handle<AudioSample>>,
+    /// Replaces `steps` and `land_sounds` on these surfaces, anything else uses those.
+    #[dependency]
+    pub(crate) surface_sounds: Vec<SurfaceSounds>,
     #[dependency]
     pub(crate) idle_animation: Handle<AnimationClip>,
     #[dependency]
     pub(crate) a_pose_animation: Handle<AnimationClip>,
 }
 
+#[derive(Clone, Reflect, VisitAssetDependencies)]
+pub(crate) struct SurfaceSounds {
+    pub(crate) surface: Surface,
+    #[dependency]
+    pub(crate) steps: ShuffleBag<Handle<AudioSample>>,
+    #[dependency]
+    pub(crate) land_sounds: ShuffleBag<Handle<AudioSample>>,
+}
+
+impl PlayerAssets {
+    pub(crate) fn steps_on(&mut self, surface: Surface) -> &mut ShuffleBag<Handle<AudioSample>> {
+        match self
+            .surface_sounds
+            .iter_mut()
+            .find(|s| s.surface == surface)
+        {
+            Some(sounds) => &mut sounds.steps,
+            None => &mut self.steps,
+        }
+    }
+
+    pub(crate) fn land_sounds_on(
+        &mut self,
+        surface: Surface,
+    ) -> &mut ShuffleBag<Handle<AudioSample>> {
+        match self
+            .surface_sounds
+            .iter_mut()
+            .find(|s| s.surface == surface)
+        {
+            Some(sounds) => &mut sounds.land_sounds,
+            None => &mut self.land_sounds,
+        }
+    }
+}
+
 impl FromWorld for PlayerAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
@@ -97,6 +141,32 @@ impl FromWorld for PlayerAssets {
                 rng,
             )
             .unwrap(),
+            // The default sets are rock, so stone uses them. Wood and metal do too until they get
+            // samples of their own.
+            surface_sounds: vec![SurfaceSounds {
+                surface: Surface::Dirt,
+                steps: ShuffleBag::try_new(
+                    [
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_01.wav"),
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_02.wav"),
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_03.wav"),
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_04.wav"),
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_05.wav"),
+                        assets.load("audio/sound_effects/step/Footsteps_Dirt_Walk_06.wav"),
+                    ],
+                    rng,
+                )
+                .unwrap(),
+                land_sounds: ShuffleBag::try_new(
+                    [
+                        assets.load("audio/sound_effects/land/Footsteps_Dirt_Jump_Land_01.wav"),
+                        assets.load("audio/sound_effects/land/Footsteps_Dirt_Jump_Land_02.wav"),
+                        assets.load("audio/sound_effects/land/Footsteps_Dirt_Jump_Land_03.wav"),
+                    ],
+                    rng,
+                )
+                .unwrap(),
+            }],
             idle_animation: assets.load(Player::animation_path(9)),
             a_pose_animation: assets.load(Player::animation_path(5)),
         }
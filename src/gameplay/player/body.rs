@@ -0,0 +1,153 @@
+//! An optional simplified body under the first person camera, so looking down shows legs.
+//!
+//! The view model only renders the arms, this is drawn by the world camera instead.
+
+use avian3d::prelude::LinearVelocity;
+use bevy::{light::NotShadowCaster, prelude::*};
+use bevy_ahoy::prelude::*;
+
+use super::{PLAYER_HALF_HEIGHT, Player, camera::PlayerCamera};
+use crate::{PostPhysicsAppSystems, screens::Screen};
+
+const BODY_COLOR: Color = Color::srgb(0.55, 0.12, 0.08);
+/// How far behind the camera the torso sits, so the camera never ends up inside it.
+const TORSO_BACK_OFFSET: f32 = 0.2;
+const TORSO_RADIUS: f32 = 0.22;
+const TORSO_LENGTH: f32 = 0.45;
+const HIP_HEIGHT: f32 = 0.75;
+const HIP_WIDTH: f32 = 0.12;
+const LEG_RADIUS: f32 = 0.09;
+/// Radians of leg swing per meter travelled.
+const STRIDE_RATE: f32 = 4.0;
+const MAX_LEG_SWING: f32 = 0.6;
+/// Horizontal speed at which legs swing the full [`MAX_LEG_SWING`].
+const FULL_SWING_SPEED: f32 = 6.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PlayerBodySettings>();
+    app.add_observer(spawn_player_body);
+    app.add_systems(
+        Update,
+        (
+            update_body_visibility.run_if(resource_changed::<PlayerBodySettings>),
+            animate_player_body,
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PostPhysicsAppSystems::PlayAnimations),
+    );
+}
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct PlayerBodySettings {
+    pub(crate) visible: bool,
+}
+
+impl PlayerBodySettings {
+    fn visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+}
+
+#[derive(Component)]
+struct PlayerBody {
+    stride: f32,
+}
+
+/// Swings around the hip, `1.0` or `-1.0` so the legs move opposite each other.
+#[derive(Component)]
+struct PlayerLeg(f32);
+
+fn spawn_player_body(
+    add: On<Add, Player>,
+    mut commands: Commands,
+    settings: Res<PlayerBodySettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: BODY_COLOR,
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let torso = meshes.add(Capsule3d::new(TORSO_RADIUS, TORSO_LENGTH));
+    let leg_length = HIP_HEIGHT - 2.0 * LEG_RADIUS;
+    let leg = meshes.add(Capsule3d::new(LEG_RADIUS, leg_length));
+
+    let feet = -PLAYER_HALF_HEIGHT;
+    let torso_y = feet + HIP_HEIGHT + TORSO_LENGTH / 2.0;
+    let hip = |side: f32| {
+        (
+            Name::new("Player Leg"),
+            PlayerLeg(side),
+            Transform::from_xyz(side * HIP_WIDTH, feet + HIP_HEIGHT, TORSO_BACK_OFFSET / 2.0),
+            Visibility::default(),
+            children![(
+                Mesh3d(leg.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(0.0, -HIP_HEIGHT / 2.0, 0.0),
+                NotShadowCaster,
+            )],
+        )
+    };
+
+    let body = commands
+        .spawn((
+            Name::new("Player Body"),
+            PlayerBody { stride: 0.0 },
+            Transform::default(),
+            settings.visibility(),
+            children![
+                (
+                    Name::new("Player Torso"),
+                    Mesh3d(torso),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_xyz(0.0, torso_y, TORSO_BACK_OFFSET),
+                    NotShadowCaster,
+                ),
+                hip(1.0),
+                hip(-1.0),
+            ],
+        ))
+        .id();
+    commands.entity(add.entity).add_child(body);
+}
+
+fn update_body_visibility(
+    settings: Res<PlayerBodySettings>,
+    mut bodies: Query<&mut Visibility, With<PlayerBody>>,
+) {
+    for mut visibility in &mut bodies {
+        *visibility = settings.visibility();
+    }
+}
+
+/// Faces the body where the camera looks, ignoring pitch, and swings the legs with speed.
+fn animate_player_body(
+    time: Res<Time>,
+    player: Single<(&CharacterControllerState, &LinearVelocity), With<Player>>,
+    camera: Single<&Transform, (With<PlayerCamera>, Without<PlayerBody>)>,
+    body: Single<(&mut PlayerBody, &mut Transform), Without<PlayerLeg>>,
+    mut legs: Query<(&PlayerLeg, &mut Transform), (Without<PlayerBody>, Without<PlayerCamera>)>,
+) {
+    let (state, velocity) = player.into_inner();
+    let (mut body, mut body_transform) = body.into_inner();
+
+    let (yaw, _, _) = camera.rotation.to_euler(EulerRot::YXZ);
+    body_transform.rotation = Quat::from_rotation_y(yaw);
+
+    let speed = velocity.xz().length();
+    let swing = if state.grounded.is_some() {
+        body.stride += speed * STRIDE_RATE * time.delta_secs();
+        body.stride.sin() * MAX_LEG_SWING * (speed / FULL_SWING_SPEED).min(1.0)
+    } else {
+        0.0
+    };
+    for (leg, mut transform) in &mut legs {
+        transform.rotation = Quat::from_rotation_x(leg.0 * swing);
+    }
+}
@@ -22,8 +22,10 @@ use bevy::{
     prelude::*,
     render::view::Hdr,
     scene::SceneInstanceReady,
+    transform::TransformSystems,
 };
-use bevy_ahoy::camera::CharacterControllerCameraOf;
+use bevy_ahoy::{camera::CharacterControllerCameraOf, prelude::RotateCamera};
+use bevy_enhanced_input::prelude::{Action, EnhancedInputSystems};
 
 use crate::{
     CameraOrder, PostPhysicsAppSystems, RenderLayer,
@@ -40,6 +42,8 @@ use super::Player;
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<CameraSensitivity>();
     app.init_resource::<WorldModelFov>();
+    app.init_resource::<ViewModelFov>();
+    app.init_resource::<CameraLookSettings>();
 
     app.add_observer(spawn_view_model);
     app.add_observer(add_render_layers_to_point_light);
@@ -47,10 +51,24 @@ pub(super) fn plugin(app: &mut App) {
     app.add_observer(add_render_layers_to_directional_light);
     app.add_systems(
         Update,
-        update_world_model_fov
-            .run_if(resource_changed::<WorldModelFov>)
+        (
+            update_world_model_fov.run_if(resource_changed::<WorldModelFov>),
+            update_view_model_fov.run_if(resource_changed::<ViewModelFov>),
+        )
             .in_set(PostPhysicsAppSystems::Update),
     );
+    app.add_systems(
+        PreUpdate,
+        smooth_look_input
+            .after(EnhancedInputSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        PostUpdate,
+        clamp_look_pitch
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
 }
 
 /// The parent entity of the player's cameras.
@@ -64,12 +82,18 @@ pub(crate) struct PlayerCamera;
 #[require(Transform, Visibility)]
 pub(crate) struct WorldModelCamera;
 
+/// Renders the held item over the world.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct ViewModelCamera;
+
 fn spawn_view_model(
     add: On<Add, Player>,
     mut commands: Commands,
     assets: Res<AssetServer>,
     level_assets: Res<LevelAssets>,
     fov: Res<WorldModelFov>,
+    view_model_fov: Res<ViewModelFov>,
 ) {
     use bevy_seedling::spatial::SpatialListener3D;
 
@@ -188,6 +212,7 @@ fn spawn_view_model(
             // Spawn view model camera.
             parent.spawn((
                 Name::new("View Model Camera"),
+                ViewModelCamera,
                 Camera3d::default(),
                 Camera {
                     // Bump the order to render on top of the world model.
@@ -196,10 +221,7 @@ fn spawn_view_model(
                 },
                 Hdr,
                 Projection::from(PerspectiveProjection {
-                    // We use whatever FOV we set in the animation software, e.g. Blender.
-                    // Tip: if you want to set a camera in Blender to the same defaults as Bevy,
-                    // see [this issue](https://github.com/kaosat-dev/Blenvy/issues/223)
-                    fov: 62.0_f32.to_radians(),
+                    fov: view_model_fov.to_radians(),
                     ..default()
                 }),
                 // Only render objects belonging to the view model.
@@ -323,6 +345,31 @@ fn update_world_model_fov(
     perspective.fov = fov.to_radians();
 }
 
+/// FOV of the held item, kept apart from [`WorldModelFov`] so a wide world FOV doesn't
+/// stretch the gun and shovel.
+#[derive(Resource, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Resource)]
+pub(crate) struct ViewModelFov(pub(crate) f32);
+
+impl Default for ViewModelFov {
+    fn default() -> Self {
+        // We use whatever FOV we set in the animation software, e.g. Blender.
+        // Tip: if you want to set a camera in Blender to the same defaults as Bevy,
+        // see [this issue](https://github.com/kaosat-dev/Blenvy/issues/223)
+        Self(62.0)
+    }
+}
+
+fn update_view_model_fov(
+    projection: Single<&mut Projection, With<ViewModelCamera>>,
+    fov: Res<ViewModelFov>,
+) {
+    let Projection::Perspective(ref mut perspective) = *projection.into_inner() else {
+        return;
+    };
+    perspective.fov = fov.to_radians();
+}
+
 #[derive(Resource, Reflect, Debug, Deref, DerefMut)]
 #[reflect(Resource)]
 pub(crate) struct CameraSensitivity(pub(crate) Vec2);
@@ -332,3 +379,64 @@ impl Default for CameraSensitivity {
         Self(Vec2::splat(1.0))
     }
 }
+
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct CameraLookSettings {
+    /// Max look pitch in degrees, applied both up and down.
+    pub(crate) pitch_limit: f32,
+    /// Exponential smoothing of look input, 0 is raw input.
+    pub(crate) smoothing: f32,
+}
+
+impl CameraLookSettings {
+    pub(crate) const MAX_SMOOTHING: f32 = 0.9;
+}
+
+impl Default for CameraLookSettings {
+    fn default() -> Self {
+        Self {
+            pitch_limit: 89.0,
+            smoothing: 0.0,
+        }
+    }
+}
+
+/// Decay rate per second of the gap between smoothed and raw look input, scaled by
+/// `1 - smoothing`. Higher settings catch up slower, and at 0 the input isn't smoothed at all.
+const LOOK_SMOOTHING_RATE: f32 = 60.0;
+
+fn smooth_look_input(
+    mut actions: Query<&mut Action<RotateCamera>>,
+    settings: Res<CameraLookSettings>,
+    time: Res<Time>,
+    mut smoothed: Local<Vec2>,
+) {
+    if settings.smoothing <= 0.0 {
+        *smoothed = Vec2::ZERO;
+        return;
+    }
+
+    let rate = (1.0 - settings.smoothing).max(0.01) * LOOK_SMOOTHING_RATE;
+    let t = 1.0 - (-rate * time.delta_secs()).exp();
+    for mut action in &mut actions {
+        *smoothed = smoothed.lerp(**action, t);
+        **action = *smoothed;
+    }
+}
+
+/// Keep the look pitch away from the poles so the camera can't flip over.
+///
+/// Runs before transform propagation so anything reading the camera's
+/// [`GlobalTransform`] (like the gun's aim ray) sees the clamped forward.
+fn clamp_look_pitch(
+    mut camera: Single<&mut Transform, With<PlayerCamera>>,
+    settings: Res<CameraLookSettings>,
+) {
+    let (yaw, pitch, roll) = camera.rotation.to_euler(EulerRot::YXZ);
+    let limit = settings.pitch_limit.to_radians();
+    let clamped = pitch.clamp(-limit, limit);
+    if clamped != pitch {
+        camera.rotation = Quat::from_euler(EulerRot::YXZ, yaw, clamped, roll);
+    }
+}
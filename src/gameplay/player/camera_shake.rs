@@ -0,0 +1,143 @@
+//! Trauma-based camera shake for explosions, hard landings and getting hurt.
+//!
+//! Only the [`WorldModelCamera`] is offset, so the [`PlayerCamera`] it hangs off, which the
+//! aim ray and everything else looking "forward" use, stays steady.
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, transform::TransformSystems};
+
+use super::{
+    Player, PlayerHurt,
+    camera::{PlayerCamera, WorldModelCamera},
+};
+use crate::screens::Screen;
+
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.2;
+/// Rotation at full trauma, in radians, for yaw, pitch and roll.
+const MAX_ANGLES: Vec3 = Vec3::new(0.04, 0.04, 0.06);
+/// Positional offset at full trauma.
+const MAX_OFFSET: f32 = 0.08;
+/// How fast the shake wobbles.
+const SHAKE_FREQUENCY: f32 = 22.0;
+
+const HURT_TRAUMA: f32 = 0.35;
+/// Trauma from an explosion right next to the camera, fading out by [`EXPLOSION_SHAKE_RADIUS`].
+const EXPLOSION_TRAUMA: f32 = 0.9;
+const EXPLOSION_SHAKE_RADIUS: f32 = 20.0;
+/// Falling faster than this shakes the camera on landing.
+const HARD_LANDING_SPEED: f32 = 12.0;
+/// Landing speed that gives [`MAX_LANDING_TRAUMA`].
+const HEAVY_LANDING_SPEED: f32 = 30.0;
+const MAX_LANDING_TRAUMA: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CameraShake>();
+    app.init_resource::<ReduceMotion>();
+    app.add_observer(shake_on_hurt);
+    app.add_systems(OnEnter(Screen::Gameplay), reset_camera_shake);
+    app.add_systems(
+        Update,
+        shake_on_hard_landing.run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        PostUpdate,
+        apply_camera_shake
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Turns off camera shake and other screen motion that isn't the player's own doing.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct ReduceMotion(pub(crate) bool);
+
+/// How shaken the camera is, from 0 to 1. The shake itself scales with trauma², so small
+/// bumps barely register and big ones stack up quickly.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    pub(crate) fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Shakes the camera at `listener` for an explosion at `center`, less the further away.
+    pub(crate) fn add_explosion(&mut self, center: Vec3, listener: Vec3) {
+        let falloff = 1.0 - center.distance(listener) / EXPLOSION_SHAKE_RADIUS;
+        self.add_trauma(EXPLOSION_TRAUMA * falloff.max(0.0));
+    }
+
+    /// Shakes the camera for landing at `speed`, if it was hard enough.
+    pub(crate) fn add_landing(&mut self, speed: f32) {
+        let t = (speed - HARD_LANDING_SPEED) / (HEAVY_LANDING_SPEED - HARD_LANDING_SPEED);
+        if t > 0.0 {
+            self.add_trauma(MAX_LANDING_TRAUMA * t.min(1.0));
+        }
+    }
+
+    fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
+
+fn reset_camera_shake(mut shake: ResMut<CameraShake>) {
+    *shake = CameraShake::default();
+}
+
+fn shake_on_hurt(_hurt: On<PlayerHurt>, mut shake: ResMut<CameraShake>) {
+    shake.add_trauma(HURT_TRAUMA);
+}
+
+/// Compares against last frame's fall speed, since the velocity is already gone on impact.
+fn shake_on_hard_landing(
+    player: Option<Single<&LinearVelocity, With<Player>>>,
+    mut shake: ResMut<CameraShake>,
+    mut falling: Local<f32>,
+) {
+    let Some(velocity) = player else {
+        *falling = 0.0;
+        return;
+    };
+    let fall_speed = (-velocity.y).max(0.0);
+    if fall_speed < 1.0 && *falling > HARD_LANDING_SPEED {
+        shake.add_landing(*falling);
+    }
+    *falling = fall_speed;
+}
+
+/// Smooth wobble in -1..1, different for each `seed`.
+fn shake_noise(t: f32, seed: f32) -> f32 {
+    (t + seed).sin() * 0.5 + (t * 2.3 + seed * 1.7).sin() * 0.3 + (t * 4.1 + seed * 2.9).sin() * 0.2
+}
+
+pub(super) fn apply_camera_shake(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut shake: ResMut<CameraShake>,
+    mut camera: Single<&mut Transform, (With<WorldModelCamera>, Without<PlayerCamera>)>,
+) {
+    if reduce_motion.0 {
+        shake.trauma = 0.0;
+    }
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_secs()).max(0.0);
+
+    let amount = shake.shake();
+    if amount <= 0.0 {
+        camera.set_if_neq(Transform::IDENTITY);
+        return;
+    }
+    let t = time.elapsed_secs() * SHAKE_FREQUENCY;
+    let angles = MAX_ANGLES * amount;
+    camera.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        angles.x * shake_noise(t, 0.0),
+        angles.y * shake_noise(t, 10.0),
+        angles.z * shake_noise(t, 20.0),
+    );
+    camera.translation =
+        Vec3::new(shake_noise(t, 30.0), shake_noise(t, 40.0), 0.0) * MAX_OFFSET * amount;
+}
@@ -0,0 +1,279 @@
+//! Crouching, to fit through hand-dug tunnels and stay out of sight.
+//!
+//! The collider shrinks around the player's feet, so nothing pops on the transition, and the
+//! camera follows it down smoothly. Standing back up waits until there's room overhead.
+//! Dialogue, death and swimming all stand the player back up.
+
+use avian3d::prelude::*;
+use bevy::{prelude::*, transform::TransformSystems};
+use bevy_ahoy::{
+    CharacterController,
+    prelude::{WaterLevel, WaterState},
+};
+use bevy_enhanced_input::prelude::*;
+
+use super::{
+    PLAYER_HEIGHT, PLAYER_RADIUS, Player, PlayerDead, camera::PlayerCamera,
+    input::PlayerInputContext, navmesh_position::LastValidPlayerNavmeshPosition,
+};
+use crate::{game_running, screens::Screen, theme::GameFont, third_party::avian3d::CollisionLayer};
+
+/// Fits through tunnels a voxel or so high.
+pub(crate) const CROUCH_HEIGHT: f32 = 1.0;
+/// How much lower the player's center sits while crouched.
+const CROUCH_DROP: f32 = (PLAYER_HEIGHT - CROUCH_HEIGHT) / 2.0;
+const CROUCH_SECONDS: f32 = 0.15;
+const CROUCH_SPEED_MULTIPLIER: f32 = 0.6;
+/// Shrinks the standing clearance check a little so brushing a wall doesn't count.
+const CLEARANCE_RADIUS: f32 = PLAYER_RADIUS * 0.9;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CrouchSettings>();
+    app.add_observer(add_crouch_state);
+    app.add_observer(start_crouch);
+    app.add_observer(stop_crouch);
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_blocked_indicator);
+    app.add_systems(
+        Update,
+        (update_crouch, update_blocked_indicator)
+            .chain()
+            .run_if(game_running),
+    );
+    app.add_systems(
+        PostUpdate,
+        lower_crouched_camera
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct Crouch;
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub(crate) struct CrouchSettings {
+    /// Pressing crouch flips it on and off instead of having to hold it.
+    pub toggle: bool,
+}
+
+#[derive(Component, Debug, Default)]
+pub(crate) struct CrouchState {
+    wants_crouch: bool,
+    /// How far the camera has gone down, from 0 standing to 1 crouched.
+    blend: f32,
+    /// Wants to stand but there's no room overhead.
+    blocked: bool,
+}
+
+/// On the player while crouched, holds the multiplier applied to the controller speed
+/// so it can be undone exactly.
+#[derive(Component, Debug)]
+pub(crate) struct Crouched {
+    multiplier: f32,
+}
+
+fn add_crouch_state(add: On<Add, Player>, mut commands: Commands) {
+    commands.entity(add.entity).insert(CrouchState::default());
+}
+
+fn start_crouch(
+    _on: On<Start<Crouch>>,
+    settings: Res<CrouchSettings>,
+    mut state: Single<&mut CrouchState, With<Player>>,
+) {
+    state.wants_crouch = !settings.toggle || !state.wants_crouch;
+}
+
+fn stop_crouch(
+    _on: On<Complete<Crouch>>,
+    settings: Res<CrouchSettings>,
+    mut state: Single<&mut CrouchState, With<Player>>,
+) {
+    if !settings.toggle {
+        state.wants_crouch = false;
+    }
+}
+
+fn update_crouch(
+    mut commands: Commands,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    player: Single<
+        (
+            Entity,
+            &mut CrouchState,
+            &mut Transform,
+            &mut CharacterController,
+            Option<&Crouched>,
+            Option<&WaterState>,
+            Has<PlayerInputContext>,
+            Has<PlayerDead>,
+        ),
+        With<Player>,
+    >,
+    mut landmass_character: Query<
+        &mut Transform,
+        (With<LastValidPlayerNavmeshPosition>, Without<Player>),
+    >,
+) {
+    let (entity, mut state, mut transform, mut controller, crouched, water, has_input, dead) =
+        player.into_inner();
+
+    if must_stand(has_input, dead, swimming(water)) {
+        state.wants_crouch = false;
+    }
+
+    match (state.wants_crouch, crouched) {
+        (true, None) => {
+            transform.translation.y -= CROUCH_DROP;
+            controller.speed *= CROUCH_SPEED_MULTIPLIER;
+            commands.entity(entity).insert((
+                Collider::cylinder(PLAYER_RADIUS, CROUCH_HEIGHT),
+                Crouched {
+                    multiplier: CROUCH_SPEED_MULTIPLIER,
+                },
+            ));
+        }
+        (false, Some(crouched)) => {
+            let filter = SpatialQueryFilter::from_mask(CollisionLayer::Level)
+                .with_excluded_entities([entity]);
+            let blocked = spatial_query
+                .cast_shape(
+                    &Collider::cylinder(CLEARANCE_RADIUS, CROUCH_HEIGHT),
+                    transform.translation,
+                    transform.rotation,
+                    Dir3::Y,
+                    &ShapeCastConfig {
+                        ignore_origin_penetration: true,
+                        ..ShapeCastConfig::from_max_distance(CROUCH_DROP * 2.0)
+                    },
+                    &filter,
+                )
+                .is_some();
+            state.blocked = blocked;
+            if !blocked {
+                transform.translation.y += CROUCH_DROP;
+                controller.speed /= crouched.multiplier;
+                commands
+                    .entity(entity)
+                    .insert(Collider::cylinder(PLAYER_RADIUS, PLAYER_HEIGHT))
+                    .remove::<Crouched>();
+            }
+        }
+        _ => state.blocked = false,
+    }
+
+    let target = if crouched.is_some() { 1.0 } else { 0.0 };
+    state.blend = ease_blend(state.blend, target, time.delta_secs() / CROUCH_SECONDS);
+
+    // Keep the navmesh character on the floor under the shorter collider.
+    let half_height = if crouched.is_some() {
+        CROUCH_HEIGHT / 2.0
+    } else {
+        PLAYER_HEIGHT / 2.0
+    };
+    for mut character in &mut landmass_character {
+        character.translation.y = -(half_height + 0.01);
+    }
+}
+
+/// Dialogue and death take the input away, so a held crouch never gets released, and the
+/// controller swims with the full-height collider. Stand up for all of them, if there's room.
+fn must_stand(has_input: bool, dead: bool, swimming: bool) -> bool {
+    !has_input || dead || swimming
+}
+
+/// Wading is fine, the player only swims once the water is past their middle.
+fn swimming(water: Option<&WaterState>) -> bool {
+    water.is_some_and(|water| matches!(water.level, WaterLevel::Center | WaterLevel::Head))
+}
+
+fn ease_blend(blend: f32, target: f32, step: f32) -> f32 {
+    if blend < target {
+        (blend + step).min(target)
+    } else {
+        (blend - step).max(target)
+    }
+}
+
+/// The controller puts the camera back on the player's (already lowered) center every frame,
+/// this eases it the rest of the way so crouching doesn't snap the view.
+fn lower_crouched_camera(
+    player: Single<(&CrouchState, Has<Crouched>), With<Player>>,
+    mut camera: Single<&mut Transform, With<PlayerCamera>>,
+) {
+    let (state, crouched) = player.into_inner();
+    let center_drop = if crouched { CROUCH_DROP } else { 0.0 };
+    let offset = state.blend * CROUCH_DROP * 2.0 - center_drop;
+    if offset != 0.0 {
+        camera.translation.y -= offset;
+    }
+}
+
+#[derive(Component)]
+struct CrouchBlockedIndicator;
+
+/// A faint arrow under the crosshair while something overhead keeps the player crouched.
+fn spawn_blocked_indicator(mut commands: Commands, font: Res<GameFont>) {
+    commands.spawn((
+        Name::new("Crouch Blocked Indicator"),
+        CrouchBlockedIndicator,
+        Text::new("▼"),
+        TextFont {
+            font: font.0.clone(),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.4)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.0),
+            top: Val::Percent(50.0),
+            margin: UiRect {
+                left: Val::Px(-6.0),
+                top: Val::Px(28.0),
+                ..default()
+            },
+            ..default()
+        },
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_blocked_indicator(
+    player: Single<&CrouchState, With<Player>>,
+    mut indicator: Single<&mut Visibility, With<CrouchBlockedIndicator>>,
+) {
+    indicator.set_if_neq(if player.blocked {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn losing_input_dying_or_swimming_stands_up() {
+        assert!(!must_stand(true, false, false));
+        assert!(must_stand(false, false, false));
+        assert!(must_stand(true, true, false));
+        assert!(must_stand(true, false, true));
+    }
+
+    #[test]
+    fn camera_eases_down_and_back_without_overshooting() {
+        let step = 0.1 / CROUCH_SECONDS;
+        let blend = ease_blend(0.0, 1.0, step);
+        assert!(blend > 0.0 && blend < 1.0);
+        assert_eq!(ease_blend(blend, 1.0, 1.0), 1.0);
+        assert_eq!(ease_blend(1.0, 0.0, 0.25), 0.75);
+        assert_eq!(ease_blend(0.25, 0.0, 0.5), 0.0);
+    }
+}
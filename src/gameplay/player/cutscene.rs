@@ -0,0 +1,186 @@
+//! Scripted camera moves for story beats. [`PlayCutscene`] spawns a [`CutsceneCamera`] that
+//! takes the view from the player, moves it through a list of keyframes and hands it back.
+//! The player's input is blocked for the whole thing.
+//!
+//! Rather than a camera of its own, the cutscene moves the [`WorldModelCamera`] away from the
+//! [`PlayerCamera`] it hangs off, so the view keeps all of its fog, exposure and sky.
+
+use std::any::TypeId;
+
+use bevy::{prelude::*, transform::TransformSystems};
+
+use super::{
+    camera::{PlayerCamera, ViewModelCamera, WorldModelCamera},
+    camera_shake::apply_camera_shake,
+    input::BlocksInput,
+};
+use crate::screens::Screen;
+
+/// Seconds to blend back to the player's view when a cutscene doesn't say.
+const BLEND_BACK_SECONDS: f32 = 1.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(play_cutscene);
+    app.add_observer(start_cutscene);
+    app.add_observer(end_cutscene);
+    app.add_systems(
+        PostUpdate,
+        apply_cutscene_camera
+            .after(apply_camera_shake)
+            .before(TransformSystems::Propagate)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Moves the view through `keyframes`, taking `durations[i]` seconds to get to `keyframes[i]`
+/// from wherever it was before. One extra duration sets how long blending back to the
+/// player's view takes. Replaces a cutscene that's already playing.
+#[derive(Event, Debug, Clone)]
+pub(crate) struct PlayCutscene {
+    pub keyframes: Vec<Transform>,
+    pub durations: Vec<f32>,
+}
+
+/// The view during a cutscene, despawned once it's back with the player.
+#[derive(Component, Debug)]
+pub(crate) struct CutsceneCamera {
+    keyframes: Vec<Transform>,
+    durations: Vec<f32>,
+    /// The player's view when the cutscene started.
+    from: Transform,
+    elapsed: f32,
+}
+
+impl CutsceneCamera {
+    fn new(cutscene: &PlayCutscene, from: Transform) -> Self {
+        let count = cutscene.keyframes.len();
+        // Missing keyframe durations cut straight to the keyframe.
+        let durations = (0..=count)
+            .map(|i| {
+                let fallback = if i == count { BLEND_BACK_SECONDS } else { 0.0 };
+                cutscene.durations.get(i).map_or(fallback, |d| d.max(0.0))
+            })
+            .collect();
+        Self {
+            keyframes: cutscene.keyframes.clone(),
+            durations,
+            from,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Where the view is `elapsed` seconds in, blending back to `to` at the end. `None` once
+    /// it's done.
+    fn sample(&self, elapsed: f32, to: Transform) -> Option<Transform> {
+        let mut start = self.from;
+        let mut time = elapsed;
+        let ends = self.keyframes.iter().copied().chain(std::iter::once(to));
+        for (end, &duration) in ends.zip(&self.durations) {
+            if time < duration {
+                let t = time / duration;
+                let eased = t * t * (3.0 - 2.0 * t);
+                return Some(Transform {
+                    translation: start.translation.lerp(end.translation, eased),
+                    rotation: start.rotation.slerp(end.rotation, eased),
+                    scale: Vec3::ONE,
+                });
+            }
+            time -= duration;
+            start = end;
+        }
+        None
+    }
+}
+
+fn play_cutscene(
+    play: On<PlayCutscene>,
+    mut commands: Commands,
+    playing: Query<Entity, With<CutsceneCamera>>,
+    player_camera: Single<&Transform, With<PlayerCamera>>,
+) {
+    for entity in &playing {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        Name::new("Cutscene Camera"),
+        CutsceneCamera::new(&play, **player_camera),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn start_cutscene(
+    _add: On<Add, CutsceneCamera>,
+    mut blocks_input: ResMut<BlocksInput>,
+    mut view_model: Query<&mut Camera, With<ViewModelCamera>>,
+) {
+    blocks_input.insert(TypeId::of::<CutsceneCamera>());
+    for mut camera in &mut view_model {
+        camera.is_active = false;
+    }
+}
+
+fn end_cutscene(
+    _remove: On<Remove, CutsceneCamera>,
+    mut blocks_input: ResMut<BlocksInput>,
+    mut view_model: Query<&mut Camera, With<ViewModelCamera>>,
+    mut world_model: Query<&mut Transform, With<WorldModelCamera>>,
+) {
+    blocks_input.remove(&TypeId::of::<CutsceneCamera>());
+    for mut camera in &mut view_model {
+        camera.is_active = true;
+    }
+    for mut transform in &mut world_model {
+        *transform = Transform::IDENTITY;
+    }
+}
+
+/// Puts the world model camera where the cutscene wants it, relative to the player camera.
+fn apply_cutscene_camera(
+    mut commands: Commands,
+    time: Res<Time>,
+    cutscene: Option<Single<(Entity, &mut CutsceneCamera)>>,
+    player_camera: Single<&Transform, (With<PlayerCamera>, Without<WorldModelCamera>)>,
+    mut world_model: Single<&mut Transform, (With<WorldModelCamera>, Without<PlayerCamera>)>,
+) {
+    let Some(cutscene) = cutscene else {
+        return;
+    };
+    let (entity, mut cutscene) = cutscene.into_inner();
+    cutscene.elapsed += time.delta_secs();
+    let Some(view) = cutscene.sample(cutscene.elapsed, **player_camera) else {
+        commands.entity(entity).despawn();
+        return;
+    };
+    let inverse = player_camera.rotation.inverse();
+    world_model.translation = inverse * (view.translation - player_camera.translation);
+    world_model.rotation = inverse * view.rotation;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cutscene_runs_through_the_keyframes_and_back() {
+        let from = Transform::from_xyz(0.0, 0.0, 0.0);
+        let keyframe = Transform::from_xyz(10.0, 0.0, 0.0);
+        let back = Transform::from_xyz(0.0, 2.0, 0.0);
+        let cutscene = CutsceneCamera::new(
+            &PlayCutscene {
+                keyframes: vec![keyframe],
+                durations: vec![2.0],
+            },
+            from,
+        );
+
+        assert_eq!(cutscene.sample(0.0, back), Some(from));
+        let halfway = cutscene.sample(1.0, back).unwrap();
+        assert!((halfway.translation.x - 5.0).abs() < 1e-4);
+        // Blending back takes the default time, toward where the player is now.
+        let returning = cutscene
+            .sample(2.0 + BLEND_BACK_SECONDS * 0.5, back)
+            .unwrap();
+        assert!((returning.translation - Vec3::new(5.0, 1.0, 0.0)).length() < 1e-4);
+        assert_eq!(cutscene.sample(2.0 + BLEND_BACK_SECONDS, back), None);
+    }
+}
@@ -0,0 +1,109 @@
+//! Red arcs around the crosshair pointing towards whatever just hurt the player.
+
+use bevy::prelude::*;
+
+use super::{PlayerHurt, camera::PlayerCamera};
+use crate::{game_running, screens::Screen};
+
+/// Distance from the screen center to the indicator.
+const INDICATOR_RADIUS_PX: f32 = 160.0;
+const INDICATOR_WIDTH_PX: f32 = 120.0;
+const INDICATOR_HEIGHT_PX: f32 = 10.0;
+const INDICATOR_SECONDS: f32 = 1.2;
+const INDICATOR_COLOR: Color = Color::srgba(0.9, 0.05, 0.05, 0.8);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(spawn_damage_indicator);
+    app.add_systems(Update, update_damage_indicators.run_if(game_running));
+}
+
+/// Rotates around the screen center so its child arc points at `source`.
+#[derive(Component)]
+struct DamageIndicator {
+    source: Vec3,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct DamageIndicatorArc;
+
+fn spawn_damage_indicator(hurt: On<PlayerHurt>, mut commands: Commands) {
+    commands.spawn((
+        Name::new("Damage Indicator"),
+        DamageIndicator {
+            source: hurt.source,
+            timer: Timer::from_seconds(INDICATOR_SECONDS, TimerMode::Once),
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.0),
+            top: Val::Percent(50.0),
+            width: Val::Px(INDICATOR_RADIUS_PX * 2.0),
+            height: Val::Px(INDICATOR_RADIUS_PX * 2.0),
+            margin: UiRect {
+                left: Val::Px(-INDICATOR_RADIUS_PX),
+                top: Val::Px(-INDICATOR_RADIUS_PX),
+                ..default()
+            },
+            ..default()
+        },
+        // Hidden until it's been pointed the right way.
+        Visibility::Hidden,
+        UiTransform::default(),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            DamageIndicatorArc,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(INDICATOR_RADIUS_PX - INDICATOR_WIDTH_PX / 2.0),
+                width: Val::Px(INDICATOR_WIDTH_PX),
+                height: Val::Px(INDICATOR_HEIGHT_PX),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+            BackgroundColor(INDICATOR_COLOR),
+            Pickable::IGNORE,
+        )],
+    ));
+}
+
+/// Points indicators at their source relative to where the camera faces now, so they
+/// follow the player turning, and fades them out.
+fn update_damage_indicators(
+    mut commands: Commands,
+    time: Res<Time>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    mut indicators: Query<(
+        Entity,
+        &mut DamageIndicator,
+        &mut UiTransform,
+        &mut Visibility,
+        &Children,
+    )>,
+    mut arcs: Query<&mut BackgroundColor, With<DamageIndicatorArc>>,
+) {
+    let to_camera = camera.affine().inverse();
+    for (entity, mut indicator, mut transform, mut visibility, children) in &mut indicators {
+        indicator.timer.tick(time.delta());
+        if indicator.timer.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // Height is ignored, camera forward is straight up on screen.
+        let local = to_camera.transform_point3(indicator.source);
+        let angle = local.x.atan2(-local.z);
+        // UI y points down, so a positive rotation turns clockwise.
+        transform.rotation = Rot2::radians(angle);
+        *visibility = Visibility::Inherited;
+
+        let alpha = INDICATOR_COLOR.alpha() * indicator.timer.fraction_remaining();
+        for child in children {
+            if let Ok(mut color) = arcs.get_mut(*child) {
+                color.0 = INDICATOR_COLOR.with_alpha(alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Presentation while a conversation is running: letterbox bars slide in from the top and
+//! bottom, the speaker's name sits above the dialogue text, and the inventory and health HUD
+//! step aside. Everything is undone on [`DialogueCompleted`], which also fires when the
+//! dialogue is cut short by leaving gameplay or dying.
+
+use bevy::prelude::*;
+use bevy_yarnspinner::events::{DialogueCompleted, DialogueStarted, PresentLine};
+use bevy_yarnspinner_example_dialogue_view::UiRootNode;
+
+use crate::{
+    gameplay::{hud::HudVisibility, npc::dialogue_state::InConversation},
+    screens::Screen,
+    theme::GameFont,
+};
+
+/// Height of each bar as a percentage of the screen height.
+const LETTERBOX_HEIGHT: f32 = 10.0;
+const LETTERBOX_SECONDS: f32 = 0.3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_letterbox);
+    app.add_systems(Update, animate_letterbox.run_if(in_state(Screen::Gameplay)));
+    app.add_observer(add_speaker_plate);
+    app.add_observer(start_presentation);
+    app.add_observer(end_presentation);
+    app.add_observer(show_speaker_name);
+}
+
+/// Root of the letterbox bars. `progress` runs from 0 (gone) to 1 (fully in).
+#[derive(Component, Default)]
+struct Letterbox {
+    shown: bool,
+    progress: f32,
+}
+
+#[derive(Component)]
+struct LetterboxBar;
+
+/// Name of whoever is talking, above the dialogue text.
+#[derive(Component)]
+struct SpeakerPlate;
+
+fn spawn_letterbox(mut commands: Commands) {
+    let bar = |name: &'static str, top: Val, bottom: Val| {
+        (
+            Name::new(name),
+            LetterboxBar,
+            Node {
+                position_type: PositionType::Absolute,
+                top,
+                bottom,
+                width: Val::Percent(100.0),
+                height: Val::Px(0.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            Pickable::IGNORE,
+        )
+    };
+    commands.spawn((
+        Name::new("Letterbox"),
+        Letterbox::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        // Over the gameplay HUD, under the pause menu.
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![
+            bar("Letterbox Top", Val::Px(0.0), Val::Auto),
+            bar("Letterbox Bottom", Val::Auto, Val::Px(0.0)),
+        ],
+    ));
+}
+
+fn add_speaker_plate(add: On<Add, UiRootNode>, mut commands: Commands, font: Res<GameFont>) {
+    let plate = commands
+        .spawn((
+            Name::new("Speaker Plate"),
+            SpeakerPlate,
+            Node {
+                justify_self: JustifySelf::Start,
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                margin: UiRect::bottom(Val::Px(6.0)),
+                ..default()
+            },
+            Text::default(),
+            TextFont {
+                font: font.0.clone(),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+            Visibility::Hidden,
+            Pickable::IGNORE,
+        ))
+        .id();
+    // First child, so it lays out above the dialogue text.
+    commands.entity(add.entity).insert_children(0, &[plate]);
+}
+
+fn start_presentation(
+    _start: On<DialogueStarted>,
+    mut letterboxes: Query<&mut Letterbox>,
+    mut hud: ResMut<HudVisibility>,
+) {
+    for mut letterbox in &mut letterboxes {
+        letterbox.shown = true;
+    }
+    hud.in_dialogue = true;
+}
+
+fn end_presentation(
+    _complete: On<DialogueCompleted>,
+    mut letterboxes: Query<&mut Letterbox>,
+    mut plates: Query<(&mut Text, &mut Visibility), With<SpeakerPlate>>,
+    mut hud: ResMut<HudVisibility>,
+) {
+    for mut letterbox in &mut letterboxes {
+        letterbox.shown = false;
+    }
+    for (mut text, mut visibility) in &mut plates {
+        text.0.clear();
+        *visibility = Visibility::Hidden;
+    }
+    hud.in_dialogue = false;
+}
+
+fn show_speaker_name(
+    present: On<PresentLine>,
+    speakers: Query<&Name, With<InConversation>>,
+    mut plates: Query<(&mut Text, &mut Visibility), With<SpeakerPlate>>,
+) {
+    let npc_name = speakers.iter().next().map(Name::as_str);
+    let name = speaker_name(present.line.character_name(), npc_name);
+    for (mut text, mut visibility) in &mut plates {
+        match name {
+            Some(name) => {
+                text.0 = name.to_string();
+                *visibility = Visibility::Inherited;
+            }
+            None => {
+                text.0.clear();
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+/// The character name from the Yarn line if it has one, otherwise the NPC's name without the
+/// tags in parentheses that NPC names carry, like "Seal (guard, dock)".
+fn speaker_name<'a>(line_name: Option<&'a str>, npc_name: Option<&'a str>) -> Option<&'a str> {
+    if let Some(name) = line_name.map(str::trim).filter(|name| !name.is_empty()) {
+        return Some(name);
+    }
+    let name = npc_name?;
+    let name = match name.rfind(" (") {
+        Some(index) if name.ends_with(')') => &name[..index],
+        _ => name,
+    };
+    Some(name.trim()).filter(|name| !name.is_empty())
+}
+
+fn animate_letterbox(
+    time: Res<Time>,
+    mut letterboxes: Query<(&mut Letterbox, &Children)>,
+    mut bars: Query<&mut Node, With<LetterboxBar>>,
+) {
+    for (mut letterbox, children) in &mut letterboxes {
+        let target = if letterbox.shown { 1.0 } else { 0.0 };
+        if letterbox.progress == target {
+            continue;
+        }
+        let step = time.delta_secs() / LETTERBOX_SECONDS;
+        letterbox.progress = if letterbox.shown {
+            (letterbox.progress + step).min(1.0)
+        } else {
+            (letterbox.progress - step).max(0.0)
+        };
+
+        let t = letterbox.progress;
+        let eased = t * t * (3.0 - 2.0 * t);
+        for child in children.iter() {
+            if let Ok(mut node) = bars.get_mut(child) {
+                node.height = Val::Vh(LETTERBOX_HEIGHT * eased);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speaker_name_prefers_the_line_then_the_untagged_npc_name() {
+        assert_eq!(
+            speaker_name(Some("Larry"), Some("Lobster (larry)")),
+            Some("Larry")
+        );
+        assert_eq!(speaker_name(None, Some("Seal (guard, dock)")), Some("Seal"));
+        assert_eq!(speaker_name(Some(" "), Some("Lobster")), Some("Lobster"));
+        assert_eq!(speaker_name(None, None), None);
+    }
+}
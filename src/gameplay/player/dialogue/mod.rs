@@ -10,7 +10,11 @@ use bevy_yarnspinner::{events::DialogueCompleted, prelude::*};
 
 use crate::{
     PostPhysicsAppSystems,
-    gameplay::crosshair::CrosshairState,
+    gameplay::{
+        crosshair::CrosshairState,
+        game_mode::story_active,
+        npc::dialogue_state::{DialogueState, InConversation},
+    },
     screens::Screen,
     third_party::{
         avian3d::CollisionLayer,
@@ -18,10 +22,11 @@ use crate::{
     },
 };
 
+mod letterbox;
 mod ui;
 
 use super::{
-    Player,
+    Player, PlayerDead,
     camera::PlayerCamera,
     input::{BlocksInput, Interact},
     pickup::is_holding_prop,
@@ -44,14 +49,16 @@ pub(super) fn plugin(app: &mut App) {
             .in_set(DialogueSystems::UpdateOpportunity)
             .run_if(
                 in_state(Screen::Gameplay)
+                    .and(story_active)
                     .and(not(is_dialogue_running))
                     .and(not(is_holding_prop)),
             ),
     );
     app.add_observer(restore_input_context);
     app.add_observer(interact_with_dialogue);
+    app.add_observer(stop_dialogue_on_death);
 
-    app.add_plugins(ui::plugin);
+    app.add_plugins((ui::plugin, letterbox::plugin));
 }
 
 #[derive(Debug, SystemSet, Hash, Eq, PartialEq, Clone, Copy)]
@@ -77,9 +84,12 @@ fn check_for_dialogue_opportunity(
         &SpatialQueryFilter::from_mask(CollisionLayer::Character)
             .with_excluded_entities([*player_collider]),
     );
-    let node = hit
-        .and_then(|hit| q_yarn_node.get(hit.entity).ok())
-        .cloned();
+    let node = hit.and_then(|hit| {
+        q_yarn_node
+            .get(hit.entity)
+            .ok()
+            .map(|node| (hit.entity, node.clone()))
+    });
     if interaction_prompt.0 != node {
         interaction_prompt.0 = node;
     }
@@ -87,19 +97,26 @@ fn check_for_dialogue_opportunity(
 
 #[derive(Component, Default, Reflect)]
 #[reflect(Component, Default)]
-struct InteractionPrompt(Option<YarnNode>);
+struct InteractionPrompt(Option<(Entity, YarnNode)>);
 
 fn interact_with_dialogue(
     _on: On<Start<Interact>>,
+    mut commands: Commands,
     mut interaction_prompt: Single<&mut InteractionPrompt>,
+    dialogue_states: Query<&DialogueState>,
     mut dialogue_runner: Single<&mut DialogueRunner>,
     mut crosshair: Single<&mut CrosshairState>,
     mut blocks_input: ResMut<BlocksInput>,
 ) {
-    let Some(node) = interaction_prompt.0.take() else {
+    let Some((speaker, node)) = interaction_prompt.0.take() else {
         return;
     };
-    dialogue_runner.start_node(&node.yarn_node);
+    // The prompt can be a frame behind an objective hook, so go by the state as it is now.
+    let start_node = dialogue_states
+        .get(speaker)
+        .map_or(node.yarn_node.as_str(), DialogueState::effective_node);
+    dialogue_runner.start_node(start_node);
+    commands.entity(speaker).try_insert(InConversation);
     blocks_input.insert(interact_with_dialogue.type_id());
     crosshair
         .wants_free_cursor
@@ -116,3 +133,15 @@ fn restore_input_context(
         .wants_free_cursor
         .remove(&interact_with_dialogue.type_id());
 }
+
+/// Dying ends the conversation, which also undoes everything that started with it.
+fn stop_dialogue_on_death(
+    _add: On<Add, PlayerDead>,
+    dialogue_runner: Option<Single<&mut DialogueRunner>>,
+) {
+    if let Some(mut dialogue_runner) = dialogue_runner {
+        if dialogue_runner.is_running() {
+            dialogue_runner.stop();
+        }
+    }
+}
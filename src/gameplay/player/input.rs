@@ -10,8 +10,15 @@ use bevy::{
 use bevy_ahoy::prelude::*;
 use bevy_enhanced_input::prelude::{Press, *};
 
-use super::Player;
-use crate::gameplay::inventory::{SelectSlot1, SelectSlot2, SelectSlot3, UseTool};
+use super::{Player, crouch, sprint::Sprint};
+use crate::gameplay::{
+    inventory::{
+        CycleSlot, Examine, QuickSwap, SelectSlot1, SelectSlot2, SelectSlot3, SelectSlot4,
+        SelectSlot5, SelectSlot6, ToggleDigMode, UseTool,
+    },
+    item_pickup::DropItem,
+    probe::Probe,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_input_context::<PlayerInputContext>();
@@ -91,16 +98,35 @@ impl PlayerInputContext {
                     ActionSettings { consume_input: false, ..default() },
                     bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
                 ),
+                // Our own crouch rather than the controller's, see `crouch`.
                 (
-                    Action::<Crouch>::new(),
+                    Action::<crouch::Crouch>::new(),
                     ActionSettings { consume_input: false, ..default() },
                     bindings![KeyCode::ControlLeft, GamepadButton::LeftTrigger2],
                 ),
+                (
+                    Action::<Sprint>::new(),
+                    ActionSettings { consume_input: false, ..default() },
+                    bindings![KeyCode::ShiftLeft, GamepadButton::LeftThumb],
+                ),
                 (
                     Action::<SwimUp>::new(),
                     ActionSettings { consume_input: false, ..default() },
                     bindings![KeyCode::Space, GamepadButton::South],
                 ),
+                // Before the pickup actions, which consume right click.
+                (
+                    Action::<ToggleDigMode>::new(),
+                    ActionSettings { consume_input: false, ..default() },
+                    Press::default(),
+                    bindings![MouseButton::Right],
+                ),
+                (
+                    Action::<Probe>::new(),
+                    ActionSettings { consume_input: false, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::KeyR, GamepadButton::West],
+                ),
                 (
                     Action::<PullObject>::new(),
                     ActionSettings { consume_input: true, ..default() },
@@ -150,11 +176,52 @@ impl PlayerInputContext {
                     Press::default(),
                     bindings![KeyCode::Digit3],
                 ),
+                (
+                    Action::<SelectSlot4>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::Digit4],
+                ),
+                (
+                    Action::<SelectSlot5>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::Digit5],
+                ),
+                (
+                    Action::<SelectSlot6>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::Digit6],
+                ),
+                (
+                    Action::<CycleSlot>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    // Vertical scroll into the action's single axis.
+                    Bindings::spawn(Spawn((Binding::mouse_wheel(), SwizzleAxis::YXZ))),
+                ),
+                (
+                    Action::<QuickSwap>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::KeyQ],
+                ),
+                (
+                    Action::<DropItem>::new(),
+                    ActionSettings { consume_input: true, ..default() },
+                    Press::default(),
+                    bindings![KeyCode::KeyG],
+                ),
                 (
                     Action::<UseTool>::new(),
                     ActionSettings { consume_input: false, ..default() },
                     bindings![MouseButton::Left],
                 ),
+                (
+                    Action::<Examine>::new(),
+                    ActionSettings { consume_input: false, ..default() },
+                    bindings![KeyCode::KeyF],
+                ),
             ]));
     }
 }
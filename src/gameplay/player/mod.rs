@@ -18,8 +18,15 @@ use std::any::TypeId;
 use crate::{
     animation::AnimationState,
     asset_tracking::LoadResource,
-    gameplay::tags::TagIndex,
-    screens::Screen,
+    game_running,
+    gameplay::{
+        crust_pickup::SpawnCrustPickup,
+        crusts::Crusts,
+        game_mode::combat_enabled,
+        npc::{critical::critical_npc_died, shooting::EnemyProjectile},
+        surface::GroundSurface,
+        tags::{TagIndex, TagResolutionCache},
+    },
     third_party::{avian3d::CollisionLayer, bevy_trenchbroom::GetTrenchbroomModelPath as _},
 };
 
@@ -43,26 +50,66 @@ struct SpawnPoint(Vec3);
 #[derive(Component)]
 pub(crate) struct PlayerDead(pub Timer);
 
+/// How quickly the player's movement responds, applied to the controller on spawn and
+/// whenever it changes. Higher is snappier, lower is floatier.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct MovementTuning {
+    /// How quickly the player reaches the wished-for speed.
+    pub acceleration_hz: f32,
+    /// How quickly the player stops on the ground once input is released.
+    pub friction_hz: f32,
+}
+
+impl MovementTuning {
+    pub(crate) const MIN_HZ: f32 = 1.0;
+    pub(crate) const MAX_HZ: f32 = 60.0;
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            acceleration_hz: 10.0,
+            friction_hz: 30.0,
+        }
+    }
+}
+
 mod animation;
 pub(crate) mod assets;
+pub(crate) mod body;
 pub(crate) mod camera;
+pub(crate) mod camera_shake;
+pub(crate) mod crouch;
+pub(crate) mod cutscene;
+mod damage_indicator;
 pub(crate) mod dialogue;
 pub(crate) mod input;
 pub(crate) mod movement_sound;
 pub(crate) mod navmesh_position;
 pub(crate) mod pickup;
+pub(crate) mod sprint;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
         assets::plugin,
+        body::plugin,
         camera::plugin,
+        camera_shake::plugin,
+        crouch::plugin,
+        cutscene::plugin,
+        damage_indicator::plugin,
         input::plugin,
         dialogue::plugin,
         movement_sound::plugin,
         pickup::plugin,
         navmesh_position::plugin,
+        sprint::plugin,
     ));
+    app.init_resource::<DeathPenalty>();
+    app.init_resource::<SpawnProtection>();
+    app.init_resource::<MovementTuning>();
     app.add_observer(setup_player);
     app.load_asset::<Gltf>(Player::model_path());
     app.add_systems(PreUpdate, assert_only_one_player);
@@ -72,10 +119,15 @@ pub(super) fn plugin(app: &mut App) {
             push_props,
             tick_invincibility,
             respawn_fallen_player,
-            detect_player_death,
-            respawn_player,
+            detect_player_death.run_if(combat_enabled),
+            // A critical NPC's death ends the run, there's no coming back from it here.
+            respawn_player.run_if(not(critical_npc_died)),
         )
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(game_running),
+    );
+    app.add_systems(
+        Update,
+        apply_movement_tuning.run_if(resource_changed::<MovementTuning>),
     );
 }
 
@@ -105,6 +157,7 @@ fn setup_player(
     mut commands: Commands,
     archipelago: Single<Entity, With<Archipelago3d>>,
     transforms: Query<&Transform>,
+    tuning: Res<MovementTuning>,
 ) {
     let spawn_pos = transforms
         .get(add.entity)
@@ -127,8 +180,8 @@ fn setup_player(
             CharacterController {
                 jump_height: 3.5,
                 filter: filter,
-                acceleration_hz: 10.0,
-                friction_hz: 30.0,
+                acceleration_hz: tuning.acceleration_hz,
+                friction_hz: tuning.friction_hz,
                 ..default()
             },
             ColliderDensity(1_000.0),
@@ -136,6 +189,7 @@ fn setup_player(
             AnimationState::<PlayerAnimationState>::default(),
             PlayerHealth { current: 3, max: 3 },
             SpawnPoint(spawn_pos),
+            GroundSurface::default(),
             children![(
                 Name::new("Player Landmass Character"),
                 Transform::from_xyz(0.0, -PLAYER_FLOAT_HEIGHT, 0.0),
@@ -152,6 +206,16 @@ fn setup_player(
         .observe(setup_player_animations);
 }
 
+fn apply_movement_tuning(
+    tuning: Res<MovementTuning>,
+    mut controllers: Query<&mut CharacterController, With<Player>>,
+) {
+    for mut controller in &mut controllers {
+        controller.acceleration_hz = tuning.acceleration_hz;
+        controller.friction_hz = tuning.friction_hz;
+    }
+}
+
 fn assert_only_one_player(player: Populated<(), With<Player>>) {
     assert_eq!(1, player.iter().count());
 }
@@ -216,13 +280,20 @@ fn respawn_fallen_player(mut player: Query<(&mut Transform, &SpawnPoint), With<P
     }
 }
 
-/// Try to deal 1 HP of damage to the player. Returns `true` if damage was applied.
-/// Grants 1 second of invincibility on hit.
+/// The player took damage from something at `source`.
+#[derive(Event)]
+pub(crate) struct PlayerHurt {
+    pub source: Vec3,
+}
+
+/// Try to deal 1 HP of damage to the player from something at `source`.
+/// Returns `true` if damage was applied. Grants 1 second of invincibility on hit.
 pub(crate) fn hurt_player(
     commands: &mut Commands,
     entity: Entity,
     health: &mut PlayerHealth,
     invincible: Option<&Invincible>,
+    source: Vec3,
 ) -> bool {
     if invincible.is_some() {
         return false;
@@ -231,10 +302,46 @@ pub(crate) fn hurt_player(
     commands
         .entity(entity)
         .insert(Invincible(Timer::from_seconds(1.0, TimerMode::Once)));
+    commands.trigger(PlayerHurt { source });
     true
 }
 
 const RESPAWN_SECONDS: f32 = 3.0;
+/// Default invincibility after respawning, well past the second a hit grants.
+const RESPAWN_IFRAMES: f32 = 3.0;
+/// Default reach of the enemy projectiles cleared around the respawn point.
+const RESPAWN_CLEAR_RADIUS: f32 = 12.0;
+
+/// Keeps enemies still around the checkpoint from killing the player again straight away.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource)]
+pub(crate) struct SpawnProtection {
+    /// Seconds of [`Invincible`] after respawning.
+    pub seconds: f32,
+    /// Enemy projectiles this close to the respawn point are removed. 0 leaves them.
+    pub clear_radius: f32,
+}
+
+impl Default for SpawnProtection {
+    fn default() -> Self {
+        Self {
+            seconds: RESPAWN_IFRAMES,
+            clear_radius: RESPAWN_CLEAR_RADIUS,
+        }
+    }
+}
+
+/// What dying costs the player, applied when they respawn.
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[reflect(Resource)]
+pub(crate) enum DeathPenalty {
+    #[default]
+    None,
+    /// Lose this fraction of the player's crusts.
+    LoseCrusts(f32),
+    /// Drop this fraction of the player's crusts where they died, to be picked back up.
+    DropCrusts(f32),
+}
 
 fn detect_player_death(
     mut commands: Commands,
@@ -261,8 +368,13 @@ fn respawn_player(
         With<Player>,
     >,
     tag_index: Res<TagIndex>,
+    mut checkpoints: Local<TagResolutionCache>,
     global_transforms: Query<&GlobalTransform>,
     mut blocks_input: ResMut<input::BlocksInput>,
+    penalty: Res<DeathPenalty>,
+    mut crusts: ResMut<Crusts>,
+    protection: Res<SpawnProtection>,
+    projectiles: Query<(Entity, &GlobalTransform), With<EnemyProjectile>>,
 ) {
     let Ok((entity, mut dead, mut health, spawn_point, mut transform)) = player.single_mut()
     else {
@@ -275,19 +387,49 @@ fn respawn_player(
     }
 
     // Find checkpoint tagged "tutorial_spawn", fall back to SpawnPoint.
-    let respawn_pos = tag_index
-        .get("tutorial_spawn")
-        .and_then(|entities| {
-            entities
-                .iter()
-                .next()
-                .and_then(|&e| global_transforms.get(e).ok())
-        })
+    let respawn_pos = checkpoints
+        .resolve(&tag_index, "tutorial_spawn", |_| true)
+        .and_then(|e| global_transforms.get(e).ok())
         .map(|tf| tf.translation())
         .unwrap_or(spawn_point.0);
 
+    match *penalty {
+        DeathPenalty::None => {}
+        DeathPenalty::LoseCrusts(fraction) => {
+            crusts.0 -= lost_crusts(crusts.0, fraction);
+        }
+        DeathPenalty::DropCrusts(fraction) => {
+            let amount = lost_crusts(crusts.0, fraction);
+            if amount > 0 {
+                crusts.0 -= amount;
+                // Still where they died, the move to the checkpoint is below.
+                commands.trigger(SpawnCrustPickup {
+                    position: transform.translation,
+                    amount,
+                });
+            }
+        }
+    }
+
     transform.translation = respawn_pos;
     health.current = health.max;
     commands.entity(entity).remove::<(PlayerDead, Invincible)>();
+    if protection.seconds > 0.0 {
+        commands
+            .entity(entity)
+            .insert(Invincible(Timer::from_seconds(
+                protection.seconds,
+                TimerMode::Once,
+            )));
+    }
+    for (projectile, projectile_transform) in &projectiles {
+        if projectile_transform.translation().distance(respawn_pos) < protection.clear_radius {
+            commands.entity(projectile).despawn();
+        }
+    }
     blocks_input.remove(&TypeId::of::<PlayerDead>());
 }
+
+fn lost_crusts(crusts: u32, fraction: f32) -> u32 {
+    ((crusts as f32 * fraction.clamp(0.0, 1.0)).round() as u32).min(crusts)
+}
@@ -2,7 +2,15 @@ use std::time::Duration;
 
 use super::{Player, assets::PlayerAssets};
 use crate::audio::SpatialPool;
-use crate::{PostPhysicsAppSystems, screens::Screen};
+use crate::{
+    PostPhysicsAppSystems,
+    asset_tracking::PickLoaded as _,
+    gameplay::{
+        dig::{VOXEL_SIZE, footpath::Footstep},
+        surface::GroundSurface,
+    },
+    screens::Screen,
+};
 use avian3d::prelude::LinearVelocity;
 use bevy::prelude::*;
 use bevy_ahoy::prelude::*;
@@ -21,6 +29,7 @@ fn play_jump_grunt(
     mut commands: Commands,
     player: Single<(Entity, &CharacterControllerState), With<Player>>,
     mut player_assets: ResMut<PlayerAssets>,
+    asset_server: Res<AssetServer>,
     mut is_jumping: Local<bool>,
     mut sound_cooldown: Local<Option<Timer>>,
     time: Res<Time>,
@@ -42,27 +51,35 @@ fn play_jump_grunt(
 
     if sound_cooldown.is_finished() {
         let rng = &mut rand::rng();
-        let grunt = player_assets.jump_grunts.pick(rng).clone();
-        let jump_start = player_assets.jump_start_sounds.pick(rng).clone();
+        let grunt = player_assets.jump_grunts.pick_loaded(rng, &asset_server);
+        let jump_start = player_assets
+            .jump_start_sounds
+            .pick_loaded(rng, &asset_server);
 
-        commands.entity(entity).with_child((
-            SamplePlayer::new(grunt),
-            SpatialPool,
-            Transform::default(),
-        ));
-        commands.entity(entity).with_child((
-            SamplePlayer::new(jump_start),
-            SpatialPool,
-            Transform::default(),
-        ));
+        for sound in grunt.into_iter().chain(jump_start) {
+            commands.entity(entity).with_child((
+                SamplePlayer::new(sound),
+                SpatialPool,
+                Transform::default(),
+            ));
+        }
         sound_cooldown.reset();
     }
 }
 
 fn play_step_sound(
     mut commands: Commands,
-    player: Single<(Entity, &CharacterControllerState, &LinearVelocity), With<Player>>,
+    player: Single<
+        (
+            Entity,
+            &CharacterControllerState,
+            &LinearVelocity,
+            &GroundSurface,
+        ),
+        With<Player>,
+    >,
     mut player_assets: ResMut<PlayerAssets>,
+    asset_server: Res<AssetServer>,
     time: Res<Time>,
     mut timer: Local<Option<Timer>>,
 ) {
@@ -73,15 +90,23 @@ fn play_step_sound(
         return;
     }
 
-    let (entity, state, linear_velocity) = player.into_inner();
-    if state.grounded.is_none() {
+    let (entity, state, linear_velocity, ground) = player.into_inner();
+    let Some(hit) = &state.grounded else {
         return;
-    }
+    };
     if linear_velocity.length_squared() < 5.0 {
         return;
     }
+    commands.trigger(Footstep {
+        point: hit.point1 - hit.normal1 * VOXEL_SIZE * 0.5,
+    });
     let rng = &mut rand::rng();
-    let sound = player_assets.steps.pick(rng).clone();
+    let Some(sound) = player_assets
+        .steps_on(ground.0)
+        .pick_loaded(rng, &asset_server)
+    else {
+        return;
+    };
     commands.entity(entity).with_child((
         SamplePlayer::new(sound),
         SpatialPool,
@@ -91,11 +116,12 @@ fn play_step_sound(
 
 fn play_land_sound(
     mut commands: Commands,
-    player: Single<(Entity, &CharacterControllerState), With<Player>>,
+    player: Single<(Entity, &CharacterControllerState, &GroundSurface), With<Player>>,
     mut player_assets: ResMut<PlayerAssets>,
+    asset_server: Res<AssetServer>,
     mut was_airborne: Local<bool>,
 ) {
-    let (entity, state) = player.into_inner();
+    let (entity, state, ground) = player.into_inner();
     let is_airborne = state.grounded.is_none();
     if is_airborne {
         *was_airborne = true;
@@ -107,7 +133,12 @@ fn play_land_sound(
     *was_airborne = false;
 
     let rng = &mut rand::rng();
-    let sound = player_assets.land_sounds.pick(rng).clone();
+    let Some(sound) = player_assets
+        .land_sounds_on(ground.0)
+        .pick_loaded(rng, &asset_server)
+    else {
+        return;
+    };
     commands.entity(entity).with_child((
         SamplePlayer::new(sound),
         SpatialPool,
@@ -0,0 +1,175 @@
+//! Sprinting, paid for with a stamina pool that drains while running and regenerates on the ground.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_ahoy::{CharacterController, CharacterControllerState};
+use bevy_enhanced_input::prelude::*;
+
+use super::{Player, input::PlayerInputContext};
+use crate::{game_running, gameplay::inventory::UseTool};
+
+/// Below this horizontal speed the player counts as standing still and doesn't drain stamina.
+const MIN_SPRINT_SPEED: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PlayerMovementSettings>();
+    app.add_observer(add_stamina);
+    app.add_observer(start_sprint);
+    app.add_observer(stop_sprint);
+    app.add_observer(interrupt_sprint);
+    app.add_systems(Update, update_sprint.run_if(game_running));
+}
+
+/// Sprint and stamina tuning, meant to be overridden by levels and difficulty.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub(crate) struct PlayerMovementSettings {
+    /// Multiplier on the controller speed while sprinting.
+    pub sprint_multiplier: f32,
+    pub max_stamina: f32,
+    /// Stamina drained per second of sprinting.
+    pub stamina_drain: f32,
+    /// Stamina regained per second once `stamina_regen_delay` has passed.
+    pub stamina_regen: f32,
+    /// Seconds after sprinting before stamina starts regenerating.
+    pub stamina_regen_delay: f32,
+    /// After running out, sprinting stays disabled until stamina climbs past this.
+    pub sprint_recover_threshold: f32,
+}
+
+impl Default for PlayerMovementSettings {
+    fn default() -> Self {
+        Self {
+            sprint_multiplier: 1.6,
+            max_stamina: 100.0,
+            stamina_drain: 20.0,
+            stamina_regen: 15.0,
+            stamina_regen_delay: 1.0,
+            sprint_recover_threshold: 20.0,
+        }
+    }
+}
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct Sprint;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub(crate) struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// Seconds since stamina was last drained.
+    since_drain: f32,
+    /// Ran out and hasn't recovered past the threshold yet.
+    exhausted: bool,
+    /// Sprint is held, cleared by using a tool until it's pressed again.
+    wants_sprint: bool,
+}
+
+impl Stamina {
+    fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            since_drain: 0.0,
+            exhausted: false,
+            wants_sprint: false,
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.max.max(1.0)).clamp(0.0, 1.0)
+    }
+}
+
+/// On the player while sprinting, holds the multiplier applied to the controller speed
+/// so it can be undone exactly.
+#[derive(Component, Debug)]
+pub(crate) struct Sprinting {
+    multiplier: f32,
+}
+
+fn add_stamina(
+    add: On<Add, Player>,
+    mut commands: Commands,
+    settings: Res<PlayerMovementSettings>,
+) {
+    commands
+        .entity(add.entity)
+        .insert(Stamina::new(settings.max_stamina));
+}
+
+fn start_sprint(_on: On<Start<Sprint>>, mut stamina: Single<&mut Stamina, With<Player>>) {
+    stamina.wants_sprint = true;
+}
+
+fn stop_sprint(_on: On<Complete<Sprint>>, mut stamina: Single<&mut Stamina, With<Player>>) {
+    stamina.wants_sprint = false;
+}
+
+/// Using a tool drops the player back to walking.
+fn interrupt_sprint(_on: On<Start<UseTool>>, mut stamina: Single<&mut Stamina, With<Player>>) {
+    stamina.wants_sprint = false;
+}
+
+fn update_sprint(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<PlayerMovementSettings>,
+    player: Single<
+        (
+            Entity,
+            &mut Stamina,
+            &mut CharacterController,
+            &CharacterControllerState,
+            &LinearVelocity,
+            Option<&Sprinting>,
+            Has<PlayerInputContext>,
+        ),
+        With<Player>,
+    >,
+) {
+    let (entity, mut stamina, mut controller, state, velocity, sprinting, has_input) =
+        player.into_inner();
+    let dt = time.delta_secs();
+
+    if stamina.exhausted && stamina.current > settings.sprint_recover_threshold {
+        stamina.exhausted = false;
+    }
+    // Input is removed while blocked, so the release never arrives.
+    if !has_input {
+        stamina.wants_sprint = false;
+    }
+
+    let moving = velocity.xz().length() > MIN_SPRINT_SPEED;
+    let sprint = stamina.wants_sprint && !stamina.exhausted;
+    if sprint && moving {
+        stamina.current = (stamina.current - settings.stamina_drain * dt).max(0.0);
+        stamina.since_drain = 0.0;
+        if stamina.current <= 0.0 {
+            stamina.exhausted = true;
+        }
+    } else {
+        stamina.since_drain += dt;
+        let grounded = state.grounded.is_some();
+        if grounded && stamina.since_drain >= settings.stamina_regen_delay {
+            stamina.current = (stamina.current + settings.stamina_regen * dt).min(stamina.max);
+        }
+    }
+
+    let sprint = sprint && !stamina.exhausted;
+    match (sprint, sprinting) {
+        (true, None) => {
+            controller.speed *= settings.sprint_multiplier;
+            commands.entity(entity).insert(Sprinting {
+                multiplier: settings.sprint_multiplier,
+            });
+        }
+        (false, Some(sprinting)) => {
+            controller.speed /= sprinting.multiplier;
+            commands.entity(entity).remove::<Sprinting>();
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,233 @@
+//! Practice range: dummies that soak up damage and report DPS, for tuning guns and upgrades.
+
+use avian3d::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    asset_tracking::LoadResource,
+    game_running,
+    gameplay::{
+        game_mode::GameMode,
+        hud::{HudAnchor, HudSlot},
+        npc::{Health, NpcDead, shooting::Faction},
+        player::Player,
+    },
+    screens::Screen,
+    theme::GameFont,
+    third_party::avian3d::CollisionLayer,
+};
+
+const DUMMY_COUNT: usize = 3;
+/// Enough to stand up to a long burst before needing a reset.
+const DUMMY_HEALTH: f32 = 10_000.0;
+const DUMMY_RADIUS: f32 = 0.4;
+const DUMMY_HEIGHT: f32 = 1.2;
+/// How far in front of the player's spawn the dummies stand.
+const DUMMY_DISTANCE: f32 = 10.0;
+const DUMMY_SPACING: f32 = 3.0;
+/// Dummies don't belong to anyone, so every faction's shots count.
+const DUMMY_FACTION: &str = "dummy";
+/// DPS is averaged over the hits in this window.
+const DPS_WINDOW: f32 = 5.0;
+const RESET_KEY: KeyCode = KeyCode::F5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<PracticeAssets>();
+    app.add_observer(spawn_practice_range);
+    app.add_observer(setup_dummy);
+    app.add_systems(
+        Update,
+        (
+            track_dummy_damage.run_if(game_running),
+            reset_dummies.run_if(game_running.and(input_just_pressed(RESET_KEY))),
+            update_practice_stats.run_if(in_state(Screen::Gameplay)),
+        )
+            .chain()
+            .run_if(resource_equals(GameMode::Practice)),
+    );
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct PracticeAssets {
+    dummy: Handle<Mesh>,
+    paint: Handle<StandardMaterial>,
+}
+
+impl FromWorld for PracticeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let dummy = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Capsule3d::new(DUMMY_RADIUS, DUMMY_HEIGHT));
+        let paint = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(Color::srgb(0.75, 0.65, 0.45));
+        Self { dummy, paint }
+    }
+}
+
+/// Static target that never fights back. Respawned at `home` when the range is reset.
+#[derive(Component)]
+pub(crate) struct PracticeDummy {
+    index: usize,
+    home: Transform,
+}
+
+/// Damage a dummy has taken since the last reset.
+#[derive(Component)]
+struct DamageLog {
+    last_health: f32,
+    /// `(time, damage)` of recent hits, pruned to `DPS_WINDOW`.
+    recent: Vec<(f32, f32)>,
+    last_hit: f32,
+    total: f32,
+    hits: u32,
+}
+
+impl DamageLog {
+    fn new(health: f32) -> Self {
+        Self {
+            last_health: health,
+            recent: Vec::new(),
+            last_hit: 0.0,
+            total: 0.0,
+            hits: 0,
+        }
+    }
+
+    fn dps(&self, now: f32) -> f32 {
+        let Some(&(first, _)) = self.recent.first() else {
+            return 0.0;
+        };
+        let damage: f32 = self.recent.iter().map(|(_, damage)| damage).sum();
+        // At least a second, so a single hit doesn't read as huge DPS.
+        damage / (now - first).clamp(1.0, DPS_WINDOW)
+    }
+}
+
+#[derive(Component)]
+struct PracticeStats;
+
+fn spawn_practice_range(
+    add: On<Add, Player>,
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    transforms: Query<&Transform>,
+    existing: Query<(), With<PracticeDummy>>,
+    font: Res<GameFont>,
+) {
+    if *mode != GameMode::Practice || !existing.is_empty() {
+        return;
+    }
+    let Ok(spawn) = transforms.get(add.entity) else {
+        return;
+    };
+    let forward = spawn
+        .forward()
+        .as_vec3()
+        .with_y(0.0)
+        .normalize_or(Vec3::NEG_Z);
+    let right = forward.cross(Vec3::Y);
+    let center = spawn.translation + forward * DUMMY_DISTANCE;
+
+    for index in 0..DUMMY_COUNT {
+        let offset = index as f32 - (DUMMY_COUNT - 1) as f32 / 2.0;
+        let home = Transform::from_translation(center + right * offset * DUMMY_SPACING)
+            .looking_to(-forward, Vec3::Y);
+        commands.spawn(PracticeDummy { index, home });
+    }
+
+    commands.spawn((
+        Name::new("Practice Stats"),
+        PracticeStats,
+        // Under the crusts and objectives.
+        HudSlot::new(HudAnchor::TopLeft, 2),
+        Text::default(),
+        TextFont {
+            font: font.0.clone(),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextShadow::default(),
+        Pickable::IGNORE,
+    ));
+}
+
+fn setup_dummy(
+    add: On<Add, PracticeDummy>,
+    mut commands: Commands,
+    dummies: Query<&PracticeDummy>,
+    assets: Res<PracticeAssets>,
+) {
+    let Ok(dummy) = dummies.get(add.entity) else {
+        return;
+    };
+    commands.entity(add.entity).insert((
+        Name::new(format!("Practice Dummy {}", dummy.index + 1)),
+        dummy.home,
+        Visibility::default(),
+        Health(DUMMY_HEALTH),
+        DamageLog::new(DUMMY_HEALTH),
+        Faction(DUMMY_FACTION.to_string()),
+        RigidBody::Static,
+        Collider::capsule(DUMMY_RADIUS, DUMMY_HEIGHT),
+        CollisionLayers::new(CollisionLayer::Character, LayerMask::ALL),
+        Mesh3d(assets.dummy.clone()),
+        MeshMaterial3d(assets.paint.clone()),
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn track_dummy_damage(
+    time: Res<Time>,
+    mut dummies: Query<(&Health, &mut DamageLog), Changed<Health>>,
+) {
+    let now = time.elapsed_secs();
+    for (health, mut log) in &mut dummies {
+        let damage = log.last_health - health.0;
+        log.last_health = health.0;
+        if damage <= 0.0 {
+            continue;
+        }
+        log.recent.push((now, damage));
+        log.last_hit = damage;
+        log.total += damage;
+        log.hits += 1;
+    }
+}
+
+/// Respawns every dummy fresh, which also brings back any that got shot to death.
+fn reset_dummies(mut commands: Commands, dummies: Query<(Entity, &PracticeDummy)>) {
+    for (entity, dummy) in &dummies {
+        commands.entity(entity).despawn();
+        commands.spawn(PracticeDummy {
+            index: dummy.index,
+            home: dummy.home,
+        });
+    }
+}
+
+fn update_practice_stats(
+    time: Res<Time>,
+    mut dummies: Query<(&PracticeDummy, &mut DamageLog), Without<NpcDead>>,
+    mut stats: Single<&mut Text, With<PracticeStats>>,
+) {
+    let now = time.elapsed_secs();
+    let mut rows: Vec<_> = dummies.iter_mut().collect();
+    rows.sort_by_key(|(dummy, _)| dummy.index);
+
+    let mut text = format!("practice range ({RESET_KEY:?} to reset)");
+    for (dummy, mut log) in rows {
+        log.recent.retain(|&(time, _)| now - time <= DPS_WINDOW);
+        text.push_str(&format!(
+            "\ndummy {}: {:.0} last, {:.1} dps, {:.0} total, {} hits",
+            dummy.index + 1,
+            log.last_hit,
+            log.dps(now),
+            log.total,
+            log.hits,
+        ));
+    }
+    stats.0 = text;
+}
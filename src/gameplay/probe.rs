@@ -0,0 +1,244 @@
+//! Shovel probe that pings graves and bodies buried under undisturbed terrain.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_seedling::prelude::*;
+
+use crate::{
+    asset_tracking::LoadResource,
+    audio::SpatialPool,
+    game_running,
+    gameplay::{
+        dig::VoxelSim,
+        grave::{GraveCenter, GraveSensor, GraveState},
+        inventory::{Inventory, Item},
+        npc::Body,
+        player::{
+            Player,
+            camera::{PlayerCamera, WorldModelCamera},
+        },
+    },
+    screens::Screen,
+    third_party::avian3d::CollisionLayer,
+};
+
+const PROBE_COOLDOWN: f32 = 2.0;
+/// How far from the camera the probe can be pushed into the ground.
+const PROBE_REACH: f32 = 3.0;
+/// How far the probe continues past the surface.
+const PROBE_DEPTH: f32 = 4.0;
+/// Objects within this distance of the probe line get pinged.
+const PROBE_RADIUS: f32 = 2.0;
+/// Tilts the probe down from where the player is looking.
+const PROBE_DOWNWARD_BIAS: f32 = 0.5;
+/// How far below the player's center to look for a voxel volume to stand on.
+const GROUND_CHECK_DISTANCE: f32 = 1.5;
+const PROBE_MAX_HITS: u32 = 8;
+
+/// Pitch of the thunk right at the probe, dropping to `FAR_PITCH` at the edge of its range.
+const CLOSE_PITCH: f64 = 1.4;
+const FAR_PITCH: f64 = 0.8;
+const EMPTY_PITCH: f64 = 0.6;
+
+const PING_SECONDS: f32 = 0.8;
+/// Ring size in pixels as it starts and ends.
+const PING_START_SIZE: f32 = 24.0;
+const PING_END_SIZE: f32 = 96.0;
+const PING_ALPHA: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.load_resource::<ProbeAssets>();
+    app.add_observer(probe_terrain);
+    app.add_systems(Update, update_probe_pings.run_if(game_running));
+}
+
+#[derive(Debug, InputAction)]
+#[action_output(bool)]
+pub(crate) struct Probe;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct ProbeAssets {
+    #[dependency]
+    thunk: Handle<AudioSample>,
+    #[dependency]
+    empty: Handle<AudioSample>,
+}
+
+impl FromWorld for ProbeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            thunk: assets.load("audio/sound_effects/land/Footsteps_Rock_Jump_Land_01.ogg"),
+            empty: assets.load("audio/sound_effects/dig/dig-1.ogg"),
+        }
+    }
+}
+
+/// Screen space ring over a detected object, fading out as it grows.
+#[derive(Component)]
+struct ProbePing {
+    position: Vec3,
+    timer: Timer,
+}
+
+fn probe_terrain(
+    _on: On<Start<Probe>>,
+    mut commands: Commands,
+    time: Res<Time>,
+    mut last_probe: Local<Option<f32>>,
+    inventory: Res<Inventory>,
+    assets: Res<ProbeAssets>,
+    player: Single<(Entity, &GlobalTransform), With<Player>>,
+    camera: Single<&GlobalTransform, With<PlayerCamera>>,
+    spatial_query: SpatialQuery,
+    voxel_sims: Query<(), With<VoxelSim>>,
+    targets: Query<(&GlobalTransform, Option<&GraveCenter>), Or<(With<GraveState>, With<Body>)>>,
+    sensors: Query<&GraveSensor>,
+    parents: Query<&ChildOf>,
+) {
+    if !matches!(inventory.active_item(), Some(Item::Shovel(..))) {
+        return;
+    }
+    let now = time.elapsed_secs();
+    if last_probe.is_some_and(|last| now - last < PROBE_COOLDOWN) {
+        return;
+    }
+
+    let (player_entity, player_transform) = *player;
+    let level = SpatialQueryFilter::from_mask(CollisionLayer::Level)
+        .with_excluded_entities([player_entity]);
+    let standing_on_voxels = spatial_query
+        .cast_ray(
+            player_transform.translation(),
+            Dir3::NEG_Y,
+            GROUND_CHECK_DISTANCE,
+            true,
+            &level,
+        )
+        .is_some_and(|hit| voxel_sims.contains(hit.entity));
+    if !standing_on_voxels {
+        return;
+    }
+    *last_probe = Some(now);
+
+    let origin = camera.translation();
+    let Ok(direction) = Dir3::new(camera.forward().as_vec3() + Vec3::NEG_Y * PROBE_DOWNWARD_BIAS)
+    else {
+        return;
+    };
+    // The probe goes through the first bit of ground it hits, so start from there.
+    let entry = spatial_query
+        .cast_ray(origin, direction, PROBE_REACH, true, &level)
+        .filter(|hit| voxel_sims.contains(hit.entity))
+        .map(|hit| origin + direction * hit.distance);
+
+    let found = entry.and_then(|entry| {
+        let filter =
+            SpatialQueryFilter::from_mask([CollisionLayer::Sensor, CollisionLayer::Ragdoll]);
+        spatial_query
+            .shape_hits(
+                &Collider::sphere(PROBE_RADIUS),
+                entry,
+                Quat::IDENTITY,
+                direction,
+                PROBE_MAX_HITS,
+                &ShapeCastConfig::from_max_distance(PROBE_DEPTH),
+                &filter,
+            )
+            .into_iter()
+            .filter_map(|hit| {
+                // Sensors point at their grave, body colliders can sit on children of the body.
+                let start = sensors
+                    .get(hit.entity)
+                    .map_or(hit.entity, |sensor| sensor.0);
+                let (transform, center) =
+                    std::iter::successors(Some(start), |&e| parents.get(e).ok().map(|p| p.0))
+                        .find_map(|e| targets.get(e).ok())?;
+                // Graves are brush entities, their transform sits at the map origin.
+                let position = center.map_or(transform.translation(), |center| center.0);
+                Some((position, position.distance(entry)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    });
+
+    let Some((position, distance)) = found else {
+        commands.spawn((
+            SamplePlayer::new(assets.empty.clone()),
+            PlaybackSettings {
+                speed: EMPTY_PITCH,
+                ..default()
+            },
+            SpatialPool,
+            Transform::from_translation(entry.unwrap_or(origin)),
+        ));
+        return;
+    };
+
+    let proximity = 1.0 - (distance / (PROBE_DEPTH + PROBE_RADIUS)).clamp(0.0, 1.0);
+    commands.spawn((
+        SamplePlayer::new(assets.thunk.clone()),
+        PlaybackSettings {
+            speed: FAR_PITCH + (CLOSE_PITCH - FAR_PITCH) * proximity as f64,
+            ..default()
+        },
+        SpatialPool,
+        Transform::from_translation(position),
+    ));
+    commands.spawn((
+        Name::new("Probe Ping"),
+        ProbePing {
+            position,
+            timer: Timer::from_seconds(PING_SECONDS, TimerMode::Once),
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(PING_START_SIZE),
+            height: Val::Px(PING_START_SIZE),
+            border: UiRect::all(Val::Px(2.0)),
+            border_radius: BorderRadius::MAX,
+            ..default()
+        },
+        BorderColor::all(Color::WHITE.with_alpha(PING_ALPHA)),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn update_probe_pings(
+    mut commands: Commands,
+    time: Res<Time>,
+    camera: Single<(&Camera, &GlobalTransform), With<WorldModelCamera>>,
+    mut pings: Query<(
+        Entity,
+        &mut ProbePing,
+        &mut Node,
+        &mut BorderColor,
+        &mut Visibility,
+    )>,
+) {
+    let (camera, camera_transform) = *camera;
+    for (entity, mut ping, mut node, mut border, mut visibility) in &mut pings {
+        ping.timer.tick(time.delta());
+        if ping.timer.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Drawn in screen space so the ring faces the camera and shows through the ground.
+        let Ok(center) = camera.world_to_viewport(camera_transform, ping.position) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let t = ping.timer.fraction();
+        let size = PING_START_SIZE.lerp(PING_END_SIZE, t);
+        node.width = Val::Px(size);
+        node.height = Val::Px(size);
+        node.left = Val::Px(center.x - size * 0.5);
+        node.top = Val::Px(center.y - size * 0.5);
+        *border = BorderColor::all(Color::WHITE.with_alpha(PING_ALPHA * (1.0 - t)));
+    }
+}
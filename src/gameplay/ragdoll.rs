@@ -32,6 +32,15 @@ pub(crate) struct RagdollConfig {
     pub swing_limit: f32,
     pub twist_limit: f32,
     pub damping: f32,
+    /// Limits for joints whose name contains the key, checked in order so more
+    /// specific keys go first. Joints that don't match use `swing_limit`/`twist_limit`.
+    pub joint_limits: Vec<(String, JointLimits)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct JointLimits {
+    pub swing: f32,
+    pub twist: f32,
 }
 
 impl Default for RagdollConfig {
@@ -41,10 +50,48 @@ impl Default for RagdollConfig {
             swing_limit: 0.8,
             twist_limit: 0.4,
             damping: 2.0,
+            joint_limits: lobster_joint_limits(),
         }
     }
 }
 
+/// Limits for the joints of `models/lobster/lowpoly_lobster.glb`.
+fn lobster_joint_limits() -> Vec<(String, JointLimits)> {
+    let limits =
+        |key: &str, swing: f32, twist: f32| (key.to_string(), JointLimits { swing, twist });
+    vec![
+        // Before "Tail" and "Arm." which they contain.
+        limits("TailFan", 0.5, 0.1),
+        limits("ForeArm", 0.5, 0.1),
+        limits("Abdomen", 0.35, 0.1),
+        limits("Tail", 0.35, 0.1),
+        limits("WalkerLeg", 0.6, 0.2),
+        // Knees and feet only bend one way, don't let them spin.
+        limits("WalkerFore", 0.3, 0.05),
+        limits("Feet", 0.3, 0.05),
+        limits("Cheli", 0.25, 0.1),
+        limits("Arm.", 1.0, 0.4),
+        limits("eye", 0.2, 0.05),
+        // Antennae are meant to be floppy.
+        limits("Ant.", 1.2, 0.6),
+    ]
+}
+
+impl RagdollConfig {
+    fn limits_for(&self, joint_name: &str) -> JointLimits {
+        self.joint_limits
+            .iter()
+            .find(|(key, _)| joint_name.contains(key.as_str()))
+            .map_or(
+                JointLimits {
+                    swing: self.swing_limit,
+                    twist: self.twist_limit,
+                },
+                |(_, limits)| *limits,
+            )
+    }
+}
+
 #[derive(Component)]
 pub(crate) struct RagdollCore;
 
@@ -109,6 +156,7 @@ fn create_ragdolls(
     mesh_handles: Query<&Mesh3d>,
     meshes: Res<Assets<Mesh>>,
     globals: Query<&GlobalTransform>,
+    names: Query<&Name>,
 ) {
     for (npc_entity, config) in &ragdoll_requests {
         // Find skinned mesh entity
@@ -264,13 +312,18 @@ fn create_ragdolls(
 
             // Anchor on parent: offset from parent joint to child joint (world-aligned at spawn)
             let parent_anchor = captured[child_idx].translation - captured[parent_idx].translation;
+            let limits = config.limits_for(
+                names
+                    .get(joints[child_idx])
+                    .map_or("", |name| name.as_str()),
+            );
 
             commands.spawn((
                 SphericalJoint::new(parent_body, child_body)
                     .with_local_anchor1(parent_anchor)
                     .with_local_anchor2(Vec3::ZERO)
-                    .with_swing_limits(-config.swing_limit, config.swing_limit)
-                    .with_twist_limits(-config.twist_limit, config.twist_limit),
+                    .with_swing_limits(-limits.swing, limits.swing)
+                    .with_twist_limits(-limits.twist, limits.twist),
                 JointDamping {
                     linear: config.damping,
                     angular: config.damping,
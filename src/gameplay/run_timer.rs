@@ -0,0 +1,260 @@
+//! Speedrun timer with a split at every completed sub-objective.
+//!
+//! Only game time counts, so pauses and menus are left out. The best time at each split is
+//! saved next to the game and the split shows the difference against it.
+
+use std::{collections::HashMap, time::Duration};
+
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+
+use crate::{
+    PostPhysicsAppSystems, game_running,
+    gameplay::{
+        hud::{HudElement, HudSettings},
+        objective::SubObjectiveCompleted,
+    },
+    persistence,
+    screens::Screen,
+    theme::GameFont,
+};
+
+const BEST_SPLITS_PATH: &str = "splits.bin";
+const SPLIT_SECONDS: f32 = 3.0;
+const AHEAD_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+const BEHIND_COLOR: Color = Color::srgb(0.95, 0.3, 0.3);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunTimer>();
+    app.init_resource::<BestSplits>();
+    app.add_observer(record_split);
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_run_timer, spawn_run_timer_hud),
+    );
+    app.add_systems(
+        Update,
+        (
+            tick_run_timer
+                .run_if(game_running)
+                .in_set(PostPhysicsAppSystems::TickTimers),
+            update_run_timer_hud
+                .run_if(in_state(Screen::Gameplay))
+                .in_set(PostPhysicsAppSystems::ChangeUi),
+        ),
+    );
+}
+
+/// Game time since the run started. Kept across deaths, reset on a new game.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct RunTimer {
+    elapsed: Duration,
+    last_split: Option<LastSplit>,
+}
+
+#[derive(Debug)]
+struct LastSplit {
+    time: Duration,
+    /// Time at the same split in the best run before this one.
+    best: Option<Duration>,
+    shown: Timer,
+}
+
+/// Fastest time seen at each split, keyed by `objective/sub_objective`.
+#[derive(Resource, Encode, Decode, Default, Debug)]
+struct BestSplits {
+    splits: HashMap<String, Duration>,
+}
+
+impl FromWorld for BestSplits {
+    fn from_world(_world: &mut World) -> Self {
+        persistence::load(BEST_SPLITS_PATH, "best splits").unwrap_or_default()
+    }
+}
+
+impl BestSplits {
+    fn save(&self) {
+        persistence::save(BEST_SPLITS_PATH, "best splits", self);
+    }
+}
+
+#[derive(Component)]
+struct RunTimerClock;
+
+#[derive(Component)]
+struct RunTimerSplit;
+
+fn reset_run_timer(mut timer: ResMut<RunTimer>) {
+    *timer = RunTimer::default();
+}
+
+/// Sums the virtual delta, which is zero while paused, so the total matches unpaused frames.
+fn tick_run_timer(time: Res<Time<Virtual>>, mut timer: ResMut<RunTimer>) {
+    timer.elapsed += time.delta();
+    if let Some(split) = &mut timer.last_split {
+        split.shown.tick(time.delta());
+    }
+}
+
+fn record_split(
+    completed: On<SubObjectiveCompleted>,
+    mut timer: ResMut<RunTimer>,
+    mut best_splits: ResMut<BestSplits>,
+) {
+    // Skipped sub-objectives would set best times nobody actually ran.
+    if completed.forced {
+        return;
+    }
+    let key = format!("{}/{}", completed.objective, completed.sub_objective);
+    let time = timer.elapsed;
+    let best = best_splits.splits.get(&key).copied();
+    timer.last_split = Some(LastSplit {
+        time,
+        best,
+        shown: Timer::from_seconds(SPLIT_SECONDS, TimerMode::Once),
+    });
+
+    if best.is_none_or(|best| time < best) {
+        best_splits.splits.insert(key, time);
+        best_splits.save();
+    }
+}
+
+fn spawn_run_timer_hud(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    hud_settings: Res<HudSettings>,
+) {
+    let text_font = TextFont {
+        font: font.0.clone(),
+        font_size: 24.0,
+        ..default()
+    };
+    commands.spawn((
+        Name::new("Run Timer HUD"),
+        HudElement::Timer,
+        hud_settings.slot(HudElement::Timer, 1),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(12.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                RunTimerSplit,
+                Text::default(),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ),
+            (
+                RunTimerClock,
+                Text::new(format_run_time(Duration::ZERO)),
+                text_font,
+                TextColor(Color::WHITE),
+            ),
+        ],
+    ));
+}
+
+fn update_run_timer_hud(
+    timer: Res<RunTimer>,
+    mut clock: Single<&mut Text, (With<RunTimerClock>, Without<RunTimerSplit>)>,
+    split: Single<(&mut Text, &mut TextColor), With<RunTimerSplit>>,
+) {
+    clock.set_if_neq(Text::new(format_run_time(timer.elapsed)));
+
+    let (mut text, mut color) = split.into_inner();
+    let (label, label_color) = match &timer.last_split {
+        Some(split) if !split.shown.is_finished() => match split.best {
+            Some(best) => (
+                format_split_delta(split.time, best),
+                if split.time <= best {
+                    AHEAD_COLOR
+                } else {
+                    BEHIND_COLOR
+                },
+            ),
+            None => (format_run_time(split.time), Color::WHITE),
+        },
+        _ => (String::new(), Color::WHITE),
+    };
+    text.set_if_neq(Text::new(label));
+    color.set_if_neq(TextColor(label_color));
+}
+
+/// `m:ss.cc`, with hours in front once the run gets that long.
+pub(crate) fn format_run_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    let (hours, minutes) = (centis / 360_000, centis / 6_000 % 60);
+    let (seconds, centis) = (centis / 100 % 60, centis % 100);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}.{centis:02}")
+    } else {
+        format!("{minutes}:{seconds:02}.{centis:02}")
+    }
+}
+
+/// Signed difference against the best time, e.g. `-1.25` when ahead.
+fn format_split_delta(time: Duration, best: Duration) -> String {
+    if time <= best {
+        format!("-{:.2}", (best - time).as_secs_f64())
+    } else {
+        format!("+{:.2}", (time - best).as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::objective::{
+        HookPolicy, Objectives, TUTORIAL_DIG_GRAVES, TUTORIAL_OBJECTIVE,
+    };
+
+    #[test]
+    fn run_time_formats_minutes_and_hours() {
+        assert_eq!(format_run_time(Duration::ZERO), "0:00.00");
+        assert_eq!(format_run_time(Duration::from_millis(83_456)), "1:23.45");
+        assert_eq!(
+            format_run_time(Duration::from_secs(3_600 + 2 * 60 + 3)),
+            "1:02:03.00"
+        );
+    }
+
+    #[test]
+    fn split_delta_is_signed_against_the_best() {
+        let best = Duration::from_secs(60);
+        assert_eq!(
+            format_split_delta(Duration::from_millis(58_750), best),
+            "-1.25"
+        );
+        assert_eq!(
+            format_split_delta(Duration::from_millis(62_500), best),
+            "+2.50"
+        );
+        assert_eq!(format_split_delta(best, best), "-0.00");
+    }
+
+    #[test]
+    fn skipping_the_tutorial_keeps_the_best_splits() {
+        let key = format!("{TUTORIAL_OBJECTIVE}/{TUTORIAL_DIG_GRAVES}");
+        let best = Duration::from_secs(90);
+        let mut world = World::new();
+        world.insert_resource(RunTimer {
+            elapsed: Duration::from_millis(500),
+            last_split: None,
+        });
+        world.insert_resource(BestSplits {
+            splits: HashMap::from([(key.clone(), best)]),
+        });
+        world.insert_resource(Objectives::default());
+        world.add_observer(record_split);
+
+        Objectives::force_complete_all(&mut world, TUTORIAL_OBJECTIVE, HookPolicy::None);
+
+        let splits = &world.resource::<BestSplits>().splits;
+        assert_eq!(splits.len(), 1);
+        assert_eq!(splits[&key], best);
+        assert!(world.resource::<RunTimer>().last_split.is_none());
+    }
+}
@@ -0,0 +1,356 @@
+//! Endless mode: waves of enemy gunners on the graveyard map, tougher every wave, until the
+//! player dies. Each cleared wave pays crusts, and the run's score goes on a best-of table.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+use rand::Rng as _;
+
+use crate::{
+    Pause, game_running,
+    gameplay::{
+        crusts::{Crusts, CrustsRewarded},
+        game_mode::GameMode,
+        grave::GraveState,
+        npc::{DEFAULT_NPC_HEALTH, EnemyGunner, EnemySpawner, NpcDead, shooting::SpawnAlerted},
+        player::{Player, PlayerDead},
+    },
+    menus::Menu,
+    persistence,
+    screens::Screen,
+};
+
+const SCORES_PATH: &str = "sandbox_scores.bin";
+/// Best runs kept on the score table.
+pub(crate) const MAX_SCORES: usize = 5;
+
+const FIRST_WAVE_DELAY: f32 = 5.0;
+const INTERMISSION_SECONDS: f32 = 8.0;
+const BASE_ENEMIES: u32 = 2;
+const ENEMIES_PER_WAVE: u32 = 1;
+const MAX_ENEMIES: u32 = 12;
+/// Fraction of base health added per wave after the first.
+const HEALTH_PER_WAVE: f32 = 0.2;
+const FIRE_RATE_PER_WAVE: f32 = 0.1;
+/// Keeps later waves from turning into a solid wall of bullets.
+const MAX_FIRE_RATE_MULTIPLIER: f32 = 3.0;
+const PROJECTILES_PER_WAVE: f32 = 0.1;
+const BASE_WAVE_REWARD: u32 = 2;
+const REWARD_PER_WAVE: u32 = 1;
+/// Enemy models cycled through within a wave.
+const WAVE_MODELS: [&str; 3] = ["lobster", "crab", "shark"];
+/// Used around the player when the map has no enemy spawners.
+const SPAWN_RING_RADIUS: f32 = 15.0;
+
+const KILL_POINTS: u32 = 100;
+const BURIAL_POINTS: u32 = 250;
+const WAVE_POINTS: u32 = 500;
+const POINTS_PER_SECOND: u32 = 1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SandboxState>();
+    app.init_resource::<SandboxScores>();
+    app.add_observer(count_sandbox_kills);
+    app.add_observer(end_sandbox_run);
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        reset_sandbox.run_if(resource_equals(GameMode::Sandbox)),
+    );
+    app.add_systems(
+        Update,
+        (tick_sandbox, count_burials, spawn_waves, finish_waves)
+            .chain()
+            .run_if(game_running.and(resource_equals(GameMode::Sandbox))),
+    );
+}
+
+/// The endless run in progress.
+#[derive(Resource, Debug)]
+pub(crate) struct SandboxState {
+    /// Wave in progress or last finished, starting from 1.
+    pub wave: u32,
+    pub score: SandboxScore,
+    /// Place on the score table the finished run got, if it made it.
+    pub rank: Option<usize>,
+    wave_active: bool,
+    /// Counts down to the next wave between waves.
+    intermission: Timer,
+    /// Graves' rewarded bodies when the run started, so earlier burials don't count.
+    burial_baseline: Option<u32>,
+}
+
+impl Default for SandboxState {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            score: default(),
+            rank: None,
+            wave_active: false,
+            intermission: Timer::from_seconds(FIRST_WAVE_DELAY, TimerMode::Once),
+            burial_baseline: None,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Clone, Debug, Default, PartialEq)]
+pub(crate) struct SandboxScore {
+    pub kills: u32,
+    pub burials: u32,
+    pub waves: u32,
+    pub time: Duration,
+}
+
+impl SandboxScore {
+    pub(crate) fn points(&self) -> u32 {
+        self.kills * KILL_POINTS
+            + self.burials * BURIAL_POINTS
+            + self.waves * WAVE_POINTS
+            + self.time.as_secs() as u32 * POINTS_PER_SECOND
+    }
+}
+
+/// Best runs, highest points first. Saved next to the game.
+#[derive(Resource, Encode, Decode, Default, Debug)]
+pub(crate) struct SandboxScores {
+    pub best: Vec<SandboxScore>,
+}
+
+impl FromWorld for SandboxScores {
+    fn from_world(_world: &mut World) -> Self {
+        persistence::load(SCORES_PATH, "sandbox scores").unwrap_or_default()
+    }
+}
+
+impl SandboxScores {
+    /// Adds `score` to the table, returning its place if it's among the best.
+    fn insert(&mut self, score: SandboxScore) -> Option<usize> {
+        let points = score.points();
+        let rank = self
+            .best
+            .iter()
+            .position(|best| points > best.points())
+            .unwrap_or(self.best.len());
+        if rank >= MAX_SCORES {
+            return None;
+        }
+        self.best.insert(rank, score);
+        self.best.truncate(MAX_SCORES);
+        Some(rank)
+    }
+
+    fn save(&self) {
+        persistence::save(SCORES_PATH, "sandbox scores", self);
+    }
+}
+
+/// Multipliers applied to an enemy gunner's base stats when it spawns.
+#[derive(Debug, PartialEq)]
+struct WaveScaling {
+    health: f32,
+    fire_rate: f32,
+    projectiles: f32,
+}
+
+fn wave_scaling(wave: u32) -> WaveScaling {
+    let step = wave.saturating_sub(1) as f32;
+    WaveScaling {
+        health: 1.0 + step * HEALTH_PER_WAVE,
+        fire_rate: (1.0 + step * FIRE_RATE_PER_WAVE).min(MAX_FIRE_RATE_MULTIPLIER),
+        projectiles: 1.0 + step * PROJECTILES_PER_WAVE,
+    }
+}
+
+fn wave_enemy_count(wave: u32) -> u32 {
+    (BASE_ENEMIES + wave.saturating_sub(1) * ENEMIES_PER_WAVE).min(MAX_ENEMIES)
+}
+
+fn wave_reward(wave: u32) -> u32 {
+    BASE_WAVE_REWARD + wave * REWARD_PER_WAVE
+}
+
+/// Enemy spawned by the wave controller.
+#[derive(Component)]
+struct SandboxEnemy;
+
+fn reset_sandbox(mut state: ResMut<SandboxState>) {
+    *state = SandboxState::default();
+}
+
+fn tick_sandbox(time: Res<Time<Virtual>>, mut state: ResMut<SandboxState>) {
+    state.score.time += time.delta();
+    if !state.wave_active {
+        state.intermission.tick(time.delta());
+    }
+}
+
+fn count_burials(graves: Query<&GraveState>, mut state: ResMut<SandboxState>) {
+    let rewarded: u32 = graves.iter().map(|grave| grave.rewarded).sum();
+    let baseline = *state.burial_baseline.get_or_insert(rewarded);
+    let burials = rewarded.saturating_sub(baseline);
+    if state.score.burials != burials {
+        state.score.burials = burials;
+    }
+}
+
+fn spawn_waves(
+    mut commands: Commands,
+    mut state: ResMut<SandboxState>,
+    spawners: Query<(&GlobalTransform, &EnemySpawner)>,
+    player: Single<&GlobalTransform, With<Player>>,
+) {
+    if state.wave_active || !state.intermission.is_finished() {
+        return;
+    }
+    state.wave += 1;
+    state.wave_active = true;
+    let wave = state.wave;
+    info!("Sandbox wave {wave} starting");
+
+    let scaling = wave_scaling(wave);
+    // Ambush spawners put out alerted enemies, so a wave can mix both kinds.
+    let spawn_points: Vec<(Vec3, bool)> = spawners
+        .iter()
+        .map(|(transform, spawner)| (transform.translation(), spawner.spawn_alerted))
+        .collect();
+    let rng = &mut rand::rng();
+    for index in 0..wave_enemy_count(wave) {
+        let (position, alerted) = if spawn_points.is_empty() {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * SPAWN_RING_RADIUS;
+            (player.translation() + offset, false)
+        } else {
+            spawn_points[rng.random_range(0..spawn_points.len())]
+        };
+        let mut enemy = commands.spawn((
+            SandboxEnemy,
+            scaled_gunner(index, &scaling),
+            Transform::from_translation(position),
+            Visibility::default(),
+            DespawnOnExit(Screen::Gameplay),
+        ));
+        if alerted {
+            enemy.insert(SpawnAlerted);
+        }
+    }
+}
+
+fn scaled_gunner(index: u32, scaling: &WaveScaling) -> EnemyGunner {
+    let base = EnemyGunner::default();
+    EnemyGunner {
+        model: WAVE_MODELS[index as usize % WAVE_MODELS.len()].to_string(),
+        health: DEFAULT_NPC_HEALTH * scaling.health,
+        fire_rate: base.fire_rate * scaling.fire_rate,
+        projectile_count: (base.projectile_count as f32 * scaling.projectiles).round() as u32,
+        ..base
+    }
+}
+
+fn finish_waves(
+    mut commands: Commands,
+    mut state: ResMut<SandboxState>,
+    alive: Query<(), (With<SandboxEnemy>, Without<NpcDead>)>,
+    mut crusts: ResMut<Crusts>,
+) {
+    if !state.wave_active || !alive.is_empty() {
+        return;
+    }
+    state.wave_active = false;
+    state.score.waves += 1;
+    state.intermission = Timer::from_seconds(INTERMISSION_SECONDS, TimerMode::Once);
+
+    let reward = wave_reward(state.wave);
+    crusts.add(reward);
+    commands.trigger(CrustsRewarded(reward));
+}
+
+fn count_sandbox_kills(
+    add: On<Add, NpcDead>,
+    enemies: Query<(), With<SandboxEnemy>>,
+    mut state: ResMut<SandboxState>,
+) {
+    if enemies.contains(add.entity) {
+        state.score.kills += 1;
+    }
+}
+
+/// Dying ends the run: the score goes on the table and the results screen comes up.
+fn end_sandbox_run(
+    _add: On<Add, PlayerDead>,
+    mode: Res<GameMode>,
+    mut state: ResMut<SandboxState>,
+    mut scores: ResMut<SandboxScores>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if *mode != GameMode::Sandbox {
+        return;
+    }
+    state.rank = scores.insert(state.score.clone());
+    if state.rank.is_some() {
+        scores.save();
+    }
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::SandboxResults);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(kills: u32) -> SandboxScore {
+        SandboxScore { kills, ..default() }
+    }
+
+    #[test]
+    fn first_wave_uses_base_stats() {
+        assert_eq!(
+            wave_scaling(1),
+            WaveScaling {
+                health: 1.0,
+                fire_rate: 1.0,
+                projectiles: 1.0,
+            }
+        );
+        assert_eq!(wave_enemy_count(1), BASE_ENEMIES);
+    }
+
+    #[test]
+    fn waves_scale_up_to_their_caps() {
+        let later = wave_scaling(6);
+        assert!((later.health - 2.0).abs() < 1e-5);
+        assert!((later.fire_rate - 1.5).abs() < 1e-5);
+        assert_eq!(wave_scaling(1000).fire_rate, MAX_FIRE_RATE_MULTIPLIER);
+        assert_eq!(wave_enemy_count(4), BASE_ENEMIES + 3 * ENEMIES_PER_WAVE);
+        assert_eq!(wave_enemy_count(1000), MAX_ENEMIES);
+        assert!(wave_reward(5) > wave_reward(1));
+    }
+
+    #[test]
+    fn score_points_add_up() {
+        let score = SandboxScore {
+            kills: 2,
+            burials: 1,
+            waves: 3,
+            time: Duration::from_secs_f32(10.5),
+        };
+        assert_eq!(
+            score.points(),
+            2 * KILL_POINTS + BURIAL_POINTS + 3 * WAVE_POINTS + 10 * POINTS_PER_SECOND
+        );
+    }
+
+    #[test]
+    fn score_table_keeps_the_best_in_order() {
+        let mut scores = SandboxScores::default();
+        for kills in [3, 1, 5, 2, 4] {
+            scores.insert(score(kills));
+        }
+        assert_eq!(scores.insert(score(6)), Some(0));
+        assert_eq!(scores.insert(score(0)), None);
+        let kills: Vec<u32> = scores.best.iter().map(|s| s.kills).collect();
+        assert_eq!(kills, vec![6, 5, 4, 3, 2]);
+        // Ties go below the existing run.
+        assert_eq!(scores.insert(score(4)), Some(3));
+    }
+}
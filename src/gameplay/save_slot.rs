@@ -0,0 +1,260 @@
+//! Save slots for story runs, and new game plus.
+//!
+//! A slot holds the crusts and upgrades its run ended with, written when the story is finished
+//! and whenever a story run is left. A slot whose story is finished can start over as new game
+//! plus: the objectives and level reset and enemies get tougher, but the crusts and upgrades
+//! carry over. Starting a slot also drops the [`Session`] left behind by the last story run,
+//! so "continue" can't bring back its world.
+
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+
+use crate::{
+    gameplay::{
+        crust_pickup::AutoPickup,
+        crusts::Crusts,
+        game_mode::GameMode,
+        objective::Objectives,
+        player::{Player, PlayerHealth},
+        session::{Session, retrying_run},
+        store::{UpgradeLevels, apply_player_upgrade_levels},
+    },
+    persistence,
+    screens::Screen,
+};
+
+pub(crate) const SLOT_COUNT: usize = 3;
+/// Extra enemy health and fire rate for each new game plus level.
+const DIFFICULTY_PER_LEVEL: f32 = 0.25;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SaveSlots>();
+    app.init_resource::<ActiveSlot>();
+    app.init_resource::<NewGamePlus>();
+    app.add_observer(start_slot);
+    app.add_observer(apply_carried_player_upgrades);
+    app.add_systems(
+        Update,
+        save_finished_story.run_if(in_state(Screen::Gameplay).and(resource_changed::<Objectives>)),
+    );
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        leave_slot.run_if(not(retrying_run)),
+    );
+}
+
+fn slot_path(slot: usize) -> String {
+    format!("save_slot_{}.bin", slot + 1)
+}
+
+#[derive(Encode, Decode, Default, Clone, Debug)]
+pub(crate) struct SlotSave {
+    /// New game plus level of the saved run, 0 on the first playthrough.
+    pub level: u32,
+    /// The story was finished at `level`, so the slot can start new game plus.
+    pub finished: bool,
+    pub crusts: u32,
+    pub upgrades: UpgradeLevels,
+}
+
+#[derive(Resource, Debug)]
+pub(crate) struct SaveSlots {
+    pub slots: [Option<SlotSave>; SLOT_COUNT],
+}
+
+impl FromWorld for SaveSlots {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            slots: std::array::from_fn(load_slot),
+        }
+    }
+}
+
+fn load_slot(slot: usize) -> Option<SlotSave> {
+    persistence::load(&slot_path(slot), "save slot")
+}
+
+impl SaveSlots {
+    fn save(&mut self, slot: usize, save: SlotSave) {
+        persistence::save(&slot_path(slot), &format!("slot {}", slot + 1), &save);
+        self.slots[slot] = Some(save);
+    }
+
+    pub(crate) fn delete(&mut self, slot: usize) {
+        persistence::delete(&slot_path(slot), &format!("slot {}", slot + 1));
+        self.slots[slot] = None;
+    }
+}
+
+/// The slot the current story run saves to, `None` outside of the story.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct ActiveSlot(pub(crate) Option<usize>);
+
+/// The new game plus level of the current run and what it carried over, all zero on a
+/// fresh game.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct NewGamePlus {
+    pub level: u32,
+    pub crusts: u32,
+    pub upgrades: UpgradeLevels,
+}
+
+impl NewGamePlus {
+    pub(crate) fn difficulty_multiplier(&self) -> f32 {
+        1.0 + self.level as f32 * DIFFICULTY_PER_LEVEL
+    }
+}
+
+/// Starts a story run in `slot`, overwriting it. With `new_game_plus` the slot's finished run
+/// is carried into the next new game plus level instead.
+#[derive(Event, Debug)]
+pub(crate) struct StartSlot {
+    pub slot: usize,
+    pub new_game_plus: bool,
+}
+
+fn start_slot(
+    start: On<StartSlot>,
+    mut commands: Commands,
+    slots: Res<SaveSlots>,
+    mut mode: ResMut<GameMode>,
+    mut active: ResMut<ActiveSlot>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut crusts: ResMut<Crusts>,
+    mut upgrades: ResMut<UpgradeLevels>,
+    mut session: ResMut<Session>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let carried = slots.slots[start.slot]
+        .as_ref()
+        .filter(|save| start.new_game_plus && save.finished);
+    *new_game_plus = match carried {
+        Some(save) => NewGamePlus {
+            level: save.level + 1,
+            crusts: save.crusts,
+            upgrades: save.upgrades.clone(),
+        },
+        None => {
+            if start.new_game_plus {
+                warn!(
+                    "Slot {} hasn't finished the story, starting a new game instead",
+                    start.slot + 1
+                );
+            }
+            NewGamePlus::default()
+        }
+    };
+    info!(
+        "Starting slot {} at new game plus level {}",
+        start.slot + 1,
+        new_game_plus.level
+    );
+
+    *mode = GameMode::Story;
+    active.0 = Some(start.slot);
+    crusts.0 = new_game_plus.crusts;
+    *upgrades = new_game_plus.upgrades.clone();
+    session.clear();
+    commands.insert_resource(Objectives::default());
+    next_screen.set(Screen::Loading);
+}
+
+/// The loadout handles item upgrades, this puts the carried health and magnet levels on the
+/// freshly spawned player.
+fn apply_carried_player_upgrades(
+    add: On<Add, AutoPickup>,
+    new_game_plus: Res<NewGamePlus>,
+    mut players: Query<(&mut PlayerHealth, &mut AutoPickup), With<Player>>,
+) {
+    let Ok((mut player_health, mut auto_pickup)) = players.get_mut(add.entity) else {
+        return;
+    };
+    apply_player_upgrade_levels(
+        &new_game_plus.upgrades,
+        &mut player_health,
+        &mut auto_pickup,
+    );
+}
+
+pub(crate) fn current_save(
+    new_game_plus: &NewGamePlus,
+    objectives: &Objectives,
+    crusts: &Crusts,
+    upgrades: &UpgradeLevels,
+) -> SlotSave {
+    SlotSave {
+        level: new_game_plus.level,
+        finished: objectives.all_complete(),
+        crusts: crusts.0,
+        upgrades: upgrades.clone(),
+    }
+}
+
+/// Saves as soon as the story is over, so new game plus unlocks without having to quit first.
+fn save_finished_story(
+    active: Res<ActiveSlot>,
+    mode: Res<GameMode>,
+    mut slots: ResMut<SaveSlots>,
+    new_game_plus: Res<NewGamePlus>,
+    objectives: Res<Objectives>,
+    crusts: Res<Crusts>,
+    upgrades: Res<UpgradeLevels>,
+) {
+    let Some(slot) = active.0 else {
+        return;
+    };
+    let already_saved = slots.slots[slot]
+        .as_ref()
+        .is_some_and(|save| save.finished && save.level == new_game_plus.level);
+    if *mode != GameMode::Story || already_saved || !objectives.all_complete() {
+        return;
+    }
+    let save = current_save(&new_game_plus, &objectives, &crusts, &upgrades);
+    slots.save(slot, save);
+}
+
+pub(crate) fn leave_slot(
+    mut active: ResMut<ActiveSlot>,
+    mode: Res<GameMode>,
+    mut slots: ResMut<SaveSlots>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    objectives: Res<Objectives>,
+    crusts: Res<Crusts>,
+    upgrades: Res<UpgradeLevels>,
+) {
+    if let Some(slot) = active.0.take() {
+        if *mode == GameMode::Story {
+            let save = current_save(&new_game_plus, &objectives, &crusts, &upgrades);
+            slots.save(slot, save);
+        }
+    }
+    *new_game_plus = NewGamePlus::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_saves_load_back_until_deleted() {
+        let path = std::env::temp_dir().join(format!("lobs_test_{}", slot_path(0)));
+        let path = path.to_str().unwrap();
+        let save = SlotSave {
+            level: 2,
+            finished: true,
+            crusts: 40,
+            upgrades: UpgradeLevels::default(),
+        };
+
+        persistence::save(path, "save slot", &save);
+        let loaded: SlotSave = persistence::load(path, "save slot").unwrap();
+        assert_eq!(loaded.level, 2);
+        assert!(loaded.finished);
+        assert_eq!(loaded.crusts, 40);
+
+        persistence::delete(path, "save slot");
+        assert!(persistence::load::<SlotSave>(path, "save slot").is_none());
+        // Deleting an empty slot is fine too.
+        persistence::delete(path, "save slot");
+    }
+}
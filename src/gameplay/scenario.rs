@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 
 use super::grave::SpawnBody;
+use super::mover::MovePlatform;
 use super::npc::SpawnNpc;
+use super::player::cutscene::PlayCutscene;
 
 pub fn plugin(app: &mut App) {
     app.add_observer(on_scenario_trigger);
@@ -23,6 +25,14 @@ pub(crate) enum ScenarioTrigger {
     QueueSpawnNpc {
         spawner_name: String,
     },
+    Cutscene {
+        keyframes: Vec<Transform>,
+        durations: Vec<f32>,
+    },
+    MovePlatform {
+        tag: String,
+        stop_index: usize,
+    },
 }
 
 fn on_scenario_trigger(event: On<ScenarioTrigger>, mut commands: Commands) {
@@ -57,5 +67,20 @@ fn on_scenario_trigger(event: On<ScenarioTrigger>, mut commands: Commands) {
                 overrides: default(),
             });
         }
+        ScenarioTrigger::Cutscene {
+            keyframes,
+            durations,
+        } => {
+            commands.trigger(PlayCutscene {
+                keyframes: keyframes.clone(),
+                durations: durations.clone(),
+            });
+        }
+        ScenarioTrigger::MovePlatform { tag, stop_index } => {
+            commands.trigger(MovePlatform {
+                tag: tag.clone(),
+                stop_index: *stop_index,
+            });
+        }
     }
 }
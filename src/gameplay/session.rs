@@ -0,0 +1,614 @@
+//! Picking a story run back up after leaving it for the main menu.
+//!
+//! Leaving the story keeps a [`SessionSnapshot`] of the world in memory: the dug terrain, which
+//! NPCs placed in the map are still alive, how full each grave is, how far each spawner got and
+//! what it put out, and the objective progress. "continue" on the main menu reloads the level and
+//! puts all of that back once the scene, its voxel volumes and its graves are in. Entity ids don't
+//! survive the reload, so everything is matched by what the map gives it instead: volume bounds,
+//! grave centers, spawner names and NPC names. Starting a slot from the menu drops the snapshot.
+//!
+//! Whatever a spawner put out comes back at the spawner rather than where it was left, the dead
+//! as bodies, so objectives that count kills still add up. The snapshot only lasts as long as the
+//! game is open, but it's made of the same encodable parts as a [`SlotSave`].
+//!
+//! Retrying after a critical NPC died puts the snapshot back the same way, without taking a
+//! new one of the failed run first. With no snapshot the level starts over.
+
+use bevy::{
+    ecs::entity::EntityHashSet, platform::collections::HashMap, prelude::*, scene::SceneInstance,
+};
+use bincode::{Decode, Encode};
+
+use crate::{
+    gameplay::{
+        crusts::Crusts,
+        dig::{VOXEL_SIZE, Voxel, VoxelSim, VoxelWorldBounds, init_voxel_volumes},
+        game_mode::GameMode,
+        grave::{
+            BodySpawner, GraveCenter, GraveState, Slotted, SpawnBody, SpawnerState, init_graves,
+        },
+        level::Level,
+        npc::{
+            EnemyGunner, EnemySpawner, EnemySpawnerState, Npc, NpcDead, NpcSpawner,
+            NpcSpawnerState, SpawnEnemy, SpawnNpc, critical::CriticalFailure, reward::DeathReward,
+        },
+        objective::{ObjectiveProgress, Objectives},
+        save_slot::{ActiveSlot, NewGamePlus, SlotSave, current_save, leave_slot},
+        store::UpgradeLevels,
+    },
+    screens::Screen,
+};
+
+/// How long to wait for the saved volumes and graves to show up before restoring whatever did.
+const RESTORE_TIMEOUT_SECONDS: f32 = 10.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Session>();
+    app.add_observer(continue_session);
+    app.add_observer(retry_run);
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        (
+            capture_session.before(leave_slot),
+            apply_retry.after(capture_session).after(leave_slot),
+        ),
+    );
+    app.add_systems(
+        Update,
+        restore_session
+            .after(init_voxel_volumes)
+            .after(init_graves)
+            .run_if(in_state(Screen::Gameplay).and(resuming_session)),
+    );
+}
+
+/// The story run left earlier this session, if there is one to continue.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct Session {
+    snapshot: Option<SessionSnapshot>,
+    /// "continue" was picked and the reloaded level hasn't been put back yet.
+    resuming: bool,
+    /// "retry" was picked on the failure screen and gameplay is being left to do it.
+    retrying: bool,
+}
+
+impl Session {
+    pub(crate) fn can_continue(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    /// The save slot the snapshot was taken from.
+    pub(crate) fn slot(&self) -> Option<usize> {
+        self.snapshot.as_ref().map(|snapshot| snapshot.slot)
+    }
+
+    /// Picking the last run back up, rather than starting the level fresh.
+    pub(crate) fn resuming(&self) -> bool {
+        self.resuming
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = default();
+    }
+}
+
+/// Objective hooks hold off while this is true, so they don't judge the fresh level.
+pub(crate) fn resuming_session(session: Res<Session>) -> bool {
+    session.resuming
+}
+
+/// Leaving gameplay to retry, so the slot isn't left and saved on the way out.
+pub(crate) fn retrying_run(session: Res<Session>) -> bool {
+    session.retrying
+}
+
+#[derive(Encode, Decode, Clone, Debug)]
+pub(crate) struct SessionSnapshot {
+    slot: usize,
+    save: SlotSave,
+    objectives: ObjectiveProgress,
+    volumes: Vec<VolumeSnapshot>,
+    graves: Vec<GraveSnapshot>,
+    spawners: Vec<SpawnerSnapshot>,
+    /// Names of the NPCs placed in the map that were still alive. The rest come back dead.
+    alive: Vec<String>,
+}
+
+#[derive(Encode, Decode, Clone, Debug)]
+struct VolumeSnapshot {
+    /// [`position_key`] of the volume's minimum corner.
+    key: [i32; 3],
+    runs: Vec<(Voxel, u32)>,
+}
+
+#[derive(Encode, Decode, Clone, Debug)]
+struct GraveSnapshot {
+    /// [`position_key`] of the grave's center.
+    key: [i32; 3],
+    filled: u32,
+    rewarded: u32,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+enum SpawnerKind {
+    Npc,
+    Enemy,
+    Body,
+}
+
+#[derive(Encode, Decode, Clone, Debug)]
+struct SpawnerSnapshot {
+    kind: SpawnerKind,
+    name: String,
+    index: usize,
+    /// Model of everything the spawner put out, in order, and what became of it.
+    spawned: Vec<(String, Spawned)>,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+enum Spawned {
+    /// Still walking around, or for bodies, still lying around.
+    Up,
+    /// Killed, with its body lying around.
+    Dead,
+    /// In a grave, or gone.
+    Buried,
+}
+
+/// Continues the run from the [`Session`], back on its save slot.
+#[derive(Event, Debug)]
+pub(crate) struct ContinueSession;
+
+/// Throws away the current run and reloads the level, from the [`Session`]'s snapshot if there
+/// is one.
+#[derive(Event, Debug)]
+pub(crate) struct RetryRun;
+
+/// Voxel coordinates of a world position, so float noise doesn't split identical keys.
+fn position_key(position: Vec3) -> [i32; 3] {
+    (position / VOXEL_SIZE).round().as_ivec3().to_array()
+}
+
+fn continue_session(
+    _continue: On<ContinueSession>,
+    mut commands: Commands,
+    mut session: ResMut<Session>,
+    mut mode: ResMut<GameMode>,
+    mut active: ResMut<ActiveSlot>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(snapshot) = &session.snapshot else {
+        warn!("No story run to continue");
+        return;
+    };
+    info!("Continuing slot {}", snapshot.slot + 1);
+
+    prepare_resume(&mut commands, snapshot, &mut new_game_plus);
+    *mode = GameMode::Story;
+    active.0 = Some(snapshot.slot);
+
+    session.resuming = true;
+    next_screen.set(Screen::Loading);
+}
+
+/// Sets up the resources the reloaded level starts from to match `snapshot`.
+fn prepare_resume(
+    commands: &mut Commands,
+    snapshot: &SessionSnapshot,
+    new_game_plus: &mut NewGamePlus,
+) {
+    // Everything bought so far counts as carried, so the level's loadout starts from it.
+    *new_game_plus = NewGamePlus {
+        level: snapshot.save.level,
+        crusts: snapshot.save.crusts,
+        upgrades: snapshot.save.upgrades.clone(),
+    };
+    let mut objectives = Objectives::default();
+    objectives.restore_progress(&snapshot.objectives);
+    commands.insert_resource(objectives);
+}
+
+fn retry_run(
+    _retry: On<RetryRun>,
+    mut session: ResMut<Session>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    session.retrying = true;
+    next_screen.set(Screen::Loading);
+}
+
+/// Runs on the way out of gameplay, after the slot would have been left, which `retrying`
+/// skipped so the run stays on it.
+fn apply_retry(
+    mut commands: Commands,
+    mut session: ResMut<Session>,
+    mode: Res<GameMode>,
+    mut new_game_plus: ResMut<NewGamePlus>,
+    mut crusts: ResMut<Crusts>,
+    mut upgrades: ResMut<UpgradeLevels>,
+) {
+    if !std::mem::take(&mut session.retrying) {
+        return;
+    }
+    if *mode == GameMode::Story {
+        if let Some(snapshot) = &session.snapshot {
+            info!("Retrying from slot {}'s last snapshot", snapshot.slot + 1);
+            prepare_resume(&mut commands, snapshot, &mut new_game_plus);
+            session.resuming = true;
+            return;
+        }
+    }
+    info!("Retrying the level from the start");
+    crusts.0 = new_game_plus.crusts;
+    *upgrades = new_game_plus.upgrades.clone();
+    commands.insert_resource(Objectives::default());
+}
+
+fn capture_session(world: &mut World) {
+    // The failed run is thrown away, the snapshot from before it stays the latest.
+    if world.resource::<Session>().retrying || world.resource::<CriticalFailure>().npc().is_some() {
+        return;
+    }
+    // Left again before the snapshot was put back, so it's still the latest.
+    if std::mem::take(&mut world.resource_mut::<Session>().resuming) {
+        return;
+    }
+    let Some(slot) = world.resource::<ActiveSlot>().0 else {
+        return;
+    };
+    if *world.resource::<GameMode>() != GameMode::Story {
+        return;
+    }
+
+    let save = current_save(
+        world.resource::<NewGamePlus>(),
+        world.resource::<Objectives>(),
+        world.resource::<Crusts>(),
+        world.resource::<UpgradeLevels>(),
+    );
+    let objectives = world.resource::<Objectives>().progress();
+
+    let volumes = world
+        .query::<(&VoxelSim, &VoxelWorldBounds)>()
+        .iter(world)
+        .map(|(sim, bounds)| VolumeSnapshot {
+            key: position_key(bounds.min),
+            runs: sim.runs(),
+        })
+        .collect();
+    let graves = world
+        .query::<(&GraveState, &GraveCenter)>()
+        .iter(world)
+        .map(|(grave, center)| GraveSnapshot {
+            key: position_key(center.0),
+            filled: grave.filled,
+            rewarded: grave.rewarded,
+        })
+        .collect();
+
+    let mut spawners = Vec::new();
+    let mut spawned_entities = EntityHashSet::default();
+    let mut capture = |kind, name: &str, index, spawned: &[(Entity, String)], world: &World| {
+        spawned_entities.extend(spawned.iter().map(|(entity, _)| *entity));
+        spawners.push(SpawnerSnapshot {
+            kind,
+            name: name.to_string(),
+            index,
+            spawned: spawned
+                .iter()
+                .map(|(entity, model)| (model.clone(), spawned_state(world, *entity)))
+                .collect(),
+        });
+    };
+    for (spawner, state) in world.query::<(&NpcSpawner, &NpcSpawnerState)>().iter(world) {
+        capture(
+            SpawnerKind::Npc,
+            &spawner.name,
+            state.index,
+            &state.spawned,
+            world,
+        );
+    }
+    for (spawner, state) in world
+        .query::<(&EnemySpawner, &EnemySpawnerState)>()
+        .iter(world)
+    {
+        capture(
+            SpawnerKind::Enemy,
+            &spawner.name,
+            state.index,
+            &state.spawned,
+            world,
+        );
+    }
+    for (spawner, state) in world.query::<(&BodySpawner, &SpawnerState)>().iter(world) {
+        capture(
+            SpawnerKind::Body,
+            &spawner.name,
+            state.index,
+            &state.spawned,
+            world,
+        );
+    }
+
+    // Dying strips `Npc` and `EnemyGunner`, so only the living placed NPCs are left here.
+    let alive = world
+        .query_filtered::<(Entity, &Name), Or<(With<Npc>, With<EnemyGunner>)>>()
+        .iter(world)
+        .filter(|(entity, _)| !spawned_entities.contains(entity))
+        .map(|(_, name)| name.to_string())
+        .collect();
+
+    info!("Keeping slot {}'s world for this session", slot + 1);
+    world.resource_mut::<Session>().snapshot = Some(SessionSnapshot {
+        slot,
+        save,
+        objectives,
+        volumes,
+        graves,
+        spawners,
+        alive,
+    });
+}
+
+fn spawned_state(world: &World, entity: Entity) -> Spawned {
+    let Ok(entity) = world.get_entity(entity) else {
+        return Spawned::Buried;
+    };
+    if entity.contains::<Slotted>() {
+        Spawned::Buried
+    } else if entity.contains::<NpcDead>() {
+        Spawned::Dead
+    } else {
+        Spawned::Up
+    }
+}
+
+fn restore_session(world: &mut World, mut waited: Local<f32>) {
+    let Some(snapshot) = world.resource::<Session>().snapshot.clone() else {
+        world.resource_mut::<Session>().resuming = false;
+        return;
+    };
+    if !level_ready(world) {
+        return;
+    }
+    *waited += world.resource::<Time>().delta_secs();
+    if !snapshot_matched(world, &snapshot) {
+        if *waited < RESTORE_TIMEOUT_SECONDS {
+            return;
+        }
+        warn!("Some of the saved voxel volumes or graves never showed up, restoring the rest");
+    }
+    *waited = 0.0;
+
+    apply_snapshot(world, &snapshot);
+    world.resource_mut::<Session>().resuming = false;
+    info!("Restored slot {}'s world", snapshot.slot + 1);
+}
+
+/// Puts the crusts, upgrades, terrain, graves and NPCs back the way `snapshot` left them.
+fn apply_snapshot(world: &mut World, snapshot: &SessionSnapshot) {
+    world.resource_mut::<Crusts>().0 = snapshot.save.crusts;
+    *world.resource_mut::<UpgradeLevels>() = snapshot.save.upgrades.clone();
+
+    let mut volumes = world.query::<(&mut VoxelSim, &VoxelWorldBounds)>();
+    for (mut sim, bounds) in volumes.iter_mut(world) {
+        let key = position_key(bounds.min);
+        let Some(saved) = snapshot.volumes.iter().find(|saved| saved.key == key) else {
+            continue;
+        };
+        if !sim.set_runs(&saved.runs) {
+            warn!(
+                "Voxel volume at {} changed size, leaving it fresh",
+                bounds.min
+            );
+        }
+    }
+
+    let mut graves = world.query::<(&mut GraveState, &GraveCenter)>();
+    for (mut grave, center) in graves.iter_mut(world) {
+        let key = position_key(center.0);
+        if let Some(saved) = snapshot.graves.iter().find(|saved| saved.key == key) {
+            grave.filled = saved.filled.min(grave.slots);
+            grave.rewarded = saved.rewarded.min(grave.filled);
+        }
+    }
+
+    // Nothing has spawned yet, so every NPC here was placed in the map.
+    let mut alive: HashMap<&str, usize> = HashMap::new();
+    for name in &snapshot.alive {
+        *alive.entry(name.as_str()).or_default() += 1;
+    }
+    let placed: Vec<(Entity, String)> = world
+        .query_filtered::<(Entity, &Name), Or<(With<Npc>, With<EnemyGunner>)>>()
+        .iter(world)
+        .map(|(entity, name)| (entity, name.to_string()))
+        .collect();
+    for (entity, name) in placed {
+        match alive.get_mut(name.as_str()) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => kill(world, entity),
+        }
+    }
+
+    for saved in &snapshot.spawners {
+        for (model, spawned) in &saved.spawned {
+            if *spawned == Spawned::Buried {
+                continue;
+            }
+            let spawner_name = saved.name.clone();
+            let model = model.clone();
+            match saved.kind {
+                SpawnerKind::Npc => world.trigger(SpawnNpc::Direct {
+                    spawner_name,
+                    model,
+                    overrides: default(),
+                }),
+                SpawnerKind::Enemy => world.trigger(SpawnEnemy::Direct {
+                    spawner_name,
+                    model,
+                    alerted: false,
+                }),
+                SpawnerKind::Body => world.trigger(SpawnBody::Direct {
+                    spawner_name,
+                    npc_name: model,
+                }),
+            }
+            world.flush();
+            if *spawned == Spawned::Dead {
+                let entity = spawner_state(world, saved.kind, &saved.name)
+                    .and_then(|(_, spawned)| spawned.last().map(|(entity, _)| *entity));
+                if let Some(entity) = entity {
+                    kill(world, entity);
+                }
+            }
+        }
+        if let Some((index, _)) = spawner_state(world, saved.kind, &saved.name) {
+            *index = saved.index;
+        }
+    }
+}
+
+/// The level scene has finished spawning.
+fn level_ready(world: &mut World) -> bool {
+    let mut levels = world.query_filtered::<&SceneInstance, With<Level>>();
+    let scenes = world.resource::<SceneSpawner>();
+    levels
+        .iter(world)
+        .any(|instance| scenes.instance_is_ready(**instance))
+}
+
+/// Every saved voxel volume and grave is back in the level.
+fn snapshot_matched(world: &mut World, snapshot: &SessionSnapshot) -> bool {
+    let volumes: Vec<[i32; 3]> = world
+        .query_filtered::<&VoxelWorldBounds, With<VoxelSim>>()
+        .iter(world)
+        .map(|bounds| position_key(bounds.min))
+        .collect();
+    let graves: Vec<[i32; 3]> = world
+        .query_filtered::<&GraveCenter, With<GraveState>>()
+        .iter(world)
+        .map(|center| position_key(center.0))
+        .collect();
+    snapshot
+        .volumes
+        .iter()
+        .all(|saved| volumes.contains(&saved.key))
+        && snapshot
+            .graves
+            .iter()
+            .all(|saved| graves.contains(&saved.key))
+}
+
+/// Puts an NPC down as a body. Without `EnemyGunner` and `DeathReward` by then, coming back
+/// dead doesn't pay out or raise the threat a second time.
+fn kill(world: &mut World, entity: Entity) {
+    world
+        .entity_mut(entity)
+        .remove::<(EnemyGunner, DeathReward)>()
+        .insert(NpcDead);
+}
+
+/// The queue index and spawned list of the spawner called `name`.
+fn spawner_state<'w>(
+    world: &'w mut World,
+    kind: SpawnerKind,
+    name: &str,
+) -> Option<(&'w mut usize, &'w mut Vec<(Entity, String)>)> {
+    match kind {
+        SpawnerKind::Npc => {
+            let mut spawners = world.query::<(&NpcSpawner, &mut NpcSpawnerState)>();
+            let (_, state) = spawners
+                .iter_mut(world)
+                .find(|(spawner, _)| spawner.name == name)?;
+            let state = state.into_inner();
+            Some((&mut state.index, &mut state.spawned))
+        }
+        SpawnerKind::Enemy => {
+            let mut spawners = world.query::<(&EnemySpawner, &mut EnemySpawnerState)>();
+            let (_, state) = spawners
+                .iter_mut(world)
+                .find(|(spawner, _)| spawner.name == name)?;
+            let state = state.into_inner();
+            Some((&mut state.index, &mut state.spawned))
+        }
+        SpawnerKind::Body => {
+            let mut spawners = world.query::<(&BodySpawner, &mut SpawnerState)>();
+            let (_, state) = spawners
+                .iter_mut(world)
+                .find(|(spawner, _)| spawner.name == name)?;
+            let state = state.into_inner();
+            Some((&mut state.index, &mut state.spawned))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_run_is_put_back_on_the_reloaded_level() {
+        let bounds = IVec3::new(2, 3, 2);
+        let grave_center = Vec3::new(4.0, 0.0, -2.0);
+        let mut world = World::new();
+        world.insert_resource(Session::default());
+        world.insert_resource(CriticalFailure::default());
+        world.insert_resource(ActiveSlot(Some(1)));
+        world.insert_resource(GameMode::Story);
+        world.insert_resource(NewGamePlus::default());
+        world.insert_resource(Objectives::default());
+        world.insert_resource(Crusts(25));
+        world.insert_resource(UpgradeLevels {
+            shovel_radius: 2,
+            ..default()
+        });
+
+        let mut dug = VoxelSim::new(bounds);
+        dug.fill(Voxel::Dirt);
+        dug.set(IVec3::new(1, 2, 1), Voxel::Air);
+        let volume = world
+            .spawn((
+                dug.clone(),
+                VoxelWorldBounds {
+                    min: Vec3::ONE,
+                    max: Vec3::ONE + bounds.as_vec3() * VOXEL_SIZE,
+                },
+            ))
+            .id();
+        let grave = world
+            .spawn((
+                GraveState {
+                    slots: 3,
+                    filled: 2,
+                    rewarded: 1,
+                    reward_multiplier: 1.0,
+                },
+                GraveCenter(grave_center),
+            ))
+            .id();
+
+        capture_session(&mut world);
+        let snapshot = world.resource::<Session>().snapshot.clone().unwrap();
+        assert_eq!(snapshot.slot, 1);
+
+        // The level reloads fresh.
+        world.resource_mut::<Crusts>().0 = 0;
+        *world.resource_mut::<UpgradeLevels>() = default();
+        let mut fresh = VoxelSim::new(bounds);
+        fresh.fill(Voxel::Dirt);
+        world.entity_mut(volume).insert(fresh);
+        let mut state = world.get_mut::<GraveState>(grave).unwrap();
+        state.filled = 0;
+        state.rewarded = 0;
+
+        apply_snapshot(&mut world, &snapshot);
+
+        assert_eq!(world.resource::<Crusts>().0, 25);
+        assert_eq!(world.resource::<UpgradeLevels>().shovel_radius, 2);
+        assert_eq!(world.get::<VoxelSim>(volume).unwrap().runs(), dug.runs());
+        let state = world.get::<GraveState>(grave).unwrap();
+        assert_eq!((state.filled, state.rewarded), (2, 1));
+    }
+}
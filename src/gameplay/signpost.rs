@@ -0,0 +1,326 @@
+//! Signposts and gravestone inscriptions placed in the level.
+//! Short texts are just drawn in the world, long ones show a short label and
+//! open a panel with the full text when the player interacts with them.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+use bevy_mod_billboard::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use std::any::Any as _;
+
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::{
+        crosshair::CrosshairState,
+        highlight,
+        player::{camera::PlayerCamera, input::Interact},
+        tags::{TagIndex, Tags},
+    },
+    screens::Screen,
+    theme::GameFont,
+    third_party::avian3d::CollisionLayer,
+};
+
+const SIGN_INTERACT_DISTANCE: f32 = 2.5;
+const TEXT_SCALE: Vec3 = Vec3::splat(0.01);
+/// Texts longer than this get a short label in the world and a panel for the rest.
+const LONG_TEXT_CHARS: usize = 80;
+const SHORT_LABEL_CHARS: usize = 24;
+/// Rough width of a glyph relative to the font size, used for word wrapping.
+const GLYPH_ASPECT: f32 = 0.5;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<LookedAtSign>();
+    app.init_resource::<ReadingSign>();
+    app.add_observer(on_add_signpost);
+    app.add_observer(on_set_sign_text);
+    app.add_observer(open_sign_panel);
+    app.add_systems(
+        Update,
+        (
+            check_looking_at_sign
+                .run_if(in_state(Screen::Gameplay))
+                .in_set(PostPhysicsAppSystems::ChangeUi),
+            update_sign_text,
+        ),
+    );
+    // In `PostUpdate` so the escape that closes the panel doesn't also open the pause menu.
+    app.add_systems(
+        PostUpdate,
+        close_sign_panel.run_if(in_state(Screen::Gameplay).and(is_reading_sign)),
+    );
+}
+
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct Signpost {
+    pub text: String,
+    pub tags: String,
+    pub font_size: f32,
+    /// Max line width in world units before wrapping.
+    pub max_width: f32,
+    /// Face the camera, otherwise the text is fixed like an engraving.
+    pub billboard: bool,
+}
+
+impl Default for Signpost {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            tags: String::new(),
+            font_size: 36.0,
+            max_width: 3.0,
+            billboard: true,
+        }
+    }
+}
+
+impl Signpost {
+    fn is_long(&self) -> bool {
+        self.text.chars().count() > LONG_TEXT_CHARS
+    }
+
+    /// What gets drawn in the world.
+    fn label(&self) -> String {
+        let text = if self.is_long() {
+            let short: String = self.text.chars().take(SHORT_LABEL_CHARS).collect();
+            format!("{}...", short.trim_end())
+        } else {
+            self.text.clone()
+        };
+        let max_chars = self.max_width / (self.font_size * TEXT_SCALE.x * GLYPH_ASPECT);
+        wrap_text(&text, (max_chars as usize).max(1))
+    }
+}
+
+/// Greedy word wrap, words longer than a line get their own line.
+fn wrap_text(text: &str, max_chars: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Replace the text of every sign with `tag`.
+#[derive(Event)]
+pub(crate) struct SetSignText {
+    pub tag: String,
+    pub text: String,
+}
+
+#[derive(Component)]
+struct SignText;
+
+#[derive(Component)]
+struct SignPanel;
+
+#[derive(Resource, Default)]
+struct LookedAtSign(Option<Entity>);
+
+#[derive(Resource, Default)]
+struct ReadingSign(Option<Entity>);
+
+pub(crate) fn is_reading_sign(reading: Res<ReadingSign>) -> bool {
+    reading.0.is_some()
+}
+
+fn on_add_signpost(
+    add: On<Add, Signpost>,
+    mut commands: Commands,
+    signs: Query<&Signpost>,
+    font: Res<GameFont>,
+) {
+    let entity = add.entity;
+    let Ok(sign) = signs.get(entity) else {
+        return;
+    };
+
+    commands.entity(entity).insert((
+        Tags::from_csv(&sign.tags),
+        Collider::cuboid(0.5, 0.5, 0.5),
+        Sensor,
+        CollisionLayers::new(CollisionLayer::Sensor, LayerMask::NONE),
+    ));
+
+    let mut text = commands.spawn((
+        SignText,
+        BillboardText::new(sign.label()),
+        TextFont {
+            font: font.0.clone(),
+            font_size: sign.font_size,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+        Transform::from_translation(Vec3::Y * 0.5).with_scale(TEXT_SCALE),
+        ChildOf(entity),
+    ));
+    if !sign.billboard {
+        text.insert(BillboardLockAxis {
+            y_axis: true,
+            rotation: true,
+        });
+    }
+}
+
+fn on_set_sign_text(
+    event: On<SetSignText>,
+    tag_index: Res<TagIndex>,
+    mut signs: Query<&mut Signpost>,
+) {
+    let Some(entities) = tag_index.get(&event.tag) else {
+        return;
+    };
+    for &entity in entities {
+        if let Ok(mut sign) = signs.get_mut(entity) {
+            sign.text = event.text.clone();
+        }
+    }
+}
+
+fn update_sign_text(
+    signs: Query<(&Signpost, &Children), Changed<Signpost>>,
+    mut texts: Query<&mut BillboardText, With<SignText>>,
+) {
+    for (sign, children) in &signs {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = sign.label();
+            }
+        }
+    }
+}
+
+fn check_looking_at_sign(
+    mut commands: Commands,
+    player: Single<&GlobalTransform, With<PlayerCamera>>,
+    spatial_query: SpatialQuery,
+    signs: Query<&Signpost>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut looked_at: ResMut<LookedAtSign>,
+) {
+    let camera_transform = player.compute_transform();
+    let system_id = check_looking_at_sign.type_id();
+
+    if let Some(hit) = spatial_query.cast_ray(
+        camera_transform.translation,
+        camera_transform.forward(),
+        SIGN_INTERACT_DISTANCE,
+        true,
+        &SpatialQueryFilter::from_mask(CollisionLayer::Sensor),
+    ) {
+        // Only long signs have anything more to read.
+        if signs.get(hit.entity).is_ok_and(|sign| sign.is_long()) {
+            highlight::look_at(&mut commands, &mut looked_at.0, Some(hit.entity));
+            crosshair.wants_square.insert(system_id);
+            return;
+        }
+    }
+
+    highlight::look_at(&mut commands, &mut looked_at.0, None);
+    crosshair.wants_square.remove(&system_id);
+}
+
+fn open_sign_panel(
+    _on: On<Start<Interact>>,
+    mut commands: Commands,
+    looked_at: Res<LookedAtSign>,
+    mut reading: ResMut<ReadingSign>,
+    signs: Query<&Signpost>,
+    panels: Query<Entity, With<SignPanel>>,
+    font: Res<GameFont>,
+) {
+    let Some(entity) = looked_at.0 else {
+        return;
+    };
+    let Ok(sign) = signs.get(entity) else {
+        return;
+    };
+
+    for panel in &panels {
+        commands.entity(panel).despawn();
+    }
+    reading.0 = Some(entity);
+
+    commands.spawn((
+        Name::new("Sign Panel"),
+        SignPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+        children![(
+            Node {
+                max_width: Val::Px(480.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            children![
+                (
+                    Text::new(sign.text.clone()),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 22.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ),
+                (
+                    Text::new("esc to close"),
+                    TextFont {
+                        font: font.0.clone(),
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgba(0.6, 0.6, 0.6, 1.0)),
+                ),
+            ],
+        )],
+    ));
+}
+
+fn close_sign_panel(
+    mut commands: Commands,
+    mut reading: ResMut<ReadingSign>,
+    keys: Res<ButtonInput<KeyCode>>,
+    player: Single<&GlobalTransform, With<PlayerCamera>>,
+    transforms: Query<&GlobalTransform, With<Signpost>>,
+    panels: Query<Entity, With<SignPanel>>,
+) {
+    let Some(entity) = reading.0 else {
+        return;
+    };
+
+    let walked_away = transforms.get(entity).is_none_or(|sign| {
+        sign.translation().distance(player.translation()) > SIGN_INTERACT_DISTANCE
+    });
+    if !walked_away && !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    reading.0 = None;
+    for panel in &panels {
+        commands.entity(panel).despawn();
+    }
+}
@@ -1,4 +1,7 @@
 //! Store for buying upgrades to shovel/bucket/gun
+//!
+//! Stations with an `unlock_after` objective or sub-objective id stay locked until it's
+//! completed.
 
 use std::any::Any as _;
 
@@ -6,14 +9,23 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy_enhanced_input::prelude::*;
 use bevy_mod_billboard::prelude::*;
+use bevy_seedling::{prelude::*, sample::AudioSample};
 use bevy_trenchbroom::prelude::*;
+use bincode::{Decode, Encode};
 
 use crate::{
     PostPhysicsAppSystems,
+    asset_tracking::LoadResource,
+    audio::SfxPool,
     gameplay::{
         crosshair::CrosshairState,
+        crust_pickup::AutoPickup,
         crusts::Crusts,
+        game_mode::GameMode,
+        highlight::{self, HighlightOriginal},
         inventory::{Inventory, Item},
+        loadout::LevelLoadout,
+        objective::{ObjectiveCompleted, Objectives, SubObjectiveCompleted},
         player::{Player, PlayerHealth, camera::PlayerCamera, input::Interact},
     },
     screens::Screen,
@@ -24,25 +36,44 @@ use crate::{
 const UPGRADE_INTERACT_DISTANCE: f32 = 3.0;
 const CUBE_SIZE: f32 = 0.5;
 const TEXT_SCALE: Vec3 = Vec3::splat(0.01);
+/// Extra crust magnet radius per `pickup_radius` level.
+const PICKUP_RADIUS_PER_LEVEL: f32 = 1.5;
+/// Station `upgrade` that sells a grenade instead of upgrading anything.
+const GRENADE_PURCHASE: &str = "grenade";
 
 pub fn plugin(app: &mut App) {
     app.add_plugins(BillboardPlugin);
     app.init_resource::<LookedAtUpgrade>();
     app.init_resource::<UpgradeLevels>();
+    app.load_resource::<StoreAssets>();
     app.add_observer(on_add_upgrade_station);
     app.add_observer(interact_with_upgrade);
+    app.add_observer(unlock_after_sub_objective);
+    app.add_observer(unlock_after_objective);
     app.add_systems(
         Update,
         (
             check_looking_at_upgrade
                 .run_if(in_state(Screen::Gameplay))
                 .in_set(PostPhysicsAppSystems::ChangeUi),
-            update_upgrade_text.run_if(resource_changed::<UpgradeLevels>),
+            update_upgrade_text
+                .run_if(resource_changed::<UpgradeLevels>.or(resource_changed::<LevelLoadout>)),
+            show_unlocked_stations,
         ),
     );
 }
 
-#[derive(Resource, Default)]
+/// Upgrades that change an [`Item`]'s stats rather than the player's.
+pub(crate) const ITEM_UPGRADES: [&str; 6] = [
+    "shovel_radius",
+    "shovel_speed",
+    "bucket_radius",
+    "bucket_speed",
+    "gun_damage",
+    "gun_firerate",
+];
+
+#[derive(Resource, Encode, Decode, Default, Clone, Debug)]
 pub(crate) struct UpgradeLevels {
     pub shovel_radius: u32,
     pub shovel_speed: u32,
@@ -51,10 +82,11 @@ pub(crate) struct UpgradeLevels {
     pub gun_damage: u32,
     pub gun_firerate: u32,
     pub max_hp: u32,
+    pub pickup_radius: u32,
 }
 
 impl UpgradeLevels {
-    fn level_for(&self, upgrade: &str) -> u32 {
+    pub(crate) fn level_for(&self, upgrade: &str) -> u32 {
         match upgrade {
             "shovel_radius" => self.shovel_radius,
             "shovel_speed" => self.shovel_speed,
@@ -63,20 +95,28 @@ impl UpgradeLevels {
             "gun_damage" => self.gun_damage,
             "gun_firerate" => self.gun_firerate,
             "max_hp" => self.max_hp,
+            "pickup_radius" => self.pickup_radius,
             _ => 0,
         }
     }
 
-    fn increment(&mut self, upgrade: &str) {
+    pub(crate) fn level_mut(&mut self, upgrade: &str) -> Option<&mut u32> {
         match upgrade {
-            "shovel_radius" => self.shovel_radius += 1,
-            "shovel_speed" => self.shovel_speed += 1,
-            "bucket_radius" => self.bucket_radius += 1,
-            "bucket_speed" => self.bucket_speed += 1,
-            "gun_damage" => self.gun_damage += 1,
-            "gun_firerate" => self.gun_firerate += 1,
-            "max_hp" => self.max_hp += 1,
-            _ => {}
+            "shovel_radius" => Some(&mut self.shovel_radius),
+            "shovel_speed" => Some(&mut self.shovel_speed),
+            "bucket_radius" => Some(&mut self.bucket_radius),
+            "bucket_speed" => Some(&mut self.bucket_speed),
+            "gun_damage" => Some(&mut self.gun_damage),
+            "gun_firerate" => Some(&mut self.gun_firerate),
+            "max_hp" => Some(&mut self.max_hp),
+            "pickup_radius" => Some(&mut self.pickup_radius),
+            _ => None,
+        }
+    }
+
+    fn increment(&mut self, upgrade: &str) {
+        if let Some(level) = self.level_mut(upgrade) {
+            *level += 1;
         }
     }
 
@@ -96,25 +136,39 @@ fn display_name(upgrade: &str) -> &str {
         "gun_damage" => "Gun Damage",
         "gun_firerate" => "Gun Firerate",
         "max_hp" => "Max HP",
+        "pickup_radius" => "Crust Magnet",
+        GRENADE_PURCHASE => "Grenade",
         _ => "Unknown",
     }
 }
 
-fn upgrade_label(upgrade: &str, cost: u32) -> String {
+fn upgrade_label(upgrade: &str, cost: u32, loadout: &LevelLoadout) -> String {
     let name = display_name(upgrade);
+    if !loadout.allows_upgrade(upgrade) {
+        return format!("{name}\nunavailable");
+    }
     let plural = if cost == 1 { "" } else { "s" };
     format!("{name}\n{cost} crust{plural}")
 }
 
+fn locked_label(upgrade: &str, requirement: &str, objectives: &Objectives) -> String {
+    let title = objectives.title_for(requirement).unwrap_or(requirement);
+    format!("{}\nLOCKED — finish {title}", display_name(upgrade))
+}
+
 #[point_class(base(Transform, Visibility))]
 pub(crate) struct UpgradeStation {
     pub upgrade: String,
+    /// Objective or sub-objective id that has to be completed before this can be used.
+    /// Empty = always unlocked.
+    pub unlock_after: String,
 }
 
 impl Default for UpgradeStation {
     fn default() -> Self {
         Self {
             upgrade: String::new(),
+            unlock_after: String::new(),
         }
     }
 }
@@ -124,6 +178,61 @@ struct UpgradeText {
     upgrade: String,
 }
 
+/// On a station whose `unlock_after` requirement isn't completed yet.
+#[derive(Component, Debug)]
+struct StationLocked {
+    requirement: String,
+}
+
+/// The station's cube, with the material it switches to once unlocked.
+#[derive(Component)]
+struct StationCube {
+    unlocked: Handle<StandardMaterial>,
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct StoreAssets {
+    #[dependency]
+    locked_sound: Handle<AudioSample>,
+    #[dependency]
+    unlock_sound: Handle<AudioSample>,
+}
+
+impl FromWorld for StoreAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            locked_sound: assets.load("audio/sound_effects/button_hover.ogg"),
+            unlock_sound: assets.load("audio/sound_effects/button_press.ogg"),
+        }
+    }
+}
+
+/// The requirement a new station is locked behind, if any. Unknown ids warn and leave the
+/// station unlocked so a typo doesn't brick it. Outside of the story nothing ever completes,
+/// so nothing is locked.
+fn station_requirement(
+    unlock_after: &str,
+    objectives: &Objectives,
+    mode: GameMode,
+) -> Option<String> {
+    let requirement = unlock_after.trim();
+    if requirement.is_empty() {
+        return None;
+    }
+    match objectives.is_done(requirement) {
+        Some(done) => (!done && mode.has_story()).then(|| requirement.to_string()),
+        None => {
+            warn!(
+                "Upgrade station has unknown unlock_after {requirement:?}, expected an objective \
+                 or sub-objective id. Leaving it unlocked"
+            );
+            None
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 struct LookedAtUpgrade(Option<Entity>);
 
@@ -134,6 +243,9 @@ fn on_add_upgrade_station(
     mut materials: ResMut<Assets<StandardMaterial>>,
     stations: Query<&UpgradeStation>,
     upgrade_levels: Res<UpgradeLevels>,
+    loadout: Res<LevelLoadout>,
+    objectives: Res<Objectives>,
+    mode: Res<GameMode>,
     font: Res<GameFont>,
 ) {
     let entity = add.entity;
@@ -141,14 +253,33 @@ fn on_add_upgrade_station(
         return;
     };
 
-    let cost = upgrade_levels.cost_for(&station.upgrade);
-    let label = upgrade_label(&station.upgrade, cost);
+    let requirement = station_requirement(&station.unlock_after, &objectives, *mode);
+    let label = match &requirement {
+        Some(requirement) => locked_label(&station.upgrade, requirement, &objectives),
+        None => upgrade_label(
+            &station.upgrade,
+            upgrade_levels.cost_for(&station.upgrade),
+            &loadout,
+        ),
+    };
 
     let cube_mesh = meshes.add(Cuboid::new(CUBE_SIZE, CUBE_SIZE, CUBE_SIZE));
-    let material = materials.add(StandardMaterial {
+    let unlocked = materials.add(StandardMaterial {
         base_color: Color::srgb(0.3, 0.6, 0.3),
         ..default()
     });
+    let material = match requirement {
+        Some(requirement) => {
+            commands
+                .entity(entity)
+                .insert(StationLocked { requirement });
+            materials.add(StandardMaterial {
+                base_color: Color::srgb(0.4, 0.4, 0.4),
+                ..default()
+            })
+        }
+        None => unlocked.clone(),
+    };
 
     commands.entity(entity).insert((
         Collider::cuboid(CUBE_SIZE, CUBE_SIZE, CUBE_SIZE),
@@ -157,7 +288,11 @@ fn on_add_upgrade_station(
     ));
 
     commands.entity(entity).with_children(|parent| {
-        parent.spawn((Mesh3d(cube_mesh), MeshMaterial3d(material)));
+        parent.spawn((
+            Mesh3d(cube_mesh),
+            MeshMaterial3d(material),
+            StationCube { unlocked },
+        ));
         parent.spawn((
             UpgradeText {
                 upgrade: station.upgrade.clone(),
@@ -177,6 +312,7 @@ fn on_add_upgrade_station(
 }
 
 fn check_looking_at_upgrade(
+    mut commands: Commands,
     player: Single<&GlobalTransform, With<PlayerCamera>>,
     spatial_query: SpatialQuery,
     stations: Query<(), With<UpgradeStation>>,
@@ -194,38 +330,68 @@ fn check_looking_at_upgrade(
         &SpatialQueryFilter::from_mask(CollisionLayer::Prop),
     ) {
         if stations.get(hit.entity).is_ok() {
-            looked_at.0 = Some(hit.entity);
+            highlight::look_at(&mut commands, &mut looked_at.0, Some(hit.entity));
             crosshair.wants_square.insert(system_id);
             return;
         }
     }
 
-    looked_at.0 = None;
+    highlight::look_at(&mut commands, &mut looked_at.0, None);
     crosshair.wants_square.remove(&system_id);
 }
 
 fn interact_with_upgrade(
     _on: On<Start<Interact>>,
+    mut commands: Commands,
     looked_at: Res<LookedAtUpgrade>,
-    stations: Query<&UpgradeStation>,
+    stations: Query<(&UpgradeStation, Has<StationLocked>)>,
+    store_assets: Res<StoreAssets>,
     mut crusts: ResMut<Crusts>,
     mut inventory: ResMut<Inventory>,
     mut upgrade_levels: ResMut<UpgradeLevels>,
-    mut player_health: Single<&mut PlayerHealth, With<Player>>,
+    loadout: Res<LevelLoadout>,
+    player: Single<(&mut PlayerHealth, &mut AutoPickup), With<Player>>,
 ) {
     let Some(entity) = looked_at.0 else {
         return;
     };
-    let Ok(station) = stations.get(entity) else {
+    let Ok((station, locked)) = stations.get(entity) else {
         return;
     };
+    if locked {
+        commands.spawn((
+            SamplePlayer::new(store_assets.locked_sound.clone()),
+            PlaybackSettings {
+                speed: 0.6,
+                ..default()
+            },
+            SfxPool,
+        ));
+        return;
+    }
+    if !loadout.allows_upgrade(&station.upgrade) {
+        return;
+    }
 
     let cost = upgrade_levels.cost_for(&station.upgrade);
     if !crusts.try_spend(cost) {
         return;
     }
+    if station.upgrade == GRENADE_PURCHASE {
+        if let Some(grenade) = Item::from_key(GRENADE_PURCHASE) {
+            inventory.give(grenade);
+            info!("Bought a grenade!");
+        }
+        return;
+    }
 
-    apply_upgrade(&station.upgrade, &mut inventory, &mut player_health);
+    let (mut player_health, mut auto_pickup) = player.into_inner();
+    apply_upgrade(
+        &station.upgrade,
+        &mut inventory,
+        &mut player_health,
+        &mut auto_pickup,
+    );
     upgrade_levels.increment(&station.upgrade);
     info!(
         "Upgraded {}! Level {} -> {}",
@@ -235,38 +401,13 @@ fn interact_with_upgrade(
     );
 }
 
-fn apply_upgrade(upgrade: &str, inventory: &mut Inventory, player_health: &mut PlayerHealth) {
+fn apply_upgrade(
+    upgrade: &str,
+    inventory: &mut Inventory,
+    player_health: &mut PlayerHealth,
+    auto_pickup: &mut AutoPickup,
+) {
     match upgrade {
-        "shovel_radius" => {
-            if let Some(Item::Shovel(stats)) = &mut inventory.slots[0] {
-                stats.radius += 0.5;
-            }
-        }
-        "shovel_speed" => {
-            if let Some(Item::Shovel(stats)) = &mut inventory.slots[0] {
-                stats.cooldown = (stats.cooldown - 0.05).max(0.05);
-            }
-        }
-        "bucket_radius" => {
-            if let Some(Item::DirtBucket(stats)) = &mut inventory.slots[2] {
-                stats.radius += 0.5;
-            }
-        }
-        "bucket_speed" => {
-            if let Some(Item::DirtBucket(stats)) = &mut inventory.slots[2] {
-                stats.cooldown = (stats.cooldown - 0.05).max(0.05);
-            }
-        }
-        "gun_damage" => {
-            if let Some(Item::Gun(stats)) = &mut inventory.slots[1] {
-                stats.damage += 3.0;
-            }
-        }
-        "gun_firerate" => {
-            if let Some(Item::Gun(stats)) = &mut inventory.slots[1] {
-                stats.cooldown = (stats.cooldown - 0.01).max(0.01);
-            }
-        }
         "max_hp" => {
             player_health.max += 1;
             player_health.current = player_health
@@ -274,18 +415,256 @@ fn apply_upgrade(upgrade: &str, inventory: &mut Inventory, player_health: &mut P
                 .saturating_add(1)
                 .min(player_health.max);
         }
+        "pickup_radius" => {
+            auto_pickup.radius += PICKUP_RADIUS_PER_LEVEL;
+        }
+        _ if ITEM_UPGRADES.contains(&upgrade) => {
+            for item in inventory.slots.iter_mut().flatten() {
+                upgrade_item(upgrade, item);
+            }
+        }
         _ => {
             warn!("Unknown upgrade type: {upgrade}");
         }
     }
 }
 
+/// Applies one level of `upgrade` to `item`, if it's for that kind of item.
+fn upgrade_item(upgrade: &str, item: &mut Item) {
+    match (upgrade, item) {
+        ("shovel_radius", Item::Shovel(stats)) | ("bucket_radius", Item::DirtBucket(stats)) => {
+            stats.radius += 0.5;
+        }
+        ("shovel_speed", Item::Shovel(stats)) | ("bucket_speed", Item::DirtBucket(stats)) => {
+            stats.cooldown = (stats.cooldown - 0.05).max(0.05);
+        }
+        ("gun_damage", Item::Gun(stats)) => {
+            stats.damage += 3.0;
+        }
+        ("gun_firerate", Item::Gun(stats)) => {
+            stats.cooldown = (stats.cooldown - 0.01).max(0.01);
+        }
+        _ => {}
+    }
+}
+
+/// Brings a freshly made item up to the current upgrade levels.
+pub(crate) fn apply_upgrade_levels(item: &mut Item, levels: &UpgradeLevels) {
+    for upgrade in ITEM_UPGRADES {
+        for _ in 0..levels.level_for(upgrade) {
+            upgrade_item(upgrade, item);
+        }
+    }
+}
+
+/// Applies the player's own upgrades in `levels` on top of their base stats, the
+/// counterpart to [`apply_upgrade_levels`] for items.
+pub(crate) fn apply_player_upgrade_levels(
+    levels: &UpgradeLevels,
+    player_health: &mut PlayerHealth,
+    auto_pickup: &mut AutoPickup,
+) {
+    player_health.max += levels.max_hp;
+    player_health.current += levels.max_hp;
+    auto_pickup.radius += levels.pickup_radius as f32 * PICKUP_RADIUS_PER_LEVEL;
+}
+
 fn update_upgrade_text(
     upgrade_levels: Res<UpgradeLevels>,
-    mut texts: Query<(&UpgradeText, &mut BillboardText)>,
+    loadout: Res<LevelLoadout>,
+    mut texts: Query<(&UpgradeText, &ChildOf, &mut BillboardText)>,
+    locked: Query<(), With<StationLocked>>,
 ) {
-    for (upgrade_text, mut text) in &mut texts {
+    for (upgrade_text, child_of, mut text) in &mut texts {
+        if locked.contains(child_of.parent()) {
+            continue;
+        }
         let cost = upgrade_levels.cost_for(&upgrade_text.upgrade);
-        text.0 = upgrade_label(&upgrade_text.upgrade, cost);
+        text.0 = upgrade_label(&upgrade_text.upgrade, cost, &loadout);
+    }
+}
+
+fn unlock_stations(
+    commands: &mut Commands,
+    completed: &str,
+    stations: &Query<(Entity, &StationLocked)>,
+) {
+    for (entity, locked) in stations {
+        if locked.requirement == completed {
+            info!("Upgrade station {entity} unlocked by '{completed}'");
+            commands.entity(entity).remove::<StationLocked>();
+        }
+    }
+}
+
+fn unlock_after_sub_objective(
+    completed: On<SubObjectiveCompleted>,
+    mut commands: Commands,
+    stations: Query<(Entity, &StationLocked)>,
+) {
+    unlock_stations(&mut commands, &completed.sub_objective, &stations);
+}
+
+fn unlock_after_objective(
+    completed: On<ObjectiveCompleted>,
+    mut commands: Commands,
+    stations: Query<(Entity, &StationLocked)>,
+) {
+    unlock_stations(&mut commands, &completed.objective, &stations);
+}
+
+/// Swaps freshly unlocked stations over to their unlocked look and plays a chime. Stations
+/// that were despawned rather than unlocked are skipped since they're gone from the query.
+fn show_unlocked_stations(
+    mut commands: Commands,
+    mut unlocked: RemovedComponents<StationLocked>,
+    stations: Query<&Children, With<UpgradeStation>>,
+    mut cubes: Query<(&StationCube, &mut MeshMaterial3d<StandardMaterial>)>,
+    mut texts: Query<(&UpgradeText, &mut BillboardText)>,
+    upgrade_levels: Res<UpgradeLevels>,
+    loadout: Res<LevelLoadout>,
+    store_assets: Res<StoreAssets>,
+) {
+    for entity in unlocked.read() {
+        let Ok(children) = stations.get(entity) else {
+            continue;
+        };
+        for child in children.iter() {
+            if let Ok((cube, mut material)) = cubes.get_mut(child) {
+                material.0 = cube.unlocked.clone();
+                // Otherwise looking away would put the locked material back.
+                commands.entity(child).remove::<HighlightOriginal>();
+            }
+            if let Ok((upgrade_text, mut text)) = texts.get_mut(child) {
+                let cost = upgrade_levels.cost_for(&upgrade_text.upgrade);
+                text.0 = upgrade_label(&upgrade_text.upgrade, cost, &loadout);
+            }
+        }
+        commands.spawn((
+            SamplePlayer::new(store_assets.unlock_sound.clone()),
+            PlaybackSettings {
+                speed: 2.0,
+                ..default()
+            },
+            SfxPool,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_app(objectives: Objectives) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .insert_resource(objectives)
+            .insert_resource(StoreAssets {
+                locked_sound: Handle::default(),
+                unlock_sound: Handle::default(),
+            })
+            .insert_resource(GameFont(Handle::default()))
+            .init_resource::<GameMode>()
+            .init_resource::<UpgradeLevels>()
+            .init_resource::<LevelLoadout>()
+            .add_observer(on_add_upgrade_station)
+            .add_observer(unlock_after_sub_objective)
+            .add_observer(unlock_after_objective)
+            .add_systems(Update, show_unlocked_stations);
+        app
+    }
+
+    fn spawn_station(app: &mut App, unlock_after: &str) -> Entity {
+        let station = app
+            .world_mut()
+            .spawn(UpgradeStation {
+                upgrade: "gun_damage".to_string(),
+                unlock_after: unlock_after.to_string(),
+            })
+            .id();
+        app.update();
+        station
+    }
+
+    fn label(app: &mut App) -> String {
+        let mut texts = app.world_mut().query::<&BillboardText>();
+        texts.single(app.world()).unwrap().0.clone()
+    }
+
+    fn cube_material(app: &mut App) -> (Handle<StandardMaterial>, Handle<StandardMaterial>) {
+        let mut cubes = app
+            .world_mut()
+            .query::<(&StationCube, &MeshMaterial3d<StandardMaterial>)>();
+        let (cube, material) = cubes.single(app.world()).unwrap();
+        (cube.unlocked.clone(), material.0.clone())
+    }
+
+    #[test]
+    fn locked_station_unlocks_when_its_sub_objective_completes() {
+        let mut app = store_app(Objectives::default());
+        let station = spawn_station(&mut app, "store_hit");
+
+        assert!(app.world().get::<StationLocked>(station).is_some());
+        assert_eq!(
+            label(&mut app),
+            "Gun Damage\nLOCKED — finish shoot the whale in the store"
+        );
+        let (unlocked, material) = cube_material(&mut app);
+        assert_ne!(material, unlocked);
+
+        app.world_mut().trigger(SubObjectiveCompleted {
+            objective: "the_molt".to_string(),
+            sub_objective: "dig_3".to_string(),
+            forced: false,
+        });
+        app.update();
+        assert!(app.world().get::<StationLocked>(station).is_some());
+
+        app.world_mut().trigger(SubObjectiveCompleted {
+            objective: "the_molt".to_string(),
+            sub_objective: "store_hit".to_string(),
+            forced: false,
+        });
+        app.update();
+
+        assert!(app.world().get::<StationLocked>(station).is_none());
+        assert_eq!(label(&mut app), "Gun Damage\n1 crust");
+        let (unlocked, material) = cube_material(&mut app);
+        assert_eq!(material, unlocked);
+    }
+
+    #[test]
+    fn locked_station_unlocks_when_its_objective_completes() {
+        let mut app = store_app(Objectives::default());
+        let station = spawn_station(&mut app, "the_molt");
+        assert!(label(&mut app).ends_with("LOCKED — finish The Molt"));
+
+        app.world_mut().trigger(ObjectiveCompleted {
+            objective: "the_molt".to_string(),
+        });
+        app.update();
+        assert!(app.world().get::<StationLocked>(station).is_none());
+    }
+
+    #[test]
+    fn station_spawns_unlocked_when_requirement_is_already_done() {
+        let mut objectives = Objectives::default();
+        objectives.complete("dig_3");
+        let mut app = store_app(objectives);
+        let station = spawn_station(&mut app, "dig_3");
+
+        assert!(app.world().get::<StationLocked>(station).is_none());
+        assert_eq!(label(&mut app), "Gun Damage\n1 crust");
+        let (unlocked, material) = cube_material(&mut app);
+        assert_eq!(material, unlocked);
+    }
+
+    #[test]
+    fn unknown_requirement_leaves_station_unlocked() {
+        let mut app = store_app(Objectives::default());
+        let station = spawn_station(&mut app, "stor_hit");
+        assert!(app.world().get::<StationLocked>(station).is_none());
     }
 }
@@ -0,0 +1,168 @@
+//! What the ground under a character is made of, for picking footstep and landing sounds.
+//!
+//! Voxel volumes report their material directly. Brush textures aren't known per hit, so brush
+//! geometry is only classified when it's part of a `surface_brush`.
+
+use bevy::prelude::*;
+use bevy_ahoy::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use crate::{
+    PostPhysicsAppSystems,
+    gameplay::dig::{VOXEL_SIZE, Voxel, VoxelSim, world_to_voxel},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        update_ground_surfaces
+            .run_if(in_state(Screen::Gameplay))
+            .before(PostPhysicsAppSystems::PlaySounds),
+    );
+}
+
+#[derive(FgdType, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[number_key]
+pub(crate) enum Surface {
+    #[default]
+    /// Unknown, uses the default sounds
+    Default = 0,
+    /// Stone
+    Stone = 1,
+    /// Wood
+    Wood = 2,
+    /// Metal
+    Metal = 3,
+    /// Dirt
+    Dirt = 4,
+}
+
+impl From<Voxel> for Surface {
+    fn from(voxel: Voxel) -> Self {
+        match voxel {
+            Voxel::Dirt | Voxel::PackedDirt | Voxel::Sand => Surface::Dirt,
+            Voxel::Barrier => Surface::Stone,
+            Voxel::Air => Surface::Default,
+        }
+    }
+}
+
+/// Brushes that sound like `surface` when walked on.
+#[solid_class(base(Transform, Visibility))]
+#[derive(Default)]
+pub(crate) struct SurfaceBrush {
+    pub surface: Surface,
+}
+
+/// The surface a character is standing on, or last stood on while airborne.
+/// Updated once per frame so footstep and landing sounds agree without each casting for it.
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct GroundSurface(pub(crate) Surface);
+
+fn update_ground_surfaces(
+    mut characters: Query<(&CharacterControllerState, &mut GroundSurface)>,
+    sims: Query<(&VoxelSim, &GlobalTransform)>,
+    brushes: Query<&SurfaceBrush>,
+    parents: Query<&ChildOf>,
+) {
+    for (state, mut ground) in &mut characters {
+        let Some(hit) = &state.grounded else {
+            continue;
+        };
+        let surface = surface_under(
+            hit.entity,
+            hit.point1,
+            hit.normal1,
+            &sims,
+            &brushes,
+            &parents,
+        );
+        if ground.0 != surface {
+            ground.0 = surface;
+        }
+    }
+}
+
+/// What `entity` is made of where it was hit at `point`, with `normal` pointing out of it.
+fn surface_under(
+    entity: Entity,
+    point: Vec3,
+    normal: Vec3,
+    sims: &Query<(&VoxelSim, &GlobalTransform)>,
+    brushes: &Query<&SurfaceBrush>,
+    parents: &Query<&ChildOf>,
+) -> Surface {
+    voxel_surface(point - normal * VOXEL_SIZE * 0.5, sims)
+        .or_else(|| {
+            std::iter::once(entity)
+                .chain(parents.iter_ancestors(entity))
+                .find_map(|entity| brushes.get(entity).ok())
+                .map(|brush| brush.surface)
+        })
+        .unwrap_or_default()
+}
+
+fn voxel_surface(point: Vec3, sims: &Query<(&VoxelSim, &GlobalTransform)>) -> Option<Surface> {
+    sims.iter().find_map(
+        |(sim, transform)| match sim.get(world_to_voxel(transform, point))? {
+            Voxel::Air => None,
+            voxel => Some(voxel.into()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce as _;
+
+    use super::*;
+
+    #[test]
+    fn ground_under_voxels_and_surface_brushes() {
+        let mut world = World::new();
+        let mut sim = VoxelSim::new(IVec3::splat(4));
+        sim.set(IVec3::new(1, 0, 1), Voxel::Dirt);
+        sim.set(IVec3::new(2, 0, 1), Voxel::Barrier);
+        let volume = world.spawn((sim, GlobalTransform::IDENTITY)).id();
+        let brush = world
+            .spawn(SurfaceBrush {
+                surface: Surface::Wood,
+            })
+            .id();
+        // Brush colliders sit on children of the brush entity.
+        let face = world.spawn(ChildOf(brush)).id();
+        let worldspawn = world.spawn_empty().id();
+
+        let on_top = |x: i32| Vec3::new(x as f32 + 0.5, 1.0, 1.5) * VOXEL_SIZE;
+        let surfaces = world
+            .run_system_once(
+                move |sims: Query<(&VoxelSim, &GlobalTransform)>,
+                      brushes: Query<&SurfaceBrush>,
+                      parents: Query<&ChildOf>| {
+                    let under = |entity, point| {
+                        surface_under(entity, point, Vec3::Y, &sims, &brushes, &parents)
+                    };
+                    [
+                        under(volume, on_top(1)),
+                        under(volume, on_top(2)),
+                        // Nothing but air there.
+                        under(volume, on_top(3)),
+                        under(face, Vec3::splat(10.0)),
+                        under(worldspawn, Vec3::splat(10.0)),
+                    ]
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            surfaces,
+            [
+                Surface::Dirt,
+                Surface::Stone,
+                Surface::Default,
+                Surface::Wood,
+                Surface::Default,
+            ]
+        );
+    }
+}
@@ -28,6 +28,8 @@ impl Tags {
 #[derive(Resource, Default)]
 pub(crate) struct TagIndex {
     map: HashMap<String, EntityHashSet>,
+    /// Bumped on every change, so lookups can be cached until the index changes.
+    generation: u64,
 }
 
 impl TagIndex {
@@ -35,13 +37,19 @@ impl TagIndex {
         self.map.get(tag)
     }
 
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     fn insert(&mut self, entity: Entity, tags: &Tags) {
+        self.generation += 1;
         for tag in &tags.0 {
             self.map.entry(tag.clone()).or_default().insert(entity);
         }
     }
 
     fn remove(&mut self, entity: Entity, tags: &Tags) {
+        self.generation += 1;
         for tag in &tags.0 {
             if let Some(set) = self.map.get_mut(tag) {
                 set.remove(&entity);
@@ -53,6 +61,42 @@ impl TagIndex {
     }
 }
 
+/// Remembers what tags resolved to, so systems that look the same tags up every tick only
+/// search the index again once it changes. Kept as a `Local` by each system, since what
+/// counts as a match differs between them.
+#[derive(Default)]
+pub(crate) struct TagResolutionCache {
+    resolved: HashMap<String, (Option<Entity>, u64)>,
+}
+
+impl TagResolutionCache {
+    /// The first entity tagged `tag` that's `usable`. A cached match is reused while it stays
+    /// usable, and a cached miss until the index changes. Entities that stop being usable
+    /// must not become usable again, like dead NPCs.
+    pub fn resolve(
+        &mut self,
+        index: &TagIndex,
+        tag: &str,
+        usable: impl Fn(Entity) -> bool,
+    ) -> Option<Entity> {
+        if let Some(&(cached, generation)) = self.resolved.get(tag) {
+            if generation == index.generation() {
+                match cached {
+                    None => return None,
+                    Some(entity) if usable(entity) => return Some(entity),
+                    Some(_) => {}
+                }
+            }
+        }
+        let resolved = index
+            .get(tag)
+            .and_then(|set| set.iter().copied().find(|&entity| usable(entity)));
+        self.resolved
+            .insert(tag.to_string(), (resolved, index.generation()));
+        resolved
+    }
+}
+
 fn on_add_tags(add: On<Add, Tags>, mut index: ResMut<TagIndex>, query: Query<&Tags>) {
     if let Ok(tags) = query.get(add.entity) {
         index.insert(add.entity, tags);
@@ -64,3 +108,39 @@ fn on_remove_tags(remove: On<Remove, Tags>, mut index: ResMut<TagIndex>, query:
         index.remove(remove.entity, tags);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn misses_are_retried_only_once_the_index_changes() {
+        let mut index = TagIndex::default();
+        let mut cache = TagResolutionCache::default();
+        let mut world = World::new();
+        let dead = world.spawn_empty().id();
+        let alive = world.spawn_empty().id();
+        index.insert(dead, &Tags::from_csv("larry"));
+
+        let checks = Cell::new(0);
+        let living = |entity| {
+            checks.set(checks.get() + 1);
+            entity != dead
+        };
+        assert_eq!(cache.resolve(&index, "larry", living), None);
+        assert_eq!(checks.get(), 1);
+        assert_eq!(cache.resolve(&index, "larry", living), None);
+        assert_eq!(checks.get(), 1, "a miss shouldn't search again");
+
+        index.insert(alive, &Tags::from_csv("larry"));
+        assert_eq!(cache.resolve(&index, "larry", living), Some(alive));
+        checks.set(0);
+        assert_eq!(cache.resolve(&index, "larry", living), Some(alive));
+        assert_eq!(checks.get(), 1, "a hit only checks the cached entity");
+
+        index.remove(alive, &Tags::from_csv("larry"));
+        assert_eq!(cache.resolve(&index, "larry", living), None);
+    }
+}
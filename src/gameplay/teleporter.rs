@@ -0,0 +1,219 @@
+//! Teleporter pads that move the player to another tagged point in the level.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use bevy_seedling::prelude::*;
+use bevy_trenchbroom::prelude::*;
+
+use std::any::TypeId;
+
+use crate::{
+    asset_tracking::LoadResource,
+    audio::SfxPool,
+    game_running,
+    gameplay::{
+        player::{Player, PlayerDead, input::BlocksInput},
+        tags::{TagIndex, Tags},
+    },
+    screens::Screen,
+};
+
+/// How long the player can't move after arriving.
+const INPUT_LOCK_SECONDS: f32 = 0.3;
+const FLASH_SECONDS: f32 = 0.4;
+
+pub fn plugin(app: &mut App) {
+    app.load_resource::<TeleporterAssets>();
+    app.add_observer(on_add_teleporter);
+    app.add_systems(
+        Update,
+        (teleport_player, tick_teleport_cooldown, fade_teleport_flash).run_if(game_running),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), release_input_lock);
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct TeleporterAssets {
+    #[dependency]
+    whoosh: Handle<AudioSample>,
+}
+
+impl FromWorld for TeleporterAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            whoosh: assets.load("audio/sound_effects/teleport.wav"),
+        }
+    }
+}
+
+#[point_class(base(Transform, Visibility))]
+pub(crate) struct Teleporter {
+    /// Tag of the entity to teleport to.
+    pub target_tag: String,
+    pub tags: String,
+    /// Horizontal radius of the pad.
+    pub radius: f32,
+    /// How far above the pad the player still counts as standing on it.
+    pub height: f32,
+    /// Seconds before any pad can teleport the player again.
+    pub cooldown: f32,
+    /// Keep the player's velocity through the teleport instead of stopping them.
+    pub keep_velocity: bool,
+}
+
+impl Default for Teleporter {
+    fn default() -> Self {
+        Self {
+            target_tag: String::new(),
+            tags: String::new(),
+            radius: 1.0,
+            height: 2.0,
+            cooldown: 1.0,
+            keep_velocity: false,
+        }
+    }
+}
+
+impl Teleporter {
+    fn contains(&self, pad: Vec3, point: Vec3) -> bool {
+        let offset = point - pad;
+        offset.xz().length() <= self.radius && (0.0..=self.height).contains(&offset.y)
+    }
+}
+
+/// On the player after teleporting.
+#[derive(Component)]
+struct TeleportCooldown {
+    cooldown: Timer,
+    input_lock: Timer,
+}
+
+#[derive(Component)]
+struct TeleportFlash(Timer);
+
+fn on_add_teleporter(add: On<Add, Teleporter>, mut commands: Commands, pads: Query<&Teleporter>) {
+    let Ok(pad) = pads.get(add.entity) else {
+        return;
+    };
+    commands
+        .entity(add.entity)
+        .insert(Tags::from_csv(&pad.tags));
+}
+
+fn teleport_player(
+    mut commands: Commands,
+    mut player: Single<
+        (
+            Entity,
+            &mut Transform,
+            &GlobalTransform,
+            &mut LinearVelocity,
+        ),
+        (With<Player>, Without<PlayerDead>, Without<TeleportCooldown>),
+    >,
+    pads: Query<(Entity, &Teleporter, &GlobalTransform)>,
+    tag_index: Res<TagIndex>,
+    global_transforms: Query<&GlobalTransform>,
+    mut blocks_input: ResMut<BlocksInput>,
+    assets: Res<TeleporterAssets>,
+    // Pad the player is currently standing on, teleports only fire when stepping onto a new one.
+    mut standing_on: Local<Option<Entity>>,
+) {
+    let (entity, transform, player_transform, velocity) = &mut *player;
+    let player_pos = player_transform.translation();
+
+    let pad = pads
+        .iter()
+        .find(|(_, pad, pad_transform)| pad.contains(pad_transform.translation(), player_pos));
+    let Some((pad_entity, pad, _)) = pad else {
+        *standing_on = None;
+        return;
+    };
+    if *standing_on == Some(pad_entity) {
+        return;
+    }
+    *standing_on = Some(pad_entity);
+
+    let Some(destination) = tag_index
+        .get(&pad.target_tag)
+        .and_then(|entities| entities.iter().find(|&&e| e != pad_entity))
+        .copied()
+    else {
+        warn!("Teleporter target '{}' not found", pad.target_tag);
+        return;
+    };
+    let Ok(destination_transform) = global_transforms.get(destination) else {
+        return;
+    };
+
+    transform.translation = destination_transform.translation();
+    if !pad.keep_velocity {
+        velocity.0 = Vec3::ZERO;
+    }
+    // Arriving on a linked pad counts as already standing on it.
+    if pads.contains(destination) {
+        *standing_on = Some(destination);
+    }
+
+    commands.entity(*entity).insert(TeleportCooldown {
+        cooldown: Timer::from_seconds(pad.cooldown, TimerMode::Once),
+        input_lock: Timer::from_seconds(INPUT_LOCK_SECONDS, TimerMode::Once),
+    });
+    blocks_input.insert(TypeId::of::<TeleportCooldown>());
+
+    commands.spawn((SamplePlayer::new(assets.whoosh.clone()), SfxPool));
+    commands.spawn((
+        Name::new("Teleport Flash"),
+        TeleportFlash(Timer::from_seconds(FLASH_SECONDS, TimerMode::Once)),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::WHITE),
+        GlobalZIndex(3),
+        Pickable::IGNORE,
+        DespawnOnExit(Screen::Gameplay),
+    ));
+}
+
+fn tick_teleport_cooldown(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player: Query<(Entity, &mut TeleportCooldown)>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    for (entity, mut cooldown) in &mut player {
+        cooldown.cooldown.tick(time.delta());
+        cooldown.input_lock.tick(time.delta());
+        if cooldown.input_lock.just_finished() {
+            blocks_input.remove(&TypeId::of::<TeleportCooldown>());
+        }
+        if cooldown.cooldown.is_finished() && cooldown.input_lock.is_finished() {
+            commands.entity(entity).remove::<TeleportCooldown>();
+        }
+    }
+}
+
+/// Leaving mid-teleport despawns the player before the lock runs out.
+fn release_input_lock(mut blocks_input: ResMut<BlocksInput>) {
+    blocks_input.remove(&TypeId::of::<TeleportCooldown>());
+}
+
+fn fade_teleport_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut TeleportFlash, &mut BackgroundColor)>,
+) {
+    for (entity, mut flash, mut background) in &mut flashes {
+        flash.0.tick(time.delta());
+        if flash.0.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        background.0 = Color::WHITE.with_alpha(1.0 - flash.0.fraction());
+    }
+}
@@ -0,0 +1,70 @@
+//! The graphics quality setting, for keeping the framerate up on low-end machines and the web.
+//! Low quality skips particle effects, turns off point light shadows and runs the voxel sim
+//! at a lower rate, which also means fewer remeshes.
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GraphicsQuality>();
+    app.add_observer(disable_new_light_shadows);
+    app.add_systems(
+        Update,
+        apply_shadow_quality.run_if(resource_changed::<GraphicsQuality>),
+    );
+}
+
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub(crate) enum GraphicsQuality {
+    #[default]
+    High,
+    Low,
+}
+
+impl GraphicsQuality {
+    pub(crate) fn particles(self) -> bool {
+        self == Self::High
+    }
+
+    pub(crate) fn shadows(self) -> bool {
+        self == Self::High
+    }
+}
+
+/// On point lights that had their shadows turned off for low quality, so they get them back.
+#[derive(Component)]
+struct ShadowsOffForQuality;
+
+fn disable_new_light_shadows(
+    add: On<Add, PointLight>,
+    mut commands: Commands,
+    quality: Res<GraphicsQuality>,
+    mut lights: Query<&mut PointLight>,
+) {
+    if quality.shadows() {
+        return;
+    }
+    let Ok(mut light) = lights.get_mut(add.entity) else {
+        return;
+    };
+    if light.shadows_enabled {
+        light.shadows_enabled = false;
+        commands.entity(add.entity).insert(ShadowsOffForQuality);
+    }
+}
+
+fn apply_shadow_quality(
+    mut commands: Commands,
+    quality: Res<GraphicsQuality>,
+    mut lights: Query<(Entity, &mut PointLight, Has<ShadowsOffForQuality>)>,
+) {
+    for (entity, mut light, turned_off) in &mut lights {
+        if quality.shadows() && turned_off {
+            light.shadows_enabled = true;
+            commands.entity(entity).remove::<ShadowsOffForQuality>();
+        } else if !quality.shadows() && light.shadows_enabled {
+            light.shadows_enabled = false;
+            commands.entity(entity).insert(ShadowsOffForQuality);
+        }
+    }
+}
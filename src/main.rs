@@ -8,8 +8,10 @@ mod audio;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod gameplay;
+mod graphics;
 mod hdr;
 mod menus;
+mod persistence;
 mod props;
 mod screens;
 mod shader_compilation;
@@ -133,6 +135,9 @@ fn main() -> AppExit {
     // Set up the `Pause` state.
     app.init_state::<Pause>();
     app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+    // Stopping virtual time freezes every `Time`-driven timer, the fixed timestep and physics.
+    app.add_systems(OnEnter(Pause(true)), pause_virtual_time);
+    app.add_systems(OnExit(Pause(true)), unpause_virtual_time);
 
     #[cfg(feature = "dev_native")]
     // Adding these here so that third party plugins can register their BRP methods.
@@ -157,6 +162,7 @@ fn main() -> AppExit {
         ui_camera::plugin,
         hdr::plugin,
         audio::plugin,
+        graphics::plugin,
     ));
 
     // Add plugins that proload levels. These have to come later than the other plugins
@@ -255,6 +261,8 @@ bitflags! {
         const GIZMO3 = 0b0001000;
         /// Used by the crab HUD render-to-texture camera and crab model.
         const CRAB_HUD = 0b00010000;
+        /// Minimap icons, only rendered by the top-down minimap camera.
+        const MINIMAP = 0b00100000;
     }
 }
 
@@ -273,3 +281,16 @@ struct Pause(pub(crate) bool);
 /// A system set for systems that shouldn't run while the game is paused.
 #[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct PausableSystems;
+
+/// Run condition for gameplay simulation: in the gameplay screen and not paused.
+fn game_running(screen: Res<State<screens::Screen>>, pause: Res<State<Pause>>) -> bool {
+    *screen.get() == screens::Screen::Gameplay && !pause.get().0
+}
+
+fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
@@ -92,7 +92,8 @@ fn spawn_credits_menu(mut commands: Commands, paused: Res<State<Pause>>, font: R
     ));
 }
 
-fn scroll_credits(time: Res<Time>, mut query: Query<(&mut CreditsScroll, &mut Node)>) {
+// Real time, the credits can be opened from the pause menu.
+fn scroll_credits(time: Res<Time<Real>>, mut query: Query<(&mut CreditsScroll, &mut Node)>) {
     for (mut scroll, mut node) in &mut query {
         scroll.0 -= SCROLL_SPEED * time.delta_secs();
         node.top = Percent(scroll.0);
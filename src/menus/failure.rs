@@ -0,0 +1,72 @@
+//! The failure screen shown after a critical NPC dies.
+
+use std::any::Any as _;
+
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        crosshair::CrosshairState, npc::critical::CriticalFailure, player::input::BlocksInput,
+        session::RetryRun,
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{GameFont, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::CriticalFailure), spawn_failure_menu);
+}
+
+fn spawn_failure_menu(
+    mut commands: Commands,
+    failure: Res<CriticalFailure>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+    font: Res<GameFont>,
+) {
+    let f = &font.0;
+    let npc = failure.npc().unwrap_or("someone important");
+    commands.spawn((
+        widget::ui_root("Failure Menu"),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::CriticalFailure),
+        children![
+            widget::header(format!("{npc} died"), f),
+            widget::label("the story can't go on without them", f),
+            widget::button("retry", retry, f),
+            widget::button("quit to title", quit_to_title, f),
+        ],
+    ));
+    crosshair
+        .wants_free_cursor
+        .insert(spawn_failure_menu.type_id());
+    blocks_input.insert(spawn_failure_menu.type_id());
+}
+
+fn retry(
+    _on: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    commands.trigger(RetryRun);
+    crosshair
+        .wants_free_cursor
+        .remove(&spawn_failure_menu.type_id());
+    blocks_input.remove(&spawn_failure_menu.type_id());
+}
+
+fn quit_to_title(
+    _on: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    next_screen.set(Screen::Title);
+    crosshair
+        .wants_free_cursor
+        .remove(&spawn_failure_menu.type_id());
+    blocks_input.remove(&spawn_failure_menu.type_id());
+}
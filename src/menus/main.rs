@@ -7,6 +7,10 @@ use bevy::{
 use bevy::ui::Val::*;
 
 use crate::{
+    gameplay::{
+        game_mode::GameMode,
+        session::{ContinueSession, Session},
+    },
     menus::Menu,
     screens::Screen,
     theme::{GameFont, TitleFont, palette::SCREEN_BACKGROUND, widget},
@@ -21,6 +25,7 @@ fn spawn_main_menu(
     mut cursor_options: Single<&mut CursorOptions>,
     font: Res<GameFont>,
     title_font: Res<TitleFont>,
+    session: Res<Session>,
 ) {
     cursor_options.grab_mode = CursorGrabMode::None;
     let f = &font.0;
@@ -49,7 +54,10 @@ fn spawn_main_menu(
                 widget::text_font(tf, 120.0),
                 TextColor(Color::WHITE),
             ),
-            widget::button("play", enter_loading_screen, f),
+            widget::button("play", open_save_slots_menu, f),
+            continue_button(f, session.can_continue()),
+            widget::button("practice", enter_practice_range, f),
+            widget::button("endless", open_sandbox_menu, f),
             widget::button("settings", open_settings_menu, f),
             widget::button("credits", open_credits_menu, f),
             widget::button("exit", exit_app, f),
@@ -61,22 +69,59 @@ fn spawn_main_menu(
                 widget::text_font(tf, 120.0),
                 TextColor(Color::WHITE),
             ),
-            widget::button("play", enter_loading_screen, f),
+            widget::button("play", open_save_slots_menu, f),
+            continue_button(f, session.can_continue()),
+            widget::button("practice", enter_practice_range, f),
+            widget::button("endless", open_sandbox_menu, f),
             widget::button("settings", open_settings_menu, f),
             widget::button("credits", open_credits_menu, f),
         ],
     ));
 }
 
-fn enter_loading_screen(
+fn continue_button(font: &Handle<Font>, can_continue: bool) -> impl Bundle {
+    widget::button_with(
+        "continue",
+        continue_session,
+        font,
+        widget::ButtonOpts {
+            // Save slots keep upgrades and crusts but not the world, so only a story run left
+            // earlier in this session can be continued.
+            enabled: can_continue,
+            disabled_reason: (!can_continue).then(|| "no run this session".to_string()),
+            ..default()
+        },
+    )
+}
+
+fn continue_session(
     _on: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    commands.trigger(ContinueSession);
+    cursor_options.grab_mode = CursorGrabMode::Locked;
+}
+
+fn enter_practice_range(
+    _on: On<Pointer<Click>>,
+    mut mode: ResMut<GameMode>,
     mut next_screen: ResMut<NextState<Screen>>,
     mut cursor_options: Single<&mut CursorOptions>,
 ) {
+    *mode = GameMode::Practice;
     next_screen.set(Screen::Loading);
     cursor_options.grab_mode = CursorGrabMode::Locked;
 }
 
+fn open_save_slots_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn open_sandbox_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Sandbox);
+}
+
 fn open_settings_menu(_: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
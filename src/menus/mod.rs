@@ -1,8 +1,11 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod failure;
 mod main;
 mod pause;
+mod sandbox;
+mod save_slots;
 mod settings;
 
 use bevy::prelude::*;
@@ -12,9 +15,12 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_plugins((
         credits::plugin,
+        failure::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
+        sandbox::plugin,
+        save_slots::plugin,
     ));
 }
 
@@ -28,4 +34,15 @@ pub(crate) enum Menu {
     Credits,
     Settings,
     Pause,
+    SkipTutorial,
+    /// Endless mode's start screen, with the best scores.
+    Sandbox,
+    /// Score breakdown after dying in endless mode.
+    SandboxResults,
+    /// Picking a save slot before starting the story.
+    SaveSlots,
+    /// Confirming a save slot's deletion.
+    DeleteSlot,
+    /// A critical NPC died, offering a retry.
+    CriticalFailure,
 }
@@ -3,7 +3,12 @@
 use std::any::Any as _;
 
 use crate::{
-    gameplay::{crosshair::CrosshairState, player::input::BlocksInput},
+    gameplay::{
+        crosshair::CrosshairState,
+        game_mode::GameMode,
+        objective::{Objectives, SkipTutorial, TUTORIAL_OBJECTIVE},
+        player::input::BlocksInput,
+    },
     menus::Menu,
     screens::Screen,
     theme::{GameFont, widget},
@@ -16,43 +21,102 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
     );
+
+    app.add_systems(OnEnter(Menu::SkipTutorial), spawn_skip_tutorial_menu);
+    app.add_systems(
+        Update,
+        back_to_pause_menu
+            .run_if(in_state(Menu::SkipTutorial).and(input_just_pressed(KeyCode::Escape))),
+    );
 }
 
 fn spawn_pause_menu(
     mut commands: Commands,
     mut crosshair: Single<&mut CrosshairState>,
-    mut time: ResMut<Time<Virtual>>,
     mut blocks_input: ResMut<BlocksInput>,
+    objectives: Res<Objectives>,
+    mode: Res<GameMode>,
     font: Res<GameFont>,
 ) {
+    let f = &font.0;
+    let menu = commands
+        .spawn((
+            widget::ui_root("Pause Menu"),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Pause),
+            children![
+                widget::header("paused", f),
+                widget::button("continue", close_menu, f),
+                widget::button("settings", open_settings_menu, f),
+            ],
+        ))
+        .id();
+    if mode.has_story() && objectives.active == TUTORIAL_OBJECTIVE {
+        commands.entity(menu).with_child(widget::button(
+            "skip tutorial",
+            open_skip_tutorial_menu,
+            f,
+        ));
+    }
+    commands
+        .entity(menu)
+        .with_child(widget::button("quit to title", quit_to_title, f));
+    crosshair
+        .wants_free_cursor
+        .insert(spawn_pause_menu.type_id());
+    blocks_input.insert(spawn_pause_menu.type_id());
+}
+
+fn open_settings_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn open_skip_tutorial_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SkipTutorial);
+}
+
+fn spawn_skip_tutorial_menu(mut commands: Commands, font: Res<GameFont>) {
     let f = &font.0;
     commands.spawn((
-        widget::ui_root("Pause Menu"),
+        widget::ui_root("Skip Tutorial Menu"),
         GlobalZIndex(2),
-        DespawnOnExit(Menu::Pause),
+        DespawnOnExit(Menu::SkipTutorial),
         children![
-            widget::header("paused", f),
-            widget::button("continue", close_menu, f),
-            widget::button("settings", open_settings_menu, f),
-            widget::button("quit to title", quit_to_title, f),
+            widget::header("skip the tutorial?", f),
+            widget::label("this can't be undone", f),
+            widget::button("skip", confirm_skip_tutorial, f),
+            widget::button("back", back_to_pause_menu_on_click, f),
         ],
     ));
+}
+
+fn confirm_skip_tutorial(
+    _on: On<Pointer<Click>>,
+    mut commands: Commands,
+    mut next_menu: ResMut<NextState<Menu>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    commands.trigger(SkipTutorial);
+    next_menu.set(Menu::None);
     crosshair
         .wants_free_cursor
-        .insert(spawn_pause_menu.type_id());
-    blocks_input.insert(spawn_pause_menu.type_id());
-    time.pause();
+        .remove(&spawn_pause_menu.type_id());
+    blocks_input.remove(&spawn_pause_menu.type_id());
 }
 
-fn open_settings_menu(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
-    next_menu.set(Menu::Settings);
+fn back_to_pause_menu_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn back_to_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
 }
 
 fn close_menu(
     _on: On<Pointer<Click>>,
     mut next_menu: ResMut<NextState<Menu>>,
     mut crosshair: Single<&mut CrosshairState>,
-    mut time: ResMut<Time<Virtual>>,
     mut blocks_input: ResMut<BlocksInput>,
 ) {
     next_menu.set(Menu::None);
@@ -60,14 +124,12 @@ fn close_menu(
         .wants_free_cursor
         .remove(&spawn_pause_menu.type_id());
     blocks_input.remove(&spawn_pause_menu.type_id());
-    time.unpause();
 }
 
 fn quit_to_title(
     _on: On<Pointer<Click>>,
     mut next_screen: ResMut<NextState<Screen>>,
     mut crosshair: Single<&mut CrosshairState>,
-    mut time: ResMut<Time<Virtual>>,
     mut blocks_input: ResMut<BlocksInput>,
 ) {
     next_screen.set(Screen::Title);
@@ -75,13 +137,11 @@ fn quit_to_title(
         .wants_free_cursor
         .remove(&spawn_pause_menu.type_id());
     blocks_input.remove(&spawn_pause_menu.type_id());
-    time.unpause();
 }
 
 fn go_back(
     mut next_menu: ResMut<NextState<Menu>>,
     mut crosshair: Single<&mut CrosshairState>,
-    mut time: ResMut<Time<Virtual>>,
     mut blocks_input: ResMut<BlocksInput>,
 ) {
     next_menu.set(Menu::None);
@@ -89,5 +149,4 @@ fn go_back(
         .wants_free_cursor
         .remove(&spawn_pause_menu.type_id());
     blocks_input.remove(&spawn_pause_menu.type_id());
-    time.unpause();
 }
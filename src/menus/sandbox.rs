@@ -0,0 +1,149 @@
+//! Endless mode's start screen and the results shown after dying in it.
+
+use std::any::Any as _;
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+
+use crate::{
+    gameplay::{
+        crosshair::CrosshairState,
+        game_mode::GameMode,
+        player::input::BlocksInput,
+        run_timer::format_run_time,
+        sandbox::{SandboxScore, SandboxScores, SandboxState},
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::{GameFont, palette::SCREEN_BACKGROUND, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Sandbox), spawn_sandbox_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Sandbox).and(input_just_pressed(KeyCode::Escape))),
+    );
+    app.add_systems(OnEnter(Menu::SandboxResults), spawn_results_menu);
+}
+
+fn spawn_sandbox_menu(mut commands: Commands, scores: Res<SandboxScores>, font: Res<GameFont>) {
+    let f = &font.0;
+    let menu = commands
+        .spawn((
+            widget::ui_root("Sandbox Menu"),
+            BackgroundColor(SCREEN_BACKGROUND),
+            GlobalZIndex(2),
+            DespawnOnExit(Menu::Sandbox),
+            children![widget::header("endless", f)],
+        ))
+        .id();
+    if scores.best.is_empty() {
+        commands
+            .entity(menu)
+            .with_child(widget::label("no runs yet", f));
+    }
+    for (rank, score) in scores.best.iter().enumerate() {
+        commands
+            .entity(menu)
+            .with_child(widget::label(score_line(rank, score), f));
+    }
+    commands.entity(menu).with_children(|menu| {
+        menu.spawn(widget::button("start", start_sandbox, f));
+        menu.spawn(widget::button("back", go_back_on_click, f));
+    });
+}
+
+fn spawn_results_menu(
+    mut commands: Commands,
+    state: Res<SandboxState>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+    font: Res<GameFont>,
+) {
+    let f = &font.0;
+    let score = &state.score;
+    let placing = match state.rank {
+        Some(0) => "new best!".to_string(),
+        Some(rank) => format!("#{} on the table", rank + 1),
+        None => "not a top score".to_string(),
+    };
+    commands.spawn((
+        widget::ui_root("Sandbox Results Menu"),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::SandboxResults),
+        children![
+            widget::header(format!("{} points", score.points()), f),
+            widget::label(placing, f),
+            widget::label(format!("waves cleared: {}", score.waves), f),
+            widget::label(format!("kills: {}", score.kills), f),
+            widget::label(format!("burials: {}", score.burials), f),
+            widget::label(format!("time: {}", format_run_time(score.time)), f),
+            widget::button("play again", play_again, f),
+            widget::button("quit to title", quit_to_title, f),
+        ],
+    ));
+    crosshair
+        .wants_free_cursor
+        .insert(spawn_results_menu.type_id());
+    blocks_input.insert(spawn_results_menu.type_id());
+}
+
+fn score_line(rank: usize, score: &SandboxScore) -> String {
+    format!(
+        "{}. {} points - wave {} - {}",
+        rank + 1,
+        score.points(),
+        score.waves,
+        format_run_time(score.time)
+    )
+}
+
+fn start_sandbox(
+    _on: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut mode: ResMut<GameMode>,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    *mode = GameMode::Sandbox;
+    next_screen.set(Screen::Loading);
+    cursor_options.grab_mode = CursorGrabMode::Locked;
+}
+
+fn play_again(
+    _on: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    next_screen.set(Screen::Loading);
+    crosshair
+        .wants_free_cursor
+        .remove(&spawn_results_menu.type_id());
+    blocks_input.remove(&spawn_results_menu.type_id());
+}
+
+fn quit_to_title(
+    _on: On<Pointer<Click>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut crosshair: Single<&mut CrosshairState>,
+    mut blocks_input: ResMut<BlocksInput>,
+) {
+    next_screen.set(Screen::Title);
+    crosshair
+        .wants_free_cursor
+        .remove(&spawn_results_menu.type_id());
+    blocks_input.remove(&spawn_results_menu.type_id());
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -0,0 +1,194 @@
+//! Picking a save slot before starting the story, and starting new game plus from one.
+
+use bevy::{
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    window::{CursorGrabMode, CursorOptions},
+};
+
+use crate::{
+    gameplay::{
+        save_slot::{SLOT_COUNT, SaveSlots, SlotSave, StartSlot},
+        session::Session,
+    },
+    menus::Menu,
+    theme::{GameFont, palette::SCREEN_BACKGROUND, widget},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SelectedSlot>();
+    app.add_systems(OnEnter(Menu::SaveSlots), spawn_save_slots_menu);
+    app.add_systems(OnEnter(Menu::DeleteSlot), spawn_delete_slot_menu);
+    app.add_systems(
+        Update,
+        (
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+            update_slot_labels,
+        )
+            .run_if(in_state(Menu::SaveSlots)),
+    );
+    app.add_systems(
+        Update,
+        back_to_save_slots
+            .run_if(in_state(Menu::DeleteSlot).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+#[derive(Resource, Default)]
+struct SelectedSlot(usize);
+
+#[derive(Component)]
+struct SlotLabel;
+
+#[derive(Component)]
+struct SlotSummary;
+
+fn spawn_save_slots_menu(mut commands: Commands, font: Res<GameFont>) {
+    let f = &font.0;
+    commands.spawn((
+        widget::ui_root("Save Slots Menu"),
+        BackgroundColor(SCREEN_BACKGROUND),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::SaveSlots),
+        children![
+            widget::header("play", f),
+            widget::plus_minus_bar(SlotLabel, previous_slot, next_slot, f),
+            (widget::label("", f), SlotSummary),
+            widget::button("new game", start_new_game, f),
+            widget::button("new game+", start_new_game_plus, f),
+            widget::button("delete", open_delete_slot_menu, f),
+            widget::button("back", go_back_on_click, f),
+        ],
+    ));
+}
+
+fn slot_summary(save: Option<&SlotSave>) -> String {
+    let Some(save) = save else {
+        return "empty".to_string();
+    };
+    let run = match save.level {
+        0 => "first run".to_string(),
+        level => format!("new game+{level}"),
+    };
+    let progress = if save.finished {
+        "finished, new game+ ready"
+    } else {
+        "finish the story to unlock new game+"
+    };
+    format!("{run} - {} crusts\n{progress}", save.crusts)
+}
+
+fn update_slot_labels(
+    selected: Res<SelectedSlot>,
+    slots: Res<SaveSlots>,
+    mut label: Single<&mut Text, (With<SlotLabel>, Without<SlotSummary>)>,
+    mut summary: Single<&mut Text, With<SlotSummary>>,
+) {
+    label.0 = format!("slot {}", selected.0 + 1);
+    summary.0 = slot_summary(slots.slots[selected.0].as_ref());
+}
+
+fn previous_slot(_on: On<Pointer<Click>>, mut selected: ResMut<SelectedSlot>) {
+    selected.0 = (selected.0 + SLOT_COUNT - 1) % SLOT_COUNT;
+}
+
+fn next_slot(_on: On<Pointer<Click>>, mut selected: ResMut<SelectedSlot>) {
+    selected.0 = (selected.0 + 1) % SLOT_COUNT;
+}
+
+fn start_new_game(
+    _on: On<Pointer<Click>>,
+    mut commands: Commands,
+    selected: Res<SelectedSlot>,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    commands.trigger(StartSlot {
+        slot: selected.0,
+        new_game_plus: false,
+    });
+    cursor_options.grab_mode = CursorGrabMode::Locked;
+}
+
+fn start_new_game_plus(
+    _on: On<Pointer<Click>>,
+    mut commands: Commands,
+    selected: Res<SelectedSlot>,
+    slots: Res<SaveSlots>,
+    mut cursor_options: Single<&mut CursorOptions>,
+) {
+    // The summary already says why it's locked.
+    if !slots.slots[selected.0]
+        .as_ref()
+        .is_some_and(|save| save.finished)
+    {
+        return;
+    }
+    commands.trigger(StartSlot {
+        slot: selected.0,
+        new_game_plus: true,
+    });
+    cursor_options.grab_mode = CursorGrabMode::Locked;
+}
+
+fn open_delete_slot_menu(
+    _on: On<Pointer<Click>>,
+    selected: Res<SelectedSlot>,
+    slots: Res<SaveSlots>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    // Nothing to lose in an empty slot.
+    if slots.slots[selected.0].is_some() {
+        next_menu.set(Menu::DeleteSlot);
+    }
+}
+
+fn spawn_delete_slot_menu(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    selected: Res<SelectedSlot>,
+) {
+    let f = &font.0;
+    commands.spawn((
+        widget::ui_root("Delete Slot Menu"),
+        BackgroundColor(SCREEN_BACKGROUND),
+        GlobalZIndex(2),
+        DespawnOnExit(Menu::DeleteSlot),
+        children![
+            widget::header(format!("delete slot {}?", selected.0 + 1), f),
+            widget::label("this can't be undone", f),
+            widget::button("delete", confirm_delete_slot, f),
+            widget::button("back", back_to_save_slots_on_click, f),
+        ],
+    ));
+}
+
+fn confirm_delete_slot(
+    _on: On<Pointer<Click>>,
+    selected: Res<SelectedSlot>,
+    mut slots: ResMut<SaveSlots>,
+    mut session: ResMut<Session>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    slots.delete(selected.0);
+    // The run can't be continued once its slot is gone.
+    if session.slot() == Some(selected.0) {
+        session.clear();
+    }
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn back_to_save_slots_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn back_to_save_slots(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::SaveSlots);
+}
+
+fn go_back_on_click(_on: On<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
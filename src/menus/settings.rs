@@ -2,16 +2,34 @@
 //! We can add all manner of settings and accessibility options here.
 //! For 3D, we'd also place the camera sensitivity and FOV here.
 
-use bevy::window::PresentMode;
+use bevy::window::{MonitorSelection, PresentMode, WindowMode};
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_framepace::{FramepaceSettings, Limiter};
 use bevy_seedling::prelude::*;
+use bincode::{Decode, Encode};
 
 use crate::{
     Pause,
     audio::{DEFAULT_MAIN_VOLUME, perceptual::PerceptualVolumeConverter},
-    gameplay::player::camera::{CameraSensitivity, WorldModelFov},
+    gameplay::{
+        captions::CaptionSettings,
+        dig::decoration::DecorationSettings,
+        game_mode::{CombatEnabled, GraveCombos},
+        highlight::HighlightSettings,
+        hud::{HudSettings, HudVisibility, MAX_HUD_SCALE, MIN_HUD_SCALE},
+        inventory::{AimAssist, HeldItemSway},
+        npc::shooting::FactionRelations,
+        player::{
+            MovementTuning,
+            body::PlayerBodySettings,
+            camera::{CameraLookSettings, CameraSensitivity, ViewModelFov, WorldModelFov},
+            camera_shake::ReduceMotion,
+            crouch::CrouchSettings,
+        },
+    },
+    graphics::GraphicsQuality,
     menus::Menu,
+    persistence,
     screens::Screen,
     theme::{palette::SCREEN_BACKGROUND, prelude::*},
 };
@@ -19,25 +37,61 @@ use crate::{
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<VolumeSliderSettings>();
     app.init_resource::<VsyncSetting>();
+    app.init_resource::<FullscreenSetting>();
     app.init_resource::<FpsLimiterSettings>();
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
     app.add_systems(
         Update,
         go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
     );
+    // Outside the menu, so F11 works anywhere and the saved mode applies at startup.
+    app.add_systems(
+        Update,
+        (
+            toggle_fullscreen.run_if(input_just_pressed(KeyCode::F11)),
+            update_fullscreen.run_if(resource_changed::<FullscreenSetting>),
+        )
+            .chain(),
+    );
 
     app.add_systems(
         Update,
         (
-            update_global_volume.run_if(resource_exists_and_changed::<VolumeSliderSettings>),
-            update_volume_label,
-            update_camera_sensitivity_label,
-            update_camera_fov_label,
-            update_vsync.run_if(resource_exists_and_changed::<VsyncSetting>),
-            update_vsync_label,
-            update_fps_limiter.run_if(resource_exists_and_changed::<FpsLimiterSettings>),
-            update_fps_limiter_enabled_label,
-            update_fps_limiter_target_label,
+            (
+                update_global_volume.run_if(resource_exists_and_changed::<VolumeSliderSettings>),
+                update_volume_label,
+                update_camera_sensitivity_label,
+                update_camera_fov_label,
+                update_view_model_fov_label,
+                update_look_smoothing_label,
+                update_show_body_label,
+                update_reduce_motion_label,
+                update_held_item_sway_label,
+                update_crouch_mode_label,
+                update_acceleration_label,
+                update_friction_label,
+                update_highlight_label,
+            ),
+            (
+                update_graphics_quality_label,
+                update_vsync.run_if(resource_exists_and_changed::<VsyncSetting>),
+                update_vsync_label,
+                update_fullscreen_label,
+                update_ground_clutter_label,
+                update_friendly_fire_label,
+                update_combat_label,
+                update_grave_combos_label,
+                update_aim_assist_label,
+                update_captions_label,
+                update_hide_hud_label,
+                update_hud_scale_label,
+                update_run_timer_label,
+                update_objective_panel_label,
+                update_objective_list_label,
+                update_fps_limiter.run_if(resource_exists_and_changed::<FpsLimiterSettings>),
+                update_fps_limiter_enabled_label,
+                update_fps_limiter_target_label,
+            ),
         )
             .run_if(in_state(Menu::Settings)),
     );
@@ -93,6 +147,126 @@ fn spawn_settings_menu(mut commands: Commands, paused: Res<State<Pause>>, font:
                         }
                     ),
                     widget::plus_minus_bar(CameraFovLabel, lower_camera_fov, raise_camera_fov, f),
+                    // Held Item FOV
+                    (
+                        widget::label("Held Item FOV", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        ViewModelFovLabel,
+                        lower_view_model_fov,
+                        raise_view_model_fov,
+                        f
+                    ),
+                    // Look Smoothing
+                    (
+                        widget::label("Look Smoothing", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        LookSmoothingLabel,
+                        lower_look_smoothing,
+                        raise_look_smoothing,
+                        f
+                    ),
+                    // Show Body
+                    (
+                        widget::label("Show Body", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(ShowBodyLabel, hide_body, show_body, f),
+                    // Crouch Mode
+                    (
+                        widget::label("Crouch", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(CrouchModeLabel, hold_to_crouch, toggle_crouch, f),
+                    // Acceleration
+                    (
+                        widget::label("Acceleration", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        AccelerationLabel,
+                        lower_acceleration,
+                        raise_acceleration,
+                        f
+                    ),
+                    // Friction
+                    (
+                        widget::label("Friction", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(FrictionLabel, lower_friction, raise_friction, f),
+                    // Highlight Interactables
+                    (
+                        widget::label("Highlight Interactables", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(HighlightLabel, disable_highlight, enable_highlight, f),
+                    // Reduce Motion
+                    (
+                        widget::label("Reduce Motion", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        ReduceMotionLabel,
+                        disable_reduce_motion,
+                        enable_reduce_motion,
+                        f
+                    ),
+                    // Held Item Sway
+                    (
+                        widget::label("Held Item Sway", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        HeldItemSwayLabel,
+                        lower_held_item_sway,
+                        raise_held_item_sway,
+                        f
+                    ),
+                    // Graphics Quality
+                    (
+                        widget::label("Graphics Quality", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        GraphicsQualityLabel,
+                        lower_graphics_quality,
+                        raise_graphics_quality,
+                        f
+                    ),
                     // VSync
                     (
                         widget::label("VSync", f),
@@ -102,6 +276,144 @@ fn spawn_settings_menu(mut commands: Commands, paused: Res<State<Pause>>, font:
                         }
                     ),
                     widget::plus_minus_bar(VsyncLabel, disable_vsync, enable_vsync, f),
+                    // Fullscreen
+                    (
+                        widget::label("Fullscreen", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        FullscreenLabel,
+                        disable_fullscreen,
+                        enable_fullscreen,
+                        f
+                    ),
+                    // Ground Clutter
+                    (
+                        widget::label("Ground Clutter", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        GroundClutterLabel,
+                        disable_ground_clutter,
+                        enable_ground_clutter,
+                        f
+                    ),
+                    // Friendly Fire
+                    (
+                        widget::label("Friendly Fire", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        FriendlyFireLabel,
+                        disable_friendly_fire,
+                        enable_friendly_fire,
+                        f
+                    ),
+                    // Combat, off for a peaceful run through the story
+                    (
+                        widget::label("Combat", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(CombatLabel, disable_combat, enable_combat, f),
+                    // Grave Combos, extra crusts for filling graves back to back
+                    (
+                        widget::label("Grave Combos", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        GraveCombosLabel,
+                        disable_grave_combos,
+                        enable_grave_combos,
+                        f
+                    ),
+                    // Aim Assist
+                    (
+                        widget::label("Aim Assist", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(AimAssistLabel, lower_aim_assist, raise_aim_assist, f),
+                    // Sound Captions
+                    (
+                        widget::label("Sound Captions", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(CaptionsLabel, hide_captions, show_captions, f),
+                    // Hide HUD
+                    (
+                        widget::label("Hide HUD", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(HideHudLabel, show_hud, hide_hud, f),
+                    // HUD Scale
+                    (
+                        widget::label("HUD Scale", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(HudScaleLabel, lower_hud_scale, raise_hud_scale, f),
+                    // Speedrun Timer
+                    (
+                        widget::label("Speedrun Timer", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(RunTimerLabel, hide_run_timer, show_run_timer, f),
+                    // Objective Panel
+                    (
+                        widget::label("Objective Panel", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        ObjectivePanelLabel,
+                        hide_objective_panel,
+                        show_objective_panel,
+                        f
+                    ),
+                    // Objective List
+                    (
+                        widget::label("Objective List", f),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        }
+                    ),
+                    widget::plus_minus_bar(
+                        ObjectiveListLabel,
+                        full_objective_list,
+                        compact_objective_list,
+                        f
+                    ),
                     // FPS Limiter (Enable/Disable)
                     (
                         widget::label("FPS Limiter", f),
@@ -132,7 +444,15 @@ fn spawn_settings_menu(mut commands: Commands, paused: Res<State<Pause>>, font:
                     ),
                 ],
             ),
-            widget::button("Back", go_back_on_click, f),
+            widget::button_with(
+                "Back",
+                go_back_on_click,
+                f,
+                widget::ButtonOpts {
+                    secondary_label: Some("Esc".to_string()),
+                    ..default()
+                },
+            ),
         ],
     ));
     if paused.get() == &Pause(false) {
@@ -252,6 +572,250 @@ fn update_camera_fov_label(
     label.0 = format!("{:.1}", camera_fov.0);
 }
 
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ViewModelFovLabel;
+
+fn lower_view_model_fov(_on: On<Pointer<Click>>, mut fov: ResMut<ViewModelFov>) {
+    fov.0 -= 1.0;
+    fov.0 = fov.0.max(40.0);
+}
+
+fn raise_view_model_fov(_on: On<Pointer<Click>>, mut fov: ResMut<ViewModelFov>) {
+    fov.0 += 1.0;
+    fov.0 = fov.0.min(90.0);
+}
+
+fn update_view_model_fov_label(
+    mut label: Single<&mut Text, With<ViewModelFovLabel>>,
+    fov: Res<ViewModelFov>,
+) {
+    label.0 = format!("{:.1}", fov.0);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct LookSmoothingLabel;
+
+fn lower_look_smoothing(_on: On<Pointer<Click>>, mut look: ResMut<CameraLookSettings>) {
+    // Round to the step so repeated clicks land back on exactly 0 (raw).
+    look.smoothing = ((look.smoothing - 0.1) * 10.0).round().max(0.0) / 10.0;
+}
+
+fn raise_look_smoothing(_on: On<Pointer<Click>>, mut look: ResMut<CameraLookSettings>) {
+    look.smoothing =
+        (((look.smoothing + 0.1) * 10.0).round() / 10.0).min(CameraLookSettings::MAX_SMOOTHING);
+}
+
+fn update_look_smoothing_label(
+    mut label: Single<&mut Text, With<LookSmoothingLabel>>,
+    look: Res<CameraLookSettings>,
+) {
+    label.0 = if look.smoothing <= 0.0 {
+        "Off".into()
+    } else {
+        format!("{:.1}", look.smoothing)
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ShowBodyLabel;
+
+fn show_body(_on: On<Pointer<Click>>, mut body: ResMut<PlayerBodySettings>) {
+    body.visible = true;
+}
+
+fn hide_body(_on: On<Pointer<Click>>, mut body: ResMut<PlayerBodySettings>) {
+    body.visible = false;
+}
+
+fn update_show_body_label(
+    mut label: Single<&mut Text, With<ShowBodyLabel>>,
+    body: Res<PlayerBodySettings>,
+) {
+    label.0 = if body.visible {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CrouchModeLabel;
+
+fn toggle_crouch(_on: On<Pointer<Click>>, mut crouch: ResMut<CrouchSettings>) {
+    crouch.toggle = true;
+}
+
+fn hold_to_crouch(_on: On<Pointer<Click>>, mut crouch: ResMut<CrouchSettings>) {
+    crouch.toggle = false;
+}
+
+fn update_crouch_mode_label(
+    mut label: Single<&mut Text, With<CrouchModeLabel>>,
+    crouch: Res<CrouchSettings>,
+) {
+    label.0 = if crouch.toggle {
+        "Toggle".into()
+    } else {
+        "Hold".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AccelerationLabel;
+
+fn lower_acceleration(_on: On<Pointer<Click>>, mut tuning: ResMut<MovementTuning>) {
+    tuning.acceleration_hz = (tuning.acceleration_hz - 1.0).max(MovementTuning::MIN_HZ);
+}
+
+fn raise_acceleration(_on: On<Pointer<Click>>, mut tuning: ResMut<MovementTuning>) {
+    tuning.acceleration_hz = (tuning.acceleration_hz + 1.0).min(MovementTuning::MAX_HZ);
+}
+
+fn update_acceleration_label(
+    mut label: Single<&mut Text, With<AccelerationLabel>>,
+    tuning: Res<MovementTuning>,
+) {
+    label.0 = format!("{:.0}", tuning.acceleration_hz);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FrictionLabel;
+
+fn lower_friction(_on: On<Pointer<Click>>, mut tuning: ResMut<MovementTuning>) {
+    tuning.friction_hz = (tuning.friction_hz - 5.0).max(MovementTuning::MIN_HZ);
+}
+
+fn raise_friction(_on: On<Pointer<Click>>, mut tuning: ResMut<MovementTuning>) {
+    tuning.friction_hz = (tuning.friction_hz + 5.0).min(MovementTuning::MAX_HZ);
+}
+
+fn update_friction_label(
+    mut label: Single<&mut Text, With<FrictionLabel>>,
+    tuning: Res<MovementTuning>,
+) {
+    label.0 = format!("{:.0}", tuning.friction_hz);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GroundClutterLabel;
+
+fn enable_ground_clutter(_on: On<Pointer<Click>>, mut clutter: ResMut<DecorationSettings>) {
+    clutter.enabled = true;
+}
+
+fn disable_ground_clutter(_on: On<Pointer<Click>>, mut clutter: ResMut<DecorationSettings>) {
+    clutter.enabled = false;
+}
+
+fn update_ground_clutter_label(
+    mut label: Single<&mut Text, With<GroundClutterLabel>>,
+    clutter: Res<DecorationSettings>,
+) {
+    label.0 = if clutter.enabled {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HighlightLabel;
+
+fn enable_highlight(_on: On<Pointer<Click>>, mut highlight: ResMut<HighlightSettings>) {
+    highlight.enabled = true;
+}
+
+fn disable_highlight(_on: On<Pointer<Click>>, mut highlight: ResMut<HighlightSettings>) {
+    highlight.enabled = false;
+}
+
+fn update_highlight_label(
+    mut label: Single<&mut Text, With<HighlightLabel>>,
+    highlight: Res<HighlightSettings>,
+) {
+    label.0 = if highlight.enabled {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ReduceMotionLabel;
+
+fn enable_reduce_motion(_on: On<Pointer<Click>>, mut reduce_motion: ResMut<ReduceMotion>) {
+    reduce_motion.0 = true;
+}
+
+fn disable_reduce_motion(_on: On<Pointer<Click>>, mut reduce_motion: ResMut<ReduceMotion>) {
+    reduce_motion.0 = false;
+}
+
+fn update_reduce_motion_label(
+    mut label: Single<&mut Text, With<ReduceMotionLabel>>,
+    reduce_motion: Res<ReduceMotion>,
+) {
+    label.0 = if reduce_motion.0 {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HeldItemSwayLabel;
+
+fn lower_held_item_sway(_on: On<Pointer<Click>>, mut sway: ResMut<HeldItemSway>) {
+    sway.0 = ((sway.0 - 0.1) * 10.0).round().max(0.0) / 10.0;
+}
+
+fn raise_held_item_sway(_on: On<Pointer<Click>>, mut sway: ResMut<HeldItemSway>) {
+    sway.0 = ((sway.0 + 0.1) * 10.0).round().min(10.0) / 10.0;
+}
+
+fn update_held_item_sway_label(
+    mut label: Single<&mut Text, With<HeldItemSwayLabel>>,
+    sway: Res<HeldItemSway>,
+) {
+    label.0 = if sway.0 > 0.0 {
+        format!("{:.0}%", sway.0 * 100.0)
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GraphicsQualityLabel;
+
+fn lower_graphics_quality(_on: On<Pointer<Click>>, mut quality: ResMut<GraphicsQuality>) {
+    *quality = GraphicsQuality::Low;
+}
+
+fn raise_graphics_quality(_on: On<Pointer<Click>>, mut quality: ResMut<GraphicsQuality>) {
+    *quality = GraphicsQuality::High;
+}
+
+fn update_graphics_quality_label(
+    mut label: Single<&mut Text, With<GraphicsQualityLabel>>,
+    quality: Res<GraphicsQuality>,
+) {
+    label.0 = match *quality {
+        GraphicsQuality::High => "High".into(),
+        GraphicsQuality::Low => "Low".into(),
+    };
+}
+
 #[derive(Resource, Reflect, Debug)]
 struct VsyncSetting(bool);
 
@@ -285,6 +849,278 @@ fn update_vsync_label(mut label: Single<&mut Text, With<VsyncLabel>>, setting: R
     label.0 = if setting.0 { "On".into() } else { "Off".into() };
 }
 
+const FULLSCREEN_SETTING_PATH: &str = "fullscreen.bin";
+
+/// Borderless fullscreen on the current monitor, or a window. Saved next to the game and
+/// restored at startup. The web build always starts windowed, since browsers only allow
+/// fullscreen in response to a key press or click.
+#[derive(Resource, Encode, Decode, Reflect, Default, Debug)]
+struct FullscreenSetting(bool);
+
+impl FromWorld for FullscreenSetting {
+    fn from_world(_world: &mut World) -> Self {
+        persistence::load(FULLSCREEN_SETTING_PATH, "fullscreen setting").unwrap_or_default()
+    }
+}
+
+impl FullscreenSetting {
+    fn save(&self) {
+        persistence::save(FULLSCREEN_SETTING_PATH, "fullscreen setting", self);
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FullscreenLabel;
+
+fn enable_fullscreen(_on: On<Pointer<Click>>, mut setting: ResMut<FullscreenSetting>) {
+    setting.0 = true;
+}
+
+fn disable_fullscreen(_on: On<Pointer<Click>>, mut setting: ResMut<FullscreenSetting>) {
+    setting.0 = false;
+}
+
+fn toggle_fullscreen(mut setting: ResMut<FullscreenSetting>) {
+    setting.0 = !setting.0;
+}
+
+/// The HUD and the cursor grab follow along on the resize this causes.
+fn update_fullscreen(mut window: Single<&mut Window>, setting: Res<FullscreenSetting>) {
+    window.mode = if setting.0 {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    // Nothing to save when it was just loaded.
+    if !setting.is_added() {
+        setting.save();
+    }
+}
+
+fn update_fullscreen_label(
+    mut label: Single<&mut Text, With<FullscreenLabel>>,
+    setting: Res<FullscreenSetting>,
+) {
+    label.0 = if setting.0 { "On".into() } else { "Off".into() };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct FriendlyFireLabel;
+
+fn enable_friendly_fire(_on: On<Pointer<Click>>, mut relations: ResMut<FactionRelations>) {
+    relations.friendly_fire = true;
+}
+
+fn disable_friendly_fire(_on: On<Pointer<Click>>, mut relations: ResMut<FactionRelations>) {
+    relations.friendly_fire = false;
+}
+
+fn update_friendly_fire_label(
+    mut label: Single<&mut Text, With<FriendlyFireLabel>>,
+    relations: Res<FactionRelations>,
+) {
+    label.0 = if relations.friendly_fire {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CombatLabel;
+
+fn enable_combat(_on: On<Pointer<Click>>, mut combat: ResMut<CombatEnabled>) {
+    combat.0 = true;
+}
+
+fn disable_combat(_on: On<Pointer<Click>>, mut combat: ResMut<CombatEnabled>) {
+    combat.0 = false;
+}
+
+fn update_combat_label(
+    mut label: Single<&mut Text, With<CombatLabel>>,
+    combat: Res<CombatEnabled>,
+) {
+    label.0 = if combat.0 { "On".into() } else { "Off".into() };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GraveCombosLabel;
+
+fn enable_grave_combos(_on: On<Pointer<Click>>, mut combos: ResMut<GraveCombos>) {
+    combos.0 = true;
+}
+
+fn disable_grave_combos(_on: On<Pointer<Click>>, mut combos: ResMut<GraveCombos>) {
+    combos.0 = false;
+}
+
+fn update_grave_combos_label(
+    mut label: Single<&mut Text, With<GraveCombosLabel>>,
+    combos: Res<GraveCombos>,
+) {
+    label.0 = if combos.0 { "On".into() } else { "Off".into() };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AimAssistLabel;
+
+fn lower_aim_assist(_on: On<Pointer<Click>>, mut aim_assist: ResMut<AimAssist>) {
+    aim_assist.tolerance_degrees = (aim_assist.tolerance_degrees - 1.0).round().max(0.0);
+}
+
+fn raise_aim_assist(_on: On<Pointer<Click>>, mut aim_assist: ResMut<AimAssist>) {
+    aim_assist.tolerance_degrees = (aim_assist.tolerance_degrees + 1.0)
+        .round()
+        .min(AimAssist::MAX_DEGREES);
+}
+
+fn update_aim_assist_label(
+    mut label: Single<&mut Text, With<AimAssistLabel>>,
+    aim_assist: Res<AimAssist>,
+) {
+    label.0 = if aim_assist.tolerance_degrees > 0.0 {
+        format!("{:.0}°", aim_assist.tolerance_degrees)
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HideHudLabel;
+
+fn hide_hud(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.hidden = true;
+}
+
+fn show_hud(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.hidden = false;
+}
+
+fn update_hide_hud_label(
+    mut label: Single<&mut Text, With<HideHudLabel>>,
+    hud: Res<HudVisibility>,
+) {
+    label.0 = if hud.hidden {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct HudScaleLabel;
+
+fn lower_hud_scale(_on: On<Pointer<Click>>, mut hud: ResMut<HudSettings>) {
+    hud.scale = (((hud.scale - 0.1) * 10.0).round() / 10.0).max(MIN_HUD_SCALE);
+}
+
+fn raise_hud_scale(_on: On<Pointer<Click>>, mut hud: ResMut<HudSettings>) {
+    hud.scale = (((hud.scale + 0.1) * 10.0).round() / 10.0).min(MAX_HUD_SCALE);
+}
+
+fn update_hud_scale_label(
+    mut label: Single<&mut Text, With<HudScaleLabel>>,
+    hud: Res<HudSettings>,
+) {
+    label.0 = format!("{:.1}x", hud.scale);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CaptionsLabel;
+
+fn show_captions(_on: On<Pointer<Click>>, mut captions: ResMut<CaptionSettings>) {
+    captions.enabled = true;
+}
+
+fn hide_captions(_on: On<Pointer<Click>>, mut captions: ResMut<CaptionSettings>) {
+    captions.enabled = false;
+}
+
+fn update_captions_label(
+    mut label: Single<&mut Text, With<CaptionsLabel>>,
+    captions: Res<CaptionSettings>,
+) {
+    label.0 = if captions.enabled {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RunTimerLabel;
+
+fn show_run_timer(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.timer = true;
+}
+
+fn hide_run_timer(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.timer = false;
+}
+
+fn update_run_timer_label(
+    mut label: Single<&mut Text, With<RunTimerLabel>>,
+    hud: Res<HudVisibility>,
+) {
+    label.0 = if hud.timer { "On".into() } else { "Off".into() };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ObjectivePanelLabel;
+
+fn show_objective_panel(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.objectives = true;
+}
+
+fn hide_objective_panel(_on: On<Pointer<Click>>, mut hud: ResMut<HudVisibility>) {
+    hud.objectives = false;
+}
+
+fn update_objective_panel_label(
+    mut label: Single<&mut Text, With<ObjectivePanelLabel>>,
+    hud: Res<HudVisibility>,
+) {
+    label.0 = if hud.objectives {
+        "On".into()
+    } else {
+        "Off".into()
+    };
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ObjectiveListLabel;
+
+fn compact_objective_list(_on: On<Pointer<Click>>, mut hud: ResMut<HudSettings>) {
+    hud.compact_objectives = true;
+}
+
+fn full_objective_list(_on: On<Pointer<Click>>, mut hud: ResMut<HudSettings>) {
+    hud.compact_objectives = false;
+}
+
+fn update_objective_list_label(
+    mut label: Single<&mut Text, With<ObjectiveListLabel>>,
+    hud: Res<HudSettings>,
+) {
+    label.0 = if hud.compact_objectives {
+        "Compact".into()
+    } else {
+        "Full".into()
+    };
+}
+
 #[derive(Resource, Reflect, Debug)]
 struct FpsLimiterSettings {
     enabled: bool,
@@ -0,0 +1,47 @@
+//! Small bincode files next to the game for what outlives a run, like best splits, high scores
+//! and settings. Native only, the web build has nowhere to keep them, so it loads nothing and
+//! saves nowhere.
+
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+
+/// Reads `T` from `path`, `None` if there's no file yet. `what` names it in the warning when
+/// the file is there but can't be read.
+pub(crate) fn load<T: Decode<()>>(path: &str, what: &str) -> Option<T> {
+    if !cfg!(feature = "native") {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    match bincode::decode_from_slice(&bytes, bincode::config::standard()) {
+        Ok((value, _)) => Some(value),
+        Err(error) => {
+            warn!("Ignoring unreadable {what} in {path}: {error}");
+            None
+        }
+    }
+}
+
+/// Writes `value` to `path`, warning instead of failing since losing it isn't worth a crash.
+pub(crate) fn save<T: Encode>(path: &str, what: &str, value: &T) {
+    if !cfg!(feature = "native") {
+        return;
+    }
+    let written = bincode::encode_to_vec(value, bincode::config::standard())
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| std::fs::write(path, bytes).map_err(Into::into));
+    if let Err(error) = written {
+        warn!("Failed to save {what}: {error}");
+    }
+}
+
+/// Removes the file at `path`, quietly if there was none to begin with.
+pub(crate) fn delete(path: &str, what: &str) {
+    if !cfg!(feature = "native") {
+        return;
+    }
+    if let Err(error) = std::fs::remove_file(path) {
+        if error.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to delete {what}: {error}");
+        }
+    }
+}
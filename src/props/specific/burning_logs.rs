@@ -14,6 +14,7 @@ use crate::third_party::bevy_trenchbroom::GetTrenchbroomModelPath as _;
 use crate::{
     PostPhysicsAppSystems,
     audio::SpatialPool,
+    graphics::GraphicsQuality,
     props::{effects::disable_shadow_casting_on_instance_ready, setup::static_bundle},
     screens::Screen,
 };
@@ -121,7 +122,12 @@ pub(super) fn add_particle_effects(
     asset_server: Res<AssetServer>,
     mut effects: ResMut<Assets<EffectAsset>>,
     mut commands: Commands,
+    quality: Res<GraphicsQuality>,
 ) {
+    // Only checked on spawn, the fire comes back with the next level load.
+    if !quality.particles() {
+        return;
+    }
     let particle_bundle = particle_bundle(&asset_server, &mut effects);
     commands.entity(add.entity).insert(particle_bundle);
 }
@@ -3,7 +3,7 @@
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 use bevy_fix_cursor_unlock_web::ForceUnlockCursor;
 
-use crate::{Pause, menus::Menu, screens::Screen};
+use crate::{Pause, gameplay::signpost::is_reading_sign, menus::Menu, screens::Screen};
 
 pub(super) fn plugin(app: &mut App) {
     // Toggle pause on key press.
@@ -13,11 +13,13 @@ pub(super) fn plugin(app: &mut App) {
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
+                    .and(not(is_reading_sign))
                     .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
+                    .and(not(in_state(Menu::CriticalFailure)))
                     .and(input_just_pressed(KeyCode::KeyP)),
             ),
         ),
@@ -58,8 +60,10 @@ fn open_pause_menu(mut next_menu: ResMut<NextState<Menu>>) {
 
 fn open_pause_menu_on_cursor_force_unlock(
     _unlock: On<ForceUnlockCursor>,
+    mut next_pause: ResMut<NextState<Pause>>,
     mut next_menu: ResMut<NextState<Menu>>,
 ) {
+    next_pause.set(Pause(true));
     next_menu.set(Menu::Pause);
 }
 
@@ -26,8 +26,16 @@ pub(crate) struct InteractionPalette {
     pub(crate) none: Color,
     pub(crate) hovered: Color,
     pub(crate) pressed: Color,
+    /// Used instead of the others while the entity has [`DisabledButton`].
+    pub(crate) disabled: Color,
 }
 
+/// Marks a button that can't be used. It's greyed out by its [`InteractionPalette`]
+/// and doesn't trigger [`OnPress`] or play interaction sounds.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub(crate) struct DisabledButton;
+
 /// Event triggered on a UI entity when the [`Interaction`] component on the same entity changes to
 /// [`Interaction::Pressed`]. Observe this event to detect e.g. button presses.
 #[derive(EntityEvent)]
@@ -36,7 +44,10 @@ pub(crate) struct OnPress {
 }
 
 fn trigger_on_press(
-    interaction_query: Query<(Entity, &Interaction), Changed<Interaction>>,
+    interaction_query: Query<
+        (Entity, &Interaction),
+        (Changed<Interaction>, Without<DisabledButton>),
+    >,
     mut commands: Commands,
 ) {
     for (entity, interaction) in &interaction_query {
@@ -48,12 +59,18 @@ fn trigger_on_press(
 
 fn apply_interaction_palette(
     mut palette_query: Query<
-        (&Interaction, &InteractionPalette, &mut BackgroundColor),
-        Changed<Interaction>,
+        (
+            &Interaction,
+            &InteractionPalette,
+            &mut BackgroundColor,
+            Has<DisabledButton>,
+        ),
+        Or<(Changed<Interaction>, Added<DisabledButton>)>,
     >,
 ) {
-    for (interaction, palette, mut background) in &mut palette_query {
+    for (interaction, palette, mut background, disabled) in &mut palette_query {
         *background = match interaction {
+            _ if disabled => palette.disabled,
             Interaction::None => palette.none,
             Interaction::Hovered => palette.hovered,
             Interaction::Pressed => palette.pressed,
@@ -86,7 +103,7 @@ impl FromWorld for InteractionAssets {
 }
 
 fn trigger_interaction_sound_effect(
-    interaction_query: Query<&Interaction, Changed<Interaction>>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, Without<DisabledButton>)>,
     interaction_assets: Res<InteractionAssets>,
     mut commands: Commands,
 ) {
@@ -14,6 +14,12 @@ pub(crate) const BUTTON_BACKGROUND: Color = Color::srgb(0.275, 0.400, 0.750);
 pub(crate) const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.384, 0.600, 0.820);
 // #3d4999
 pub(crate) const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
+/// #a6a6a6
+pub(crate) const BUTTON_SECONDARY_TEXT: Color = Color::srgb(0.650, 0.650, 0.650);
+/// #6b6b6b
+pub(crate) const BUTTON_DISABLED_TEXT: Color = Color::srgb(0.420, 0.420, 0.420);
+/// #38393d, a touch lighter than the screen background
+pub(crate) const BUTTON_DISABLED_BACKGROUND: Color = Color::srgb(0.220, 0.224, 0.239);
 
 /// #2b2c2f, taken from the Bevy website
 pub(crate) const SCREEN_BACKGROUND: Color = Color::srgb(0.16862746, 0.17254902, 0.18431373);
@@ -8,7 +8,10 @@ use bevy::{
     ui::Val::*,
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{
+    interaction::{DisabledButton, InteractionPalette},
+    palette::*,
+};
 
 /// A root UI node that fills the window and centers its content.
 pub(crate) fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
@@ -77,15 +80,58 @@ where
     B: Bundle,
     I: IntoObserverSystem<E, B, M>,
 {
+    button_with(text, action, font, ButtonOpts::default())
+}
+
+/// Extra options for [`button_with`].
+#[derive(Debug, Clone)]
+pub(crate) struct ButtonOpts {
+    /// Disabled buttons are greyed out and ignore the pointer, so their action never runs.
+    /// Fixed once the button is spawned.
+    pub enabled: bool,
+    /// Shown in place of the secondary label while disabled, saying why.
+    pub disabled_reason: Option<String>,
+    /// Smaller right-aligned text, like a cost or a keybind.
+    pub secondary_label: Option<String>,
+    pub width: Option<Val>,
+}
+
+impl Default for ButtonOpts {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled_reason: None,
+            secondary_label: None,
+            width: None,
+        }
+    }
+}
+
+/// A text button with an action defined as an [`Observer`], see [`ButtonOpts`].
+pub(crate) fn button_with<E, B, M, I>(
+    text: impl Into<String>,
+    action: I,
+    font: &Handle<Font>,
+    opts: ButtonOpts,
+) -> impl Bundle
+where
+    E: EntityEvent,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    let width = opts.width.unwrap_or(Auto);
     button_base(
         text,
         action,
         Node {
+            width,
             align_items: AlignItems::Center,
-            justify_content: JustifyContent::FlexStart,
+            justify_content: JustifyContent::SpaceBetween,
+            column_gap: Px(20.0),
             ..default()
         },
         font,
+        opts,
     )
 }
 
@@ -111,6 +157,7 @@ where
             ..default()
         },
         font,
+        ButtonOpts::default(),
     )
 }
 
@@ -120,6 +167,7 @@ fn button_base<E, B, M, I>(
     action: I,
     button_bundle: impl Bundle,
     font: &Handle<Font>,
+    opts: ButtonOpts,
 ) -> impl Bundle
 where
     E: EntityEvent,
@@ -128,31 +176,54 @@ where
 {
     let text = text.into();
     let action = IntoObserverSystem::into_system(action);
+    let secondary_font = text_font(font, 24.0);
     let font = text_font(font, 40.0);
+    let (text_color, secondary_label) = if opts.enabled {
+        (BUTTON_TEXT, opts.secondary_label)
+    } else {
+        (
+            BUTTON_DISABLED_TEXT,
+            opts.disabled_reason.or(opts.secondary_label),
+        )
+    };
     (
         Name::new("Button"),
         Node::default(),
-        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
-            parent
-                .spawn((
-                    Name::new("Button Inner"),
-                    Button,
-                    BackgroundColor(Color::NONE),
-                    InteractionPalette {
-                        none: Color::NONE,
-                        hovered: BUTTON_HOVERED_BACKGROUND,
-                        pressed: BUTTON_PRESSED_BACKGROUND,
-                    },
-                    children![(
-                        Name::new("Button Text"),
-                        Text(text),
-                        font,
-                        TextColor(BUTTON_TEXT),
-                        Pickable::IGNORE,
-                    )],
-                ))
-                .insert(button_bundle)
-                .observe(action);
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            let mut button = parent.spawn((
+                Name::new("Button Inner"),
+                Button,
+                BackgroundColor(Color::NONE),
+                InteractionPalette {
+                    none: Color::NONE,
+                    hovered: BUTTON_HOVERED_BACKGROUND,
+                    pressed: BUTTON_PRESSED_BACKGROUND,
+                    disabled: BUTTON_DISABLED_BACKGROUND,
+                },
+                children![(
+                    Name::new("Button Text"),
+                    Text(text),
+                    font,
+                    TextColor(text_color),
+                    Pickable::IGNORE,
+                )],
+            ));
+            button.insert(button_bundle);
+            if let Some(secondary_label) = secondary_label {
+                button.with_child((
+                    Name::new("Button Secondary Text"),
+                    Text(secondary_label),
+                    secondary_font,
+                    TextColor(BUTTON_SECONDARY_TEXT),
+                    Pickable::IGNORE,
+                ));
+            }
+            if opts.enabled {
+                button.observe(action);
+            } else {
+                // Never picked, so pointer observers can't fire either.
+                button.insert((DisabledButton, Pickable::IGNORE));
+            }
         })),
     )
 }
@@ -20,6 +20,43 @@ pub(crate) enum CollisionLayer {
     VoxelAabb,
     Ragdoll,
     Projectile,
+    /// Dev tool colliders, only ever raycast against explicitly.
+    Debug,
+}
+
+impl CollisionLayer {
+    pub(crate) const ALL: [Self; 9] = [
+        Self::Default,
+        Self::Prop,
+        Self::Character,
+        Self::Sensor,
+        Self::Level,
+        Self::VoxelAabb,
+        Self::Ragdoll,
+        Self::Projectile,
+        Self::Debug,
+    ];
+
+    /// Names of the layers set in `mask`, for debugging. Bits that aren't a layer show up in
+    /// hex.
+    pub(crate) fn names(mask: LayerMask) -> Vec<String> {
+        if mask == LayerMask::ALL {
+            return vec!["ALL".to_string()];
+        }
+        let mut rest = mask.0;
+        let mut names = Vec::new();
+        for layer in Self::ALL {
+            let bits = layer.to_bits();
+            if rest & bits != 0 {
+                names.push(format!("{layer:?}"));
+                rest &= !bits;
+            }
+        }
+        if rest != 0 {
+            names.push(format!("{rest:#x}"));
+        }
+        names
+    }
 }
 
 fn enable_interpolation(
@@ -31,3 +68,29 @@ fn enable_interpolation(
         commands.entity(add.entity).insert(TransformInterpolation);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_names_round_trip() {
+        assert_eq!(
+            CollisionLayer::ALL.len() as u32,
+            CollisionLayer::all_bits().count_ones()
+        );
+        for layer in CollisionLayer::ALL {
+            let name = format!("{layer:?}");
+            assert_eq!(CollisionLayer::names(LayerMask::from(layer)), vec![name]);
+        }
+        assert_eq!(
+            CollisionLayer::names(LayerMask::from([
+                CollisionLayer::Prop,
+                CollisionLayer::Ragdoll
+            ])),
+            vec!["Prop", "Ragdoll"]
+        );
+        assert!(CollisionLayer::names(LayerMask::NONE).is_empty());
+        assert_eq!(CollisionLayer::names(LayerMask::ALL), vec!["ALL"]);
+    }
+}
@@ -26,9 +26,10 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
+/// Keeps the dialogue box clear of the inventory bar and the letterbox.
 fn offset_dialogue_ui(_on: On<Add, UiRootNode>, mut roots: Query<&mut Node, With<UiRootNode>>) {
     for mut node in &mut roots {
-        node.padding.bottom = Val::Px(90.0);
+        node.padding.bottom = Val::Vh(12.0);
     }
 }
 